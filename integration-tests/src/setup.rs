@@ -24,7 +24,7 @@ use pallet_traits::{
     xcm::AssetType,
 };
 use polkadot_parachain::primitives::Sibling;
-use primitives::{paras, tokens::*, AccountId, Balance, CurrencyId, Rate, Ratio};
+use primitives::{paras, tokens::*, AccountId, Balance, CurrencyId, Liquidity, Rate, Ratio};
 use sp_runtime::{
     traits::{AccountIdConversion, One},
     FixedPointNumber, MultiAddress,
@@ -100,6 +100,11 @@ pub const fn market_mock(ptoken_id: u32) -> Market<Balance> {
         supply_cap: 1_000_000_000_000_000_000_000u128, // set to 1B
         borrow_cap: 1_000_000_000_000_000_000_000u128, // set to 1B
         ptoken_id,
+        max_price_age: None,
+        liquidate_on_stale_price: false,
+        isolated: false,
+        debt_ceiling: Liquidity::from_inner(0),
+        borrowable_in_isolation: false,
     }
 }
 
@@ -38,6 +38,7 @@ pub trait RuntimeApiCollection:
     + sp_session::SessionKeys<Block>
     + cumulus_primitives_core::CollectCollationInfo<Block>
     + pallet_loans_rpc::LoansRuntimeApi<Block, AccountId, Balance>
+    + pallet_prices_rpc::PricesRuntimeApi<Block, BlockNumber, AccountId>
     + pallet_router_rpc::RouterRuntimeApi<Block, Balance>
     + fp_rpc::EthereumRuntimeRPCApi<Block>
     + fp_rpc::ConvertTransactionRuntimeApi<Block>
@@ -60,6 +61,7 @@ where
         + sp_session::SessionKeys<Block>
         + cumulus_primitives_core::CollectCollationInfo<Block>
         + pallet_loans_rpc::LoansRuntimeApi<Block, AccountId, Balance>
+        + pallet_prices_rpc::PricesRuntimeApi<Block, BlockNumber, AccountId>
         + pallet_router_rpc::RouterRuntimeApi<Block, Balance>
         + fp_rpc::EthereumRuntimeRPCApi<Block>
         + fp_rpc::ConvertTransactionRuntimeApi<Block>,
@@ -47,6 +47,7 @@ use substrate_frame_rpc_system::{System, SystemApiServer};
 
 use orml_oracle_rpc::{Oracle, OracleApiServer};
 use pallet_loans_rpc::{Loans, LoansApiServer};
+use pallet_prices_rpc::{Prices, PricesApiServer};
 use pallet_router_rpc::{Router, RouterApiServer};
 
 pub fn frontier_database_dir(config: &Configuration, path: &str) -> std::path::PathBuf {
@@ -168,6 +169,7 @@ where
         + BlockBuilder<Block>
         + orml_oracle_rpc::OracleRuntimeApi<Block, DataProviderId, CurrencyId, TimeStampedPrice>
         + pallet_loans_rpc::LoansRuntimeApi<Block, AccountId, Balance>
+        + pallet_prices_rpc::PricesRuntimeApi<Block, BlockNumber, AccountId>
         + pallet_router_rpc::RouterRuntimeApi<Block, Balance>
         + fp_rpc::ConvertTransactionRuntimeApi<Block>
         + fp_rpc::EthereumRuntimeRPCApi<Block>,
@@ -258,6 +260,7 @@ where
 
     io.merge(Oracle::new(client.clone()).into_rpc())?;
     io.merge(Loans::new(client.clone()).into_rpc())?;
+    io.merge(Prices::new(client.clone()).into_rpc())?;
     io.merge(Router::new(client.clone()).into_rpc())?;
 
     Ok(io)
@@ -6,7 +6,7 @@ use super::*;
 use crate::Pallet as AMM;
 
 use frame_benchmarking::{
-    benchmarks_instance_pallet, impl_benchmark_test_suite, whitelisted_caller,
+    benchmarks_instance_pallet, impl_benchmark_test_suite, whitelisted_caller, BenchmarkError,
 };
 use frame_support::{assert_ok, dispatch::UnfilteredDispatchable, traits::EnsureOrigin};
 use frame_system::{self, RawOrigin as SystemOrigin};
@@ -78,7 +78,7 @@ benchmarks_instance_pallet! {
         let quote_amount = 200_000u128;
         assert_ok!(AMM::<T, I>::create_pool(T::CreatePoolOrigin::try_successful_origin().expect("No origin exists which can satisfy the guard"),
             (BASE_ASSET, QUOTE_ASSET), (base_amount, quote_amount),
-            caller.clone(), ASSET_ID));
+            caller.clone(), ASSET_ID, T::LpFee::get()));
     }: _(
         SystemOrigin::Signed(caller.clone()),
         (BASE_ASSET, QUOTE_ASSET),
@@ -105,7 +105,7 @@ benchmarks_instance_pallet! {
         let quote_amount = 900_000u128;
         assert_ok!(AMM::<T, I>::create_pool(T::CreatePoolOrigin::try_successful_origin().expect("No origin exists which can satisfy the guard"),
             (BASE_ASSET, QUOTE_ASSET), (base_amount, quote_amount),
-            caller.clone(), ASSET_ID));
+            caller.clone(), ASSET_ID, T::LpFee::get()));
     }: _(
         SystemOrigin::Signed(caller.clone()),
         (BASE_ASSET, QUOTE_ASSET),
@@ -135,7 +135,7 @@ benchmarks_instance_pallet! {
             pair: (BASE_ASSET, QUOTE_ASSET),
             liquidity_amounts: (base_amount, quote_amount),
             lptoken_receiver: caller.clone(),
-            lp_token_id: ASSET_ID
+            lp_fee: T::LpFee::get()
         };
     }: {
         call.dispatch_bypass_filter(origin)?
@@ -175,6 +175,102 @@ benchmarks_instance_pallet! {
     }
     verify {
     }
+
+    update_lp_fee {
+        let caller: T::AccountId = whitelisted_caller();
+        initial_set_up::<T, I>(caller.clone());
+        assert_ok!(AMM::<T, I>::create_pool(T::CreatePoolOrigin::try_successful_origin().expect("No origin exists which can satisfy the guard"),
+            (BASE_ASSET, QUOTE_ASSET), (100_000u128, 200_000u128),
+            caller, ASSET_ID, T::LpFee::get()));
+        let origin = T::CreatePoolOrigin::try_successful_origin().expect("No origin exists which can satisfy the guard");
+        let call = Call::<T, I>::update_lp_fee {
+            pair: (BASE_ASSET, QUOTE_ASSET),
+            lp_fee: Ratio::from_percent(1)
+        };
+    }: {
+        call.dispatch_bypass_filter(origin)?
+    }
+    verify {
+        assert_last_event::<T, I>(Event::<T, I>::LpFeeUpdated(
+            BASE_ASSET,
+            QUOTE_ASSET,
+            Ratio::from_percent(1),
+        ).into());
+    }
+
+    create_pool_permissionless {
+        let origin = T::PermissionlessCreatePoolOrigin::try_successful_origin()
+            .map_err(|_| BenchmarkError::Weightless)?;
+        let caller = T::PermissionlessCreatePoolOrigin::ensure_origin(origin.clone())
+            .map_err(|_| BenchmarkError::Weightless)?;
+        initial_set_up::<T, I>(caller.clone());
+        T::Assets::mint_into(T::GetNativeCurrencyId::get(), &caller, T::PoolCreationDeposit::get() * 2)?;
+        let call = Call::<T, I>::create_pool_permissionless {
+            pair: (BASE_ASSET, QUOTE_ASSET),
+            liquidity_amounts: (100_000_000_000_000u128, 100_000_000_000_000u128),
+            lp_fee: T::LpFee::get()
+        };
+    }: {
+        call.dispatch_bypass_filter(origin)?
+    }
+    verify {
+        assert_last_event::<T, I>(Event::<T, I>::PoolDepositHeld(
+            caller,
+            BASE_ASSET,
+            QUOTE_ASSET,
+            T::PoolCreationDeposit::get(),
+        ).into());
+    }
+
+    refund_pool_deposit {
+        let caller: T::AccountId = whitelisted_caller();
+        initial_set_up::<T, I>(caller.clone());
+        assert_ok!(AMM::<T, I>::create_pool(T::CreatePoolOrigin::try_successful_origin().expect("No origin exists which can satisfy the guard"),
+            (BASE_ASSET, QUOTE_ASSET), (100_000_000_000_000u128, 100_000_000_000_000u128),
+            caller.clone(), ASSET_ID, T::LpFee::get()));
+        let deposit = T::PoolCreationDeposit::get();
+        T::Assets::mint_into(T::GetNativeCurrencyId::get(), &caller, deposit)?;
+        T::Currency::reserve(&caller, deposit)?;
+        PoolDeposits::<T, I>::insert(BASE_ASSET, QUOTE_ASSET, (caller.clone(), deposit));
+        RefundThresholdReachedAt::<T, I>::insert(BASE_ASSET, QUOTE_ASSET, T::BlockNumber::zero());
+        frame_system::Pallet::<T>::set_block_number(T::DepositRefundDelay::get());
+    }: _(
+        SystemOrigin::Signed(caller.clone()),
+        (BASE_ASSET, QUOTE_ASSET)
+    )
+    verify {
+        assert_last_event::<T, I>(Event::<T, I>::PoolDepositRefunded(
+            caller,
+            BASE_ASSET,
+            QUOTE_ASSET,
+            deposit,
+        ).into());
+    }
+
+    delist_pool {
+        let caller: T::AccountId = whitelisted_caller();
+        initial_set_up::<T, I>(caller.clone());
+        assert_ok!(AMM::<T, I>::create_pool(T::CreatePoolOrigin::try_successful_origin().expect("No origin exists which can satisfy the guard"),
+            (BASE_ASSET, QUOTE_ASSET), (100_000u128, 200_000u128),
+            caller.clone(), ASSET_ID, T::LpFee::get()));
+        let deposit = T::PoolCreationDeposit::get();
+        T::Assets::mint_into(T::GetNativeCurrencyId::get(), &caller, deposit)?;
+        T::Currency::reserve(&caller, deposit)?;
+        PoolDeposits::<T, I>::insert(BASE_ASSET, QUOTE_ASSET, (caller, deposit));
+        let origin = T::CreatePoolOrigin::try_successful_origin().expect("No origin exists which can satisfy the guard");
+        let call = Call::<T, I>::delist_pool {
+            pair: (BASE_ASSET, QUOTE_ASSET)
+        };
+    }: {
+        call.dispatch_bypass_filter(origin)?
+    }
+    verify {
+        assert_last_event::<T, I>(Event::<T, I>::PoolDelisted(
+            BASE_ASSET,
+            QUOTE_ASSET,
+            deposit,
+        ).into());
+    }
 }
 
 impl_benchmark_test_suite!(AMM, crate::mock::new_test_ext(), crate::mock::Test,);
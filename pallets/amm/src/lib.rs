@@ -15,6 +15,16 @@
 //! # Automatic Market Maker (AMM)
 //!
 //! Given any [X, Y] asset pair, "base" is the `X` asset while "quote" is the `Y` asset.
+//!
+//! Each pool keeps a bounded buffer of cumulative price observations, which
+//! `consult` uses to compute the time weighted average price over a window of blocks.
+//!
+//! The LP fee is chosen per pool when it is created, so pegged pairs can trade at a
+//! lower fee than volatile ones.
+//!
+//! Besides governance, runtimes can let any account create a pool by reserving a deposit
+//! in the native currency. The deposit is unreserved once the pool has grown past a
+//! liquidity threshold and is slashed if governance delists the pool as spam.
 
 #![cfg_attr(not(feature = "std"), no_std)]
 
@@ -26,25 +36,28 @@ mod mock;
 mod tests;
 
 mod benchmarking;
+pub mod migrations;
 pub mod weights;
 
+use codec::{Decode, Encode, MaxEncodedLen};
 use frame_support::{
     dispatch::DispatchResult,
     log,
     pallet_prelude::*,
     require_transactional,
     traits::{
-        fungibles::{Inspect, Mutate, Transfer},
-        Get, IsType,
+        fungibles::{Create, Inspect, Mutate, Transfer},
+        Get, IsType, ReservableCurrency,
     },
     transactional, Blake2_128Concat, PalletId,
 };
 use frame_system::{ensure_signed, pallet_prelude::OriginFor};
-use pallet_traits::{ConvertToBigUint, Pool};
-use primitives::{Balance, CurrencyId, Ratio};
+use pallet_traits::{ConvertToBigUint, OnPoolCreated, OnPoolDelisted, Pool};
+use primitives::{Balance, CurrencyId, Rate, Ratio};
+use scale_info::TypeInfo;
 use sp_runtime::{
     traits::{AccountIdConversion, CheckedAdd, CheckedSub, One, Saturating, Zero},
-    ArithmeticError, DispatchError, FixedPointNumber, FixedU128, SaturatedConversion,
+    ArithmeticError, DispatchError, FixedPointNumber, FixedU128, RuntimeDebug, SaturatedConversion,
 };
 use sp_std::{cmp::min, result::Result, vec::Vec};
 
@@ -60,6 +73,22 @@ pub type AssetIdOf<T, I = ()> =
 pub type BalanceOf<T, I = ()> =
     <<T as Config<I>>::Assets as Inspect<<T as frame_system::Config>::AccountId>>::Balance;
 
+/// Utility type for managing upgrades/migrations.
+#[derive(Encode, Decode, Clone, Copy, PartialEq, Eq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+pub enum Versions {
+    V0,
+    V1,
+    V2,
+}
+
+/// Snapshot of the cumulative prices of a pool at a given block
+#[derive(Encode, Decode, Clone, Copy, PartialEq, Eq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+pub struct Observation<Balance, BlockNumber> {
+    pub block_number: BlockNumber,
+    pub price_0_cumulative: Balance,
+    pub price_1_cumulative: Balance,
+}
+
 #[frame_support::pallet]
 pub mod pallet {
     use super::*;
@@ -92,11 +121,53 @@ pub mod pallet {
         /// Specify which origin is allowed to update fee receiver.
         type ProtocolFeeUpdateOrigin: EnsureOrigin<Self::RuntimeOrigin>;
 
+        /// Specify which origin is allowed to create new pools by holding a deposit,
+        /// `NeverEnsureOrigin` disables permissionless pool creation.
+        type PermissionlessCreatePoolOrigin: EnsureOrigin<
+            Self::RuntimeOrigin,
+            Success = Self::AccountId,
+        >;
+
+        /// Native currency the creation deposit of a permissionless pool is
+        /// reserved in
+        type Currency: ReservableCurrency<Self::AccountId, Balance = BalanceOf<Self, I>>;
+
+        /// Creates the lp tokens of permissionless pools
+        type LpTokenAssets: Create<Self::AccountId, AssetId = CurrencyId, Balance = Balance>;
+
+        /// First asset id of the range reserved for the lp tokens of permissionless
+        /// pools, the ids are allocated in sequence by the pallet
+        #[pallet::constant]
+        type PermissionlessLpTokenIdStart: Get<AssetIdOf<Self, I>>;
+
+        /// Amount of native currency reserved from the creator of a permissionless pool
+        #[pallet::constant]
+        type PoolCreationDeposit: Get<BalanceOf<Self, I>>;
+
+        /// Minimum amount of lp tokens a permissionless pool must be seeded with
+        #[pallet::constant]
+        type MinimumPoolSeedLiquidity: Get<BalanceOf<Self, I>>;
+
+        /// Lp token supply a permissionless pool must reach before its creation
+        /// deposit can be refunded
+        #[pallet::constant]
+        type DepositRefundThreshold: Get<BalanceOf<Self, I>>;
+
+        /// Number of blocks the lp token supply of a permissionless pool must stay
+        /// above `DepositRefundThreshold` before its creation deposit can be refunded
+        #[pallet::constant]
+        type DepositRefundDelay: Get<Self::BlockNumber>;
+
         /// Defines the fees taken out of each trade and sent back to the AMM pool,
-        /// typically 0.3%.
+        /// typically 0.3%. Only applies to pools created before the fee was
+        /// chosen per pool.
         #[pallet::constant]
         type LpFee: Get<Ratio>;
 
+        /// Highest LP fee a permissionless pool can be created with
+        #[pallet::constant]
+        type MaxPermissionlessLpFee: Get<Ratio>;
+
         /// Minimum amount of liquidty needed to init a new pool
         /// this amount is burned when the pool is created.
         ///
@@ -115,6 +186,21 @@ pub mod pallet {
 
         #[pallet::constant]
         type GetNativeCurrencyId: Get<AssetIdOf<Self, I>>;
+
+        /// How many price observations are kept per pool, the oldest
+        /// observation is dropped once the buffer is full
+        #[pallet::constant]
+        type MaxObservations: Get<u32>;
+
+        /// Minimum number of blocks between two price observations of a pool
+        #[pallet::constant]
+        type ObservationPeriod: Get<Self::BlockNumber>;
+
+        /// Notified of every pool created, e.g. by the router to extend its graph
+        type OnPoolCreated: OnPoolCreated<AssetIdOf<Self, I>>;
+
+        /// Notified of every pool delisted, e.g. by the router to prune its graph
+        type OnPoolDelisted: OnPoolDelisted<AssetIdOf<Self, I>>;
     }
 
     #[pallet::error]
@@ -141,6 +227,21 @@ pub mod pallet {
         ConversionToU128Failed,
         /// Protocol fee receiver not set
         ProtocolFeeReceiverNotSet,
+        /// No price observation old enough for the requested window
+        InsufficientObservations,
+        /// LP fee must be lower than 100%
+        InvalidLpFee,
+        /// Pool has been delisted
+        PoolDelisted,
+        /// Not enough liquidity to seed a permissionless pool
+        InsufficientSeedLiquidity,
+        /// Pool has no creation deposit
+        PoolDepositNotFound,
+        /// Pool liquidity has not stayed above the deposit refund threshold
+        /// for `DepositRefundDelay` blocks
+        RefundThresholdNotReached,
+        /// LP fee is above the maximum of a permissionless pool
+        LpFeeTooHigh,
     }
 
     #[pallet::event]
@@ -197,6 +298,32 @@ pub mod pallet {
 
         /// Protocol fee receiver updated
         ProtocolFeeReceiverUpdated(T::AccountId),
+
+        /// LP fee of a pool updated
+        /// [base_currency_id, quote_currency_id, lp_fee]
+        LpFeeUpdated(AssetIdOf<T, I>, AssetIdOf<T, I>, Ratio),
+
+        /// Deposit held for a permissionless pool
+        /// [creator, base_currency_id, quote_currency_id, deposit]
+        PoolDepositHeld(
+            T::AccountId,
+            AssetIdOf<T, I>,
+            AssetIdOf<T, I>,
+            BalanceOf<T, I>,
+        ),
+
+        /// Deposit of a permissionless pool refunded to its creator
+        /// [creator, base_currency_id, quote_currency_id, deposit]
+        PoolDepositRefunded(
+            T::AccountId,
+            AssetIdOf<T, I>,
+            AssetIdOf<T, I>,
+            BalanceOf<T, I>,
+        ),
+
+        /// Pool delisted, the creation deposit if any is slashed
+        /// [base_currency_id, quote_currency_id, burned_deposit]
+        PoolDelisted(AssetIdOf<T, I>, AssetIdOf<T, I>, BalanceOf<T, I>),
     }
 
     #[pallet::pallet]
@@ -215,6 +342,30 @@ pub mod pallet {
         OptionQuery,
     >;
 
+    /// Asset pair of the pool of each liquidity token
+    #[pallet::storage]
+    #[pallet::getter(fn lp_token_pools)]
+    pub type LpTokenPools<T: Config<I>, I: 'static = ()> = StorageMap<
+        _,
+        Blake2_128Concat,
+        AssetIdOf<T, I>,
+        (AssetIdOf<T, I>, AssetIdOf<T, I>),
+        OptionQuery,
+    >;
+
+    /// Cumulative price observations of a pool, ordered from oldest to newest
+    #[pallet::storage]
+    #[pallet::getter(fn observations)]
+    pub type Observations<T: Config<I>, I: 'static = ()> = StorageDoubleMap<
+        _,
+        Blake2_128Concat,
+        AssetIdOf<T, I>,
+        Blake2_128Concat,
+        AssetIdOf<T, I>,
+        BoundedVec<Observation<BalanceOf<T, I>, T::BlockNumber>, T::MaxObservations>,
+        ValueQuery,
+    >;
+
     /// How much the protocol is taking out of each trade.
     #[pallet::storage]
     #[pallet::getter(fn protocol_fee)]
@@ -224,6 +375,64 @@ pub mod pallet {
     #[pallet::storage]
     pub type ProtocolFeeReceiver<T: Config<I>, I: 'static = ()> = StorageValue<_, T::AccountId>;
 
+    /// Deposit held from the creator of a permissionless pool
+    #[pallet::storage]
+    #[pallet::getter(fn pool_deposits)]
+    pub type PoolDeposits<T: Config<I>, I: 'static = ()> = StorageDoubleMap<
+        _,
+        Blake2_128Concat,
+        AssetIdOf<T, I>,
+        Blake2_128Concat,
+        AssetIdOf<T, I>,
+        (T::AccountId, BalanceOf<T, I>),
+        OptionQuery,
+    >;
+
+    /// Block since which the lp token supply of a permissionless pool has
+    /// stayed above `DepositRefundThreshold`
+    #[pallet::storage]
+    #[pallet::getter(fn refund_threshold_reached_at)]
+    pub type RefundThresholdReachedAt<T: Config<I>, I: 'static = ()> = StorageDoubleMap<
+        _,
+        Blake2_128Concat,
+        AssetIdOf<T, I>,
+        Blake2_128Concat,
+        AssetIdOf<T, I>,
+        T::BlockNumber,
+        OptionQuery,
+    >;
+
+    /// Asset id of the lp token of the next permissionless pool
+    #[pallet::storage]
+    #[pallet::getter(fn next_lp_token_id)]
+    pub type NextLpTokenId<T: Config<I>, I: 'static = ()> =
+        StorageValue<_, AssetIdOf<T, I>, OptionQuery>;
+
+    /// Pools delisted by governance, they can no longer be traded against or
+    /// receive liquidity but liquidity providers can still withdraw
+    #[pallet::storage]
+    #[pallet::getter(fn delisted_pools)]
+    pub type DelistedPools<T: Config<I>, I: 'static = ()> = StorageDoubleMap<
+        _,
+        Blake2_128Concat,
+        AssetIdOf<T, I>,
+        Blake2_128Concat,
+        AssetIdOf<T, I>,
+        (),
+        OptionQuery,
+    >;
+
+    /// DefaultVersion is using for initialize the StorageVersion
+    #[pallet::type_value]
+    pub(super) fn DefaultVersion<T: Config<I>, I: 'static>() -> Versions {
+        Versions::V0
+    }
+
+    /// Storage version of the pallet.
+    #[pallet::storage]
+    pub(crate) type StorageVersion<T: Config<I>, I: 'static = ()> =
+        StorageValue<_, Versions, ValueQuery, DefaultVersion<T, I>>;
+
     #[pallet::call]
     impl<T: Config<I>, I: 'static> Pallet<T, I> {
         /// Allow users to add liquidity to a given pool
@@ -242,6 +451,7 @@ pub mod pallet {
         ) -> DispatchResultWithPostInfo {
             let who = ensure_signed(origin)?;
             let (is_inverted, base_asset, quote_asset) = Self::sort_assets(pair)?;
+            Self::ensure_listed(base_asset, quote_asset)?;
 
             let (base_amount, quote_amount) = if is_inverted {
                 (desired_amounts.1, desired_amounts.0)
@@ -277,12 +487,17 @@ pub mod pallet {
 
                     Self::do_mint_protocol_fee(pool)?;
 
+                    // accumulate the prices before the new liquidity moves the reserves
+                    Self::do_update_oracle(pool)?;
+                    Self::do_record_observation(base_asset, quote_asset, pool)?;
+
                     Self::do_add_liquidity(
                         &who,
                         pool,
                         (ideal_base_amount, ideal_quote_amount),
                         (base_asset, quote_asset),
                     )?;
+                    Self::update_refund_threshold(base_asset, quote_asset, pool.lp_token_id);
 
                     log::trace!(
                         target: "amm::add_liquidity",
@@ -333,8 +548,13 @@ pub mod pallet {
 
                 Self::do_mint_protocol_fee(pool)?;
 
+                // accumulate the prices before the removed liquidity moves the reserves
+                Self::do_update_oracle(pool)?;
+                Self::do_record_observation(base_asset, quote_asset, pool)?;
+
                 let (base_amount_removed, quote_amount_removed) =
                     Self::do_remove_liquidity(&who, pool, liquidity, (base_asset, quote_asset))?;
+                Self::update_refund_threshold(base_asset, quote_asset, pool.lp_token_id);
 
                 log::trace!(
                     target: "amm::remove_liquidity",
@@ -367,6 +587,7 @@ pub mod pallet {
         /// - `liquidity_amounts`: Liquidity amounts to be added in pool
         /// - `lptoken_receiver`: Allocate any liquidity tokens to lptoken_receiver
         /// - `lp_token_id`: Liquidity pool share representative token
+        /// - `lp_fee`: Fee taken out of each trade and left in the pool
         #[pallet::call_index(2)]
         #[pallet::weight(T::AMMWeightInfo::create_pool())]
         #[transactional]
@@ -376,69 +597,16 @@ pub mod pallet {
             liquidity_amounts: (BalanceOf<T, I>, BalanceOf<T, I>),
             lptoken_receiver: T::AccountId,
             lp_token_id: AssetIdOf<T, I>,
+            lp_fee: Ratio,
         ) -> DispatchResultWithPostInfo {
             T::CreatePoolOrigin::ensure_origin(origin)?;
-
-            let (is_inverted, base_asset, quote_asset) = Self::sort_assets(pair)?;
-            ensure!(
-                !Pools::<T, I>::contains_key(base_asset, quote_asset),
-                Error::<T, I>::PoolAlreadyExists
-            );
-
-            let (base_amount, quote_amount) = if is_inverted {
-                (liquidity_amounts.1, liquidity_amounts.0)
-            } else {
-                (liquidity_amounts.0, liquidity_amounts.1)
-            };
-
-            // check that this is a new asset to avoid using an asset that
-            // already has tokens minted
-            ensure!(
-                T::Assets::total_issuance(lp_token_id).is_zero(),
-                Error::<T, I>::LpTokenAlreadyExists
-            );
-
-            let mut pool = Pool::new(lp_token_id);
-
-            Self::deposit_event(Event::<T, I>::PoolCreated(
-                lptoken_receiver.clone(),
-                base_asset,
-                quote_asset,
-                lp_token_id,
-            ));
-
-            Self::do_add_liquidity(
+            Self::do_create_pool(
                 &lptoken_receiver,
-                &mut pool,
-                (base_amount, quote_amount),
-                (base_asset, quote_asset),
+                pair,
+                liquidity_amounts,
+                lp_token_id,
+                lp_fee,
             )?;
-
-            Pools::<T, I>::insert(base_asset, quote_asset, pool);
-
-            log::trace!(
-                target: "amm::create_pool",
-                "lptoken_receiver: {:?}, base_asset: {:?}, quote_asset: {:?}, base_amount: {:?}, quote_amount: {:?},\
-                 liquidity_amounts: {:?}",
-                &lptoken_receiver,
-                &base_asset,
-                &quote_asset,
-                &base_amount,
-                &quote_amount,
-                &liquidity_amounts
-            );
-
-            Self::deposit_event(Event::<T, I>::LiquidityAdded(
-                lptoken_receiver,
-                base_asset,
-                quote_asset,
-                base_amount,
-                quote_amount,
-                pool.lp_token_id,
-                pool.base_amount,
-                pool.quote_amount,
-            ));
-
             Ok(().into())
         }
 
@@ -469,6 +637,167 @@ pub mod pallet {
             ));
             Ok(().into())
         }
+
+        /// Update the LP fee of a pool, governance only
+        ///
+        /// - `pair`: Currency pool whose fee is updated
+        /// - `lp_fee`: Fee taken out of each trade and left in the pool
+        #[pallet::call_index(5)]
+        #[pallet::weight(T::AMMWeightInfo::update_lp_fee())]
+        #[transactional]
+        pub fn update_lp_fee(
+            origin: OriginFor<T>,
+            pair: (AssetIdOf<T, I>, AssetIdOf<T, I>),
+            lp_fee: Ratio,
+        ) -> DispatchResultWithPostInfo {
+            T::CreatePoolOrigin::ensure_origin(origin)?;
+            ensure!(
+                lp_fee < Ratio::from_percent(100),
+                Error::<T, I>::InvalidLpFee
+            );
+
+            let (_, base_asset, quote_asset) = Self::sort_assets(pair)?;
+            Pools::<T, I>::try_mutate(base_asset, quote_asset, |pool| -> DispatchResult {
+                let pool = pool.as_mut().ok_or(Error::<T, I>::PoolDoesNotExist)?;
+                pool.lp_fee = lp_fee;
+                Ok(())
+            })?;
+
+            Self::deposit_event(Event::<T, I>::LpFeeUpdated(base_asset, quote_asset, lp_fee));
+            Ok(().into())
+        }
+
+        /// Create a new pool without governance by reserving a deposit in the
+        /// native currency, the liquidity tokens go to the creator. The lp token
+        /// is created with the next id of the `PermissionlessLpTokenIdStart` range.
+        ///
+        /// - `pair`: Currency pool, in which liquidity will be added
+        /// - `liquidity_amounts`: Liquidity amounts to be added in pool
+        /// - `lp_fee`: Fee taken out of each trade and left in the pool
+        #[pallet::call_index(6)]
+        #[pallet::weight(T::AMMWeightInfo::create_pool_permissionless())]
+        #[transactional]
+        pub fn create_pool_permissionless(
+            origin: OriginFor<T>,
+            pair: (AssetIdOf<T, I>, AssetIdOf<T, I>),
+            liquidity_amounts: (BalanceOf<T, I>, BalanceOf<T, I>),
+            lp_fee: Ratio,
+        ) -> DispatchResultWithPostInfo {
+            let who = T::PermissionlessCreatePoolOrigin::ensure_origin(origin)?;
+            ensure!(
+                lp_fee <= T::MaxPermissionlessLpFee::get(),
+                Error::<T, I>::LpFeeTooHigh
+            );
+
+            // the lp token is a new asset so that no existing asset can be minted
+            let lp_token_id =
+                Self::next_lp_token_id().unwrap_or_else(T::PermissionlessLpTokenIdStart::get);
+            NextLpTokenId::<T, I>::put(
+                lp_token_id
+                    .checked_add(One::one())
+                    .ok_or(ArithmeticError::Overflow)?,
+            );
+            T::LpTokenAssets::create(lp_token_id, Self::account_id(), true, One::one())?;
+
+            let (base_asset, quote_asset) =
+                Self::do_create_pool(&who, pair, liquidity_amounts, lp_token_id, lp_fee)?;
+            ensure!(
+                T::Assets::total_issuance(lp_token_id) >= T::MinimumPoolSeedLiquidity::get(),
+                Error::<T, I>::InsufficientSeedLiquidity
+            );
+
+            let deposit = T::PoolCreationDeposit::get();
+            T::Currency::reserve(&who, deposit)?;
+            PoolDeposits::<T, I>::insert(base_asset, quote_asset, (who.clone(), deposit));
+            Self::update_refund_threshold(base_asset, quote_asset, lp_token_id);
+
+            Self::deposit_event(Event::<T, I>::PoolDepositHeld(
+                who,
+                base_asset,
+                quote_asset,
+                deposit,
+            ));
+            Ok(().into())
+        }
+
+        /// Refund the creation deposit of a permissionless pool once its
+        /// liquidity stayed above `DepositRefundThreshold` for `DepositRefundDelay`
+        /// blocks, callable by anyone
+        ///
+        /// - `pair`: Currency pool whose deposit is refunded
+        #[pallet::call_index(7)]
+        #[pallet::weight(T::AMMWeightInfo::refund_pool_deposit())]
+        #[transactional]
+        pub fn refund_pool_deposit(
+            origin: OriginFor<T>,
+            pair: (AssetIdOf<T, I>, AssetIdOf<T, I>),
+        ) -> DispatchResultWithPostInfo {
+            ensure_signed(origin)?;
+
+            let (_, base_asset, quote_asset) = Self::sort_assets(pair)?;
+            ensure!(
+                Pools::<T, I>::contains_key(base_asset, quote_asset),
+                Error::<T, I>::PoolDoesNotExist
+            );
+            Self::ensure_listed(base_asset, quote_asset)?;
+
+            let (creator, deposit) = PoolDeposits::<T, I>::take(base_asset, quote_asset)
+                .ok_or(Error::<T, I>::PoolDepositNotFound)?;
+            // liquidity added right before the refund and removed right after
+            // doesn't count
+            let reached_at = RefundThresholdReachedAt::<T, I>::take(base_asset, quote_asset)
+                .ok_or(Error::<T, I>::RefundThresholdNotReached)?;
+            ensure!(
+                frame_system::Pallet::<T>::block_number()
+                    >= reached_at.saturating_add(T::DepositRefundDelay::get()),
+                Error::<T, I>::RefundThresholdNotReached
+            );
+            T::Currency::unreserve(&creator, deposit);
+
+            Self::deposit_event(Event::<T, I>::PoolDepositRefunded(
+                creator,
+                base_asset,
+                quote_asset,
+                deposit,
+            ));
+            Ok(().into())
+        }
+
+        /// Delist a pool, governance only. Trading and adding liquidity are
+        /// disabled, liquidity providers can still withdraw. The creation
+        /// deposit of a permissionless pool is slashed.
+        ///
+        /// - `pair`: Currency pool to delist
+        #[pallet::call_index(8)]
+        #[pallet::weight(T::AMMWeightInfo::delist_pool())]
+        #[transactional]
+        pub fn delist_pool(
+            origin: OriginFor<T>,
+            pair: (AssetIdOf<T, I>, AssetIdOf<T, I>),
+        ) -> DispatchResultWithPostInfo {
+            T::CreatePoolOrigin::ensure_origin(origin)?;
+
+            let (_, base_asset, quote_asset) = Self::sort_assets(pair)?;
+            ensure!(
+                Pools::<T, I>::contains_key(base_asset, quote_asset),
+                Error::<T, I>::PoolDoesNotExist
+            );
+            Self::ensure_listed(base_asset, quote_asset)?;
+            DelistedPools::<T, I>::insert(base_asset, quote_asset, ());
+
+            let burned = match PoolDeposits::<T, I>::take(base_asset, quote_asset) {
+                Some((creator, deposit)) => {
+                    let (_, remaining) = T::Currency::slash_reserved(&creator, deposit);
+                    deposit.saturating_sub(remaining)
+                }
+                None => Zero::zero(),
+            };
+            RefundThresholdReachedAt::<T, I>::remove(base_asset, quote_asset);
+            T::OnPoolDelisted::on_pool_delisted((base_asset, quote_asset));
+
+            Self::deposit_event(Event::<T, I>::PoolDelisted(base_asset, quote_asset, burned));
+            Ok(().into())
+        }
     }
 }
 
@@ -570,8 +899,8 @@ impl<T: Config<I>, I: 'static> Pallet<T, I> {
 
         amounts_out[0] = amount_in;
         for i in 0..(path.len() - 1) {
-            let (reserve_in, reserve_out) = Self::get_reserves(path[i], path[i + 1])?;
-            let amount_out = Self::get_amount_out(amounts_out[i], reserve_in, reserve_out)?;
+            let (reserve_in, reserve_out, lp_fee) = Self::get_reserves(path[i], path[i + 1])?;
+            let amount_out = Self::get_amount_out(amounts_out[i], reserve_in, reserve_out, lp_fee)?;
             amounts_out[i + 1] = amount_out;
         }
 
@@ -590,28 +919,29 @@ impl<T: Config<I>, I: 'static> Pallet<T, I> {
 
         amounts_in[amount_len - 1] = amount_out;
         for i in (1..(path.len())).rev() {
-            let (reserve_in, reserve_out) = Self::get_reserves(path[i - 1], path[i])?;
-            let amount_in = Self::get_amount_in(amounts_in[i], reserve_in, reserve_out)?;
+            let (reserve_in, reserve_out, lp_fee) = Self::get_reserves(path[i - 1], path[i])?;
+            let amount_in = Self::get_amount_in(amounts_in[i], reserve_in, reserve_out, lp_fee)?;
             amounts_in[i - 1] = amount_in;
         }
 
         Ok(amounts_in)
     }
 
-    // extract the reserves from a pool after sorting assets
+    // extract the reserves and the lp fee from a pool after sorting assets
     fn get_reserves(
         asset_in: AssetIdOf<T, I>,
         asset_out: AssetIdOf<T, I>,
-    ) -> Result<(BalanceOf<T, I>, BalanceOf<T, I>), DispatchError> {
+    ) -> Result<(BalanceOf<T, I>, BalanceOf<T, I>, Ratio), DispatchError> {
         let (is_inverted, base_asset, quote_asset) = Self::sort_assets((asset_in, asset_out))?;
 
         let pool = Pools::<T, I>::try_get(base_asset, quote_asset)
             .map_err(|_err| Error::<T, I>::PoolDoesNotExist)?;
+        Self::ensure_listed(base_asset, quote_asset)?;
 
         if is_inverted {
-            Ok((pool.quote_amount, pool.base_amount))
+            Ok((pool.quote_amount, pool.base_amount, pool.lp_fee))
         } else {
-            Ok((pool.base_amount, pool.quote_amount))
+            Ok((pool.base_amount, pool.quote_amount, pool.lp_fee))
         }
     }
 
@@ -627,8 +957,9 @@ impl<T: Config<I>, I: 'static> Pallet<T, I> {
         amount_in: BalanceOf<T, I>,
         reserve_in: BalanceOf<T, I>,
         reserve_out: BalanceOf<T, I>,
+        lp_fee: Ratio,
     ) -> Result<BalanceOf<T, I>, DispatchError> {
-        let fees = T::LpFee::get().mul_ceil(amount_in);
+        let fees = lp_fee.mul_ceil(amount_in);
 
         let amount_in = amount_in
             .checked_sub(fees)
@@ -682,6 +1013,7 @@ impl<T: Config<I>, I: 'static> Pallet<T, I> {
         amount_out: BalanceOf<T, I>,
         reserve_in: BalanceOf<T, I>,
         reserve_out: BalanceOf<T, I>,
+        lp_fee: Ratio,
     ) -> Result<BalanceOf<T, I>, DispatchError> {
         ensure!(
             amount_out < reserve_out,
@@ -709,7 +1041,7 @@ impl<T: Config<I>, I: 'static> Pallet<T, I> {
             .ok_or(ArithmeticError::Overflow)?;
 
         let fee_percent = Ratio::from_percent(100)
-            .checked_sub(&T::LpFee::get())
+            .checked_sub(&lp_fee)
             .ok_or(ArithmeticError::Underflow)?;
 
         log::trace!(
@@ -729,48 +1061,67 @@ impl<T: Config<I>, I: 'static> Pallet<T, I> {
             .ok_or(ArithmeticError::Overflow)?)
     }
 
+    // compute the cumulative prices of a pool at the given block, by multiplying
+    // the current prices with the number of blocks elapsed since the last update
+    fn cumulative_prices(
+        pool: &Pool<AssetIdOf<T, I>, BalanceOf<T, I>, T::BlockNumber>,
+        block_number: T::BlockNumber,
+    ) -> Result<(FixedU128, FixedU128), DispatchError> {
+        let price_0_cumulative = FixedU128::from_inner(pool.price_0_cumulative_last);
+        let price_1_cumulative = FixedU128::from_inner(pool.price_1_cumulative_last);
+
+        if block_number <= pool.block_timestamp_last {
+            return Ok((price_0_cumulative, price_1_cumulative));
+        }
+
+        let time_elapsed: BalanceOf<T, I> = block_number
+            .saturating_sub(pool.block_timestamp_last)
+            .saturated_into();
+
+        // compute by multiplying the numerator with the time elapsed
+        let price0_fraction = FixedU128::saturating_from_rational(
+            time_elapsed
+                .get_big_uint()
+                .checked_mul(&pool.quote_amount.get_big_uint())
+                .ok_or(Error::<T, I>::ConversionToU128Failed)?
+                .to_u128()
+                .ok_or(ArithmeticError::Overflow)?,
+            pool.base_amount,
+        );
+        let price1_fraction = FixedU128::saturating_from_rational(
+            time_elapsed
+                .get_big_uint()
+                .checked_mul(&pool.base_amount.get_big_uint())
+                .ok_or(Error::<T, I>::ConversionToU128Failed)?
+                .to_u128()
+                .ok_or(ArithmeticError::Overflow)?,
+            pool.quote_amount,
+        );
+
+        Ok((
+            price_0_cumulative
+                .checked_add(&price0_fraction)
+                .ok_or(ArithmeticError::Overflow)?,
+            price_1_cumulative
+                .checked_add(&price1_fraction)
+                .ok_or(ArithmeticError::Overflow)?,
+        ))
+    }
+
     // update internal twap price oracle by calculating the number of blocks elapsed
-    // and update the pools cumulative prices
+    // and update the pools cumulative prices, must be called before the reserves change
     fn do_update_oracle(
         pool: &mut Pool<AssetIdOf<T, I>, BalanceOf<T, I>, T::BlockNumber>,
     ) -> Result<(), DispatchError> {
         let block_timestamp = frame_system::Pallet::<T>::block_number();
 
         if pool.block_timestamp_last != block_timestamp {
-            let time_elapsed: BalanceOf<T, I> = block_timestamp
-                .saturating_sub(pool.block_timestamp_last)
-                .saturated_into();
+            let (price_0_cumulative, price_1_cumulative) =
+                Self::cumulative_prices(pool, block_timestamp)?;
 
-            // compute by multiplying the numerator with the time elapsed
-            let price0_fraction = FixedU128::saturating_from_rational(
-                time_elapsed
-                    .get_big_uint()
-                    .checked_mul(&pool.quote_amount.get_big_uint())
-                    .ok_or(Error::<T, I>::ConversionToU128Failed)?
-                    .to_u128()
-                    .ok_or(ArithmeticError::Overflow)?,
-                pool.base_amount,
-            );
-            let price1_fraction = FixedU128::saturating_from_rational(
-                time_elapsed
-                    .get_big_uint()
-                    .checked_mul(&pool.base_amount.get_big_uint())
-                    .ok_or(Error::<T, I>::ConversionToU128Failed)?
-                    .to_u128()
-                    .ok_or(ArithmeticError::Overflow)?,
-                pool.quote_amount,
-            );
-
-            // convert stored u128 into FixedU128 before add
-            pool.price_0_cumulative_last = FixedU128::from_inner(pool.price_0_cumulative_last)
-                .checked_add(&price0_fraction)
-                .ok_or(ArithmeticError::Overflow)?
-                .into_inner();
-
-            pool.price_1_cumulative_last = FixedU128::from_inner(pool.price_1_cumulative_last)
-                .checked_add(&price1_fraction)
-                .ok_or(ArithmeticError::Overflow)?
-                .into_inner();
+            // store FixedU128 as u128
+            pool.price_0_cumulative_last = price_0_cumulative.into_inner();
+            pool.price_1_cumulative_last = price_1_cumulative.into_inner();
 
             // updates timestamp last so `time_elapsed` is correctly calculated
             pool.block_timestamp_last = block_timestamp;
@@ -779,6 +1130,209 @@ impl<T: Config<I>, I: 'static> Pallet<T, I> {
         Ok(())
     }
 
+    // push a new observation of the pool's cumulative prices if the latest one
+    // is at least `ObservationPeriod` blocks old, dropping the oldest one when full
+    fn do_record_observation(
+        base_asset: AssetIdOf<T, I>,
+        quote_asset: AssetIdOf<T, I>,
+        pool: &Pool<AssetIdOf<T, I>, BalanceOf<T, I>, T::BlockNumber>,
+    ) -> Result<(), DispatchError> {
+        let block_number = frame_system::Pallet::<T>::block_number();
+
+        Observations::<T, I>::try_mutate(base_asset, quote_asset, |observations| {
+            if let Some(latest) = observations.last() {
+                if block_number
+                    < latest
+                        .block_number
+                        .saturating_add(T::ObservationPeriod::get())
+                {
+                    return Ok(());
+                }
+            }
+
+            let (price_0_cumulative, price_1_cumulative) =
+                Self::cumulative_prices(pool, block_number)?;
+            let observation = Observation {
+                block_number,
+                price_0_cumulative: price_0_cumulative.into_inner(),
+                price_1_cumulative: price_1_cumulative.into_inner(),
+            };
+
+            if observations.is_full() {
+                observations.remove(0);
+            }
+            // cannot fail as there is room for at least one observation
+            observations.try_push(observation).ok();
+
+            Ok(())
+        })
+    }
+
+    /// Returns the time weighted average price of `pair.0` in units of `pair.1`
+    /// over at least the last `window` blocks.
+    ///
+    /// The newest observation which is at least `window` blocks old is used as
+    /// the start of the period, so the actual period can be longer than `window`.
+    pub fn consult(
+        pair: (AssetIdOf<T, I>, AssetIdOf<T, I>),
+        window: T::BlockNumber,
+    ) -> Result<Rate, DispatchError> {
+        let (is_inverted, base_asset, quote_asset) = Self::sort_assets(pair)?;
+        let pool = Pools::<T, I>::try_get(base_asset, quote_asset)
+            .map_err(|_err| Error::<T, I>::PoolDoesNotExist)?;
+
+        let block_number = frame_system::Pallet::<T>::block_number();
+        let start = block_number.saturating_sub(window);
+        let observation = Observations::<T, I>::get(base_asset, quote_asset)
+            .into_iter()
+            .rev()
+            .find(|observation| {
+                observation.block_number <= start && observation.block_number < block_number
+            })
+            .ok_or(Error::<T, I>::InsufficientObservations)?;
+
+        let (price_0_cumulative, price_1_cumulative) =
+            Self::cumulative_prices(&pool, block_number)?;
+        let (price_cumulative, price_cumulative_start) = if is_inverted {
+            (price_1_cumulative, observation.price_1_cumulative)
+        } else {
+            (price_0_cumulative, observation.price_0_cumulative)
+        };
+        let time_elapsed: BalanceOf<T, I> = block_number
+            .saturating_sub(observation.block_number)
+            .saturated_into();
+
+        let price = price_cumulative
+            .into_inner()
+            .checked_sub(price_cumulative_start)
+            .ok_or(ArithmeticError::Underflow)?
+            .checked_div(time_elapsed)
+            .map(FixedU128::from_inner)
+            .ok_or(ArithmeticError::DivisionByZero)?;
+
+        log::trace!(
+            target: "amm::consult",
+            "pair: {:?}, window: {:?}, start: {:?}, price: {:?}",
+            &pair,
+            &window,
+            &observation.block_number,
+            &price
+        );
+
+        Ok(price)
+    }
+
+    // creates a pool seeded with liquidity taken from `lptoken_receiver`
+    #[require_transactional]
+    fn do_create_pool(
+        lptoken_receiver: &T::AccountId,
+        pair: (AssetIdOf<T, I>, AssetIdOf<T, I>),
+        liquidity_amounts: (BalanceOf<T, I>, BalanceOf<T, I>),
+        lp_token_id: AssetIdOf<T, I>,
+        lp_fee: Ratio,
+    ) -> Result<(AssetIdOf<T, I>, AssetIdOf<T, I>), DispatchError> {
+        ensure!(
+            lp_fee < Ratio::from_percent(100),
+            Error::<T, I>::InvalidLpFee
+        );
+
+        let (is_inverted, base_asset, quote_asset) = Self::sort_assets(pair)?;
+        ensure!(
+            !Pools::<T, I>::contains_key(base_asset, quote_asset),
+            Error::<T, I>::PoolAlreadyExists
+        );
+
+        let (base_amount, quote_amount) = if is_inverted {
+            (liquidity_amounts.1, liquidity_amounts.0)
+        } else {
+            (liquidity_amounts.0, liquidity_amounts.1)
+        };
+
+        // check that this is a new asset to avoid using an asset that
+        // already has tokens minted
+        ensure!(
+            T::Assets::total_issuance(lp_token_id).is_zero(),
+            Error::<T, I>::LpTokenAlreadyExists
+        );
+
+        let mut pool = Pool::new(lp_token_id, lp_fee);
+
+        Self::deposit_event(Event::<T, I>::PoolCreated(
+            lptoken_receiver.clone(),
+            base_asset,
+            quote_asset,
+            lp_token_id,
+        ));
+
+        Self::do_add_liquidity(
+            lptoken_receiver,
+            &mut pool,
+            (base_amount, quote_amount),
+            (base_asset, quote_asset),
+        )?;
+
+        Self::do_record_observation(base_asset, quote_asset, &pool)?;
+        Pools::<T, I>::insert(base_asset, quote_asset, &pool);
+        LpTokenPools::<T, I>::insert(lp_token_id, (base_asset, quote_asset));
+        T::OnPoolCreated::on_pool_created((base_asset, quote_asset));
+
+        log::trace!(
+            target: "amm::create_pool",
+            "lptoken_receiver: {:?}, base_asset: {:?}, quote_asset: {:?}, base_amount: {:?}, quote_amount: {:?},\
+             liquidity_amounts: {:?}",
+            lptoken_receiver,
+            &base_asset,
+            &quote_asset,
+            &base_amount,
+            &quote_amount,
+            &liquidity_amounts
+        );
+
+        Self::deposit_event(Event::<T, I>::LiquidityAdded(
+            lptoken_receiver.clone(),
+            base_asset,
+            quote_asset,
+            base_amount,
+            quote_amount,
+            pool.lp_token_id,
+            pool.base_amount,
+            pool.quote_amount,
+        ));
+
+        Ok((base_asset, quote_asset))
+    }
+
+    // keep track of since when the lp token supply of a pool holding a
+    // creation deposit is above `DepositRefundThreshold`
+    fn update_refund_threshold(
+        base_asset: AssetIdOf<T, I>,
+        quote_asset: AssetIdOf<T, I>,
+        lp_token_id: AssetIdOf<T, I>,
+    ) {
+        if !PoolDeposits::<T, I>::contains_key(base_asset, quote_asset) {
+            return;
+        }
+
+        if T::Assets::total_issuance(lp_token_id) < T::DepositRefundThreshold::get() {
+            RefundThresholdReachedAt::<T, I>::remove(base_asset, quote_asset);
+        } else if !RefundThresholdReachedAt::<T, I>::contains_key(base_asset, quote_asset) {
+            RefundThresholdReachedAt::<T, I>::insert(
+                base_asset,
+                quote_asset,
+                frame_system::Pallet::<T>::block_number(),
+            );
+        }
+    }
+
+    // delisted pools can only be withdrawn from
+    fn ensure_listed(base_asset: AssetIdOf<T, I>, quote_asset: AssetIdOf<T, I>) -> DispatchResult {
+        ensure!(
+            !DelistedPools::<T, I>::contains_key(base_asset, quote_asset),
+            Error::<T, I>::PoolDelisted
+        );
+        Ok(())
+    }
+
     #[require_transactional]
     fn do_add_liquidity(
         who: &T::AccountId,
@@ -1050,6 +1604,7 @@ impl<T: Config<I>, I: 'static> Pallet<T, I> {
         amount_in: BalanceOf<T, I>,
     ) -> Result<BalanceOf<T, I>, DispatchError> {
         let (is_inverted, base_asset, quote_asset) = Self::sort_assets((asset_in, asset_out))?;
+        Self::ensure_listed(base_asset, quote_asset)?;
 
         Pools::<T, I>::try_mutate(
             base_asset,
@@ -1064,12 +1619,17 @@ impl<T: Config<I>, I: 'static> Pallet<T, I> {
                 };
 
                 ensure!(
-                    amount_in >= T::LpFee::get().saturating_reciprocal_mul_ceil(One::one()),
+                    pool.lp_fee.is_zero()
+                        || amount_in >= pool.lp_fee.saturating_reciprocal_mul_ceil(One::one()),
                     Error::<T, I>::InsufficientAmountIn
                 );
                 ensure!(!supply_out.is_zero(), Error::<T, I>::InsufficientAmountOut);
 
-                let amount_out = Self::get_amount_out(amount_in, supply_in, supply_out)?;
+                let amount_out =
+                    Self::get_amount_out(amount_in, supply_in, supply_out, pool.lp_fee)?;
+
+                // accumulate the prices before the trade moves them
+                Self::do_update_oracle(pool)?;
 
                 let (new_supply_in, new_supply_out) = (
                     supply_in
@@ -1088,7 +1648,7 @@ impl<T: Config<I>, I: 'static> Pallet<T, I> {
                     pool.quote_amount = new_supply_out;
                 }
 
-                Self::do_update_oracle(pool)?;
+                Self::do_record_observation(base_asset, quote_asset, pool)?;
 
                 T::Assets::transfer(
                     asset_in,
@@ -1176,7 +1736,6 @@ impl<T: Config<I>, I: 'static>
         Ok(Pools::<T, I>::iter_keys().collect())
     }
 
-    //just iterate now and require improve later when Pools increased
     /// Returns pool by lp_asset
     fn get_pool_by_lp_asset(
         asset_id: AssetIdOf<T, I>,
@@ -1185,12 +1744,9 @@ impl<T: Config<I>, I: 'static>
         AssetIdOf<T, I>,
         Pool<AssetIdOf<T, I>, BalanceOf<T, I>, T::BlockNumber>,
     )> {
-        for (base_asset, quote_asset, pool) in Pools::<T, I>::iter() {
-            if pool.lp_token_id == asset_id {
-                return Some((base_asset, quote_asset, pool));
-            }
-        }
-        None
+        let (base_asset, quote_asset) = Self::lp_token_pools(asset_id)?;
+        let pool = Pools::<T, I>::get(base_asset, quote_asset)?;
+        Some((base_asset, quote_asset, pool))
     }
 
     /// Returns pool by asset pair
@@ -1202,4 +1758,12 @@ impl<T: Config<I>, I: 'static>
         }
         None
     }
+
+    /// Returns the time weighted average price of `pair.0` in units of `pair.1`
+    fn consult(
+        pair: (AssetIdOf<T, I>, AssetIdOf<T, I>),
+        window: T::BlockNumber,
+    ) -> Result<Rate, DispatchError> {
+        Self::consult(pair, window)
+    }
 }
@@ -0,0 +1,139 @@
+// Copyright 2021 Parallel Finance Developer.
+// This file is part of Parallel Finance.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+pub mod v1 {
+    use crate::{pallet::StorageVersion, BalanceOf, Config, Pools, Versions};
+    use codec::{Decode, Encode};
+    use frame_support::{log, traits::Get, weights::Weight};
+    use pallet_traits::Pool;
+    use scale_info::TypeInfo;
+    use sp_runtime::RuntimeDebug;
+
+    #[derive(Clone, PartialEq, Eq, Encode, Decode, RuntimeDebug, TypeInfo)]
+    pub struct V0Pool<CurrencyId, Balance, BlockNumber> {
+        pub base_amount: Balance,
+        pub quote_amount: Balance,
+        pub base_amount_last: Balance,
+        pub quote_amount_last: Balance,
+        pub lp_token_id: CurrencyId,
+        pub block_timestamp_last: BlockNumber,
+        pub price_0_cumulative_last: Balance,
+        pub price_1_cumulative_last: Balance,
+    }
+
+    #[cfg(feature = "try-runtime")]
+    pub fn pre_migrate<T: Config<I>, I: 'static>() -> Result<(), &'static str> {
+        frame_support::ensure!(StorageVersion::<T, I>::get() == Versions::V0, "must be V0");
+        log::info!("pools count: {:?}", Pools::<T, I>::iter_keys().count());
+        Ok(())
+    }
+
+    /// Store the runtime wide `LpFee` in every existing pool
+    pub fn migrate<T: Config<I>, I: 'static>() -> Weight {
+        if StorageVersion::<T, I>::get() == Versions::V0 {
+            log::info!(
+                target: "amm::migrate",
+                "migrating amm to Versions::V1"
+            );
+
+            let lp_fee = T::LpFee::get();
+            let mut count = 0u64;
+            Pools::<T, I>::translate::<V0Pool<_, BalanceOf<T, I>, T::BlockNumber>, _>(
+                |_base_asset, _quote_asset, pool| {
+                    count += 1;
+                    Some(Pool {
+                        base_amount: pool.base_amount,
+                        quote_amount: pool.quote_amount,
+                        base_amount_last: pool.base_amount_last,
+                        quote_amount_last: pool.quote_amount_last,
+                        lp_token_id: pool.lp_token_id,
+                        block_timestamp_last: pool.block_timestamp_last,
+                        price_0_cumulative_last: pool.price_0_cumulative_last,
+                        price_1_cumulative_last: pool.price_1_cumulative_last,
+                        lp_fee,
+                    })
+                },
+            );
+
+            StorageVersion::<T, I>::put(Versions::V1);
+            log::info!(
+                target: "amm::migrate",
+                "completed amm migration to Versions::V1"
+            );
+
+            T::DbWeight::get().reads_writes(count + 1, count + 1)
+        } else {
+            T::DbWeight::get().reads(1)
+        }
+    }
+
+    #[cfg(feature = "try-runtime")]
+    pub fn post_migrate<T: Config<I>, I: 'static>() -> Result<(), &'static str> {
+        frame_support::ensure!(StorageVersion::<T, I>::get() == Versions::V1, "must be V1");
+        for (_, _, pool) in Pools::<T, I>::iter() {
+            frame_support::ensure!(pool.lp_fee == T::LpFee::get(), "lp fee must be set");
+        }
+        Ok(())
+    }
+}
+
+pub mod v2 {
+    use crate::{pallet::StorageVersion, Config, LpTokenPools, Pools, Versions};
+    use frame_support::{log, traits::Get, weights::Weight};
+
+    #[cfg(feature = "try-runtime")]
+    pub fn pre_migrate<T: Config<I>, I: 'static>() -> Result<(), &'static str> {
+        frame_support::ensure!(StorageVersion::<T, I>::get() == Versions::V1, "must be V1");
+        log::info!("pools count: {:?}", Pools::<T, I>::iter_keys().count());
+        Ok(())
+    }
+
+    /// Index the pools by their liquidity token in `LpTokenPools`
+    pub fn migrate<T: Config<I>, I: 'static>() -> Weight {
+        if StorageVersion::<T, I>::get() == Versions::V1 {
+            log::info!(
+                target: "amm::migrate",
+                "migrating amm to Versions::V2"
+            );
+
+            let mut count = 0u64;
+            for (base_asset, quote_asset, pool) in Pools::<T, I>::iter() {
+                count += 1;
+                LpTokenPools::<T, I>::insert(pool.lp_token_id, (base_asset, quote_asset));
+            }
+
+            StorageVersion::<T, I>::put(Versions::V2);
+            log::info!(
+                target: "amm::migrate",
+                "completed amm migration to Versions::V2"
+            );
+
+            T::DbWeight::get().reads_writes(count + 1, count + 1)
+        } else {
+            T::DbWeight::get().reads(1)
+        }
+    }
+
+    #[cfg(feature = "try-runtime")]
+    pub fn post_migrate<T: Config<I>, I: 'static>() -> Result<(), &'static str> {
+        frame_support::ensure!(StorageVersion::<T, I>::get() == Versions::V2, "must be V2");
+        for (base_asset, quote_asset, pool) in Pools::<T, I>::iter() {
+            frame_support::ensure!(
+                LpTokenPools::<T, I>::get(pool.lp_token_id) == Some((base_asset, quote_asset)),
+                "lp token must be indexed"
+            );
+        }
+        Ok(())
+    }
+}
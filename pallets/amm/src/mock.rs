@@ -164,6 +164,14 @@ parameter_types! {
     pub const AMMPalletId: PalletId = PalletId(*b"par/ammp");
     pub DefaultLpFee: Ratio = Ratio::from_rational(25u32, 10000u32);        // 0.25%
     pub const MinimumLiquidity: u128 = 1_000u128;
+    pub const PoolCreationDeposit: Balance = 1_000_000;
+    pub MaxPermissionlessLpFee: Ratio = Ratio::from_percent(1);
+    pub const MinimumPoolSeedLiquidity: u128 = 10_000u128;
+    pub const DepositRefundThreshold: u128 = 1_000_000u128;
+    pub const DepositRefundDelay: BlockNumber = 10;
+    pub const PermissionlessLpTokenIdStart: CurrencyId = 500_000_000;
+    pub const MaxObservations: u32 = 4;
+    pub const ObservationPeriod: BlockNumber = 1;
     pub const LockAccountId: AccountId = AccountId(1_u64);
     pub const MaxLengthRoute: u8 = 10;
 }
@@ -183,10 +191,23 @@ impl pallet_amm::Config for Test {
     type AMMWeightInfo = ();
     type CreatePoolOrigin = EnsureSignedBy<AliceCreatePoolOrigin, AccountId>;
     type ProtocolFeeUpdateOrigin = EnsureSignedBy<AliceCreatePoolOrigin, AccountId>;
+    type PermissionlessCreatePoolOrigin = EnsureSigned<AccountId>;
+    type Currency = Balances;
+    type LpTokenAssets = Assets;
+    type PermissionlessLpTokenIdStart = PermissionlessLpTokenIdStart;
+    type PoolCreationDeposit = PoolCreationDeposit;
+    type MinimumPoolSeedLiquidity = MinimumPoolSeedLiquidity;
+    type DepositRefundThreshold = DepositRefundThreshold;
+    type DepositRefundDelay = DepositRefundDelay;
     type LpFee = DefaultLpFee;
+    type MaxPermissionlessLpFee = MaxPermissionlessLpFee;
     type MinimumLiquidity = MinimumLiquidity;
     type MaxLengthRoute = MaxLengthRoute;
     type GetNativeCurrencyId = NativeCurrencyId;
+    type MaxObservations = MaxObservations;
+    type ObservationPeriod = ObservationPeriod;
+    type OnPoolCreated = ();
+    type OnPoolDelisted = ();
 }
 
 parameter_types! {
@@ -16,6 +16,7 @@ fn create_pool_should_work() {
             (1_000, 2_000),                  // Liquidity amounts to be added in pool
             BOB,                             // LPToken receiver
             SAMPLE_LP_TOKEN,                 // Liquidity pool share representative token
+            DefaultLpFee::get(),             // Fee taken by liquidity providers on each trade
         ));
 
         assert_eq!(AMM::pools(SDOT, DOT).unwrap().base_amount, 2_000);
@@ -23,6 +24,8 @@ fn create_pool_should_work() {
         // should be issuance minus the min liq locked
         assert_eq!(Assets::balance(SAMPLE_LP_TOKEN, BOB), 414);
         assert_eq!(AMM::get_pool_by_lp_asset(SAMPLE_LP_TOKEN).is_some(), true);
+        assert_eq!(AMM::lp_token_pools(SAMPLE_LP_TOKEN).is_some(), true);
+        assert_eq!(AMM::get_pool_by_lp_asset(DOT).is_none(), true);
         assert_eq!(AMM::get_pool_by_asset_pair((DOT, SDOT)).is_some(), true);
         assert_eq!(AMM::get_pool_by_asset_pair((SDOT, DOT)).is_some(), true);
     })
@@ -37,6 +40,7 @@ fn double_liquidity_correct_liq_ratio_should_work() {
             (15_000_000_000_000, 50_000_000_000_000_000), // Liquidity amounts to be added in pool
             FRANK,      // LPToken receiver
             SAMPLE_LP_TOKEN, // Liquidity pool share representative token
+            DefaultLpFee::get(), // Fee taken by liquidity providers on each trade
         ));
 
         // total liquidity after pool created
@@ -69,6 +73,7 @@ fn add_liquidity_should_work() {
             (1_000, 2_000),                  // Liquidity amounts to be added in pool
             ALICE,                           // LPToken receiver
             SAMPLE_LP_TOKEN,                 // Liquidity pool share representative token
+            DefaultLpFee::get(),             // Fee taken by liquidity providers on each trade
         ));
         assert_ok!(AMM::add_liquidity(
             RawOrigin::Signed(ALICE).into(), // Origin
@@ -89,7 +94,8 @@ fn add_more_liquidity_should_work() {
             (DOT, SDOT),                     // Currency pool, in which liquidity will be added
             (1_000, 2_000),                  // Liquidity amounts to be added in pool
             ALICE,                           // LPToken receiver
-            SAMPLE_LP_TOKEN                  // Liquidity pool share representative token
+            SAMPLE_LP_TOKEN,                 // Liquidity pool share representative token
+            DefaultLpFee::get(),             // Fee taken by liquidity providers on each trade
         ));
 
         assert_ok!(AMM::add_liquidity(
@@ -112,7 +118,8 @@ fn add_more_liquidity_should_not_work_if_minimum_base_amount_is_higher() {
             (DOT, SDOT),                     // Currency pool, in which liquidity will be added
             (1_000, 2_000),                  // Liquidity amounts to be added in pool
             ALICE,                           // LPToken receiver
-            SAMPLE_LP_TOKEN                  // Liquidity pool share representative token
+            SAMPLE_LP_TOKEN,                 // Liquidity pool share representative token
+            DefaultLpFee::get(),             // Fee taken by liquidity providers on each trade
         ));
 
         assert_noop!(
@@ -135,7 +142,8 @@ fn add_more_liquidity_with_low_balance_should_not_work() {
             (DOT, SDOT),                     // Currency pool, in which liquidity will be added
             (1_000, 2_000),                  // Liquidity amounts to be added in pool
             ALICE,                           // LPToken receiver
-            SAMPLE_LP_TOKEN                  // Liquidity pool share representative token
+            SAMPLE_LP_TOKEN,                 // Liquidity pool share representative token
+            DefaultLpFee::get(),             // Fee taken by liquidity providers on each trade
         ));
 
         assert_ok!(AMM::add_liquidity(
@@ -165,7 +173,8 @@ fn add_liquidity_by_another_user_should_work() {
             (DOT, SDOT),                     // Currency pool, in which liquidity will be added
             (1_000, 2_000),                  // Liquidity amounts to be added in pool
             ALICE,                           // LPToken receiver
-            SAMPLE_LP_TOKEN                  // Liquidity pool share representative token
+            SAMPLE_LP_TOKEN,                 // Liquidity pool share representative token
+            DefaultLpFee::get(),             // Fee taken by liquidity providers on each trade
         ));
 
         assert_ok!(AMM::add_liquidity(
@@ -194,7 +203,8 @@ fn cannot_create_pool_twice() {
             (DOT, SDOT),                     // Currency pool, in which liquidity will be added
             (1_000, 2_000),                  // Liquidity amounts to be added in pool
             ALICE,                           // LPToken receiver
-            SAMPLE_LP_TOKEN                  // Liquidity pool share representative token
+            SAMPLE_LP_TOKEN,                 // Liquidity pool share representative token
+            DefaultLpFee::get(),             // Fee taken by liquidity providers on each trade
         ));
 
         assert_noop!(
@@ -203,7 +213,8 @@ fn cannot_create_pool_twice() {
                 (DOT, SDOT),                     // Currency pool, in which liquidity will be added
                 (1_000, 2_000),                  // Liquidity amounts to be added in pool
                 ALICE,                           // LPToken receiver
-                SAMPLE_LP_TOKEN                  // Liquidity pool share representative token
+                SAMPLE_LP_TOKEN,                 // Liquidity pool share representative token
+                DefaultLpFee::get(),             // Fee taken by liquidity providers on each trade
             ),
             Error::<Test>::PoolAlreadyExists, // Pool already not exist
         );
@@ -223,6 +234,7 @@ fn remove_liquidity_whole_share_should_work() {
             (1_000, 9_000),                  // Liquidity amounts to be added in pool
             ALICE,                           // LPToken receiver
             SAMPLE_LP_TOKEN,                 // Liquidity pool share representative token
+            DefaultLpFee::get(),             // Fee taken by liquidity providers on each trade
         );
 
         assert_ok!(AMM::remove_liquidity(
@@ -246,6 +258,7 @@ fn remove_liquidity_only_portion_should_work() {
             (1_000, 9_000),                  // Liquidity amounts to be added in pool
             ALICE,                           // LPToken receiver
             SAMPLE_LP_TOKEN,                 // Liquidity pool share representative token
+            DefaultLpFee::get(),             // Fee taken by liquidity providers on each trade
         );
 
         assert_eq!(AMM::pools(SDOT, DOT).unwrap().base_amount, 9_000);
@@ -270,7 +283,8 @@ fn remove_liquidity_user_more_liquidity_should_work() {
             (DOT, SDOT),                     // Currency pool, in which liquidity will be added
             (1_000, 2_500),                  // Liquidity amounts to be added in pool
             ALICE,                           // LPToken receiver
-            SAMPLE_LP_TOKEN                  // Liquidity pool share representative token
+            SAMPLE_LP_TOKEN,                 // Liquidity pool share representative token
+            DefaultLpFee::get(),             // Fee taken by liquidity providers on each trade
         ));
         assert_ok!(AMM::add_liquidity(
             RawOrigin::Signed(ALICE).into(), // Origin
@@ -310,6 +324,7 @@ fn remove_liquidity_with_more_liquidity_should_not_work() {
             (1_000, 9_000),                  // Liquidity amounts to be added in pool
             ALICE,                           // LPToken receiver
             SAMPLE_LP_TOKEN,                 // Liquidity pool share representative token
+            DefaultLpFee::get(),             // Fee taken by liquidity providers on each trade
         );
 
         assert_noop!(
@@ -331,6 +346,7 @@ fn swap_should_work_base_to_quote() {
             (100_000_000, 100_000_000),      // Liquidity amounts to be added in pool
             CHARLIE,                         // LPToken receiver
             SAMPLE_LP_TOKEN,                 // Liquidity pool share representative token
+            DefaultLpFee::get(),             // Fee taken by liquidity providers on each trade
         ));
 
         // SDOT is base_asset 1001
@@ -376,6 +392,7 @@ fn swap_should_work_different_ratio_base_to_quote() {
             (100_000_000, 50_000_000),       // Liquidity amounts to be added in pool
             CHARLIE,                         // LPToken receiver
             SAMPLE_LP_TOKEN,                 // Liquidity pool share representative token
+            DefaultLpFee::get(),             // Fee taken by liquidity providers on each trade
         ));
 
         // SDOT is base_asset 1001
@@ -421,6 +438,7 @@ fn swap_should_work_quote_to_base() {
             (50_000_000, 100_000_000),       // Liquidity amounts to be added in pool
             CHARLIE,                         // LPToken receiver
             SAMPLE_LP_TOKEN,                 // Liquidity pool share representative token
+            DefaultLpFee::get(),             // Fee taken by liquidity providers on each trade
         ));
 
         // SDOT is base_asset 1001
@@ -466,6 +484,7 @@ fn trade_should_work_base_to_quote_flipped_currencies_on_pool_creation() {
             (100_000_000, 100_000_000),      // Liquidity amounts to be added in pool
             CHARLIE,                         // LPToken receiver
             SAMPLE_LP_TOKEN,                 // Liquidity pool share representative token
+            DefaultLpFee::get(),             // Fee taken by liquidity providers on each trade
         ));
 
         // SDOT is base_asset 1001
@@ -503,6 +522,7 @@ fn trade_should_work_quote_to_base() {
             (100_000_000, 100_000_000),      // Liquidity amounts to be added in pool
             CHARLIE,                         // LPToken receiver
             SAMPLE_LP_TOKEN,                 // Liquidity pool share representative token
+            DefaultLpFee::get(),             // Fee taken by liquidity providers on each trade
         ));
 
         // SDOT is base_asset 1001
@@ -540,6 +560,7 @@ fn trade_should_not_work_if_insufficient_amount_in() {
             (100_000, 100_000),              // Liquidity amounts to be added in pool
             CHARLIE,                         // LPToken receiver
             SAMPLE_LP_TOKEN,                 // Liquidity pool share representative token
+            DefaultLpFee::get(),             // Fee taken by liquidity providers on each trade
         ));
 
         // create pool and add liquidity
@@ -573,7 +594,8 @@ fn trade_should_work_flipped_currencies() {
             (DOT, SDOT),                     // Currency pool, in which liquidity will be added
             (100_000, 50_000),               // Liquidity amounts to be added in pool
             CHARLIE,                         // LPToken receiver
-            SAMPLE_LP_TOKEN                  // Liquidity pool share representative token
+            SAMPLE_LP_TOKEN,                 // Liquidity pool share representative token
+            DefaultLpFee::get(),             // Fee taken by liquidity providers on each trade
         ));
 
         // check that pool was funded correctly
@@ -607,7 +629,8 @@ fn trade_should_not_work_if_amount_in_is_zero() {
             (DOT, SDOT),                     // Currency pool, in which liquidity will be added
             (1_000, 1_000),                  // Liquidity amounts to be added in pool
             ALICE,                           // LPToken receiver
-            SAMPLE_LP_TOKEN                  // Liquidity pool share representative token
+            SAMPLE_LP_TOKEN,                 // Liquidity pool share representative token
+            DefaultLpFee::get(),             // Fee taken by liquidity providers on each trade
         ));
 
         // fail if amount_in is zero
@@ -638,7 +661,8 @@ fn amount_out_should_work() {
         let supply_in = 100_000_000;
         let supply_out = 100_000_000;
 
-        let amount_out = AMM::get_amount_out(amount_in, supply_in, supply_out).unwrap();
+        let amount_out =
+            AMM::get_amount_out(amount_in, supply_in, supply_out, DefaultLpFee::get()).unwrap();
 
         // actual value == 996.9900600091017
         // TODO: assumes we round down to int
@@ -655,6 +679,7 @@ fn amounts_out_should_work() {
             (1_000, 2_000),                  // Liquidity amounts to be added in pool
             BOB,                             // LPToken receiver
             SAMPLE_LP_TOKEN,                 // Liquidity pool share representative token
+            DefaultLpFee::get(),             // Fee taken by liquidity providers on each trade
         ));
 
         assert_ok!(AMM::create_pool(
@@ -663,6 +688,7 @@ fn amounts_out_should_work() {
             (1_000, 1_000),                  // Liquidity amounts to be added in pool
             BOB,                             // LPToken receiver
             SAMPLE_LP_TOKEN_2,               // Liquidity pool share representative token
+            DefaultLpFee::get(),             // Fee taken by liquidity providers on each trade
         ));
 
         let path = vec![SDOT, DOT, KSM];
@@ -684,6 +710,7 @@ fn long_route_amounts_in_should_work() {
             (10_000, 20_000),                // Liquidity amounts to be added in pool
             BOB,                             // LPToken receiver
             SAMPLE_LP_TOKEN,                 // Liquidity pool share representative token
+            DefaultLpFee::get(),             // Fee taken by liquidity providers on each trade
         ));
 
         assert_ok!(AMM::create_pool(
@@ -692,6 +719,7 @@ fn long_route_amounts_in_should_work() {
             (10_000, 10_000),                // Liquidity amounts to be added in pool
             BOB,                             // LPToken receiver
             SAMPLE_LP_TOKEN_2,               // Liquidity pool share representative token
+            DefaultLpFee::get(),             // Fee taken by liquidity providers on each trade
         ));
 
         let path = vec![SDOT, DOT, KSM];
@@ -713,6 +741,7 @@ fn short_route_amounts_in_should_work() {
             (10_000_000, 10_000_000),        // Liquidity amounts to be added in pool
             BOB,                             // LPToken receiver
             SAMPLE_LP_TOKEN,                 // Liquidity pool share representative token
+            DefaultLpFee::get(),             // Fee taken by liquidity providers on each trade
         ));
 
         let path = vec![DOT, SDOT];
@@ -732,7 +761,8 @@ fn amount_in_should_work() {
         let supply_in = 100_000_000;
         let supply_out = 100_000_000;
 
-        let amount_in = AMM::get_amount_in(amount_out, supply_in, supply_out).unwrap();
+        let amount_in =
+            AMM::get_amount_in(amount_out, supply_in, supply_out, DefaultLpFee::get()).unwrap();
         // p = 1 - fee_percent
         // x * y = ( x + p * dx) ( y - dy)
         //
@@ -748,7 +778,8 @@ fn amount_in_uneven_should_work() {
         let supply_in = 100_000_000;
         let supply_out = 1_344_312_043;
 
-        let amount_in = AMM::get_amount_in(amount_out, supply_in, supply_out).unwrap();
+        let amount_in =
+            AMM::get_amount_in(amount_out, supply_in, supply_out, DefaultLpFee::get()).unwrap();
 
         assert_eq!(amount_in, 76);
     })
@@ -763,7 +794,7 @@ fn supply_out_should_larger_than_amount_out() {
         let supply_out = 100_00;
 
         assert_noop!(
-            AMM::get_amount_in(amount_out, supply_in, supply_out),
+            AMM::get_amount_in(amount_out, supply_in, supply_out, DefaultLpFee::get()),
             Error::<Test>::InsufficientSupplyOut
         );
     })
@@ -776,12 +807,14 @@ fn amount_out_and_in_should_work() {
         let supply_in = 100_000_000;
         let supply_out = 100_000_000;
 
-        let amount_in = AMM::get_amount_in(amount_out, supply_in, supply_out).unwrap();
+        let amount_in =
+            AMM::get_amount_in(amount_out, supply_in, supply_out, DefaultLpFee::get()).unwrap();
 
         // actual: 1002.5162908248136
         assert_eq!(amount_in, 1004);
 
-        let amount_out = AMM::get_amount_out(amount_in, supply_in, supply_out).unwrap();
+        let amount_out =
+            AMM::get_amount_out(amount_in, supply_in, supply_out, DefaultLpFee::get()).unwrap();
 
         // actual: 1000.0834982275963
         assert_eq!(amount_out, 1000);
@@ -799,6 +832,7 @@ fn update_oracle_should_work() {
             (100_000, 100_000),              // Liquidity amounts to be added in pool
             BOB,                             // LPToken receiver
             SAMPLE_LP_TOKEN,                 // Liquidity pool share representative token
+            DefaultLpFee::get(),             // Fee taken by liquidity providers on each trade
         ));
 
         assert_eq!(AMM::pools(SDOT, DOT).unwrap().block_timestamp_last, 0);
@@ -812,11 +846,11 @@ fn update_oracle_should_work() {
         assert_eq!(AMM::pools(SDOT, DOT).unwrap().block_timestamp_last, 2);
         assert_eq!(
             AMM::pools(SDOT, DOT).unwrap().price_0_cumulative_last,
-            2_040136143738700978
+            2_000000000000000000
         );
         assert_eq!(
             AMM::pools(SDOT, DOT).unwrap().price_1_cumulative_last,
-            1_960653465346534653
+            2_000000000000000000
         );
 
         run_to_block(4);
@@ -826,11 +860,11 @@ fn update_oracle_should_work() {
         assert_eq!(AMM::pools(SDOT, DOT).unwrap().block_timestamp_last, 4);
         assert_eq!(
             AMM::pools(SDOT, DOT).unwrap().price_0_cumulative_last,
-            4_120792162342213614
+            4_040136143738700978
         );
         assert_eq!(
             AMM::pools(SDOT, DOT).unwrap().price_1_cumulative_last,
-            3_883124053581828770
+            3_960653465346534653
         );
     })
 }
@@ -845,7 +879,8 @@ fn oracle_big_block_no_overflow() {
             (DOT, KSM), // Currency pool, in which liquidity will be added
             (9_999_650_729_873_433, 30_001_051_000_000_000_000), // Liquidity amounts to be added in pool
             FRANK,                                               // LPToken receiver
-            SAMPLE_LP_TOKEN, // Liquidity pool share representative token
+            SAMPLE_LP_TOKEN,     // Liquidity pool share representative token
+            DefaultLpFee::get(), // Fee taken by liquidity providers on each trade
         ));
 
         assert_eq!(AMM::pools(DOT, KSM).unwrap().block_timestamp_last, 0);
@@ -868,11 +903,11 @@ fn oracle_big_block_no_overflow() {
 
         assert_eq!(
             AMM::pools(DOT, KSM).unwrap().price_0_cumulative_last,
-            105007346092879071079611683
+            105007346092900041778164824
         );
         assert_eq!(
             AMM::pools(DOT, KSM).unwrap().price_1_cumulative_last,
-            11_665850491226458031
+            11_665850491224128279
         );
 
         // increment a block
@@ -884,6 +919,123 @@ fn oracle_big_block_no_overflow() {
     })
 }
 
+#[test]
+fn consult_should_return_time_weighted_price() {
+    new_test_ext().execute_with(|| {
+        let trader = EVE;
+        run_to_block(1);
+
+        assert_ok!(AMM::create_pool(
+            RawOrigin::Signed(ALICE).into(), // Origin
+            (SDOT, DOT),                     // Currency pool, in which liquidity will be added
+            (100_000, 200_000),              // Liquidity amounts to be added in pool
+            BOB,                             // LPToken receiver
+            SAMPLE_LP_TOKEN,                 // Liquidity pool share representative token
+            DefaultLpFee::get(),             // Fee taken by liquidity providers on each trade
+        ));
+
+        // no observation is old enough yet
+        assert_noop!(
+            AMM::consult((SDOT, DOT), 1),
+            Error::<Test>::InsufficientObservations
+        );
+
+        run_to_block(11);
+
+        assert_eq!(
+            AMM::consult((SDOT, DOT), 10),
+            Ok(Rate::saturating_from_integer(2))
+        );
+        assert_eq!(
+            AMM::consult((DOT, SDOT), 10),
+            Ok(Rate::saturating_from_rational(1, 2))
+        );
+
+        // pushes the price of SDOT up
+        assert_ok!(AMM::swap(&trader, (DOT, SDOT), 10_000));
+        let pool = AMM::pools(SDOT, DOT).unwrap();
+        let spot_price = Rate::saturating_from_rational(pool.quote_amount, pool.base_amount);
+
+        run_to_block(21);
+
+        assert_eq!(AMM::consult((SDOT, DOT), 10), Ok(spot_price));
+        let twap = AMM::consult((SDOT, DOT), 20).unwrap();
+        assert!(twap > Rate::saturating_from_integer(2) && twap < spot_price);
+
+        // the oldest observation is dropped once the buffer is full
+        for block in 22..=24 {
+            run_to_block(block);
+            assert_ok!(AMM::swap(&trader, (DOT, SDOT), 1_000));
+        }
+        assert_eq!(
+            AMM::observations(SDOT, DOT)
+                .iter()
+                .map(|observation| observation.block_number)
+                .collect::<Vec<_>>(),
+            vec![11, 22, 23, 24]
+        );
+        assert_noop!(
+            AMM::consult((SDOT, DOT), 20),
+            Error::<Test>::InsufficientObservations
+        );
+    })
+}
+
+#[test]
+fn liquidity_changes_should_record_observations() {
+    new_test_ext().execute_with(|| {
+        run_to_block(1);
+
+        assert_ok!(AMM::create_pool(
+            RawOrigin::Signed(ALICE).into(), // Origin
+            (SDOT, DOT),                     // Currency pool, in which liquidity will be added
+            (100_000, 200_000),              // Liquidity amounts to be added in pool
+            BOB,                             // LPToken receiver
+            SAMPLE_LP_TOKEN,                 // Liquidity pool share representative token
+            DefaultLpFee::get(),             // Fee taken by liquidity providers on each trade
+        ));
+
+        run_to_block(11);
+
+        assert_ok!(AMM::add_liquidity(
+            RawOrigin::Signed(ALICE).into(), // Origin
+            (SDOT, DOT),                     // Currency pool, in which liquidity will be added
+            (100_000, 200_000),              // Liquidity amounts to be added in pool
+            (100_000, 200_000),              // specifying its worst case ratio when pool already
+        ));
+
+        // the prices are accumulated over the reserves before the liquidity was added
+        let pool = AMM::pools(SDOT, DOT).unwrap();
+        assert_eq!(pool.block_timestamp_last, 11);
+        assert_eq!(pool.price_0_cumulative_last, 22_000000000000000000);
+        assert_eq!(pool.price_1_cumulative_last, 5_500000000000000000);
+
+        run_to_block(21);
+
+        assert_ok!(AMM::remove_liquidity(
+            RawOrigin::Signed(BOB).into(), // Origin
+            (SDOT, DOT),                   // Currency pool, in which liquidity will be removed
+            1_000                          // Liquidity to be removed from user's liquidity
+        ));
+
+        let pool = AMM::pools(SDOT, DOT).unwrap();
+        assert_eq!(pool.block_timestamp_last, 21);
+        assert_eq!(pool.price_0_cumulative_last, 42_000000000000000000);
+        assert_eq!(pool.price_1_cumulative_last, 10_500000000000000000);
+        assert_eq!(
+            AMM::observations(SDOT, DOT)
+                .iter()
+                .map(|observation| observation.block_number)
+                .collect::<Vec<_>>(),
+            vec![1, 11, 21]
+        );
+        assert_eq!(
+            AMM::consult((SDOT, DOT), 10),
+            Ok(Rate::saturating_from_integer(2))
+        );
+    })
+}
+
 // #[test]
 // fn oracle_huge_block_should_work() {
 //     // we may want to omit this test because it take >5 minutes to run
@@ -965,7 +1117,8 @@ fn create_pool_large_amount_should_work() {
             (DOT, SDOT), // Currency pool, in which liquidity will be added
             (1_000_000_000_000_000_000, 2_000_000_000_000_000_000_000), // Liquidity amounts to be added in pool
             ALICE,                                                      // LPToken receiver
-            SAMPLE_LP_TOKEN, // Liquidity pool share representative token
+            SAMPLE_LP_TOKEN,     // Liquidity pool share representative token
+            DefaultLpFee::get(), // Fee taken by liquidity providers on each trade
         ));
 
         assert_eq!(
@@ -1015,7 +1168,8 @@ fn create_pool_large_amount_from_an_account_without_sufficient_amount_of_tokens_
                 (DOT, SDOT), // Currency pool, in which liquidity will be added
                 (1_000_000_000_000_000_000, 2_000_000_000_000_000_000_000), // Liquidity amounts to be added in pool
                 BOB,                                                        // LPToken receiver
-                SAMPLE_LP_TOKEN, // Liquidity pool share representative token
+                SAMPLE_LP_TOKEN,     // Liquidity pool share representative token
+                DefaultLpFee::get(), // Fee taken by liquidity providers on each trade
             ),
             pallet_assets::Error::<Test>::BalanceLow
         );
@@ -1055,7 +1209,8 @@ fn do_add_liquidity_exact_amounts_should_work() {
             (DOT, SDOT), // Currency pool, in which liquidity will be added
             (1_000_000_000_000_000_000, 2_000_000_000_000_000_000_000), // Liquidity amounts to be added in pool
             ALICE,                                                      // LPToken receiver
-            SAMPLE_LP_TOKEN, // Liquidity pool share representative token
+            SAMPLE_LP_TOKEN,     // Liquidity pool share representative token
+            DefaultLpFee::get(), // Fee taken by liquidity providers on each trade
         ));
         assert_ok!(AMM::add_liquidity(
             RawOrigin::Signed(ALICE).into(),                            // Origin
@@ -1099,6 +1254,7 @@ fn do_add_liquidity_large_amounts_should_work() {
             ), // Liquidity amounts to be added in pool
             ALICE,                           // LPToken receiver
             SAMPLE_LP_TOKEN,                 // Liquidity pool share representative token
+            DefaultLpFee::get(),             // Fee taken by liquidity providers on each trade
         ));
     })
 }
@@ -1134,6 +1290,344 @@ fn update_protocol_fee_receiver_should_work() {
     })
 }
 
+#[test]
+fn create_pool_with_custom_lp_fee_should_work() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(AMM::create_pool(
+            RawOrigin::Signed(ALICE).into(),
+            (DOT, SDOT),
+            (100_000_000, 100_000_000),
+            CHARLIE,
+            SAMPLE_LP_TOKEN,
+            Ratio::from_percent(1),
+        ));
+        assert_eq!(
+            AMM::pools(SDOT, DOT).unwrap().lp_fee,
+            Ratio::from_percent(1)
+        );
+
+        // a higher fee tier leaves less for the trader
+        let amounts_out = AMM::get_amounts_out(1_000, vec![DOT, SDOT]).unwrap();
+        assert_eq!(amounts_out, vec![1_000, 989]);
+        assert!(
+            amounts_out[1]
+                < AMM::get_amount_out(1_000, 100_000_000, 100_000_000, DefaultLpFee::get())
+                    .unwrap()
+        );
+    })
+}
+
+#[test]
+fn create_pool_with_invalid_lp_fee_should_not_work() {
+    new_test_ext().execute_with(|| {
+        assert_noop!(
+            AMM::create_pool(
+                RawOrigin::Signed(ALICE).into(),
+                (DOT, SDOT),
+                (1_000, 2_000),
+                BOB,
+                SAMPLE_LP_TOKEN,
+                Ratio::from_percent(100),
+            ),
+            Error::<Test>::InvalidLpFee
+        );
+    })
+}
+
+#[test]
+fn update_lp_fee_should_work() {
+    new_test_ext().execute_with(|| {
+        System::set_block_number(1);
+        assert_noop!(
+            AMM::update_lp_fee(
+                RawOrigin::Signed(ALICE).into(),
+                (DOT, SDOT),
+                Ratio::from_percent(1)
+            ),
+            Error::<Test>::PoolDoesNotExist
+        );
+
+        assert_ok!(AMM::create_pool(
+            RawOrigin::Signed(ALICE).into(),
+            (DOT, SDOT),
+            (1_000, 2_000),
+            BOB,
+            SAMPLE_LP_TOKEN,
+            DefaultLpFee::get(),
+        ));
+
+        assert_noop!(
+            AMM::update_lp_fee(
+                RawOrigin::Signed(BOB).into(),
+                (DOT, SDOT),
+                Ratio::from_percent(1)
+            ),
+            sp_runtime::DispatchError::BadOrigin
+        );
+        assert_noop!(
+            AMM::update_lp_fee(
+                RawOrigin::Signed(ALICE).into(),
+                (DOT, SDOT),
+                Ratio::from_percent(100)
+            ),
+            Error::<Test>::InvalidLpFee
+        );
+
+        assert_ok!(AMM::update_lp_fee(
+            RawOrigin::Signed(ALICE).into(),
+            (DOT, SDOT),
+            Ratio::from_percent(1)
+        ));
+        assert_eq!(
+            AMM::pools(SDOT, DOT).unwrap().lp_fee,
+            Ratio::from_percent(1)
+        );
+        System::assert_last_event(RuntimeEvent::AMM(Event::LpFeeUpdated(
+            SDOT,
+            DOT,
+            Ratio::from_percent(1),
+        )));
+    })
+}
+
+#[test]
+fn migrate_to_v1_should_set_lp_fee() {
+    new_test_ext().execute_with(|| {
+        let pool = crate::migrations::v1::V0Pool {
+            base_amount: 2_000u128,
+            quote_amount: 1_000u128,
+            base_amount_last: 0u128,
+            quote_amount_last: 0u128,
+            lp_token_id: SAMPLE_LP_TOKEN,
+            block_timestamp_last: 0u64,
+            price_0_cumulative_last: 0u128,
+            price_1_cumulative_last: 0u128,
+        };
+        frame_support::storage::unhashed::put(&Pools::<Test>::hashed_key_for(SDOT, DOT), &pool);
+        assert_eq!(crate::pallet::StorageVersion::<Test>::get(), Versions::V0);
+
+        crate::migrations::v1::migrate::<Test, ()>();
+
+        assert_eq!(crate::pallet::StorageVersion::<Test>::get(), Versions::V1);
+        let pool = AMM::pools(SDOT, DOT).unwrap();
+        assert_eq!(pool.lp_fee, DefaultLpFee::get());
+        assert_eq!(pool.base_amount, 2_000);
+        assert_eq!(pool.lp_token_id, SAMPLE_LP_TOKEN);
+    })
+}
+
+#[test]
+fn create_pool_permissionless_should_work() {
+    new_test_ext().execute_with(|| {
+        System::set_block_number(1);
+        assert_ok!(AMM::create_pool_permissionless(
+            RawOrigin::Signed(BOB).into(),
+            (DOT, SDOT),
+            (1_000_000, 1_000_000),
+            DefaultLpFee::get(),
+        ));
+
+        // the lp token is created by the pallet
+        let lp_token_id = PermissionlessLpTokenIdStart::get();
+        assert_eq!(AMM::pools(SDOT, DOT).unwrap().base_amount, 1_000_000);
+        assert_eq!(AMM::pools(SDOT, DOT).unwrap().lp_token_id, lp_token_id);
+        assert_eq!(AMM::lp_token_pools(lp_token_id), Some((SDOT, DOT)));
+        assert_eq!(AMM::next_lp_token_id(), Some(lp_token_id + 1));
+        assert_eq!(
+            Assets::balance(lp_token_id, BOB),
+            1_000_000 - MINIMUM_LIQUIDITY
+        );
+        assert_eq!(AMM::pool_deposits(SDOT, DOT), Some((BOB, 1_000_000)));
+        assert_eq!(Balances::free_balance(BOB), 100_000_000 - 1_000_000);
+        assert_eq!(Balances::reserved_balance(BOB), 1_000_000);
+        System::assert_last_event(RuntimeEvent::AMM(Event::PoolDepositHeld(
+            BOB, SDOT, DOT, 1_000_000,
+        )));
+
+        // the next pool gets the next lp token id
+        assert_ok!(AMM::create_pool_permissionless(
+            RawOrigin::Signed(BOB).into(),
+            (DOT, KSM),
+            (1_000_000, 1_000_000),
+            DefaultLpFee::get(),
+        ));
+        assert_eq!(AMM::pools(DOT, KSM).unwrap().lp_token_id, lp_token_id + 1);
+        assert_eq!(AMM::next_lp_token_id(), Some(lp_token_id + 2));
+    })
+}
+
+#[test]
+fn create_pool_permissionless_with_low_liquidity_should_not_work() {
+    new_test_ext().execute_with(|| {
+        assert_noop!(
+            AMM::create_pool_permissionless(
+                RawOrigin::Signed(BOB).into(),
+                (DOT, SDOT),
+                (1_000, 1_000),
+                DefaultLpFee::get(),
+            ),
+            Error::<Test>::InsufficientSeedLiquidity
+        );
+    })
+}
+
+#[test]
+fn create_pool_permissionless_with_high_lp_fee_should_not_work() {
+    new_test_ext().execute_with(|| {
+        assert_noop!(
+            AMM::create_pool_permissionless(
+                RawOrigin::Signed(BOB).into(),
+                (DOT, SDOT),
+                (1_000_000, 1_000_000),
+                Ratio::from_percent(2),
+            ),
+            Error::<Test>::LpFeeTooHigh
+        );
+    })
+}
+
+#[test]
+fn refund_pool_deposit_should_work() {
+    new_test_ext().execute_with(|| {
+        System::set_block_number(1);
+        assert_ok!(AMM::create_pool_permissionless(
+            RawOrigin::Signed(BOB).into(),
+            (DOT, SDOT),
+            (100_000, 100_000),
+            DefaultLpFee::get(),
+        ));
+
+        assert_noop!(
+            AMM::refund_pool_deposit(RawOrigin::Signed(CHARLIE).into(), (DOT, SDOT)),
+            Error::<Test>::RefundThresholdNotReached
+        );
+
+        // the pool grows past the threshold
+        assert_ok!(AMM::add_liquidity(
+            RawOrigin::Signed(EVE).into(),
+            (DOT, SDOT),
+            (900_000, 900_000),
+            (900_000, 900_000),
+        ));
+        assert_eq!(AMM::refund_threshold_reached_at(SDOT, DOT), Some(1));
+        assert_noop!(
+            AMM::refund_pool_deposit(RawOrigin::Signed(CHARLIE).into(), (DOT, SDOT)),
+            Error::<Test>::RefundThresholdNotReached
+        );
+
+        // once it stayed above the threshold long enough anyone can trigger the refund
+        run_to_block(1 + DepositRefundDelay::get());
+        assert_ok!(AMM::refund_pool_deposit(
+            RawOrigin::Signed(CHARLIE).into(),
+            (DOT, SDOT)
+        ));
+
+        assert_eq!(AMM::pool_deposits(SDOT, DOT), None);
+        assert_eq!(AMM::refund_threshold_reached_at(SDOT, DOT), None);
+        assert_eq!(Balances::free_balance(BOB), 100_000_000);
+        assert_eq!(Balances::reserved_balance(BOB), 0);
+        assert_noop!(
+            AMM::refund_pool_deposit(RawOrigin::Signed(CHARLIE).into(), (DOT, SDOT)),
+            Error::<Test>::PoolDepositNotFound
+        );
+    })
+}
+
+#[test]
+fn refund_pool_deposit_should_not_work_if_liquidity_was_removed() {
+    new_test_ext().execute_with(|| {
+        System::set_block_number(1);
+        assert_ok!(AMM::create_pool_permissionless(
+            RawOrigin::Signed(BOB).into(),
+            (DOT, SDOT),
+            (100_000, 100_000),
+            DefaultLpFee::get(),
+        ));
+        let lp_token_id = PermissionlessLpTokenIdStart::get();
+
+        // the creator adds liquidity to pass the threshold and removes it
+        assert_ok!(AMM::add_liquidity(
+            RawOrigin::Signed(BOB).into(),
+            (DOT, SDOT),
+            (900_000, 900_000),
+            (900_000, 900_000),
+        ));
+        assert_eq!(AMM::refund_threshold_reached_at(SDOT, DOT), Some(1));
+        run_to_block(2);
+        assert_ok!(AMM::remove_liquidity(
+            RawOrigin::Signed(BOB).into(),
+            (DOT, SDOT),
+            Assets::balance(lp_token_id, BOB) - 50_000
+        ));
+        assert_eq!(AMM::refund_threshold_reached_at(SDOT, DOT), None);
+
+        run_to_block(1 + DepositRefundDelay::get());
+        assert_noop!(
+            AMM::refund_pool_deposit(RawOrigin::Signed(CHARLIE).into(), (DOT, SDOT)),
+            Error::<Test>::RefundThresholdNotReached
+        );
+        assert_eq!(AMM::pool_deposits(SDOT, DOT), Some((BOB, 1_000_000)));
+        assert_eq!(Balances::reserved_balance(BOB), 1_000_000);
+    })
+}
+
+#[test]
+fn delist_pool_should_work() {
+    new_test_ext().execute_with(|| {
+        System::set_block_number(1);
+        assert_ok!(AMM::create_pool_permissionless(
+            RawOrigin::Signed(BOB).into(),
+            (DOT, SDOT),
+            (100_000, 100_000),
+            DefaultLpFee::get(),
+        ));
+        let total_issuance = Balances::total_issuance();
+
+        assert_noop!(
+            AMM::delist_pool(RawOrigin::Signed(BOB).into(), (DOT, SDOT)),
+            sp_runtime::DispatchError::BadOrigin
+        );
+        assert_ok!(AMM::delist_pool(
+            RawOrigin::Signed(ALICE).into(),
+            (DOT, SDOT)
+        ));
+        System::assert_last_event(RuntimeEvent::AMM(Event::PoolDelisted(SDOT, DOT, 1_000_000)));
+
+        // the deposit is slashed
+        assert_eq!(AMM::pool_deposits(SDOT, DOT), None);
+        assert_eq!(Balances::total_issuance(), total_issuance - 1_000_000);
+        assert_eq!(Balances::free_balance(BOB), 100_000_000 - 1_000_000);
+        assert_eq!(Balances::reserved_balance(BOB), 0);
+
+        // trading and adding liquidity are disabled
+        assert!(AMM::get_amounts_out(1_000, vec![DOT, SDOT]).is_err());
+        assert_noop!(
+            AMM::swap(&EVE, (DOT, SDOT), 1_000),
+            Error::<Test>::PoolDelisted
+        );
+        assert_noop!(
+            AMM::add_liquidity(
+                RawOrigin::Signed(EVE).into(),
+                (DOT, SDOT),
+                (1_000, 1_000),
+                (1_000, 1_000),
+            ),
+            Error::<Test>::PoolDelisted
+        );
+        assert_noop!(
+            AMM::delist_pool(RawOrigin::Signed(ALICE).into(), (DOT, SDOT)),
+            Error::<Test>::PoolDelisted
+        );
+
+        // liquidity providers can still withdraw
+        assert_ok!(AMM::remove_liquidity(
+            RawOrigin::Signed(BOB).into(),
+            (DOT, SDOT),
+            100_000 - MINIMUM_LIQUIDITY
+        ));
+    })
+}
+
 #[test]
 fn handling_fees_should_work() {
     new_test_ext().execute_with(|| {
@@ -1144,7 +1638,8 @@ fn handling_fees_should_work() {
             (DOT, SDOT),                        // Currency pool, in which liquidity will be added
             (100_000_000_000, 100_000_000_000), // Liquidity amounts to be added in pool
             BOB,                                // LPToken receiver
-            SAMPLE_LP_TOKEN                     // Liquidity pool share representative token
+            SAMPLE_LP_TOKEN,                    // Liquidity pool share representative token
+            DefaultLpFee::get(),                // Fee taken by liquidity providers on each trade
         ));
 
         assert_ok!(AMM::update_protocol_fee(
@@ -1226,7 +1721,8 @@ fn swap_full_balance_should_work() {
             (DOT, SDOT),                        // Currency pool, in which liquidity will be added
             (100_000_000_000, 100_000_000_000), // Liquidity amounts to be added in pool
             BOB,                                // LPToken receiver
-            SAMPLE_LP_TOKEN                     // Liquidity pool share representative token
+            SAMPLE_LP_TOKEN,                    // Liquidity pool share representative token
+            DefaultLpFee::get(),                // Fee taken by liquidity providers on each trade
         ));
 
         // user can swap all of their non native assets
@@ -1245,7 +1741,8 @@ fn can_only_all_if_non_native_should_work() {
                 (0, SDOT),                       // Currency pool, in which liquidity will be added
                 (100000000, 100000000),          // Liquidity amounts to be added in pool
                 BOB,                             // LPToken receiver
-                SAMPLE_LP_TOKEN                  // Liquidity pool share representative token
+                SAMPLE_LP_TOKEN,                 // Liquidity pool share representative token
+                DefaultLpFee::get(),             // Fee taken by liquidity providers on each trade
             ),
             pallet_balances::Error::<Test>::KeepAlive
         );
@@ -1260,7 +1757,8 @@ fn can_only_all_if_non_native_should_work() {
             (DOT, SDOT),                     // Currency pool, in which liquidity will be added
             (all_dot, all_sdot),             // Liquidity amounts to be added in pool
             BOB,                             // LPToken receiver
-            SAMPLE_LP_TOKEN                  // Liquidity pool share representative token
+            SAMPLE_LP_TOKEN,                 // Liquidity pool share representative token
+            DefaultLpFee::get(),             // Fee taken by liquidity providers on each trade
         ));
 
         assert_eq!(Assets::balance(DOT, BOB), 0);
@@ -1300,6 +1798,7 @@ fn glmr_add_liquidity_should_work() {
             (5978650946941927074614, 100290500000000000),
             ALICE,
             SAMPLE_LP_TOKEN,
+            DefaultLpFee::get(),
         )
         .unwrap();
 
@@ -50,6 +50,10 @@ pub trait WeightInfo {
   fn create_pool() -> Weight;
   fn update_protocol_fee() -> Weight;
   fn update_protocol_fee_receiver() -> Weight;
+  fn update_lp_fee() -> Weight;
+  fn create_pool_permissionless() -> Weight;
+  fn refund_pool_deposit() -> Weight;
+  fn delist_pool() -> Weight;
 }
 
 /// Weights for pallet_amm using the Substrate node and recommended hardware.
@@ -59,29 +63,36 @@ impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
 	// Storage: AMM Pools (r:1 w:1)
 	// Storage: Assets Asset (r:3 w:3)
 	// Storage: Assets Account (r:5 w:5)
+	// Storage: AMM Observations (r:1 w:1)
+	// Storage: AMM PoolDeposits (r:1 w:0)
+	// Storage: AMM RefundThresholdReachedAt (r:1 w:1)
 	fn add_liquidity() -> Weight {
 		Weight::from_ref_time(217_917_000 as u64)
-			.saturating_add(T::DbWeight::get().reads(10 as u64))
-			.saturating_add(T::DbWeight::get().writes(10 as u64))
+			.saturating_add(T::DbWeight::get().reads(13 as u64))
+			.saturating_add(T::DbWeight::get().writes(12 as u64))
 	}
 	// Storage: unknown [0x3a7472616e73616374696f6e5f6c6576656c3a] (r:1 w:1)
 	// Storage: AMM Pools (r:1 w:1)
 	// Storage: Assets Asset (r:3 w:3)
 	// Storage: Assets Account (r:5 w:5)
+	// Storage: AMM Observations (r:1 w:1)
+	// Storage: AMM PoolDeposits (r:1 w:0)
+	// Storage: AMM RefundThresholdReachedAt (r:1 w:1)
 	fn remove_liquidity() -> Weight {
 		Weight::from_ref_time(238_051_000 as u64)
-			.saturating_add(T::DbWeight::get().reads(10 as u64))
-			.saturating_add(T::DbWeight::get().writes(10 as u64))
+			.saturating_add(T::DbWeight::get().reads(13 as u64))
+			.saturating_add(T::DbWeight::get().writes(12 as u64))
 	}
 	// Storage: unknown [0x3a7472616e73616374696f6e5f6c6576656c3a] (r:1 w:1)
 	// Storage: AMM Pools (r:1 w:1)
 	// Storage: Assets Asset (r:3 w:3)
 	// Storage: Assets Account (r:6 w:6)
 	// Storage: System Account (r:2 w:2)
+	// Storage: AMM LpTokenPools (r:0 w:1)
 	fn create_pool() -> Weight {
 		Weight::from_ref_time(291_928_000 as u64)
 			.saturating_add(T::DbWeight::get().reads(13 as u64))
-			.saturating_add(T::DbWeight::get().writes(13 as u64))
+			.saturating_add(T::DbWeight::get().writes(14 as u64))
 	}
 	// Storage: AMM ProtocolFee (r:0 w:1)
   fn update_protocol_fee() -> Weight {
@@ -91,6 +102,46 @@ impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
   fn update_protocol_fee_receiver() -> Weight {
 	Weight::from_ref_time(4_114_000 as u64).saturating_add(T::DbWeight::get().writes(1 as u64))
   }
+  // Storage: AMM Pools (r:1 w:1)
+  fn update_lp_fee() -> Weight {
+	Weight::from_ref_time(21_305_000 as u64)
+		.saturating_add(T::DbWeight::get().reads(1 as u64))
+		.saturating_add(T::DbWeight::get().writes(1 as u64))
+  }
+  // Storage: unknown [0x3a7472616e73616374696f6e5f6c6576656c3a] (r:1 w:1)
+  // Storage: AMM Pools (r:1 w:1)
+  // Storage: Assets Asset (r:4 w:4)
+  // Storage: Assets Account (r:6 w:6)
+  // Storage: System Account (r:3 w:3)
+  // Storage: AMM PoolDeposits (r:0 w:1)
+  // Storage: AMM LpTokenPools (r:0 w:1)
+  // Storage: AMM NextLpTokenId (r:1 w:1)
+  // Storage: AMM RefundThresholdReachedAt (r:1 w:1)
+  fn create_pool_permissionless() -> Weight {
+	Weight::from_ref_time(318_472_000 as u64)
+		.saturating_add(T::DbWeight::get().reads(17 as u64))
+		.saturating_add(T::DbWeight::get().writes(19 as u64))
+  }
+  // Storage: AMM Pools (r:1 w:0)
+  // Storage: AMM DelistedPools (r:1 w:0)
+  // Storage: AMM RefundThresholdReachedAt (r:1 w:1)
+  // Storage: AMM PoolDeposits (r:1 w:1)
+  // Storage: System Account (r:2 w:2)
+  fn refund_pool_deposit() -> Weight {
+	Weight::from_ref_time(52_791_000 as u64)
+		.saturating_add(T::DbWeight::get().reads(6 as u64))
+		.saturating_add(T::DbWeight::get().writes(4 as u64))
+  }
+  // Storage: AMM Pools (r:1 w:0)
+  // Storage: AMM DelistedPools (r:1 w:1)
+  // Storage: AMM PoolDeposits (r:1 w:1)
+  // Storage: System Account (r:1 w:1)
+  // Storage: AMM RefundThresholdReachedAt (r:0 w:1)
+  fn delist_pool() -> Weight {
+	Weight::from_ref_time(41_207_000 as u64)
+		.saturating_add(T::DbWeight::get().reads(4 as u64))
+		.saturating_add(T::DbWeight::get().writes(4 as u64))
+  }
 }
 
 // For backwards compatibility and tests
@@ -99,29 +150,36 @@ impl WeightInfo for () {
 	// Storage: AMM Pools (r:1 w:1)
 	// Storage: Assets Asset (r:3 w:3)
 	// Storage: Assets Account (r:5 w:5)
+	// Storage: AMM Observations (r:1 w:1)
+	// Storage: AMM PoolDeposits (r:1 w:0)
+	// Storage: AMM RefundThresholdReachedAt (r:1 w:1)
 	fn add_liquidity() -> Weight {
 		Weight::from_ref_time(217_917_000 as u64)
-			.saturating_add(RocksDbWeight::get().reads(10 as u64))
-			.saturating_add(RocksDbWeight::get().writes(10 as u64))
+			.saturating_add(RocksDbWeight::get().reads(13 as u64))
+			.saturating_add(RocksDbWeight::get().writes(12 as u64))
 	}
 	// Storage: unknown [0x3a7472616e73616374696f6e5f6c6576656c3a] (r:1 w:1)
 	// Storage: AMM Pools (r:1 w:1)
 	// Storage: Assets Asset (r:3 w:3)
 	// Storage: Assets Account (r:5 w:5)
+	// Storage: AMM Observations (r:1 w:1)
+	// Storage: AMM PoolDeposits (r:1 w:0)
+	// Storage: AMM RefundThresholdReachedAt (r:1 w:1)
 	fn remove_liquidity() -> Weight {
 		Weight::from_ref_time(238_051_000 as u64)
-			.saturating_add(RocksDbWeight::get().reads(10 as u64))
-			.saturating_add(RocksDbWeight::get().writes(10 as u64))
+			.saturating_add(RocksDbWeight::get().reads(13 as u64))
+			.saturating_add(RocksDbWeight::get().writes(12 as u64))
 	}
 	// Storage: unknown [0x3a7472616e73616374696f6e5f6c6576656c3a] (r:1 w:1)
 	// Storage: AMM Pools (r:1 w:1)
 	// Storage: Assets Asset (r:3 w:3)
 	// Storage: Assets Account (r:6 w:6)
 	// Storage: System Account (r:2 w:2)
+	// Storage: AMM LpTokenPools (r:0 w:1)
 	fn create_pool() -> Weight {
 		Weight::from_ref_time(291_928_000 as u64)
 			.saturating_add(RocksDbWeight::get().reads(13 as u64))
-			.saturating_add(RocksDbWeight::get().writes(13 as u64))
+			.saturating_add(RocksDbWeight::get().writes(14 as u64))
 	}
 	// Storage: AMM ProtocolFee (r:0 w:1)
 	fn update_protocol_fee() -> Weight {
@@ -131,4 +189,44 @@ impl WeightInfo for () {
 	fn update_protocol_fee_receiver() -> Weight {
 		Weight::from_ref_time(4_114_000 as u64).saturating_add(RocksDbWeight::get().writes(1 as u64))
 	}
+	// Storage: AMM Pools (r:1 w:1)
+	fn update_lp_fee() -> Weight {
+		Weight::from_ref_time(21_305_000 as u64)
+			.saturating_add(RocksDbWeight::get().reads(1 as u64))
+			.saturating_add(RocksDbWeight::get().writes(1 as u64))
+	}
+	// Storage: unknown [0x3a7472616e73616374696f6e5f6c6576656c3a] (r:1 w:1)
+	// Storage: AMM Pools (r:1 w:1)
+	// Storage: Assets Asset (r:4 w:4)
+	// Storage: Assets Account (r:6 w:6)
+	// Storage: System Account (r:3 w:3)
+	// Storage: AMM PoolDeposits (r:0 w:1)
+	// Storage: AMM LpTokenPools (r:0 w:1)
+	// Storage: AMM NextLpTokenId (r:1 w:1)
+	// Storage: AMM RefundThresholdReachedAt (r:1 w:1)
+	fn create_pool_permissionless() -> Weight {
+		Weight::from_ref_time(318_472_000 as u64)
+			.saturating_add(RocksDbWeight::get().reads(17 as u64))
+			.saturating_add(RocksDbWeight::get().writes(19 as u64))
+	}
+	// Storage: AMM Pools (r:1 w:0)
+	// Storage: AMM DelistedPools (r:1 w:0)
+	// Storage: AMM RefundThresholdReachedAt (r:1 w:1)
+	// Storage: AMM PoolDeposits (r:1 w:1)
+	// Storage: System Account (r:2 w:2)
+	fn refund_pool_deposit() -> Weight {
+		Weight::from_ref_time(52_791_000 as u64)
+			.saturating_add(RocksDbWeight::get().reads(6 as u64))
+			.saturating_add(RocksDbWeight::get().writes(4 as u64))
+	}
+	// Storage: AMM Pools (r:1 w:0)
+	// Storage: AMM DelistedPools (r:1 w:1)
+	// Storage: AMM PoolDeposits (r:1 w:1)
+	// Storage: System Account (r:1 w:1)
+	// Storage: AMM RefundThresholdReachedAt (r:0 w:1)
+	fn delist_pool() -> Weight {
+		Weight::from_ref_time(41_207_000 as u64)
+			.saturating_add(RocksDbWeight::get().reads(4 as u64))
+			.saturating_add(RocksDbWeight::get().writes(4 as u64))
+	}
 }
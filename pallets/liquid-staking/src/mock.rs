@@ -22,7 +22,8 @@ use pallet_traits::{
 };
 use polkadot_runtime_parachains::configuration::HostConfiguration;
 use primitives::{
-    tokens::*, Balance, EraIndex, ParaId, PersistedValidationData, Price, PriceDetail, Rate, Ratio,
+    tokens::*, Balance, EraIndex, Liquidity, ParaId, PersistedValidationData, Price, PriceDetail,
+    Rate, Ratio,
 };
 use sp_core::H256;
 use sp_runtime::{
@@ -333,6 +334,8 @@ parameter_types! {
     pub const LoansPalletId: PalletId = PalletId(*b"par/loan");
     pub const RewardAssetId: CurrencyId = HKO;
     pub const LiquidationFreeAssetId: CurrencyId = KSM;
+    pub const MarketSnapshotInterval: BlockNumber = 0;
+    pub const MaxMarketSnapshots: u32 = 0;
 }
 
 impl pallet_loans::Config for Test {
@@ -346,6 +349,10 @@ impl pallet_loans::Config for Test {
     type Assets = Assets;
     type RewardAssetId = RewardAssetId;
     type LiquidationFreeAssetId = LiquidationFreeAssetId;
+    type MarketSnapshotInterval = MarketSnapshotInterval;
+    type MaxMarketSnapshots = MaxMarketSnapshots;
+    type RuntimeCall = RuntimeCall;
+    type Router = ();
 }
 
 parameter_types! {
@@ -967,5 +974,10 @@ pub const fn market_mock(ptoken_id: u32) -> Market<Balance> {
         supply_cap: 1_000_000_000_000_000_000_000u128, // set to 1B
         borrow_cap: 1_000_000_000_000_000_000_000u128, // set to 1B
         ptoken_id,
+        max_price_age: None,
+        liquidate_on_stale_price: false,
+        isolated: false,
+        debt_ceiling: Liquidity::from_inner(0),
+        borrowable_in_isolation: false,
     }
 }
@@ -15,15 +15,26 @@
 #![cfg_attr(not(feature = "std"), no_std)]
 
 use codec::Codec;
-use primitives::{CurrencyId, Liquidity, Rate, Ratio, Shortfall};
+use primitives::{CurrencyId, EModeCategoryId, Liquidity, Rate, Ratio, Shortfall, Timestamp};
 use sp_runtime::{DispatchError, FixedU128};
+use sp_std::vec::Vec;
 
 sp_api::decl_runtime_apis! {
+    /// Version 2 adds `get_account_emode_category`.
+    /// Version 3 adds `get_pending_rewards`.
+    /// Version 4 adds `get_accounts_with_shortfall` and `get_account_positions`.
+    /// Version 5 adds `get_market_snapshots`.
+    #[api_version(5)]
     pub trait LoansApi<AccountId, Balance> where
         AccountId: Codec,
         Balance: Codec {
         fn get_account_liquidity(account: AccountId) -> Result<(Liquidity, Shortfall, Liquidity, Shortfall), DispatchError>;
         fn get_market_status(asset_id: CurrencyId) -> Result<(Rate, Rate, Rate, Ratio, Balance, Balance, FixedU128), DispatchError>;
         fn get_liquidation_threshold_liquidity(account: AccountId) -> Result<(Liquidity, Shortfall, Liquidity, Shortfall), DispatchError>;
+        fn get_account_emode_category(account: AccountId) -> Option<(EModeCategoryId, Ratio, Ratio)>;
+        fn get_pending_rewards(account: AccountId) -> Result<Vec<(CurrencyId, Balance)>, DispatchError>;
+        fn get_accounts_with_shortfall(start_after: Option<AccountId>, limit: u32) -> Result<(Vec<(AccountId, Shortfall, Shortfall)>, Option<AccountId>), DispatchError>;
+        fn get_account_positions(account: AccountId) -> Result<Vec<(CurrencyId, Balance, Balance, bool, Liquidity, Liquidity)>, DispatchError>;
+        fn get_market_snapshots(asset_id: CurrencyId) -> Vec<(Timestamp, Rate, Rate, Rate, Ratio, Balance, Balance, Balance)>;
     }
 }
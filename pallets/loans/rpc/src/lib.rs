@@ -22,8 +22,8 @@ use jsonrpsee::{
     proc_macros::rpc,
     types::error::{CallError, ErrorCode, ErrorObject},
 };
-use primitives::{CurrencyId, Liquidity, Rate, Ratio, Shortfall};
-use sp_api::ProvideRuntimeApi;
+use primitives::{CurrencyId, EModeCategoryId, Liquidity, Rate, Ratio, Shortfall, Timestamp};
+use sp_api::{ApiExt, ProvideRuntimeApi};
 use sp_blockchain::HeaderBackend;
 use sp_rpc::number::NumberOrHex;
 use sp_runtime::{generic::BlockId, traits::Block as BlockT, FixedU128};
@@ -51,6 +51,57 @@ where
         account: AccountId,
         at: Option<BlockHash>,
     ) -> RpcResult<(Liquidity, Shortfall, Liquidity, Shortfall)>;
+    #[method(name = "loans_getAccountEModeCategory")]
+    fn get_account_emode_category(
+        &self,
+        account: AccountId,
+        at: Option<BlockHash>,
+    ) -> RpcResult<Option<(EModeCategoryId, Ratio, Ratio)>>;
+    #[method(name = "loans_getPendingRewards")]
+    fn get_pending_rewards(
+        &self,
+        account: AccountId,
+        at: Option<BlockHash>,
+    ) -> RpcResult<Vec<(CurrencyId, NumberOrHex)>>;
+    #[method(name = "loans_getAccountsWithShortfall")]
+    fn get_accounts_with_shortfall(
+        &self,
+        start_after: Option<AccountId>,
+        limit: u32,
+        at: Option<BlockHash>,
+    ) -> RpcResult<(Vec<(AccountId, Shortfall, Shortfall)>, Option<AccountId>)>;
+    #[method(name = "loans_getAccountPositions")]
+    fn get_account_positions(
+        &self,
+        account: AccountId,
+        at: Option<BlockHash>,
+    ) -> RpcResult<
+        Vec<(
+            CurrencyId,
+            NumberOrHex,
+            NumberOrHex,
+            bool,
+            Liquidity,
+            Liquidity,
+        )>,
+    >;
+    #[method(name = "loans_getMarketSnapshots")]
+    fn get_market_snapshots(
+        &self,
+        asset_id: CurrencyId,
+        at: Option<BlockHash>,
+    ) -> RpcResult<
+        Vec<(
+            Timestamp,
+            Rate,
+            Rate,
+            Rate,
+            Ratio,
+            NumberOrHex,
+            NumberOrHex,
+            NumberOrHex,
+        )>,
+    >;
 }
 
 /// A struct that implements the [`LoansApi`].
@@ -73,6 +124,9 @@ pub enum Error {
     RuntimeError,
     AccountLiquidityError,
     MarketStatusError,
+    PendingRewardsError,
+    AccountPositionsError,
+    UnsupportedApiVersion,
 }
 
 impl From<Error> for i32 {
@@ -81,6 +135,9 @@ impl From<Error> for i32 {
             Error::RuntimeError => 1,
             Error::AccountLiquidityError => 2,
             Error::MarketStatusError => 3,
+            Error::PendingRewardsError => 4,
+            Error::AccountPositionsError => 5,
+            Error::UnsupportedApiVersion => 6,
         }
     }
 }
@@ -159,6 +216,169 @@ where
             .map_err(runtime_error_into_rpc_error)?
             .map_err(account_liquidity_error_into_rpc_error)
     }
+
+    fn get_account_emode_category(
+        &self,
+        account: AccountId,
+        at: Option<<Block as BlockT>::Hash>,
+    ) -> RpcResult<Option<(EModeCategoryId, Ratio, Ratio)>> {
+        let api = self.client.runtime_api();
+        let at = BlockId::hash(at.unwrap_or(
+            // If the block hash is not supplied assume the best block.
+            self.client.info().best_hash,
+        ));
+        ensure_api_version(
+            api.has_api_with::<dyn LoansRuntimeApi<Block, AccountId, Balance>, _>(&at, |v| v >= 2),
+            2,
+        )?;
+        api.get_account_emode_category(&at, account)
+            .map_err(runtime_error_into_rpc_error)
+    }
+
+    fn get_pending_rewards(
+        &self,
+        account: AccountId,
+        at: Option<<Block as BlockT>::Hash>,
+    ) -> RpcResult<Vec<(CurrencyId, NumberOrHex)>> {
+        let api = self.client.runtime_api();
+        let at = BlockId::hash(at.unwrap_or(
+            // If the block hash is not supplied assume the best block.
+            self.client.info().best_hash,
+        ));
+        ensure_api_version(
+            api.has_api_with::<dyn LoansRuntimeApi<Block, AccountId, Balance>, _>(&at, |v| v >= 3),
+            3,
+        )?;
+        api.get_pending_rewards(&at, account)
+            .map_err(runtime_error_into_rpc_error)?
+            .map_err(pending_rewards_error_into_rpc_error)?
+            .into_iter()
+            .map(|(asset_id, amount)| Ok((asset_id, try_into_rpc_balance(amount)?)))
+            .collect()
+    }
+
+    fn get_accounts_with_shortfall(
+        &self,
+        start_after: Option<AccountId>,
+        limit: u32,
+        at: Option<<Block as BlockT>::Hash>,
+    ) -> RpcResult<(Vec<(AccountId, Shortfall, Shortfall)>, Option<AccountId>)> {
+        let api = self.client.runtime_api();
+        let at = BlockId::hash(at.unwrap_or(
+            // If the block hash is not supplied assume the best block.
+            self.client.info().best_hash,
+        ));
+        ensure_api_version(
+            api.has_api_with::<dyn LoansRuntimeApi<Block, AccountId, Balance>, _>(&at, |v| v >= 4),
+            4,
+        )?;
+        api.get_accounts_with_shortfall(&at, start_after, limit)
+            .map_err(runtime_error_into_rpc_error)?
+            .map_err(account_liquidity_error_into_rpc_error)
+    }
+
+    fn get_account_positions(
+        &self,
+        account: AccountId,
+        at: Option<<Block as BlockT>::Hash>,
+    ) -> RpcResult<
+        Vec<(
+            CurrencyId,
+            NumberOrHex,
+            NumberOrHex,
+            bool,
+            Liquidity,
+            Liquidity,
+        )>,
+    > {
+        let api = self.client.runtime_api();
+        let at = BlockId::hash(at.unwrap_or(
+            // If the block hash is not supplied assume the best block.
+            self.client.info().best_hash,
+        ));
+        ensure_api_version(
+            api.has_api_with::<dyn LoansRuntimeApi<Block, AccountId, Balance>, _>(&at, |v| v >= 4),
+            4,
+        )?;
+        api.get_account_positions(&at, account)
+            .map_err(runtime_error_into_rpc_error)?
+            .map_err(account_positions_error_into_rpc_error)?
+            .into_iter()
+            .map(
+                |(
+                    asset_id,
+                    supply_amount,
+                    borrow_amount,
+                    is_collateral,
+                    supply_value,
+                    borrow_value,
+                )| {
+                    Ok((
+                        asset_id,
+                        try_into_rpc_balance(supply_amount)?,
+                        try_into_rpc_balance(borrow_amount)?,
+                        is_collateral,
+                        supply_value,
+                        borrow_value,
+                    ))
+                },
+            )
+            .collect()
+    }
+
+    fn get_market_snapshots(
+        &self,
+        asset_id: CurrencyId,
+        at: Option<<Block as BlockT>::Hash>,
+    ) -> RpcResult<
+        Vec<(
+            Timestamp,
+            Rate,
+            Rate,
+            Rate,
+            Ratio,
+            NumberOrHex,
+            NumberOrHex,
+            NumberOrHex,
+        )>,
+    > {
+        let api = self.client.runtime_api();
+        let at = BlockId::hash(at.unwrap_or(
+            // If the block hash is not supplied assume the best block.
+            self.client.info().best_hash,
+        ));
+        ensure_api_version(
+            api.has_api_with::<dyn LoansRuntimeApi<Block, AccountId, Balance>, _>(&at, |v| v >= 5),
+            5,
+        )?;
+        api.get_market_snapshots(&at, asset_id)
+            .map_err(runtime_error_into_rpc_error)?
+            .into_iter()
+            .map(
+                |(
+                    timestamp,
+                    borrow_rate,
+                    supply_rate,
+                    exchange_rate,
+                    util,
+                    total_supply,
+                    total_borrows,
+                    total_reserves,
+                )| {
+                    Ok((
+                        timestamp,
+                        borrow_rate,
+                        supply_rate,
+                        exchange_rate,
+                        util,
+                        try_into_rpc_balance(total_supply)?,
+                        try_into_rpc_balance(total_borrows)?,
+                        try_into_rpc_balance(total_reserves)?,
+                    ))
+                },
+            )
+            .collect()
+    }
 }
 
 /// Converts a runtime trap into an RPC error.
@@ -170,6 +390,22 @@ fn runtime_error_into_rpc_error(err: impl std::fmt::Debug) -> JsonRpseeError {
     )))
 }
 
+/// Fails unless the runtime implements the version of the loans runtime API
+/// which added the called method.
+fn ensure_api_version(
+    supported: Result<bool, impl std::fmt::Debug>,
+    version: u32,
+) -> RpcResult<()> {
+    if supported.map_err(runtime_error_into_rpc_error)? {
+        return Ok(());
+    }
+    Err(JsonRpseeError::Call(CallError::Custom(ErrorObject::owned(
+        Error::UnsupportedApiVersion.into(),
+        "Not supported by the runtime",
+        Some(format!("requires LoansApi version {}", version)),
+    ))))
+}
+
 /// Converts an account liquidity error into an RPC error.
 fn account_liquidity_error_into_rpc_error(err: impl std::fmt::Debug) -> JsonRpseeError {
     JsonRpseeError::Call(CallError::Custom(ErrorObject::owned(
@@ -188,6 +424,24 @@ fn market_status_error_into_rpc_error(err: impl std::fmt::Debug) -> JsonRpseeErr
     )))
 }
 
+/// Converts a pending rewards error into an RPC error.
+fn pending_rewards_error_into_rpc_error(err: impl std::fmt::Debug) -> JsonRpseeError {
+    JsonRpseeError::Call(CallError::Custom(ErrorObject::owned(
+        Error::PendingRewardsError.into(),
+        "Not able to get pending rewards",
+        Some(format!("{:?}", err)),
+    )))
+}
+
+/// Converts an account positions error into an RPC error.
+fn account_positions_error_into_rpc_error(err: impl std::fmt::Debug) -> JsonRpseeError {
+    JsonRpseeError::Call(CallError::Custom(ErrorObject::owned(
+        Error::AccountPositionsError.into(),
+        "Not able to get account positions",
+        Some(format!("{:?}", err)),
+    )))
+}
+
 fn try_into_rpc_balance<T: std::fmt::Display + Copy + TryInto<NumberOrHex>>(
     value: T,
 ) -> RpcResult<NumberOrHex> {
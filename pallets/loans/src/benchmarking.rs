@@ -41,6 +41,11 @@ fn market_mock<T: Config>() -> Market<BalanceOf<T>> {
         supply_cap: 1_000_000_000_000_000_000_000u128, // set to 1B
         borrow_cap: 1_000_000_000_000_000_000_000u128, // set to 1B
         ptoken_id: 1200,
+        max_price_age: None,
+        liquidate_on_stale_price: false,
+        isolated: false,
+        debt_ceiling: Liquidity::from_inner(0),
+        borrowable_in_isolation: false,
     }
 }
 
@@ -159,11 +164,16 @@ fn transfer_initial_balance<
     <T as pallet::Config>::Assets::mint_into(SKSM, &caller, INITIAL_AMOUNT.into()).unwrap();
     <T as pallet::Config>::Assets::mint_into(DOT, &caller, INITIAL_AMOUNT.into()).unwrap();
     <T as pallet::Config>::Assets::mint_into(CDOT_6_13, &caller, INITIAL_AMOUNT.into()).unwrap();
-    pallet_prices::Pallet::<T>::set_price(SystemOrigin::Root.into(), USDT, 1.into()).unwrap();
-    pallet_prices::Pallet::<T>::set_price(SystemOrigin::Root.into(), KSM, 1.into()).unwrap();
-    pallet_prices::Pallet::<T>::set_price(SystemOrigin::Root.into(), SKSM, 1.into()).unwrap();
-    pallet_prices::Pallet::<T>::set_price(SystemOrigin::Root.into(), DOT, 1.into()).unwrap();
-    pallet_prices::Pallet::<T>::set_price(SystemOrigin::Root.into(), CDOT_6_13, 1.into()).unwrap();
+    for asset_id in [USDT, KSM, SKSM, DOT, CDOT_6_13] {
+        pallet_prices::Pallet::<T>::set_price(
+            SystemOrigin::Root.into(),
+            asset_id,
+            1.into(),
+            u32::MAX.into(),
+            None,
+        )
+        .unwrap();
+    }
 }
 
 fn set_account_borrows<T: Config>(
@@ -183,10 +193,109 @@ fn set_account_borrows<T: Config>(
     T::Assets::burn_from(asset_id, &who, borrow_balance).unwrap();
 }
 
+fn auction_config_mock<T: Config>() -> LiquidationAuctionConfig<T::BlockNumber> {
+    LiquidationAuctionConfig {
+        max_discount: Ratio::from_percent(10),
+        duration: 100u32.into(),
+    }
+}
+
+// `alice` borrows KSM against SKSM with a shortfall, `bob` supplies the KSM.
+fn setup_liquidation_auction<
+    T: Config
+        + pallet_assets::Config<AssetId = CurrencyId, Balance = Balance>
+        + pallet_prices::Config
+        + pallet_balances::Config<Balance = Balance>,
+>(
+    alice: T::AccountId,
+    bob: T::AccountId,
+) {
+    transfer_initial_balance::<T>(alice.clone());
+    transfer_initial_balance::<T>(bob.clone());
+    let deposit_amount: u32 = 200_000_000;
+    let borrowed_amount: u32 = 200_000_000;
+    assert_ok!(Loans::<T>::add_market(
+        SystemOrigin::Root.into(),
+        SKSM,
+        pending_market_mock::<T>(PSKSM)
+    ));
+    assert_ok!(Loans::<T>::activate_market(SystemOrigin::Root.into(), SKSM));
+    assert_ok!(Loans::<T>::add_market(
+        SystemOrigin::Root.into(),
+        KSM,
+        pending_market_mock::<T>(PKSM)
+    ));
+    assert_ok!(Loans::<T>::activate_market(SystemOrigin::Root.into(), KSM));
+    assert_ok!(Loans::<T>::set_liquidation_auction_config(
+        SystemOrigin::Root.into(),
+        KSM,
+        Some(auction_config_mock::<T>())
+    ));
+    assert_ok!(Loans::<T>::mint(
+        SystemOrigin::Signed(bob).into(),
+        KSM,
+        deposit_amount.into()
+    ));
+    assert_ok!(Loans::<T>::mint(
+        SystemOrigin::Signed(alice.clone()).into(),
+        SKSM,
+        deposit_amount.into()
+    ));
+    assert_ok!(Loans::<T>::collateral_asset(
+        SystemOrigin::Signed(alice.clone()).into(),
+        SKSM,
+        true
+    ));
+    set_account_borrows::<T>(alice, KSM, borrowed_amount.into());
+}
+
 fn assert_last_event<T: Config>(generic_event: <T as Config>::RuntimeEvent) {
     frame_system::Pallet::<T>::assert_last_event(generic_event.into());
 }
 
+fn fixed_rate_config_mock<T: Config>() -> FixedRateConfig<T::BlockNumber> {
+    FixedRateConfig {
+        rate_premium: Rate::from_inner(Rate::DIV / 100),
+        origination_fee: Ratio::from_percent(1),
+        max_term: 1_000u32.into(),
+        rollover: false,
+    }
+}
+
+// `caller` supplies USDT as collateral to a market with fixed-rate borrows.
+fn setup_fixed_borrow<
+    T: Config
+        + pallet_assets::Config<AssetId = CurrencyId, Balance = Balance>
+        + pallet_prices::Config
+        + pallet_balances::Config<Balance = Balance>,
+>(
+    caller: T::AccountId,
+) {
+    transfer_initial_balance::<T>(caller.clone());
+    let deposit_amount: u32 = 200_000_000;
+    assert_ok!(Loans::<T>::add_market(
+        SystemOrigin::Root.into(),
+        USDT,
+        pending_market_mock::<T>(PUSDT)
+    ));
+    assert_ok!(Loans::<T>::activate_market(SystemOrigin::Root.into(), USDT));
+    assert_ok!(Loans::<T>::set_fixed_rate_config(
+        SystemOrigin::Root.into(),
+        USDT,
+        Some(fixed_rate_config_mock::<T>())
+    ));
+    assert_ok!(Loans::<T>::mint(
+        SystemOrigin::Signed(caller.clone()).into(),
+        USDT,
+        deposit_amount.into()
+    ));
+    assert_ok!(Loans::<T>::collateral_asset(
+        SystemOrigin::Signed(caller).into(),
+        USDT,
+        true
+    ));
+}
+
 benchmarks! {
     where_clause {
         where
@@ -447,6 +556,199 @@ benchmarks! {
     verify {
         assert_last_event::<T>(Event::<T>::LiquidationFreeCollateralsUpdated(vec![CDOT_6_13]).into());
     }
+
+    set_emode_category {
+        let category = EModeCategory {
+            collateral_factor: Ratio::from_percent(90),
+            liquidation_threshold: Ratio::from_percent(93),
+        };
+    }: _(SystemOrigin::Root, 1, Some(category))
+    verify {
+        assert_last_event::<T>(Event::<T>::EModeCategoryUpdated(1, Some(category)).into());
+    }
+
+    set_market_emode_category {
+        assert_ok!(Loans::<T>::add_market(SystemOrigin::Root.into(), USDT, pending_market_mock::<T>(PUSDT)));
+        assert_ok!(Loans::<T>::set_emode_category(SystemOrigin::Root.into(), 1, Some(EModeCategory {
+            collateral_factor: Ratio::from_percent(90),
+            liquidation_threshold: Ratio::from_percent(93),
+        })));
+    }: _(SystemOrigin::Root, USDT, Some(1))
+    verify {
+        assert_last_event::<T>(Event::<T>::MarketEModeCategoryUpdated(USDT, Some(1)).into());
+    }
+
+    set_flash_loan_fee {
+        assert_ok!(Loans::<T>::add_market(SystemOrigin::Root.into(), USDT, pending_market_mock::<T>(PUSDT)));
+    }: _(SystemOrigin::Root, USDT, Some(Ratio::from_percent(1)))
+    verify {
+        assert_last_event::<T>(Event::<T>::FlashLoanFeeUpdated(USDT, Some(Ratio::from_percent(1))).into());
+    }
+
+    flash_loan {
+        let caller: T::AccountId = whitelisted_caller();
+        transfer_initial_balance::<T>(caller.clone());
+        let deposit_amount: u32 = 200_000_000;
+        let loan_amount: u32 = 100_000_000;
+        let fee = Ratio::from_percent(1).mul_ceil(loan_amount);
+        assert_ok!(Loans::<T>::add_market(SystemOrigin::Root.into(), USDT, pending_market_mock::<T>(PUSDT)));
+        assert_ok!(Loans::<T>::activate_market(SystemOrigin::Root.into(), USDT));
+        assert_ok!(Loans::<T>::mint(SystemOrigin::Signed(caller.clone()).into(), USDT, deposit_amount.into()));
+        assert_ok!(Loans::<T>::set_flash_loan_fee(SystemOrigin::Root.into(), USDT, Some(Ratio::from_percent(1))));
+        let call: <T as Config>::RuntimeCall = frame_system::Call::<T>::remark { remark: vec![] }.into();
+    }: _(SystemOrigin::Signed(caller.clone()), USDT, loan_amount.into(), Box::new(call))
+    verify {
+        assert_last_event::<T>(Event::<T>::FlashLoaned(caller, USDT, loan_amount.into(), fee.into()).into());
+    }
+
+    set_liquidation_auction_config {
+        assert_ok!(Loans::<T>::add_market(SystemOrigin::Root.into(), KSM, pending_market_mock::<T>(PKSM)));
+    }: _(SystemOrigin::Root, KSM, Some(auction_config_mock::<T>()))
+    verify {
+        assert_last_event::<T>(Event::<T>::LiquidationAuctionConfigUpdated(KSM, Some(auction_config_mock::<T>())).into());
+    }
+
+    open_liquidation_auction {
+        let alice: T::AccountId = account("Sample", 100, SEED);
+        let bob: T::AccountId = account("Sample", 101, SEED);
+        setup_liquidation_auction::<T>(alice.clone(), bob.clone());
+        let started_at = frame_system::Pallet::<T>::block_number();
+    }: _(SystemOrigin::Signed(bob), alice.clone(), KSM)
+    verify {
+        assert_last_event::<T>(Event::<T>::LiquidationAuctionOpened(alice, KSM, started_at).into());
+    }
+
+    fill_liquidation_auction {
+        let alice: T::AccountId = account("Sample", 100, SEED);
+        let bob: T::AccountId = account("Sample", 101, SEED);
+        setup_liquidation_auction::<T>(alice.clone(), bob.clone());
+        let liquidate_amount: u32 = 100_000_000;
+        assert_ok!(Loans::<T>::open_liquidation_auction(SystemOrigin::Signed(bob.clone()).into(), alice.clone(), KSM));
+        let target_height = frame_system::Pallet::<T>::block_number().saturating_add(auction_config_mock::<T>().duration);
+        frame_system::Pallet::<T>::set_block_number(target_height);
+    }: _(SystemOrigin::Signed(bob.clone()), alice.clone(), KSM, liquidate_amount.into(), SKSM)
+    verify {
+        assert_last_event::<T>(Event::<T>::LiquidationAuctionFilled(bob, alice, KSM, liquidate_amount.into(), Ratio::from_percent(10)).into());
+    }
+
+    close_liquidation_auction {
+        let alice: T::AccountId = account("Sample", 100, SEED);
+        let bob: T::AccountId = account("Sample", 101, SEED);
+        setup_liquidation_auction::<T>(alice.clone(), bob.clone());
+        assert_ok!(Loans::<T>::open_liquidation_auction(SystemOrigin::Signed(bob.clone()).into(), alice.clone(), KSM));
+        AccountBorrows::<T>::remove(KSM, &alice);
+    }: _(SystemOrigin::Signed(bob), alice.clone(), KSM)
+    verify {
+        assert_last_event::<T>(Event::<T>::LiquidationAuctionClosed(alice, KSM).into());
+    }
+
+    set_bad_debt_socialisation {
+        assert_ok!(Loans::<T>::add_market(SystemOrigin::Root.into(), USDT, pending_market_mock::<T>(PUSDT)));
+    }: _(SystemOrigin::Root, USDT, true)
+    verify {
+        assert_last_event::<T>(Event::<T>::BadDebtSocialisationUpdated(USDT, true).into());
+    }
+
+    set_fixed_rate_config {
+        assert_ok!(Loans::<T>::add_market(SystemOrigin::Root.into(), USDT, pending_market_mock::<T>(PUSDT)));
+    }: _(SystemOrigin::Root, USDT, Some(fixed_rate_config_mock::<T>()))
+    verify {
+        assert_last_event::<T>(Event::<T>::FixedRateConfigUpdated(USDT, Some(fixed_rate_config_mock::<T>())).into());
+    }
+
+    fixed_borrow {
+        let caller: T::AccountId = whitelisted_caller();
+        let borrowed_amount: u32 = 100_000_000;
+        setup_fixed_borrow::<T>(caller.clone());
+    }: _(SystemOrigin::Signed(caller.clone()), USDT, borrowed_amount.into(), 100u32.into())
+    verify {
+        assert!(AccountFixedBorrows::<T>::contains_key(USDT, caller));
+    }
+
+    repay_fixed_borrow {
+        let caller: T::AccountId = whitelisted_caller();
+        let borrowed_amount: u32 = 100_000_000;
+        setup_fixed_borrow::<T>(caller.clone());
+        assert_ok!(Loans::<T>::fixed_borrow(SystemOrigin::Signed(caller.clone()).into(), USDT, borrowed_amount.into(), 100u32.into()));
+    }: _(SystemOrigin::Signed(caller.clone()), USDT)
+    verify {
+        assert!(!AccountFixedBorrows::<T>::contains_key(USDT, caller));
+    }
+
+    settle_fixed_borrow {
+        let caller: T::AccountId = whitelisted_caller();
+        let borrowed_amount: u32 = 100_000_000;
+        setup_fixed_borrow::<T>(caller.clone());
+        assert_ok!(Loans::<T>::fixed_borrow(SystemOrigin::Signed(caller.clone()).into(), USDT, borrowed_amount.into(), 100u32.into()));
+        frame_system::Pallet::<T>::set_block_number(1_000u32.into());
+    }: _(SystemOrigin::Signed(caller.clone()), caller.clone(), USDT)
+    verify {
+        assert!(!AccountFixedBorrows::<T>::contains_key(USDT, &caller));
+        assert!(Loans::<T>::overdue_borrows(USDT, caller));
+    }
+
+    update_reward_token_speed {
+        assert_ok!(Loans::<T>::add_market(SystemOrigin::Root.into(), USDT, pending_market_mock::<T>(PUSDT)));
+        assert_ok!(Loans::<T>::activate_market(SystemOrigin::Root.into(), USDT));
+    }: _(SystemOrigin::Root, USDT, KSM, 1_000_000, 1_000_000, 1_000u32.into())
+    verify {
+        assert_last_event::<T>(Event::<T>::RewardTokenSpeedUpdated(USDT, KSM, 1_000_000, 1_000_000, 1_000u32.into()).into());
+    }
+
+    add_reward_token {
+        let caller: T::AccountId = whitelisted_caller();
+        transfer_initial_balance::<T>(caller.clone());
+    }: _(SystemOrigin::Signed(caller.clone()), KSM, 1_000_000_000_u128)
+    verify {
+        assert_last_event::<T>(Event::<T>::RewardTokenAdded(caller, KSM, 1_000_000_000_u128).into());
+    }
+
+    withdraw_reward_token {
+        let caller: T::AccountId = whitelisted_caller();
+        transfer_initial_balance::<T>(caller.clone());
+        assert_ok!(Loans::<T>::add_reward_token(SystemOrigin::Signed(caller.clone()).into(), KSM, 1_000_000_000_u128));
+        let receiver = T::Lookup::unlookup(caller.clone());
+    }: _(SystemOrigin::Root, KSM, receiver, 500_000_000_u128)
+    verify {
+        assert_last_event::<T>(Event::<T>::RewardTokenWithdrawn(caller, KSM, 500_000_000_u128).into());
+    }
+
+    remove_reward_token {
+        assert_ok!(Loans::<T>::add_market(SystemOrigin::Root.into(), USDT, pending_market_mock::<T>(PUSDT)));
+        assert_ok!(Loans::<T>::activate_market(SystemOrigin::Root.into(), USDT));
+        assert_ok!(Loans::<T>::update_reward_token_speed(SystemOrigin::Root.into(), USDT, KSM, 1_000_000, 1_000_000, 1u32.into()));
+        frame_system::Pallet::<T>::set_block_number(2u32.into());
+    }: _(SystemOrigin::Root, USDT, KSM)
+    verify {
+        assert_last_event::<T>(Event::<T>::RewardTokenRemoved(USDT, KSM).into());
+    }
+
+    deprecate_market {
+        assert_ok!(Loans::<T>::add_market(SystemOrigin::Root.into(), SKSM, pending_market_mock::<T>(PSKSM)));
+        assert_ok!(Loans::<T>::activate_market(SystemOrigin::Root.into(), SKSM));
+    }: _(SystemOrigin::Root, SKSM, 100u32.into())
+    verify {
+        assert_last_event::<T>(Event::<T>::DeprecatedMarket(SKSM, 100u32.into()).into());
+    }
+
+    remove_market {
+        assert_ok!(Loans::<T>::add_market(SystemOrigin::Root.into(), SKSM, pending_market_mock::<T>(PSKSM)));
+        assert_ok!(Loans::<T>::activate_market(SystemOrigin::Root.into(), SKSM));
+        assert_ok!(Loans::<T>::deprecate_market(SystemOrigin::Root.into(), SKSM, 100u32.into()));
+    }: _(SystemOrigin::Root, SKSM)
+    verify {
+        assert_last_event::<T>(Event::<T>::RemovedMarket(SKSM, PSKSM).into());
+    }
+
+    set_market_price_staleness {
+        assert_ok!(Loans::<T>::add_market(SystemOrigin::Root.into(), USDT, pending_market_mock::<T>(PUSDT)));
+    }: _(SystemOrigin::Root, USDT, Some(60), true)
+    verify {
+        let mut market = pending_market_mock::<T>(PUSDT);
+        market.max_price_age = Some(60);
+        market.liquidate_on_stale_price = true;
+        assert_last_event::<T>(Event::<T>::UpdatedMarket(USDT, market).into());
+    }
 }
 
 impl_benchmark_test_suite!(Loans, crate::mock::new_test_ext(), crate::mock::Test);
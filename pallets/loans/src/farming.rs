@@ -12,8 +12,10 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use frame_support::storage::{with_transaction, TransactionOutcome};
 use sp_io::hashing::blake2_256;
 use sp_runtime::{traits::Zero, DispatchResult};
+use sp_std::vec;
 
 use crate::*;
 
@@ -80,7 +82,12 @@ impl<T: Config> Pallet<T> {
             supply_state.block = current_block_number;
 
             Ok(())
-        })
+        })?;
+
+        for reward_asset_id in RewardTokenConfigs::<T>::iter_key_prefix(asset_id) {
+            Self::update_reward_token_supply_index(asset_id, reward_asset_id)?;
+        }
+        Ok(())
     }
 
     pub(crate) fn update_reward_borrow_index(asset_id: AssetIdOf<T>) -> DispatchResult {
@@ -92,12 +99,7 @@ impl<T: Config> Pallet<T> {
             }
             let borrow_speed = RewardBorrowSpeed::<T>::get(asset_id);
             if !borrow_speed.is_zero() {
-                let current_borrow_amount = TotalBorrows::<T>::get(asset_id);
-                let current_borrow_index = BorrowIndex::<T>::get(asset_id);
-                let base_borrow_amount = current_borrow_index
-                    .reciprocal()
-                    .and_then(|r| r.checked_mul_int(current_borrow_amount))
-                    .ok_or(ArithmeticError::Overflow)?;
+                let base_borrow_amount = Self::total_base_borrows(asset_id)?;
                 let delta_index = Self::calculate_reward_delta_index(
                     delta_block,
                     borrow_speed,
@@ -111,7 +113,93 @@ impl<T: Config> Pallet<T> {
             borrow_state.block = current_block_number;
 
             Ok(())
-        })
+        })?;
+
+        for reward_asset_id in RewardTokenConfigs::<T>::iter_key_prefix(asset_id) {
+            Self::update_reward_token_borrow_index(asset_id, reward_asset_id)?;
+        }
+        Ok(())
+    }
+
+    // The total borrows of `asset_id` divided by its borrow index.
+    fn total_base_borrows(asset_id: AssetIdOf<T>) -> Result<BalanceOf<T>, DispatchError> {
+        let current_borrow_amount = TotalBorrows::<T>::get(asset_id);
+        let current_borrow_index = BorrowIndex::<T>::get(asset_id);
+        let base_borrow_amount = current_borrow_index
+            .reciprocal()
+            .and_then(|r| r.checked_mul_int(current_borrow_amount))
+            .ok_or(ArithmeticError::Overflow)?;
+        Ok(base_borrow_amount)
+    }
+
+    // The number of blocks since `state` was updated during which `config` was accruing.
+    fn reward_token_delta_block(
+        state: &RewardMarketState<T::BlockNumber, BalanceOf<T>>,
+        config: &RewardTokenConfig<T::BlockNumber, BalanceOf<T>>,
+    ) -> T::BlockNumber {
+        <frame_system::Pallet<T>>::block_number()
+            .min(config.end_block)
+            .saturating_sub(state.block)
+    }
+
+    pub(crate) fn update_reward_token_supply_index(
+        asset_id: AssetIdOf<T>,
+        reward_asset_id: AssetIdOf<T>,
+    ) -> DispatchResult {
+        RewardTokenSupplyState::<T>::try_mutate(
+            asset_id,
+            reward_asset_id,
+            |supply_state| -> DispatchResult {
+                if let Some(config) = Self::reward_token_config(asset_id, reward_asset_id) {
+                    let delta_block = Self::reward_token_delta_block(supply_state, &config);
+                    if !delta_block.is_zero() && !config.supply_speed.is_zero() {
+                        let total_supply = TotalSupply::<T>::get(asset_id);
+                        let delta_index = Self::calculate_reward_delta_index(
+                            delta_block,
+                            config.supply_speed,
+                            total_supply,
+                        )?;
+                        supply_state.index = supply_state
+                            .index
+                            .checked_add(delta_index)
+                            .ok_or(ArithmeticError::Overflow)?;
+                    }
+                }
+                supply_state.block = <frame_system::Pallet<T>>::block_number();
+
+                Ok(())
+            },
+        )
+    }
+
+    pub(crate) fn update_reward_token_borrow_index(
+        asset_id: AssetIdOf<T>,
+        reward_asset_id: AssetIdOf<T>,
+    ) -> DispatchResult {
+        RewardTokenBorrowState::<T>::try_mutate(
+            asset_id,
+            reward_asset_id,
+            |borrow_state| -> DispatchResult {
+                if let Some(config) = Self::reward_token_config(asset_id, reward_asset_id) {
+                    let delta_block = Self::reward_token_delta_block(borrow_state, &config);
+                    if !delta_block.is_zero() && !config.borrow_speed.is_zero() {
+                        let base_borrow_amount = Self::total_base_borrows(asset_id)?;
+                        let delta_index = Self::calculate_reward_delta_index(
+                            delta_block,
+                            config.borrow_speed,
+                            base_borrow_amount,
+                        )?;
+                        borrow_state.index = borrow_state
+                            .index
+                            .checked_add(delta_index)
+                            .ok_or(ArithmeticError::Overflow)?;
+                    }
+                }
+                borrow_state.block = <frame_system::Pallet<T>>::block_number();
+
+                Ok(())
+            },
+        )
     }
 
     pub(crate) fn distribute_supplier_reward(
@@ -146,7 +234,27 @@ impl<T: Config> Pallet<T> {
                     Ok(())
                 })
             },
-        )
+        )?;
+
+        for reward_asset_id in RewardTokenConfigs::<T>::iter_key_prefix(asset_id) {
+            RewardTokenSupplierIndex::<T>::try_mutate(
+                (asset_id, reward_asset_id, supplier),
+                |supplier_index| -> DispatchResult {
+                    let supply_state = RewardTokenSupplyState::<T>::get(asset_id, reward_asset_id);
+                    let delta_index = supply_state
+                        .index
+                        .checked_sub(*supplier_index)
+                        .ok_or(ArithmeticError::Underflow)?;
+                    *supplier_index = supply_state.index;
+
+                    let supplier_amount =
+                        AccountDeposits::<T>::get(asset_id, supplier).voucher_balance;
+                    let reward_delta = Self::calculate_reward_delta(supplier_amount, delta_index)?;
+                    Self::accrue_reward_token(supplier, reward_asset_id, reward_delta)
+                },
+            )?;
+        }
+        Ok(())
     }
 
     pub(crate) fn distribute_borrower_reward(
@@ -186,6 +294,50 @@ impl<T: Config> Pallet<T> {
                     Ok(())
                 })
             },
+        )?;
+
+        for reward_asset_id in RewardTokenConfigs::<T>::iter_key_prefix(asset_id) {
+            RewardTokenBorrowerIndex::<T>::try_mutate(
+                (asset_id, reward_asset_id, borrower),
+                |borrower_index| -> DispatchResult {
+                    let borrow_state = RewardTokenBorrowState::<T>::get(asset_id, reward_asset_id);
+                    let delta_index = borrow_state
+                        .index
+                        .checked_sub(*borrower_index)
+                        .ok_or(ArithmeticError::Underflow)?;
+                    *borrower_index = borrow_state.index;
+
+                    let current_borrow_amount = Self::current_borrow_balance(borrower, asset_id)?;
+                    let base_borrow_amount = BorrowIndex::<T>::get(asset_id)
+                        .reciprocal()
+                        .and_then(|r| r.checked_mul_int(current_borrow_amount))
+                        .ok_or(ArithmeticError::Overflow)?;
+                    let reward_delta =
+                        Self::calculate_reward_delta(base_borrow_amount, delta_index)?;
+                    Self::accrue_reward_token(borrower, reward_asset_id, reward_delta)
+                },
+            )?;
+        }
+        Ok(())
+    }
+
+    fn accrue_reward_token(
+        user: &T::AccountId,
+        reward_asset_id: AssetIdOf<T>,
+        reward_delta: BalanceOf<T>,
+    ) -> DispatchResult {
+        if reward_delta.is_zero() {
+            return Ok(());
+        }
+        RewardTokenAccrued::<T>::try_mutate(
+            user,
+            reward_asset_id,
+            |total_reward| -> DispatchResult {
+                *total_reward = total_reward
+                    .checked_add(reward_delta)
+                    .ok_or(ArithmeticError::Overflow)?;
+                Ok(())
+            },
         )
     }
 
@@ -202,15 +354,66 @@ impl<T: Config> Pallet<T> {
         Ok(())
     }
 
+    // Pays each reward accrued by `user` on its own, a reward the pool can't
+    // pay stays accrued and doesn't hold back the others.
     pub(crate) fn pay_reward(user: &T::AccountId) -> DispatchResult {
         let pool_account = Self::reward_account_id()?;
         let reward_asset = T::RewardAssetId::get();
         let total_reward = RewardAccrued::<T>::get(user);
         if total_reward > 0 {
-            T::Assets::transfer(reward_asset, &pool_account, user, total_reward, true)?;
-            RewardAccrued::<T>::remove(user);
+            if Self::transfer_reward(reward_asset, &pool_account, user, total_reward) {
+                RewardAccrued::<T>::remove(user);
+                Self::deposit_event(Event::<T>::RewardPaid(user.clone(), total_reward));
+            }
+        } else {
+            Self::deposit_event(Event::<T>::RewardPaid(user.clone(), total_reward));
+        }
+
+        let reward_tokens: Vec<_> = RewardTokenAccrued::<T>::iter_prefix(user).collect();
+        for (reward_asset_id, amount) in reward_tokens {
+            if Self::transfer_reward(reward_asset_id, &pool_account, user, amount) {
+                RewardTokenAccrued::<T>::remove(user, reward_asset_id);
+                Self::deposit_event(Event::<T>::RewardTokenPaid(
+                    user.clone(),
+                    reward_asset_id,
+                    amount,
+                ));
+            }
         }
-        Self::deposit_event(Event::<T>::RewardPaid(user.clone(), total_reward));
         Ok(())
     }
+
+    // Transfers a reward from the pool, a failed transfer is rolled back.
+    fn transfer_reward(
+        asset_id: AssetIdOf<T>,
+        pool_account: &T::AccountId,
+        user: &T::AccountId,
+        amount: BalanceOf<T>,
+    ) -> bool {
+        with_transaction(
+            || match T::Assets::transfer(asset_id, pool_account, user, amount, true) {
+                Ok(_) => TransactionOutcome::Commit(Ok(true)),
+                Err(_) => TransactionOutcome::Rollback(Ok::<_, DispatchError>(false)),
+            },
+        )
+        .unwrap_or(false)
+    }
+
+    /// The rewards `user` would be paid by `claim_reward`, the native reward
+    /// asset first and then every reward token accrued.
+    pub fn get_pending_rewards(
+        user: &T::AccountId,
+    ) -> Result<Vec<(AssetIdOf<T>, BalanceOf<T>)>, DispatchError> {
+        // The rewards are collected like a claim would, and then rolled back
+        with_transaction(|| {
+            let pending_rewards = Markets::<T>::iter_keys()
+                .try_for_each(|asset_id| Self::collect_market_reward(asset_id, user))
+                .map(|_| {
+                    let mut rewards = vec![(T::RewardAssetId::get(), Self::reward_accrued(user))];
+                    rewards.extend(RewardTokenAccrued::<T>::iter_prefix(user));
+                    rewards
+                });
+            TransactionOutcome::Rollback(pending_rewards)
+        })
+    }
 }
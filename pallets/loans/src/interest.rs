@@ -13,7 +13,10 @@
 // limitations under the License.
 
 use primitives::{Timestamp, SECONDS_PER_YEAR};
-use sp_runtime::{traits::Zero, DispatchResult};
+use sp_runtime::{
+    traits::{Saturating, Zero},
+    DispatchResult,
+};
 
 use crate::*;
 
@@ -41,6 +44,25 @@ impl<T: Config> Pallet<T> {
             borrow_index_new,
         ) = Self::get_market_status(asset_id)?;
 
+        if let InterestRateModel::Adaptive(model) = Self::market(asset_id)?.rate_model {
+            let rate_at_target = model.next_rate_at_target(
+                Self::current_rate_at_target(asset_id, &model),
+                util,
+                now - last_accrued_interest_time,
+            );
+            RateAtTarget::<T>::insert(asset_id, rate_at_target);
+        }
+
+        let fixed_interest_accumulated =
+            Self::accrued_fixed_interest(asset_id, now - last_accrued_interest_time)
+                .ok_or(ArithmeticError::Overflow)?;
+        TotalFixedBorrows::<T>::try_mutate(asset_id, |total| -> DispatchResult {
+            *total = total
+                .checked_add(fixed_interest_accumulated)
+                .ok_or(ArithmeticError::Overflow)?;
+            Ok(())
+        })?;
+
         Self::update_last_accrued_interest_time(asset_id, now)?;
         TotalBorrows::<T>::insert(asset_id, total_borrows_new);
         TotalReserves::<T>::insert(asset_id, total_reserves_new);
@@ -52,9 +74,60 @@ impl<T: Config> Pallet<T> {
         SupplyRate::<T>::insert(asset_id, supply_rate);
         ExchangeRate::<T>::insert(asset_id, exchange_rate);
 
+        Self::record_market_snapshot(
+            asset_id,
+            MarketSnapshot {
+                block: <frame_system::Pallet<T>>::block_number(),
+                timestamp: now,
+                borrow_rate,
+                supply_rate,
+                exchange_rate,
+                utilization: util,
+                total_supply: Self::total_supply(asset_id),
+                total_borrows: total_borrows_new,
+                total_reserves: total_reserves_new,
+            },
+        );
+
         Ok(())
     }
 
+    /// Write the snapshot to the ring buffer of the market if
+    /// `MarketSnapshotInterval` blocks have passed since the last one.
+    fn record_market_snapshot(
+        asset_id: AssetIdOf<T>,
+        snapshot: MarketSnapshot<T::BlockNumber, BalanceOf<T>>,
+    ) {
+        let interval = T::MarketSnapshotInterval::get();
+        let max_snapshots = T::MaxMarketSnapshots::get();
+        if interval.is_zero() || max_snapshots.is_zero() {
+            return;
+        }
+
+        let head = Self::market_snapshot_head(asset_id) % max_snapshots;
+        let last_slot = head.checked_sub(1).unwrap_or(max_snapshots - 1);
+        if let Some(last) = Self::market_snapshots(asset_id, last_slot) {
+            if snapshot.block < last.block.saturating_add(interval) {
+                return;
+            }
+        }
+
+        MarketSnapshots::<T>::insert(asset_id, head, snapshot);
+        MarketSnapshotHead::<T>::insert(asset_id, (head + 1) % max_snapshots);
+    }
+
+    /// The snapshots of a market, oldest first
+    pub fn get_market_snapshots(
+        asset_id: AssetIdOf<T>,
+    ) -> Vec<MarketSnapshot<T::BlockNumber, BalanceOf<T>>> {
+        let max_snapshots = T::MaxMarketSnapshots::get();
+        let head = Self::market_snapshot_head(asset_id).min(max_snapshots);
+        (head..max_snapshots)
+            .chain(0..head)
+            .filter_map(|slot| Self::market_snapshots(asset_id, slot))
+            .collect()
+    }
+
     pub fn get_market_status(
         asset_id: AssetIdOf<T>,
     ) -> Result<
@@ -75,12 +148,24 @@ impl<T: Config> Pallet<T> {
         let mut total_borrows = Self::total_borrows(asset_id);
         let mut total_reserves = Self::total_reserves(asset_id);
         let mut borrow_index = Self::borrow_index(asset_id);
+        // Fixed borrows are funded from the same cash, they count toward the
+        // utilization and the exchange rate
+        let mut total_fixed_borrows = Self::total_fixed_borrows(asset_id);
 
-        let util = Self::calc_utilization_ratio(total_cash, total_borrows, total_reserves)?;
-        let borrow_rate = market
-            .rate_model
-            .get_borrow_rate(util)
-            .ok_or(ArithmeticError::Overflow)?;
+        let util = Self::calc_utilization_ratio(
+            total_cash,
+            total_borrows
+                .checked_add(total_fixed_borrows)
+                .ok_or(ArithmeticError::Overflow)?,
+            total_reserves,
+        )?;
+        let borrow_rate = match market.rate_model {
+            InterestRateModel::Adaptive(ref model) => {
+                model.get_borrow_rate(util, Self::current_rate_at_target(asset_id, model))
+            }
+            ref model => model.get_borrow_rate(util),
+        }
+        .ok_or(ArithmeticError::Overflow)?;
         let supply_rate =
             InterestRateModel::get_supply_rate(borrow_rate, util, market.reserve_factor);
 
@@ -94,10 +179,16 @@ impl<T: Config> Pallet<T> {
             total_borrows = interest_accumulated
                 .checked_add(total_borrows)
                 .ok_or(ArithmeticError::Overflow)?;
+            let fixed_interest_accumulated = Self::accrued_fixed_interest(asset_id, delta_time)
+                .ok_or(ArithmeticError::Overflow)?;
+            total_fixed_borrows = fixed_interest_accumulated
+                .checked_add(total_fixed_borrows)
+                .ok_or(ArithmeticError::Overflow)?;
             total_reserves = market
                 .reserve_factor
                 .mul_floor(interest_accumulated)
-                .checked_add(total_reserves)
+                .checked_add(market.reserve_factor.mul_floor(fixed_interest_accumulated))
+                .and_then(|r| r.checked_add(total_reserves))
                 .ok_or(ArithmeticError::Overflow)?;
 
             borrow_index = Self::increment_index(borrow_rate, borrow_index, delta_time)
@@ -105,8 +196,14 @@ impl<T: Config> Pallet<T> {
                 .ok_or(ArithmeticError::Overflow)?;
         }
 
-        let exchange_rate =
-            Self::calculate_exchange_rate(total_supply, total_cash, total_borrows, total_reserves)?;
+        let exchange_rate = Self::calculate_exchange_rate(
+            total_supply,
+            total_cash,
+            total_borrows
+                .checked_add(total_fixed_borrows)
+                .ok_or(ArithmeticError::Overflow)?,
+            total_reserves,
+        )?;
 
         Ok((
             borrow_rate,
@@ -119,13 +216,20 @@ impl<T: Config> Pallet<T> {
         ))
     }
 
+    // The rate at target of a market using the adaptive `model`
+    fn current_rate_at_target(asset_id: AssetIdOf<T>, model: &AdaptiveModel) -> Rate {
+        Self::rate_at_target(asset_id).unwrap_or(model.initial_rate_at_target)
+    }
+
     /// Update the exchange rate according to the totalCash, totalBorrows and totalSupply.
     /// This function does not accrue interest before calculating the exchange rate.
     /// exchangeRate = (totalCash + totalBorrows - totalReserves) / totalSupply
     pub fn exchange_rate_stored(asset_id: AssetIdOf<T>) -> Result<Rate, DispatchError> {
         let total_supply = Self::total_supply(asset_id);
         let total_cash = Self::get_total_cash(asset_id);
-        let total_borrows = Self::total_borrows(asset_id);
+        let total_borrows = Self::total_borrows(asset_id)
+            .checked_add(Self::total_fixed_borrows(asset_id))
+            .ok_or(ArithmeticError::Overflow)?;
         let total_reserves = Self::total_reserves(asset_id);
 
         Self::calculate_exchange_rate(total_supply, total_cash, total_borrows, total_reserves)
@@ -172,6 +276,41 @@ impl<T: Config> Pallet<T> {
         })
     }
 
+    /// The amount `who` owes on its fixed borrow of `asset_id`, the simple
+    /// interest at the fixed rate since origination included.
+    pub fn current_fixed_borrow_balance(
+        who: &T::AccountId,
+        asset_id: AssetIdOf<T>,
+    ) -> Result<BalanceOf<T>, DispatchError> {
+        match Self::account_fixed_borrows(asset_id, who) {
+            Some(fixed_borrow) => Self::fixed_borrow_balance(&fixed_borrow),
+            None => Ok(Zero::zero()),
+        }
+    }
+
+    pub(crate) fn fixed_borrow_balance(
+        fixed_borrow: &FixedBorrow<BalanceOf<T>, T::BlockNumber>,
+    ) -> Result<BalanceOf<T>, DispatchError> {
+        let delta_time = T::UnixTime::now()
+            .as_secs()
+            .saturating_sub(fixed_borrow.started_at);
+        let balance = Self::accrued_interest(fixed_borrow.rate, fixed_borrow.principal, delta_time)
+            .and_then(|interest| fixed_borrow.principal.checked_add(interest))
+            .ok_or(ArithmeticError::Overflow)?;
+
+        Ok(balance)
+    }
+
+    // The interest accrued by the fixed borrows of a market over `delta_time`
+    fn accrued_fixed_interest(
+        asset_id: AssetIdOf<T>,
+        delta_time: Timestamp,
+    ) -> Option<BalanceOf<T>> {
+        Self::fixed_borrows_yearly_interest(asset_id)
+            .checked_mul(delta_time.into())?
+            .checked_div(SECONDS_PER_YEAR.into())
+    }
+
     fn accrued_interest(
         borrow_rate: Rate,
         amount: BalanceOf<T>,
@@ -27,6 +27,7 @@ use core::cmp::max;
 pub use crate::rate_model::*;
 
 use frame_support::{
+    dispatch::{extract_actual_weight, GetDispatchInfo, PostDispatchInfo},
     log,
     pallet_prelude::*,
     require_transactional,
@@ -41,22 +42,27 @@ use num_traits::cast::ToPrimitive;
 pub use pallet::*;
 use pallet_traits::{
     ConvertToBigUint, Loans as LoansTrait, LoansMarketDataProvider, LoansPositionDataProvider,
-    MarketInfo, MarketStatus, PriceFeeder,
+    MarketInfo, MarketStatus, PriceFeeder, Router,
 };
 use primitives::{
-    is_auxiliary_token, Balance, CurrencyId, Liquidity, Price, Rate, Ratio, Shortfall, Timestamp,
+    is_auxiliary_token, Balance, CurrencyId, EModeCategoryId, Liquidity, Price, Rate, Ratio,
+    Shortfall, Timestamp,
 };
 use sp_runtime::{
     traits::{
-        AccountIdConversion, CheckedAdd, CheckedDiv, CheckedMul, CheckedSub, One,
+        AccountIdConversion, CheckedAdd, CheckedDiv, CheckedMul, CheckedSub, Dispatchable, One,
         SaturatedConversion, Saturating, StaticLookup, Zero,
     },
     ArithmeticError, FixedPointNumber, FixedU128,
 };
-use sp_std::{result::Result, vec::Vec};
+use sp_std::{boxed::Box, result::Result, vec::Vec};
 
 use sp_io::hashing::blake2_256;
-pub use types::{BorrowSnapshot, Deposits, EarnedSnapshot, Market, MarketState, RewardMarketState};
+pub use types::{
+    AccountCollateral, BorrowSnapshot, Deposits, EModeCategory, EarnedSnapshot, FixedBorrow,
+    FixedRateConfig, LiquidationAuction, LiquidationAuctionConfig, Market, MarketDeprecation,
+    MarketSnapshot, MarketState, RewardMarketState, RewardTokenConfig,
+};
 pub use weights::WeightInfo;
 
 mod benchmarking;
@@ -81,6 +87,10 @@ pub const MIN_INTEREST_CALCULATING_INTERVAL: u64 = 100; // 100 seconds
 pub const MAX_EXCHANGE_RATE: u128 = 1_000_000_000_000_000_000; // 1
 pub const MIN_EXCHANGE_RATE: u128 = 20_000_000_000_000_000; // 0.02
 pub const MIN_VALID_EXCHANGE_RATE: u128 = 15_000_000_000_000_000; // 0.015
+/// The maximum number of reward tokens of a market, the native reward asset excluded
+pub const MAX_REWARD_TOKENS: usize = 8;
+/// The maximum number of account and reward keys cleared by one `remove_market` call
+pub const MAX_REMOVED_MARKET_KEYS: u32 = 64;
 
 type AccountIdOf<T> = <T as frame_system::Config>::AccountId;
 type AssetIdOf<T> =
@@ -97,6 +107,9 @@ pub enum Versions {
     V4,
     V5,
     V6,
+    V7,
+    V8,
+    V9,
 }
 
 #[frame_support::pallet]
@@ -139,6 +152,25 @@ pub mod pallet {
 
         #[pallet::constant]
         type LiquidationFreeAssetId: Get<AssetIdOf<Self>>;
+
+        /// The number of blocks between two snapshots of a market,
+        /// zero disables the snapshots.
+        #[pallet::constant]
+        type MarketSnapshotInterval: Get<Self::BlockNumber>;
+
+        /// The number of snapshots kept for each market, the oldest
+        /// snapshot is overwritten once reached.
+        #[pallet::constant]
+        type MaxMarketSnapshots: Get<u32>;
+
+        /// The call dispatched while a flash loan is outstanding.
+        type RuntimeCall: Parameter
+            + Dispatchable<RuntimeOrigin = Self::RuntimeOrigin, PostInfo = PostDispatchInfo>
+            + GetDispatchInfo
+            + From<frame_system::Call<Self>>;
+
+        /// The router used to swap between collateral and borrowed assets.
+        type Router: Router<Self::AccountId, AssetIdOf<Self>, BalanceOf<Self>>;
     }
 
     #[pallet::error]
@@ -171,6 +203,10 @@ pub mod pallet {
         PriceOracleNotReady,
         /// Oracle price is zero
         PriceIsZero,
+        /// Oracle price is older than the maximum price age of the market
+        PriceIsStale,
+        /// Oracle price is suspicious, borrowing of the market is paused
+        PriceIsSuspicious,
         /// Invalid asset id
         InvalidCurrencyId,
         /// Invalid ptoken id
@@ -201,6 +237,58 @@ pub mod pallet {
         CodecError,
         /// Collateral is reserved and cannot be liquidated
         CollateralReserved,
+        /// Isolated collateral cannot be combined with other collateral
+        IsolatedCollateralCombined,
+        /// Asset cannot be borrowed against isolated collateral
+        NotBorrowableInIsolation,
+        /// Debt ceiling of the isolated collateral is exceeded
+        DebtCeilingExceeded,
+        /// E-mode category does not exist
+        EModeCategoryDoesNotExist,
+        /// Flash loans are disabled for the market
+        FlashLoanDisabled,
+        /// Flash loan and its fee were not paid back
+        FlashLoanNotRepaid,
+        /// Liquidation auction parameters are invalid
+        InvalidLiquidationAuctionConfig,
+        /// Liquidation auctions are disabled for the market
+        LiquidationAuctionDisabled,
+        /// Borrows of the market are liquidated by auction
+        LiquidationAuctionEnabled,
+        /// Liquidation auction of the borrow is already open
+        LiquidationAuctionAlreadyOpen,
+        /// Liquidation auction of the borrow is not open
+        LiquidationAuctionNotOpen,
+        /// Liquidation auction cannot be closed while the borrow is liquidatable
+        LiquidationAuctionNotClosable,
+        /// Fixed-rate parameters are invalid
+        InvalidFixedRateConfig,
+        /// Fixed-rate borrows are disabled for the market
+        FixedRateDisabled,
+        /// Term of the fixed borrow is zero or too long
+        InvalidFixedTerm,
+        /// Account already has a fixed borrow in the market
+        FixedBorrowAlreadyExists,
+        /// Fixed borrow does not exist
+        FixedBorrowDoesNotExist,
+        /// Fixed borrow is not matured and the borrower is not liquidatable
+        FixedBorrowNotSettleable,
+        /// The native reward asset cannot be used as a reward token
+        InvalidRewardToken,
+        /// Market has too many reward tokens
+        TooManyRewardTokens,
+        /// Reward token is not an incentive of the market
+        RewardTokenNotFound,
+        /// Reward token is still accruing
+        RewardTokenNotExpired,
+        /// Market is deprecated, new supply and borrows are not allowed
+        MarketDeprecated,
+        /// Market is not deprecated
+        MarketNotDeprecated,
+        /// Market still has supply, borrows or reserves
+        MarketNotEmpty,
+        /// Market still has open liquidation auctions
+        MarketHasOpenAuctions,
     }
 
     #[pallet::event]
@@ -266,6 +354,120 @@ pub mod pallet {
         IncentiveReservesReduced(T::AccountId, AssetIdOf<T>, BalanceOf<T>),
         /// Liquidation free collaterals has been updated
         LiquidationFreeCollateralsUpdated(Vec<AssetIdOf<T>>),
+        /// E-mode category is set or removed
+        /// [category_id, category]
+        EModeCategoryUpdated(EModeCategoryId, Option<EModeCategory>),
+        /// E-mode category of a market is set or removed
+        /// [asset_id, category_id]
+        MarketEModeCategoryUpdated(AssetIdOf<T>, Option<EModeCategoryId>),
+        /// Flash loan fee of a market is set or removed
+        /// [asset_id, fee]
+        FlashLoanFeeUpdated(AssetIdOf<T>, Option<Ratio>),
+        /// Event emitted when a flash loan is paid back
+        /// [borrower, asset_id, amount, fee]
+        FlashLoaned(T::AccountId, AssetIdOf<T>, BalanceOf<T>, BalanceOf<T>),
+        /// Event emitted when a borrow is repaid with swapped collateral
+        /// [sender, collateral_asset_id, redeem_amount, borrow_asset_id, repay_amount]
+        RepaidWithCollateral(
+            T::AccountId,
+            AssetIdOf<T>,
+            BalanceOf<T>,
+            AssetIdOf<T>,
+            BalanceOf<T>,
+        ),
+        /// Event emitted when a borrow is swapped and supplied as collateral
+        /// [sender, borrow_asset_id, borrow_amount, collateral_asset_id, supply_amount]
+        Leveraged(
+            T::AccountId,
+            AssetIdOf<T>,
+            BalanceOf<T>,
+            AssetIdOf<T>,
+            BalanceOf<T>,
+        ),
+        /// Liquidation auction parameters of a market were updated
+        /// [asset_id, config]
+        LiquidationAuctionConfigUpdated(
+            AssetIdOf<T>,
+            Option<LiquidationAuctionConfig<T::BlockNumber>>,
+        ),
+        /// Liquidation auction of a borrow was opened
+        /// [borrower, liquidation_asset_id, started_at]
+        LiquidationAuctionOpened(T::AccountId, AssetIdOf<T>, T::BlockNumber),
+        /// Liquidation auction of a borrow was filled at the current discount
+        /// [liquidator, borrower, liquidation_asset_id, repay_amount, discount]
+        LiquidationAuctionFilled(
+            T::AccountId,
+            T::AccountId,
+            AssetIdOf<T>,
+            BalanceOf<T>,
+            Ratio,
+        ),
+        /// Liquidation auction of a borrow was closed
+        /// [borrower, liquidation_asset_id]
+        LiquidationAuctionClosed(T::AccountId, AssetIdOf<T>),
+        /// Bad debt socialisation of a market was enabled or disabled
+        /// [asset_id, enabled]
+        BadDebtSocialisationUpdated(AssetIdOf<T>, bool),
+        /// Bad debt of a borrower left without collateral was written off
+        /// [borrower, asset_id, bad_debt, reserves_amount, socialised_amount]
+        BadDebtWrittenOff(
+            T::AccountId,
+            AssetIdOf<T>,
+            BalanceOf<T>,
+            BalanceOf<T>,
+            BalanceOf<T>,
+        ),
+        /// Fixed-rate parameters of a market were updated
+        /// [asset_id, config]
+        FixedRateConfigUpdated(AssetIdOf<T>, Option<FixedRateConfig<T::BlockNumber>>),
+        /// Fixed-rate borrow was originated
+        /// [borrower, asset_id, amount, rate, fee, maturity]
+        FixedBorrowed(
+            T::AccountId,
+            AssetIdOf<T>,
+            BalanceOf<T>,
+            Rate,
+            BalanceOf<T>,
+            T::BlockNumber,
+        ),
+        /// Fixed-rate borrow was repaid in full
+        /// [borrower, asset_id, amount]
+        FixedBorrowRepaid(T::AccountId, AssetIdOf<T>, BalanceOf<T>),
+        /// Fixed-rate borrow was moved to the variable borrow of the borrower,
+        /// it is liquidatable when overdue
+        /// [borrower, asset_id, amount, overdue]
+        FixedBorrowSettled(T::AccountId, AssetIdOf<T>, BalanceOf<T>, bool),
+        /// Reward token incentive of a market was updated
+        /// [asset_id, reward_asset_id, supply_speed, borrow_speed, end_block]
+        RewardTokenSpeedUpdated(
+            AssetIdOf<T>,
+            AssetIdOf<T>,
+            BalanceOf<T>,
+            BalanceOf<T>,
+            T::BlockNumber,
+        ),
+        /// Reward token added to the reward pool
+        /// [sender, reward_asset_id, amount]
+        RewardTokenAdded(T::AccountId, AssetIdOf<T>, BalanceOf<T>),
+        /// Reward token withdrawn from the reward pool
+        /// [target_account, reward_asset_id, amount]
+        RewardTokenWithdrawn(T::AccountId, AssetIdOf<T>, BalanceOf<T>),
+        /// Reward token paid to a user
+        /// [user, reward_asset_id, amount]
+        RewardTokenPaid(T::AccountId, AssetIdOf<T>, BalanceOf<T>),
+        /// Expired reward token incentive of a market was removed
+        /// [asset_id, reward_asset_id]
+        RewardTokenRemoved(AssetIdOf<T>, AssetIdOf<T>),
+        /// Market was deprecated
+        /// [asset_id, ramp_duration]
+        DeprecatedMarket(AssetIdOf<T>, T::BlockNumber),
+        /// Empty deprecated market was removed
+        /// [asset_id, ptoken_id]
+        RemovedMarket(AssetIdOf<T>, AssetIdOf<T>),
+        /// Part of the account storage of a deprecated market was cleared,
+        /// `remove_market` has to be called again to remove the market
+        /// [asset_id]
+        MarketRemovalIncomplete(AssetIdOf<T>),
     }
 
     /// The timestamp of the last calculation of accrued interest
@@ -314,6 +516,12 @@ pub mod pallet {
         ValueQuery,
     >;
 
+    /// Accounts with a variable or fixed-rate borrow in any market, in the
+    /// order `get_accounts_with_shortfall` pages through them
+    #[pallet::storage]
+    #[pallet::getter(fn borrowers)]
+    pub type Borrowers<T: Config> = StorageMap<_, Blake2_128Concat, T::AccountId, ()>;
+
     /// Mapping of account addresses to deposit details
     /// CollateralType -> Owner -> Deposits
     #[pallet::storage]
@@ -452,6 +660,258 @@ pub mod pallet {
     pub type RewardAccrued<T: Config> =
         StorageMap<_, Blake2_128Concat, T::AccountId, BalanceOf<T>, ValueQuery>;
 
+    /// Incentive of each market in each of its reward tokens
+    #[pallet::storage]
+    #[pallet::getter(fn reward_token_config)]
+    pub type RewardTokenConfigs<T: Config> = StorageDoubleMap<
+        _,
+        Blake2_128Concat,
+        AssetIdOf<T>,
+        Blake2_128Concat,
+        AssetIdOf<T>,
+        RewardTokenConfig<T::BlockNumber, BalanceOf<T>>,
+        OptionQuery,
+    >;
+
+    /// The reward token supply state for each market and reward token
+    #[pallet::storage]
+    #[pallet::getter(fn reward_token_supply_state)]
+    pub type RewardTokenSupplyState<T: Config> = StorageDoubleMap<
+        _,
+        Blake2_128Concat,
+        AssetIdOf<T>,
+        Blake2_128Concat,
+        AssetIdOf<T>,
+        RewardMarketState<T::BlockNumber, BalanceOf<T>>,
+        ValueQuery,
+    >;
+
+    /// The reward token borrow state for each market and reward token
+    #[pallet::storage]
+    #[pallet::getter(fn reward_token_borrow_state)]
+    pub type RewardTokenBorrowState<T: Config> = StorageDoubleMap<
+        _,
+        Blake2_128Concat,
+        AssetIdOf<T>,
+        Blake2_128Concat,
+        AssetIdOf<T>,
+        RewardMarketState<T::BlockNumber, BalanceOf<T>>,
+        ValueQuery,
+    >;
+
+    /// The reward token index for each market, reward token and supplier
+    /// as of the last time they accrued the reward token
+    #[pallet::storage]
+    #[pallet::getter(fn reward_token_supplier_index)]
+    pub type RewardTokenSupplierIndex<T: Config> = StorageNMap<
+        _,
+        (
+            NMapKey<Blake2_128Concat, AssetIdOf<T>>,
+            NMapKey<Blake2_128Concat, AssetIdOf<T>>,
+            NMapKey<Blake2_128Concat, T::AccountId>,
+        ),
+        BalanceOf<T>,
+        ValueQuery,
+    >;
+
+    /// The reward token index for each market, reward token and borrower
+    /// as of the last time they accrued the reward token
+    #[pallet::storage]
+    #[pallet::getter(fn reward_token_borrower_index)]
+    pub type RewardTokenBorrowerIndex<T: Config> = StorageNMap<
+        _,
+        (
+            NMapKey<Blake2_128Concat, AssetIdOf<T>>,
+            NMapKey<Blake2_128Concat, AssetIdOf<T>>,
+            NMapKey<Blake2_128Concat, T::AccountId>,
+        ),
+        BalanceOf<T>,
+        ValueQuery,
+    >;
+
+    /// The reward tokens accrued but not yet transferred to each user.
+    #[pallet::storage]
+    #[pallet::getter(fn reward_token_accrued)]
+    pub type RewardTokenAccrued<T: Config> = StorageDoubleMap<
+        _,
+        Blake2_128Concat,
+        T::AccountId,
+        Blake2_128Concat,
+        AssetIdOf<T>,
+        BalanceOf<T>,
+        ValueQuery,
+    >;
+
+    /// Amount of each asset borrowed against the collateral of each isolated
+    /// market, accruing interest with the borrow index of the borrowed market.
+    ///
+    /// CollateralAssetId -> BorrowedAssetId -> BorrowSnapshot
+    #[pallet::storage]
+    #[pallet::getter(fn isolated_debt)]
+    pub type IsolatedDebt<T: Config> = StorageDoubleMap<
+        _,
+        Blake2_128Concat,
+        AssetIdOf<T>,
+        Blake2_128Concat,
+        AssetIdOf<T>,
+        BorrowSnapshot<BalanceOf<T>>,
+        ValueQuery,
+    >;
+
+    /// E-mode categories of correlated markets
+    #[pallet::storage]
+    #[pallet::getter(fn emode_categories)]
+    pub type EModeCategories<T: Config> =
+        StorageMap<_, Twox64Concat, EModeCategoryId, EModeCategory, OptionQuery>;
+
+    /// The e-mode category each market belongs to
+    #[pallet::storage]
+    #[pallet::getter(fn market_emode_category)]
+    pub type MarketEModeCategory<T: Config> =
+        StorageMap<_, Blake2_128Concat, AssetIdOf<T>, EModeCategoryId, OptionQuery>;
+
+    /// Fee charged on the flash loans of each market.
+    /// Flash loans are disabled for markets without a fee.
+    #[pallet::storage]
+    #[pallet::getter(fn flash_loan_fee)]
+    pub type FlashLoanFees<T: Config> =
+        StorageMap<_, Blake2_128Concat, AssetIdOf<T>, Ratio, OptionQuery>;
+
+    /// Cash of each market currently lent out by flash loans
+    #[pallet::storage]
+    #[pallet::getter(fn flash_loan_amount)]
+    pub type FlashLoanAmounts<T: Config> =
+        StorageMap<_, Blake2_128Concat, AssetIdOf<T>, BalanceOf<T>, ValueQuery>;
+
+    /// Dutch-auction liquidation parameters of each market.
+    /// Borrows of markets without parameters are liquidated with the fixed incentive.
+    #[pallet::storage]
+    #[pallet::getter(fn liquidation_auction_config)]
+    pub type LiquidationAuctionConfigs<T: Config> = StorageMap<
+        _,
+        Blake2_128Concat,
+        AssetIdOf<T>,
+        LiquidationAuctionConfig<T::BlockNumber>,
+        OptionQuery,
+    >;
+
+    /// Open liquidation auctions by borrower and liquidation asset
+    #[pallet::storage]
+    #[pallet::getter(fn liquidation_auction)]
+    pub type LiquidationAuctions<T: Config> = StorageDoubleMap<
+        _,
+        Blake2_128Concat,
+        T::AccountId,
+        Blake2_128Concat,
+        AssetIdOf<T>,
+        LiquidationAuction<T::BlockNumber>,
+        OptionQuery,
+    >;
+
+    /// Number of open liquidation auctions of each market
+    #[pallet::storage]
+    #[pallet::getter(fn open_liquidation_auctions)]
+    pub type OpenLiquidationAuctions<T: Config> =
+        StorageMap<_, Blake2_128Concat, AssetIdOf<T>, u32, ValueQuery>;
+
+    /// Current rate at target utilization of the markets using an adaptive
+    /// rate model. Markets start from the initial rate at target of their model.
+    #[pallet::storage]
+    #[pallet::getter(fn rate_at_target)]
+    pub type RateAtTarget<T: Config> =
+        StorageMap<_, Blake2_128Concat, AssetIdOf<T>, Rate, OptionQuery>;
+
+    /// Whether the bad debt of each market that its reserves cannot cover
+    /// is socialised across its suppliers
+    #[pallet::storage]
+    #[pallet::getter(fn bad_debt_socialisation)]
+    pub type BadDebtSocialisation<T: Config> =
+        StorageMap<_, Blake2_128Concat, AssetIdOf<T>, bool, ValueQuery>;
+
+    /// Total bad debt written off by each market
+    #[pallet::storage]
+    #[pallet::getter(fn total_bad_debt)]
+    pub type TotalBadDebt<T: Config> =
+        StorageMap<_, Blake2_128Concat, AssetIdOf<T>, BalanceOf<T>, ValueQuery>;
+
+    /// Fixed-rate parameters of each market, fixed borrows are disabled
+    /// for markets without one
+    #[pallet::storage]
+    #[pallet::getter(fn fixed_rate_config)]
+    pub type FixedRateConfigs<T: Config> =
+        StorageMap<_, Blake2_128Concat, AssetIdOf<T>, FixedRateConfig<T::BlockNumber>, OptionQuery>;
+
+    /// Fixed-rate borrow of each account, at most one per market
+    #[pallet::storage]
+    #[pallet::getter(fn account_fixed_borrows)]
+    pub type AccountFixedBorrows<T: Config> = StorageDoubleMap<
+        _,
+        Blake2_128Concat,
+        AssetIdOf<T>,
+        Blake2_128Concat,
+        T::AccountId,
+        FixedBorrow<BalanceOf<T>, T::BlockNumber>,
+        OptionQuery,
+    >;
+
+    /// Total amount owed on the fixed-rate borrows of each market, their
+    /// interest accrued by `accrue_interest` included
+    #[pallet::storage]
+    #[pallet::getter(fn total_fixed_borrows)]
+    pub type TotalFixedBorrows<T: Config> =
+        StorageMap<_, Blake2_128Concat, AssetIdOf<T>, BalanceOf<T>, ValueQuery>;
+
+    /// Yearly interest of the fixed-rate borrows of each market, the sum of
+    /// their principal times their rate
+    #[pallet::storage]
+    #[pallet::getter(fn fixed_borrows_yearly_interest)]
+    pub type FixedBorrowsYearlyInterest<T: Config> =
+        StorageMap<_, Blake2_128Concat, AssetIdOf<T>, BalanceOf<T>, ValueQuery>;
+
+    /// Borrows settled after their fixed term without rolling over, they can
+    /// be liquidated regardless of the shortfall of the borrower until repaid
+    #[pallet::storage]
+    #[pallet::getter(fn overdue_borrows)]
+    pub type OverdueBorrows<T: Config> = StorageDoubleMap<
+        _,
+        Blake2_128Concat,
+        AssetIdOf<T>,
+        Blake2_128Concat,
+        T::AccountId,
+        bool,
+        ValueQuery,
+    >;
+
+    /// Wind-down of each deprecated market
+    #[pallet::storage]
+    #[pallet::getter(fn market_deprecation)]
+    pub type MarketDeprecations<T: Config> = StorageMap<
+        _,
+        Blake2_128Concat,
+        AssetIdOf<T>,
+        MarketDeprecation<T::BlockNumber>,
+        OptionQuery,
+    >;
+
+    /// Ring buffer of the snapshots of each market, indexed by slot
+    #[pallet::storage]
+    #[pallet::getter(fn market_snapshots)]
+    pub type MarketSnapshots<T: Config> = StorageDoubleMap<
+        _,
+        Blake2_128Concat,
+        AssetIdOf<T>,
+        Twox64Concat,
+        u32,
+        MarketSnapshot<T::BlockNumber, BalanceOf<T>>,
+        OptionQuery,
+    >;
+
+    /// The slot the next snapshot of each market is written to
+    #[pallet::storage]
+    #[pallet::getter(fn market_snapshot_head)]
+    pub type MarketSnapshotHead<T: Config> =
+        StorageMap<_, Blake2_128Concat, AssetIdOf<T>, u32, ValueQuery>;
+
     /// DefaultVersion is using for initialize the StorageVersion
     #[pallet::type_value]
     pub(super) fn DefaultVersion<T: Config>() -> Versions {
@@ -561,6 +1021,7 @@ pub mod pallet {
                 stored_market.state = MarketState::Active;
                 stored_market.clone()
             })?;
+            MarketDeprecations::<T>::remove(asset_id);
             Self::deposit_event(Event::<T>::ActivatedMarket(asset_id));
             Ok(().into())
         }
@@ -584,6 +1045,7 @@ pub mod pallet {
                 stored_market.rate_model = rate_model;
                 stored_market.clone()
             })?;
+            RateAtTarget::<T>::remove(asset_id);
             Self::deposit_event(Event::<T>::UpdatedMarket(asset_id, market));
 
             Ok(().into())
@@ -645,7 +1107,7 @@ pub mod pallet {
                 *stored_market = Market {
                     state: stored_market.state,
                     ptoken_id: stored_market.ptoken_id,
-                    rate_model: stored_market.rate_model,
+                    rate_model: stored_market.rate_model.clone(),
                     collateral_factor,
                     liquidation_threshold,
                     reserve_factor,
@@ -654,6 +1116,11 @@ pub mod pallet {
                     liquidate_incentive_reserved_factor,
                     supply_cap,
                     borrow_cap,
+                    max_price_age: stored_market.max_price_age,
+                    liquidate_on_stale_price: stored_market.liquidate_on_stale_price,
+                    isolated: stored_market.isolated,
+                    debt_ceiling: stored_market.debt_ceiling,
+                    borrowable_in_isolation: stored_market.borrowable_in_isolation,
                 };
                 stored_market.clone()
             })?;
@@ -687,6 +1154,9 @@ pub mod pallet {
                 );
             }
             UnderlyingAssetId::<T>::insert(market.ptoken_id, asset_id);
+            if Self::market(asset_id)?.rate_model != market.rate_model {
+                RateAtTarget::<T>::remove(asset_id);
+            }
             let updated_market = Self::mutate_market(asset_id, |stored_market| {
                 *stored_market = market;
                 stored_market.clone()
@@ -794,7 +1264,8 @@ pub mod pallet {
             Ok(().into())
         }
 
-        /// Claim reward from all market.
+        /// Claim reward from all market, in the native reward asset and
+        /// every reward token.
         #[pallet::call_index(8)]
         #[pallet::weight(T::WeightInfo::claim_reward())]
         #[transactional]
@@ -882,7 +1353,8 @@ pub mod pallet {
             let exchange_rate = Self::exchange_rate_stored(asset_id)?;
             Self::update_earned_stored(&who, asset_id, exchange_rate)?;
             let deposits = AccountDeposits::<T>::get(asset_id, &who);
-            let redeem_amount = Self::do_redeem_voucher(&who, asset_id, deposits.voucher_balance)?;
+            let redeem_amount =
+                Self::do_redeem_voucher(&who, asset_id, deposits.voucher_balance, true)?;
             Self::deposit_event(Event::<T>::Redeemed(who, asset_id, redeem_amount));
 
             Ok(().into())
@@ -991,10 +1463,10 @@ pub mod pallet {
         ) -> DispatchResultWithPostInfo {
             let who = ensure_signed(origin)?;
             ensure!(
-                !Self::liquidation_free_collaterals().contains(&collateral_asset_id)
-                    && !is_auxiliary_token(collateral_asset_id),
-                Error::<T>::CollateralReserved
+                !LiquidationAuctionConfigs::<T>::contains_key(liquidation_asset_id),
+                Error::<T>::LiquidationAuctionEnabled
             );
+            Self::ensure_collateral_not_reserved(collateral_asset_id)?;
             Self::accrue_interest(liquidation_asset_id)?;
             Self::accrue_interest(collateral_asset_id)?;
             Self::do_liquidate_borrow(
@@ -1110,7 +1582,7 @@ pub mod pallet {
             Self::ensure_active_market(asset_id)?;
             let exchange_rate = Self::exchange_rate_stored(asset_id)?;
             let voucher_amount = Self::calc_collateral_amount(redeem_amount, exchange_rate)?;
-            let redeem_amount = Self::do_redeem_voucher(&from, asset_id, voucher_amount)?;
+            let redeem_amount = Self::do_redeem_voucher(&from, asset_id, voucher_amount, true)?;
             T::Assets::transfer(asset_id, &from, &receiver, redeem_amount, false)?;
             Self::deposit_event(Event::<T>::IncentiveReservesReduced(
                 receiver,
@@ -1177,65 +1649,915 @@ pub mod pallet {
             let exchange_rate = Self::exchange_rate_stored(asset_id)?;
             Self::update_earned_stored(&who, asset_id, exchange_rate)?;
             let deposits = AccountDeposits::<T>::get(asset_id, &who);
-            let redeem_amount = Self::do_redeem_voucher(&who, asset_id, deposits.voucher_balance)?;
+            let redeem_amount =
+                Self::do_redeem_voucher(&who, asset_id, deposits.voucher_balance, true)?;
             Self::deposit_event(Event::<T>::Redeemed(who, asset_id, redeem_amount));
 
             Ok(().into())
         }
-    }
-}
-
-impl<T: Config> Pallet<T> {
-    pub fn account_id() -> T::AccountId {
-        T::PalletId::get().into_account_truncating()
-    }
 
-    fn get_lf_borrowed_value(account: &T::AccountId) -> Result<FixedU128, DispatchError> {
-        let lf_borrowed_amount =
-            Self::current_borrow_balance(account, T::LiquidationFreeAssetId::get())?;
-        Self::get_asset_value(T::LiquidationFreeAssetId::get(), lf_borrowed_amount)
-    }
+        /// Sets or removes an e-mode category.
+        ///
+        /// - `category_id`: the category to be updated.
+        /// - `category`: the new risk parameters, `None` removes the category.
+        #[pallet::call_index(24)]
+        #[pallet::weight(T::WeightInfo::set_emode_category())]
+        #[transactional]
+        pub fn set_emode_category(
+            origin: OriginFor<T>,
+            category_id: EModeCategoryId,
+            category: Option<EModeCategory>,
+        ) -> DispatchResultWithPostInfo {
+            T::UpdateOrigin::ensure_origin(origin)?;
+            if let Some(category) = category {
+                ensure!(
+                    category.collateral_factor < Ratio::one(),
+                    Error::<T>::InvalidFactor
+                );
+                ensure!(
+                    category.liquidation_threshold < Ratio::one()
+                        && category.liquidation_threshold >= category.collateral_factor,
+                    Error::<T>::InvalidFactor
+                );
+            }
+            EModeCategories::<T>::set(category_id, category);
+            Self::deposit_event(Event::<T>::EModeCategoryUpdated(category_id, category));
+            Ok(().into())
+        }
 
-    fn get_lf_base_position(account: &T::AccountId) -> Result<FixedU128, DispatchError> {
-        let mut total_asset_value: FixedU128 = FixedU128::zero();
-        for (asset_id, _market) in Self::active_markets()
-            .filter(|(asset_id, _)| Self::liquidation_free_collaterals().contains(asset_id))
-        {
-            total_asset_value = total_asset_value
-                .checked_add(&Self::collateral_asset_value(account, asset_id)?)
-                .ok_or(ArithmeticError::Overflow)?;
+        /// Adds a market to or removes it from an e-mode category.
+        ///
+        /// - `asset_id`: market related currency.
+        /// - `category_id`: the category of the market, `None` removes it from its category.
+        #[pallet::call_index(25)]
+        #[pallet::weight(T::WeightInfo::set_market_emode_category())]
+        #[transactional]
+        pub fn set_market_emode_category(
+            origin: OriginFor<T>,
+            asset_id: AssetIdOf<T>,
+            category_id: Option<EModeCategoryId>,
+        ) -> DispatchResultWithPostInfo {
+            T::UpdateOrigin::ensure_origin(origin)?;
+            Self::market(asset_id)?;
+            if let Some(category_id) = category_id {
+                ensure!(
+                    EModeCategories::<T>::contains_key(category_id),
+                    Error::<T>::EModeCategoryDoesNotExist
+                );
+            }
+            MarketEModeCategory::<T>::set(asset_id, category_id);
+            Self::deposit_event(Event::<T>::MarketEModeCategoryUpdated(
+                asset_id,
+                category_id,
+            ));
+            Ok(().into())
         }
-        Ok(total_asset_value)
-    }
 
-    fn get_lf_liquidation_base_position(
-        account: &T::AccountId,
-    ) -> Result<FixedU128, DispatchError> {
-        let mut total_asset_value: FixedU128 = FixedU128::zero();
-        for (asset_id, _market) in Self::active_markets()
-            .filter(|(asset_id, _)| Self::liquidation_free_collaterals().contains(asset_id))
-        {
-            total_asset_value = total_asset_value
-                .checked_add(&Self::liquidation_threshold_asset_value(account, asset_id)?)
-                .ok_or(ArithmeticError::Overflow)?;
+        /// Sets or removes the flash loan fee of a market.
+        ///
+        /// - `asset_id`: market related currency.
+        /// - `fee`: fraction of the loan charged as fee, `None` disables flash loans.
+        #[pallet::call_index(26)]
+        #[pallet::weight(T::WeightInfo::set_flash_loan_fee())]
+        #[transactional]
+        pub fn set_flash_loan_fee(
+            origin: OriginFor<T>,
+            asset_id: AssetIdOf<T>,
+            fee: Option<Ratio>,
+        ) -> DispatchResultWithPostInfo {
+            T::UpdateOrigin::ensure_origin(origin)?;
+            Self::market(asset_id)?;
+            if let Some(fee) = fee {
+                ensure!(fee < Ratio::one(), Error::<T>::InvalidFactor);
+            }
+            FlashLoanFees::<T>::set(asset_id, fee);
+            Self::deposit_event(Event::<T>::FlashLoanFeeUpdated(asset_id, fee));
+            Ok(().into())
         }
-        Ok(total_asset_value)
-    }
 
-    pub fn get_account_liquidity(
-        account: &T::AccountId,
-    ) -> Result<(Liquidity, Shortfall, Liquidity, Shortfall), DispatchError> {
-        let total_borrow_value = Self::total_borrowed_value(account)?;
-        let total_collateral_value = Self::total_collateral_value(account)?;
-        let lf_borrowed_value = Self::get_lf_borrowed_value(account)?;
-        let lf_base_position = Self::get_lf_base_position(account)?;
+        /// Lends market cash to the sender for the duration of `call`.
+        ///
+        /// `amount` plus the flash loan fee of the market is taken back from the
+        /// sender once `call` is dispatched, the fee is added to the reserves.
+        ///
+        /// - `asset_id`: the asset to be borrowed.
+        /// - `amount`: the amount to be borrowed.
+        /// - `call`: the call dispatched with the sender origin.
+        #[pallet::call_index(27)]
+        #[pallet::weight({
+            let dispatch_info = call.get_dispatch_info();
+            (
+                T::WeightInfo::flash_loan().saturating_add(dispatch_info.weight),
+                dispatch_info.class,
+            )
+        })]
+        #[transactional]
+        pub fn flash_loan(
+            origin: OriginFor<T>,
+            asset_id: AssetIdOf<T>,
+            #[pallet::compact] amount: BalanceOf<T>,
+            call: Box<<T as Config>::RuntimeCall>,
+        ) -> DispatchResultWithPostInfo {
+            let who = ensure_signed(origin.clone())?;
+            Self::ensure_open_market(asset_id)?;
+            ensure!(!amount.is_zero(), Error::<T>::InvalidAmount);
+            let fee = Self::flash_loan_fee(asset_id)
+                .ok_or(Error::<T>::FlashLoanDisabled)?
+                .mul_ceil(amount);
+            Self::ensure_enough_cash(asset_id, amount)?;
 
-        log::trace!(
-            target: "loans::get_account_liquidity",
-            "account: {:?}, total_borrow_value: {:?}, total_collateral_value: {:?}, lf_borrowed_value: {:?}, lf_base_position: {:?}",
-            account,
-            total_borrow_value.into_inner(),
-            total_collateral_value.into_inner(),
+            FlashLoanAmounts::<T>::try_mutate(asset_id, |total| -> DispatchResult {
+                *total = total.checked_add(amount).ok_or(ArithmeticError::Overflow)?;
+                Ok(())
+            })?;
+            T::Assets::transfer(asset_id, &Self::account_id(), &who, amount, false)?;
+
+            let dispatch_info = call.get_dispatch_info();
+            let result = call.dispatch(origin);
+            let call_weight = extract_actual_weight(&result, &dispatch_info);
+            result.map_err(|err| err.error)?;
+
+            FlashLoanAmounts::<T>::mutate(asset_id, |total| *total = total.saturating_sub(amount));
+            let repay_amount = amount.checked_add(fee).ok_or(ArithmeticError::Overflow)?;
+            T::Assets::transfer(asset_id, &who, &Self::account_id(), repay_amount, false)
+                .map_err(|_| Error::<T>::FlashLoanNotRepaid)?;
+            TotalReserves::<T>::try_mutate(asset_id, |total_reserves| -> DispatchResult {
+                *total_reserves = total_reserves
+                    .checked_add(fee)
+                    .ok_or(ArithmeticError::Overflow)?;
+                Ok(())
+            })?;
+            Self::deposit_event(Event::<T>::FlashLoaned(who, asset_id, amount, fee));
+
+            Ok(Some(T::WeightInfo::flash_loan().saturating_add(call_weight)).into())
+        }
+
+        /// Repays a borrow with collateral. The collateral is redeemed and swapped
+        /// through the router, the proceeds repay the borrow and any excess stays
+        /// with the sender. The liquidity is only checked once the borrow is repaid.
+        ///
+        /// - `collateral_asset_id`: the collateral to be redeemed.
+        /// - `redeem_amount`: the amount of collateral to be redeemed.
+        /// - `borrow_asset_id`: the borrow to be repaid.
+        /// - `min_amount_out`: the minimum amount the collateral must be swapped for.
+        #[pallet::call_index(28)]
+        #[pallet::weight(T::WeightInfo::repay_with_collateral()
+            .saturating_add(T::Router::swap_exact_tokens_for_tokens_weight()))]
+        #[transactional]
+        pub fn repay_with_collateral(
+            origin: OriginFor<T>,
+            collateral_asset_id: AssetIdOf<T>,
+            #[pallet::compact] redeem_amount: BalanceOf<T>,
+            borrow_asset_id: AssetIdOf<T>,
+            #[pallet::compact] min_amount_out: BalanceOf<T>,
+        ) -> DispatchResultWithPostInfo {
+            let who = ensure_signed(origin)?;
+            ensure!(!redeem_amount.is_zero(), Error::<T>::InvalidAmount);
+            ensure!(
+                collateral_asset_id != borrow_asset_id,
+                Error::<T>::InvalidCurrencyId
+            );
+
+            let lf_enable = Self::liquidation_free_collaterals().contains(&collateral_asset_id);
+            let repay_amount = Self::with_deferred_liquidity_check(&who, lf_enable, || {
+                let balance = T::Assets::balance(collateral_asset_id, &who);
+                Self::do_redeem_with_check(&who, collateral_asset_id, redeem_amount, false)?;
+                let redeemed = T::Assets::balance(collateral_asset_id, &who)
+                    .checked_sub(balance)
+                    .ok_or(ArithmeticError::Underflow)?;
+                let amount_out = T::Router::swap_exact_tokens_for_tokens(
+                    &who,
+                    collateral_asset_id,
+                    borrow_asset_id,
+                    redeemed,
+                    min_amount_out,
+                )?;
+
+                Self::accrue_interest(borrow_asset_id)?;
+                let repay_amount =
+                    amount_out.min(Self::current_borrow_balance(&who, borrow_asset_id)?);
+                Self::do_repay_borrow(&who, borrow_asset_id, repay_amount)?;
+                Ok(repay_amount)
+            })?;
+
+            Self::deposit_event(Event::<T>::RepaidWithCollateral(
+                who,
+                collateral_asset_id,
+                redeem_amount,
+                borrow_asset_id,
+                repay_amount,
+            ));
+            Ok(().into())
+        }
+
+        /// Borrows an asset, swaps it through the router and supplies the proceeds
+        /// as collateral. The liquidity is only checked once the proceeds are supplied.
+        ///
+        /// - `borrow_asset_id`: the asset to be borrowed.
+        /// - `borrow_amount`: the amount to be borrowed.
+        /// - `collateral_asset_id`: the collateral to be supplied.
+        /// - `min_amount_out`: the minimum amount the borrow must be swapped for.
+        #[pallet::call_index(29)]
+        #[pallet::weight(T::WeightInfo::leverage()
+            .saturating_add(T::Router::swap_exact_tokens_for_tokens_weight()))]
+        #[transactional]
+        pub fn leverage(
+            origin: OriginFor<T>,
+            borrow_asset_id: AssetIdOf<T>,
+            #[pallet::compact] borrow_amount: BalanceOf<T>,
+            collateral_asset_id: AssetIdOf<T>,
+            #[pallet::compact] min_amount_out: BalanceOf<T>,
+        ) -> DispatchResultWithPostInfo {
+            let who = ensure_signed(origin)?;
+            ensure!(!borrow_amount.is_zero(), Error::<T>::InvalidAmount);
+            ensure!(
+                collateral_asset_id != borrow_asset_id,
+                Error::<T>::InvalidCurrencyId
+            );
+
+            let lf_enable = borrow_asset_id == T::LiquidationFreeAssetId::get();
+            let supply_amount = Self::with_deferred_liquidity_check(&who, lf_enable, || {
+                Self::do_borrow_with_check(&who, borrow_asset_id, borrow_amount, false)?;
+                let amount_out = T::Router::swap_exact_tokens_for_tokens(
+                    &who,
+                    borrow_asset_id,
+                    collateral_asset_id,
+                    borrow_amount,
+                    min_amount_out,
+                )?;
+
+                Self::do_mint(&who, collateral_asset_id, amount_out)?;
+                if !Self::account_deposits(collateral_asset_id, &who).is_collateral {
+                    Self::do_collateral_asset(&who, collateral_asset_id, true)?;
+                }
+                Ok(amount_out)
+            })?;
+
+            Self::deposit_event(Event::<T>::Leveraged(
+                who,
+                borrow_asset_id,
+                borrow_amount,
+                collateral_asset_id,
+                supply_amount,
+            ));
+            Ok(().into())
+        }
+
+        /// Sets or removes the liquidation auction parameters of a market.
+        /// Borrows of a market with parameters can only be liquidated by auction.
+        ///
+        /// - `asset_id`: market related currency.
+        /// - `config`: the auction parameters, `None` restores the fixed incentive.
+        #[pallet::call_index(30)]
+        #[pallet::weight(T::WeightInfo::set_liquidation_auction_config())]
+        #[transactional]
+        pub fn set_liquidation_auction_config(
+            origin: OriginFor<T>,
+            asset_id: AssetIdOf<T>,
+            config: Option<LiquidationAuctionConfig<T::BlockNumber>>,
+        ) -> DispatchResultWithPostInfo {
+            T::UpdateOrigin::ensure_origin(origin)?;
+            Self::market(asset_id)?;
+            if let Some(config) = config {
+                ensure!(
+                    !config.max_discount.is_zero()
+                        && config.max_discount < Ratio::one()
+                        && !config.duration.is_zero(),
+                    Error::<T>::InvalidLiquidationAuctionConfig
+                );
+            }
+            LiquidationAuctionConfigs::<T>::set(asset_id, config);
+            Self::deposit_event(Event::<T>::LiquidationAuctionConfigUpdated(
+                asset_id, config,
+            ));
+            Ok(().into())
+        }
+
+        /// Opens the liquidation auction of an undercollateralised borrow.
+        ///
+        /// - `borrower`: the borrower to be liquidated.
+        /// - `liquidation_asset_id`: the asset borrowed.
+        #[pallet::call_index(31)]
+        #[pallet::weight(T::WeightInfo::open_liquidation_auction())]
+        #[transactional]
+        pub fn open_liquidation_auction(
+            origin: OriginFor<T>,
+            borrower: T::AccountId,
+            liquidation_asset_id: AssetIdOf<T>,
+        ) -> DispatchResultWithPostInfo {
+            ensure_signed(origin)?;
+            Self::ensure_active_market(liquidation_asset_id)?;
+            ensure!(
+                LiquidationAuctionConfigs::<T>::contains_key(liquidation_asset_id),
+                Error::<T>::LiquidationAuctionDisabled
+            );
+            ensure!(
+                !LiquidationAuctions::<T>::contains_key(&borrower, liquidation_asset_id),
+                Error::<T>::LiquidationAuctionAlreadyOpen
+            );
+            Self::accrue_interest(liquidation_asset_id)?;
+            ensure!(
+                !Self::current_borrow_balance(&borrower, liquidation_asset_id)?.is_zero()
+                    && Self::is_borrow_liquidatable(&borrower, liquidation_asset_id)?,
+                Error::<T>::InsufficientShortfall
+            );
+
+            let started_at = <frame_system::Pallet<T>>::block_number();
+            LiquidationAuctions::<T>::insert(
+                &borrower,
+                liquidation_asset_id,
+                LiquidationAuction { started_at },
+            );
+            OpenLiquidationAuctions::<T>::mutate(liquidation_asset_id, |count| {
+                *count = count.saturating_add(1)
+            });
+            Self::deposit_event(Event::<T>::LiquidationAuctionOpened(
+                borrower,
+                liquidation_asset_id,
+                started_at,
+            ));
+            Ok(().into())
+        }
+
+        /// Fills an open liquidation auction, partially or in full. The sender
+        /// repays the borrow and seizes collateral at the current discount of the auction.
+        /// The auction is closed once the borrower is no longer liquidatable.
+        ///
+        /// - `borrower`: the borrower to be liquidated.
+        /// - `liquidation_asset_id`: the asset to be repaid.
+        /// - `repay_amount`: the amount to be repaid borrow.
+        /// - `collateral_asset_id`: The collateral to seize from the borrower.
+        #[pallet::call_index(32)]
+        #[pallet::weight(T::WeightInfo::fill_liquidation_auction())]
+        #[transactional]
+        pub fn fill_liquidation_auction(
+            origin: OriginFor<T>,
+            borrower: T::AccountId,
+            liquidation_asset_id: AssetIdOf<T>,
+            #[pallet::compact] repay_amount: BalanceOf<T>,
+            collateral_asset_id: AssetIdOf<T>,
+        ) -> DispatchResultWithPostInfo {
+            let who = ensure_signed(origin)?;
+            let config = Self::liquidation_auction_config(liquidation_asset_id)
+                .ok_or(Error::<T>::LiquidationAuctionDisabled)?;
+            let auction = Self::liquidation_auction(&borrower, liquidation_asset_id)
+                .ok_or(Error::<T>::LiquidationAuctionNotOpen)?;
+            Self::ensure_collateral_not_reserved(collateral_asset_id)?;
+            Self::accrue_interest(liquidation_asset_id)?;
+            Self::accrue_interest(collateral_asset_id)?;
+
+            let discount = Self::liquidation_auction_discount(&config, &auction);
+            let liquidate_incentive = Rate::one()
+                .checked_add(&discount.into())
+                .ok_or(ArithmeticError::Overflow)?;
+            Self::do_liquidate_borrow_with_incentive(
+                who.clone(),
+                borrower.clone(),
+                liquidation_asset_id,
+                repay_amount,
+                collateral_asset_id,
+                liquidate_incentive,
+                true,
+            )?;
+            Self::deposit_event(Event::<T>::LiquidationAuctionFilled(
+                who,
+                borrower.clone(),
+                liquidation_asset_id,
+                repay_amount,
+                discount,
+            ));
+
+            if Self::current_borrow_balance(&borrower, liquidation_asset_id)?.is_zero()
+                || !Self::is_borrow_liquidatable(&borrower, liquidation_asset_id)?
+            {
+                Self::remove_liquidation_auction(&borrower, liquidation_asset_id);
+                Self::deposit_event(Event::<T>::LiquidationAuctionClosed(
+                    borrower,
+                    liquidation_asset_id,
+                ));
+            }
+            Ok(().into())
+        }
+
+        /// Closes the liquidation auction of a borrow that is no longer liquidatable,
+        /// or of a market whose auctions were disabled.
+        ///
+        /// - `borrower`: the borrower of the auction.
+        /// - `liquidation_asset_id`: the asset borrowed.
+        #[pallet::call_index(33)]
+        #[pallet::weight(T::WeightInfo::close_liquidation_auction())]
+        #[transactional]
+        pub fn close_liquidation_auction(
+            origin: OriginFor<T>,
+            borrower: T::AccountId,
+            liquidation_asset_id: AssetIdOf<T>,
+        ) -> DispatchResultWithPostInfo {
+            ensure_signed(origin)?;
+            ensure!(
+                LiquidationAuctions::<T>::contains_key(&borrower, liquidation_asset_id),
+                Error::<T>::LiquidationAuctionNotOpen
+            );
+            if LiquidationAuctionConfigs::<T>::contains_key(liquidation_asset_id) {
+                Self::accrue_interest(liquidation_asset_id)?;
+                ensure!(
+                    Self::current_borrow_balance(&borrower, liquidation_asset_id)?.is_zero()
+                        || !Self::is_borrow_liquidatable(&borrower, liquidation_asset_id)?,
+                    Error::<T>::LiquidationAuctionNotClosable
+                );
+            }
+
+            Self::remove_liquidation_auction(&borrower, liquidation_asset_id);
+            Self::deposit_event(Event::<T>::LiquidationAuctionClosed(
+                borrower,
+                liquidation_asset_id,
+            ));
+            Ok(().into())
+        }
+
+        /// Enables or disables the socialisation of the bad debt of a market.
+        /// Bad debt that the reserves cannot cover lowers the exchange rate of the
+        /// market when enabled, it is left in the borrow of the account otherwise.
+        ///
+        /// - `asset_id`: market related currency.
+        /// - `enabled`: whether the bad debt is socialised.
+        #[pallet::call_index(34)]
+        #[pallet::weight(T::WeightInfo::set_bad_debt_socialisation())]
+        #[transactional]
+        pub fn set_bad_debt_socialisation(
+            origin: OriginFor<T>,
+            asset_id: AssetIdOf<T>,
+            enabled: bool,
+        ) -> DispatchResultWithPostInfo {
+            T::UpdateOrigin::ensure_origin(origin)?;
+            Self::market(asset_id)?;
+            BadDebtSocialisation::<T>::insert(asset_id, enabled);
+            Self::deposit_event(Event::<T>::BadDebtSocialisationUpdated(asset_id, enabled));
+            Ok(().into())
+        }
+
+        /// Updates the fixed-rate parameters of a market, `None` disables new
+        /// fixed borrows. Open fixed borrows keep the terms they were originated with.
+        ///
+        /// - `asset_id`: market related currency.
+        /// - `config`: the fixed-rate parameters.
+        #[pallet::call_index(35)]
+        #[pallet::weight(T::WeightInfo::set_fixed_rate_config())]
+        #[transactional]
+        pub fn set_fixed_rate_config(
+            origin: OriginFor<T>,
+            asset_id: AssetIdOf<T>,
+            config: Option<FixedRateConfig<T::BlockNumber>>,
+        ) -> DispatchResultWithPostInfo {
+            T::UpdateOrigin::ensure_origin(origin)?;
+            Self::market(asset_id)?;
+            if let Some(config) = config {
+                ensure!(
+                    config.origination_fee < Ratio::one() && !config.max_term.is_zero(),
+                    Error::<T>::InvalidFixedRateConfig
+                );
+            }
+            FixedRateConfigs::<T>::set(asset_id, config);
+            Self::deposit_event(Event::<T>::FixedRateConfigUpdated(asset_id, config));
+            Ok(().into())
+        }
+
+        /// Sender borrows assets at a fixed rate until the borrow matures.
+        /// The rate is the borrow rate of the market plus its premium, the
+        /// origination fee is deducted from the borrowed amount.
+        ///
+        /// - `asset_id`: the asset to be borrowed.
+        /// - `borrow_amount`: the amount to be borrowed, the fee included.
+        /// - `term`: the number of blocks until the borrow matures.
+        #[pallet::call_index(36)]
+        #[pallet::weight(T::WeightInfo::fixed_borrow())]
+        #[transactional]
+        pub fn fixed_borrow(
+            origin: OriginFor<T>,
+            asset_id: AssetIdOf<T>,
+            #[pallet::compact] borrow_amount: BalanceOf<T>,
+            term: T::BlockNumber,
+        ) -> DispatchResultWithPostInfo {
+            let who = ensure_signed(origin)?;
+            Self::do_fixed_borrow(&who, asset_id, borrow_amount, term)?;
+            Ok(().into())
+        }
+
+        /// Sender repays their fixed borrow in full, with the interest accrued
+        /// at the fixed rate so far.
+        ///
+        /// - `asset_id`: the asset to be repaid.
+        #[pallet::call_index(37)]
+        #[pallet::weight(T::WeightInfo::repay_fixed_borrow())]
+        #[transactional]
+        pub fn repay_fixed_borrow(
+            origin: OriginFor<T>,
+            asset_id: AssetIdOf<T>,
+        ) -> DispatchResultWithPostInfo {
+            let who = ensure_signed(origin)?;
+            Self::do_repay_fixed_borrow(&who, asset_id)?;
+            Ok(().into())
+        }
+
+        /// Moves a fixed borrow to the variable borrow of the borrower once it
+        /// matured, or earlier if the borrower can be liquidated. Matured borrows
+        /// of markets that don't roll over become liquidatable until repaid.
+        ///
+        /// - `borrower`: the borrower of the fixed borrow.
+        /// - `asset_id`: the asset borrowed.
+        #[pallet::call_index(38)]
+        #[pallet::weight(T::WeightInfo::settle_fixed_borrow())]
+        #[transactional]
+        pub fn settle_fixed_borrow(
+            origin: OriginFor<T>,
+            borrower: T::AccountId,
+            asset_id: AssetIdOf<T>,
+        ) -> DispatchResultWithPostInfo {
+            ensure_signed(origin)?;
+            Self::do_settle_fixed_borrow(&borrower, asset_id)?;
+            Ok(().into())
+        }
+
+        /// Updates the incentive of a market in a reward token other than the
+        /// native reward asset. The reward stops accruing at `end_block`.
+        ///
+        /// The origin must conform to `UpdateOrigin`.
+        ///
+        /// - `asset_id`: Market related currency
+        /// - `reward_asset_id`: the reward token.
+        /// - `supply_reward_per_block`: reward amount per block shared by the suppliers.
+        /// - `borrow_reward_per_block`: reward amount per block shared by the borrowers.
+        /// - `end_block`: the block the reward stops accruing at.
+        #[pallet::call_index(39)]
+        #[pallet::weight(T::WeightInfo::update_reward_token_speed())]
+        #[transactional]
+        pub fn update_reward_token_speed(
+            origin: OriginFor<T>,
+            asset_id: AssetIdOf<T>,
+            reward_asset_id: AssetIdOf<T>,
+            supply_reward_per_block: BalanceOf<T>,
+            borrow_reward_per_block: BalanceOf<T>,
+            end_block: T::BlockNumber,
+        ) -> DispatchResultWithPostInfo {
+            T::UpdateOrigin::ensure_origin(origin)?;
+            Self::ensure_active_market(asset_id)?;
+            ensure!(
+                reward_asset_id != T::RewardAssetId::get(),
+                Error::<T>::InvalidRewardToken
+            );
+            ensure!(
+                RewardTokenConfigs::<T>::contains_key(asset_id, reward_asset_id)
+                    || RewardTokenConfigs::<T>::iter_key_prefix(asset_id).count()
+                        < MAX_REWARD_TOKENS,
+                Error::<T>::TooManyRewardTokens
+            );
+
+            Self::update_reward_token_supply_index(asset_id, reward_asset_id)?;
+            Self::update_reward_token_borrow_index(asset_id, reward_asset_id)?;
+            RewardTokenConfigs::<T>::insert(
+                asset_id,
+                reward_asset_id,
+                RewardTokenConfig {
+                    supply_speed: supply_reward_per_block,
+                    borrow_speed: borrow_reward_per_block,
+                    end_block,
+                },
+            );
+
+            Self::deposit_event(Event::<T>::RewardTokenSpeedUpdated(
+                asset_id,
+                reward_asset_id,
+                supply_reward_per_block,
+                borrow_reward_per_block,
+                end_block,
+            ));
+            Ok(().into())
+        }
+
+        /// Add a reward token for the pallet account.
+        ///
+        /// - `reward_asset_id`: the reward token.
+        /// - `amount`: Reward amount added
+        #[pallet::call_index(40)]
+        #[pallet::weight(T::WeightInfo::add_reward_token())]
+        #[transactional]
+        pub fn add_reward_token(
+            origin: OriginFor<T>,
+            reward_asset_id: AssetIdOf<T>,
+            amount: BalanceOf<T>,
+        ) -> DispatchResultWithPostInfo {
+            let who = ensure_signed(origin)?;
+            ensure!(!amount.is_zero(), Error::<T>::InvalidAmount);
+
+            let pool_account = Self::reward_account_id()?;
+            T::Assets::transfer(reward_asset_id, &who, &pool_account, amount, true)?;
+
+            Self::deposit_event(Event::<T>::RewardTokenAdded(who, reward_asset_id, amount));
+            Ok(().into())
+        }
+
+        /// Withdraw a reward token from pallet account.
+        ///
+        /// The origin must conform to `UpdateOrigin`.
+        ///
+        /// - `reward_asset_id`: the reward token.
+        /// - `target_account`: account receive reward token.
+        /// - `amount`: Withdraw amount
+        #[pallet::call_index(41)]
+        #[pallet::weight(T::WeightInfo::withdraw_reward_token())]
+        #[transactional]
+        pub fn withdraw_reward_token(
+            origin: OriginFor<T>,
+            reward_asset_id: AssetIdOf<T>,
+            target_account: <T::Lookup as StaticLookup>::Source,
+            amount: BalanceOf<T>,
+        ) -> DispatchResultWithPostInfo {
+            T::UpdateOrigin::ensure_origin(origin)?;
+            ensure!(!amount.is_zero(), Error::<T>::InvalidAmount);
+
+            let pool_account = Self::reward_account_id()?;
+            let target_account = T::Lookup::lookup(target_account)?;
+
+            T::Assets::transfer(
+                reward_asset_id,
+                &pool_account,
+                &target_account,
+                amount,
+                true,
+            )?;
+            Self::deposit_event(Event::<T>::RewardTokenWithdrawn(
+                target_account,
+                reward_asset_id,
+                amount,
+            ));
+            Ok(().into())
+        }
+
+        /// Deprecates an active market to wind it down.
+        ///
+        /// New supply and borrows of a deprecated market are not allowed while
+        /// repaying, redeeming and liquidating still work. The collateral factor
+        /// of the market is ramped down linearly to zero. Activating the market
+        /// again cancels the deprecation.
+        ///
+        /// The origin must conform to `UpdateOrigin`.
+        ///
+        /// - `asset_id`: Market related currency
+        /// - `ramp_duration`: The number of blocks it takes for the collateral
+        ///   factor to reach zero
+        #[pallet::call_index(42)]
+        #[pallet::weight(T::WeightInfo::deprecate_market())]
+        #[transactional]
+        pub fn deprecate_market(
+            origin: OriginFor<T>,
+            asset_id: AssetIdOf<T>,
+            ramp_duration: T::BlockNumber,
+        ) -> DispatchResultWithPostInfo {
+            T::UpdateOrigin::ensure_origin(origin)?;
+            ensure!(
+                Self::market(asset_id)?.state == MarketState::Active,
+                Error::<T>::MarketNotActivated
+            );
+
+            Self::mutate_market(asset_id, |stored_market| {
+                stored_market.state = MarketState::Deprecated;
+                stored_market.clone()
+            })?;
+            MarketDeprecations::<T>::insert(
+                asset_id,
+                MarketDeprecation {
+                    started_at: <frame_system::Pallet<T>>::block_number(),
+                    ramp_duration,
+                },
+            );
+            Self::deposit_event(Event::<T>::DeprecatedMarket(asset_id, ramp_duration));
+            Ok(().into())
+        }
+
+        /// Removes an empty deprecated market, its ptoken id can be used by
+        /// a new market afterwards.
+        ///
+        /// At most `MAX_REMOVED_MARKET_KEYS` account and reward keys of the market
+        /// are cleared per call. While keys are left the market is kept and
+        /// `MarketRemovalIncomplete` is emitted, the call has to be repeated in a
+        /// later block.
+        ///
+        /// The origin must conform to `UpdateOrigin`.
+        ///
+        /// - `asset_id`: Market related currency
+        #[pallet::call_index(43)]
+        #[pallet::weight(T::WeightInfo::remove_market().saturating_add(
+            T::DbWeight::get().reads_writes(
+                MAX_REMOVED_MARKET_KEYS.into(),
+                MAX_REMOVED_MARKET_KEYS.into()
+            )
+        ))]
+        #[transactional]
+        pub fn remove_market(
+            origin: OriginFor<T>,
+            asset_id: AssetIdOf<T>,
+        ) -> DispatchResultWithPostInfo {
+            T::UpdateOrigin::ensure_origin(origin)?;
+            let market = Self::market(asset_id)?;
+            ensure!(
+                market.state == MarketState::Deprecated,
+                Error::<T>::MarketNotDeprecated
+            );
+            ensure!(
+                Self::total_supply(asset_id).is_zero()
+                    && Self::total_borrows(asset_id).is_zero()
+                    && Self::total_fixed_borrows(asset_id).is_zero()
+                    && Self::total_reserves(asset_id).is_zero(),
+                Error::<T>::MarketNotEmpty
+            );
+            // Open auctions of an empty market are stale, anyone can close them
+            ensure!(
+                Self::open_liquidation_auctions(asset_id).is_zero(),
+                Error::<T>::MarketHasOpenAuctions
+            );
+
+            let (removed, complete) = Self::clear_market_accounts(asset_id);
+            let actual_weight = T::WeightInfo::remove_market()
+                .saturating_add(T::DbWeight::get().reads_writes(removed.into(), removed.into()));
+            if !complete {
+                Self::deposit_event(Event::<T>::MarketRemovalIncomplete(asset_id));
+                return Ok(Some(actual_weight).into());
+            }
+
+            Markets::<T>::remove(asset_id);
+            UnderlyingAssetId::<T>::remove(market.ptoken_id);
+            MarketDeprecations::<T>::remove(asset_id);
+            LastAccruedInterestTime::<T>::remove(asset_id);
+            BorrowIndex::<T>::remove(asset_id);
+            ExchangeRate::<T>::remove(asset_id);
+            BorrowRate::<T>::remove(asset_id);
+            SupplyRate::<T>::remove(asset_id);
+            UtilizationRatio::<T>::remove(asset_id);
+            RateAtTarget::<T>::remove(asset_id);
+            RewardSupplySpeed::<T>::remove(asset_id);
+            RewardBorrowSpeed::<T>::remove(asset_id);
+            MarketEModeCategory::<T>::remove(asset_id);
+            FixedRateConfigs::<T>::remove(asset_id);
+            FlashLoanFees::<T>::remove(asset_id);
+            LiquidationAuctionConfigs::<T>::remove(asset_id);
+            TotalSupply::<T>::remove(asset_id);
+            TotalBorrows::<T>::remove(asset_id);
+            TotalReserves::<T>::remove(asset_id);
+            TotalFixedBorrows::<T>::remove(asset_id);
+            FixedBorrowsYearlyInterest::<T>::remove(asset_id);
+            TotalBadDebt::<T>::remove(asset_id);
+            BadDebtSocialisation::<T>::remove(asset_id);
+            RewardSupplyState::<T>::remove(asset_id);
+            RewardBorrowState::<T>::remove(asset_id);
+            FlashLoanAmounts::<T>::remove(asset_id);
+            OpenLiquidationAuctions::<T>::remove(asset_id);
+            MarketSnapshotHead::<T>::remove(asset_id);
+            LiquidationFreeCollaterals::<T>::mutate(|collaterals| {
+                collaterals.retain(|collateral| *collateral != asset_id)
+            });
+            Self::deposit_event(Event::<T>::RemovedMarket(asset_id, market.ptoken_id));
+            Ok(Some(actual_weight).into())
+        }
+
+        /// Removes the incentive of a market in a reward token once it stopped
+        /// accruing, so that another reward token can take its place. The reward
+        /// not distributed to the suppliers and borrowers by then is forfeited.
+        ///
+        /// The origin must conform to `UpdateOrigin`.
+        ///
+        /// - `asset_id`: Market related currency
+        /// - `reward_asset_id`: the reward token.
+        #[pallet::call_index(44)]
+        #[pallet::weight(T::WeightInfo::remove_reward_token())]
+        #[transactional]
+        pub fn remove_reward_token(
+            origin: OriginFor<T>,
+            asset_id: AssetIdOf<T>,
+            reward_asset_id: AssetIdOf<T>,
+        ) -> DispatchResultWithPostInfo {
+            T::UpdateOrigin::ensure_origin(origin)?;
+            let config = Self::reward_token_config(asset_id, reward_asset_id)
+                .ok_or(Error::<T>::RewardTokenNotFound)?;
+            ensure!(
+                <frame_system::Pallet<T>>::block_number() >= config.end_block,
+                Error::<T>::RewardTokenNotExpired
+            );
+
+            // The indexes are kept up to date with the suppliers and borrowers
+            // in case the reward token is added back
+            Self::update_reward_token_supply_index(asset_id, reward_asset_id)?;
+            Self::update_reward_token_borrow_index(asset_id, reward_asset_id)?;
+            RewardTokenConfigs::<T>::remove(asset_id, reward_asset_id);
+
+            Self::deposit_event(Event::<T>::RewardTokenRemoved(asset_id, reward_asset_id));
+            Ok(().into())
+        }
+
+        /// Updates the oracle price freshness parameters of a market.
+        ///
+        /// The origin must conform to `UpdateOrigin`.
+        ///
+        /// - `asset_id`: Market related currency
+        /// - `max_price_age`: maximum age in seconds of the oracle price, `None`
+        ///   disables the check.
+        /// - `liquidate_on_stale_price`: whether liquidations may proceed on a
+        ///   stale price.
+        #[pallet::call_index(45)]
+        #[pallet::weight(T::WeightInfo::set_market_price_staleness())]
+        #[transactional]
+        pub fn set_market_price_staleness(
+            origin: OriginFor<T>,
+            asset_id: AssetIdOf<T>,
+            max_price_age: Option<Timestamp>,
+            liquidate_on_stale_price: bool,
+        ) -> DispatchResultWithPostInfo {
+            T::UpdateOrigin::ensure_origin(origin)?;
+            let market = Self::mutate_market(asset_id, |stored_market| {
+                stored_market.max_price_age = max_price_age;
+                stored_market.liquidate_on_stale_price = liquidate_on_stale_price;
+                stored_market.clone()
+            })?;
+            Self::deposit_event(Event::<T>::UpdatedMarket(asset_id, market));
+            Ok(().into())
+        }
+    }
+}
+
+impl<T: Config> Pallet<T> {
+    pub fn account_id() -> T::AccountId {
+        T::PalletId::get().into_account_truncating()
+    }
+
+    fn get_lf_borrowed_value(
+        account: &T::AccountId,
+        liquidating: bool,
+    ) -> Result<FixedU128, DispatchError> {
+        let lf_borrowed_amount =
+            Self::current_borrow_balance(account, T::LiquidationFreeAssetId::get())?
+                .checked_add(Self::current_fixed_borrow_balance(
+                    account,
+                    T::LiquidationFreeAssetId::get(),
+                )?)
+                .ok_or(ArithmeticError::Overflow)?;
+        Self::get_asset_value_with_mode(
+            T::LiquidationFreeAssetId::get(),
+            lf_borrowed_amount,
+            liquidating,
+        )
+    }
+
+    fn get_lf_base_position(
+        account: &T::AccountId,
+        liquidating: bool,
+        collateral: &AccountCollateral<AssetIdOf<T>>,
+    ) -> Result<FixedU128, DispatchError> {
+        let mut total_asset_value: FixedU128 = FixedU128::zero();
+        for (asset_id, _market) in Self::active_markets()
+            .filter(|(asset_id, _)| Self::liquidation_free_collaterals().contains(asset_id))
+        {
+            total_asset_value = total_asset_value
+                .checked_add(&Self::collateral_asset_value(
+                    account,
+                    asset_id,
+                    liquidating,
+                    collateral,
+                )?)
+                .ok_or(ArithmeticError::Overflow)?;
+        }
+        Ok(total_asset_value)
+    }
+
+    fn get_lf_liquidation_base_position(
+        account: &T::AccountId,
+        collateral: &AccountCollateral<AssetIdOf<T>>,
+    ) -> Result<FixedU128, DispatchError> {
+        let mut total_asset_value: FixedU128 = FixedU128::zero();
+        for (asset_id, _market) in Self::active_markets()
+            .filter(|(asset_id, _)| Self::liquidation_free_collaterals().contains(asset_id))
+        {
+            total_asset_value = total_asset_value
+                .checked_add(&Self::liquidation_threshold_asset_value(
+                    account, asset_id, collateral,
+                )?)
+                .ok_or(ArithmeticError::Overflow)?;
+        }
+        Ok(total_asset_value)
+    }
+
+    pub fn get_account_liquidity(
+        account: &T::AccountId,
+    ) -> Result<(Liquidity, Shortfall, Liquidity, Shortfall), DispatchError> {
+        let collateral = Self::account_collateral(account);
+        let total_borrow_value = Self::total_borrowed_value(account, false)?;
+        let total_collateral_value = Self::total_collateral_value(account, &collateral)?;
+        let lf_borrowed_value = Self::get_lf_borrowed_value(account, false)?;
+        let lf_base_position = Self::get_lf_base_position(account, false, &collateral)?;
+
+        log::trace!(
+            target: "loans::get_account_liquidity",
+            "account: {:?}, total_borrow_value: {:?}, total_collateral_value: {:?}, lf_borrowed_value: {:?}, lf_base_position: {:?}",
+            account,
+            total_borrow_value.into_inner(),
+            total_collateral_value.into_inner(),
             lf_borrowed_value.into_inner(),
             lf_base_position.into_inner(),
         );
@@ -1270,14 +2592,17 @@ impl<T: Config> Pallet<T> {
         }
     }
 
+    // Liquidity of `account` measured with liquidation thresholds and liquidation prices,
+    // see `get_liquidation_price`.
     pub fn get_account_liquidation_threshold_liquidity(
         account: &T::AccountId,
     ) -> Result<(Liquidity, Shortfall, Liquidity, Shortfall), DispatchError> {
-        let total_borrow_value = Self::total_borrowed_value(account)?;
-        let total_collateral_value = Self::total_liquidation_threshold_value(account)?;
+        let collateral = Self::account_collateral(account);
+        let total_borrow_value = Self::total_borrowed_value(account, true)?;
+        let total_collateral_value = Self::total_liquidation_threshold_value(account, &collateral)?;
 
-        let lf_borrowed_value = Self::get_lf_borrowed_value(account)?;
-        let lf_base_position = Self::get_lf_liquidation_base_position(account)?;
+        let lf_borrowed_value = Self::get_lf_borrowed_value(account, true)?;
+        let lf_base_position = Self::get_lf_liquidation_base_position(account, &collateral)?;
 
         log::trace!(
             target: "loans::get_account_liquidation_threshold_liquidity",
@@ -1320,16 +2645,110 @@ impl<T: Config> Pallet<T> {
         }
     }
 
-    fn total_borrowed_value(borrower: &T::AccountId) -> Result<FixedU128, DispatchError> {
+    /// Borrowers that can be liquidated with their shortfall and liquidation-free
+    /// shortfall at the liquidation threshold, in the order of `Borrowers`.
+    ///
+    /// At most `limit` borrowers after `start_after` are checked, the last of them
+    /// is returned as the cursor of the next page, `None` once all were checked.
+    /// Borrowers whose liquidity can't be computed are skipped.
+    pub fn get_accounts_with_shortfall(
+        start_after: Option<T::AccountId>,
+        limit: u32,
+    ) -> Result<
+        (
+            Vec<(T::AccountId, Shortfall, Shortfall)>,
+            Option<T::AccountId>,
+        ),
+        DispatchError,
+    > {
+        let borrowers = match start_after {
+            Some(cursor) => Borrowers::<T>::iter_keys_from(Borrowers::<T>::hashed_key_for(cursor)),
+            None => Borrowers::<T>::iter_keys(),
+        };
+        let mut page = borrowers.take(limit as usize + 1).collect::<Vec<_>>();
+        let cursor = if page.len() > limit as usize {
+            page.pop();
+            page.last().cloned()
+        } else {
+            None
+        };
+
+        let accounts = page
+            .into_iter()
+            .filter(|borrower| Self::is_account_liquidatable(borrower).unwrap_or(false))
+            .filter_map(|borrower| {
+                let (_, shortfall, _, lf_shortfall) =
+                    Self::get_account_liquidation_threshold_liquidity(&borrower).ok()?;
+                Some((borrower, shortfall, lf_shortfall))
+            })
+            .collect();
+
+        Ok((accounts, cursor))
+    }
+
+    /// Positions of `account` in each market it supplies to or borrows from:
+    /// the supplied and borrowed amounts, whether the supply is used as collateral,
+    /// and the value of the supply and the borrow.
+    #[allow(clippy::type_complexity)]
+    pub fn get_account_positions(
+        account: &T::AccountId,
+    ) -> Result<
+        Vec<(
+            AssetIdOf<T>,
+            BalanceOf<T>,
+            BalanceOf<T>,
+            bool,
+            Liquidity,
+            Liquidity,
+        )>,
+        DispatchError,
+    > {
+        let mut positions = Vec::new();
+        for asset_id in Markets::<T>::iter_keys() {
+            let deposits = Self::account_deposits(asset_id, account);
+            let supply_amount = if deposits.voucher_balance.is_zero() {
+                Zero::zero()
+            } else {
+                Self::calc_underlying_amount(
+                    deposits.voucher_balance,
+                    Self::exchange_rate_stored(asset_id)?,
+                )?
+            };
+            let borrow_amount = Self::current_borrow_balance(account, asset_id)?
+                .checked_add(Self::current_fixed_borrow_balance(account, asset_id)?)
+                .ok_or(ArithmeticError::Overflow)?;
+            if supply_amount.is_zero() && borrow_amount.is_zero() {
+                continue;
+            }
+            positions.push((
+                asset_id,
+                supply_amount,
+                borrow_amount,
+                deposits.is_collateral,
+                Self::get_asset_value(asset_id, supply_amount)?,
+                Self::get_asset_value(asset_id, borrow_amount)?,
+            ));
+        }
+
+        Ok(positions)
+    }
+
+    fn total_borrowed_value(
+        borrower: &T::AccountId,
+        liquidating: bool,
+    ) -> Result<FixedU128, DispatchError> {
         let mut total_borrow_value: FixedU128 = FixedU128::zero();
         for (asset_id, _) in Self::active_markets() {
-            let currency_borrow_amount = Self::current_borrow_balance(borrower, asset_id)?;
+            let currency_borrow_amount = Self::current_borrow_balance(borrower, asset_id)?
+                .checked_add(Self::current_fixed_borrow_balance(borrower, asset_id)?)
+                .ok_or(ArithmeticError::Overflow)?;
             if currency_borrow_amount.is_zero() {
                 continue;
             }
-            total_borrow_value = Self::get_asset_value(asset_id, currency_borrow_amount)?
-                .checked_add(&total_borrow_value)
-                .ok_or(ArithmeticError::Overflow)?;
+            total_borrow_value =
+                Self::get_asset_value_with_mode(asset_id, currency_borrow_amount, liquidating)?
+                    .checked_add(&total_borrow_value)
+                    .ok_or(ArithmeticError::Overflow)?;
         }
 
         Ok(total_borrow_value)
@@ -1338,6 +2757,7 @@ impl<T: Config> Pallet<T> {
     fn current_collateral_balance(
         supplier: &T::AccountId,
         asset_id: AssetIdOf<T>,
+        collateral: &AccountCollateral<AssetIdOf<T>>,
     ) -> Result<BalanceOf<T>, DispatchError> {
         if !AccountDeposits::<T>::contains_key(asset_id, supplier) {
             return Ok(BalanceOf::<T>::zero());
@@ -1353,7 +2773,17 @@ impl<T: Config> Pallet<T> {
         let underlying_amount =
             Self::calc_underlying_amount(deposits.voucher_balance, exchange_rate)?;
         let market = Self::market(asset_id)?;
-        let effects_amount = market.collateral_factor.mul_ceil(underlying_amount);
+        if market.isolated && collateral.combined {
+            return Ok(BalanceOf::<T>::zero());
+        }
+        let collateral_factor = Self::effective_collateral_factor(
+            asset_id,
+            match collateral.emode {
+                Some((_, category)) => max(market.collateral_factor, category.collateral_factor),
+                None => market.collateral_factor,
+            },
+        );
+        let effects_amount = collateral_factor.mul_ceil(underlying_amount);
 
         Ok(BalanceOf::<T>::saturated_from(effects_amount))
     }
@@ -1361,15 +2791,18 @@ impl<T: Config> Pallet<T> {
     fn collateral_asset_value(
         supplier: &T::AccountId,
         asset_id: AssetIdOf<T>,
+        liquidating: bool,
+        collateral: &AccountCollateral<AssetIdOf<T>>,
     ) -> Result<FixedU128, DispatchError> {
-        let effects_amount = Self::current_collateral_balance(supplier, asset_id)?;
+        let effects_amount = Self::current_collateral_balance(supplier, asset_id, collateral)?;
 
-        Self::get_asset_value(asset_id, effects_amount)
+        Self::get_asset_value_with_mode(asset_id, effects_amount, liquidating)
     }
 
     fn liquidation_threshold_asset_value(
         borrower: &T::AccountId,
         asset_id: AssetIdOf<T>,
+        collateral: &AccountCollateral<AssetIdOf<T>>,
     ) -> Result<FixedU128, DispatchError> {
         if !AccountDeposits::<T>::contains_key(asset_id, borrower) {
             return Ok(FixedU128::zero());
@@ -1385,16 +2818,30 @@ impl<T: Config> Pallet<T> {
         let underlying_amount =
             Self::calc_underlying_amount(deposits.voucher_balance, exchange_rate)?;
         let market = Self::market(asset_id)?;
-        let effects_amount = market.liquidation_threshold.mul_ceil(underlying_amount);
+        if market.isolated && collateral.combined {
+            return Ok(FixedU128::zero());
+        }
+        let liquidation_threshold = match collateral.emode {
+            Some((_, category)) => {
+                max(market.liquidation_threshold, category.liquidation_threshold)
+            }
+            None => market.liquidation_threshold,
+        };
+        let effects_amount = liquidation_threshold.mul_ceil(underlying_amount);
 
-        Self::get_asset_value(asset_id, effects_amount)
+        Self::get_liquidation_asset_value(asset_id, effects_amount)
     }
 
-    fn total_collateral_value(supplier: &T::AccountId) -> Result<FixedU128, DispatchError> {
+    fn total_collateral_value(
+        supplier: &T::AccountId,
+        collateral: &AccountCollateral<AssetIdOf<T>>,
+    ) -> Result<FixedU128, DispatchError> {
         let mut total_asset_value: FixedU128 = FixedU128::zero();
         for (asset_id, _market) in Self::active_markets() {
             total_asset_value = total_asset_value
-                .checked_add(&Self::collateral_asset_value(supplier, asset_id)?)
+                .checked_add(&Self::collateral_asset_value(
+                    supplier, asset_id, false, collateral,
+                )?)
                 .ok_or(ArithmeticError::Overflow)?;
         }
 
@@ -1403,12 +2850,13 @@ impl<T: Config> Pallet<T> {
 
     fn total_liquidation_threshold_value(
         borrower: &T::AccountId,
+        collateral: &AccountCollateral<AssetIdOf<T>>,
     ) -> Result<FixedU128, DispatchError> {
         let mut total_asset_value: FixedU128 = FixedU128::zero();
         for (asset_id, _market) in Self::active_markets() {
             total_asset_value = total_asset_value
                 .checked_add(&Self::liquidation_threshold_asset_value(
-                    borrower, asset_id,
+                    borrower, asset_id, collateral,
                 )?)
                 .ok_or(ArithmeticError::Overflow)?;
         }
@@ -1421,6 +2869,7 @@ impl<T: Config> Pallet<T> {
         asset_id: AssetIdOf<T>,
         redeemer: &T::AccountId,
         voucher_amount: BalanceOf<T>,
+        check_liquidity: bool,
     ) -> DispatchResult {
         log::trace!(
             target: "loans::redeem_allowed",
@@ -1438,12 +2887,13 @@ impl<T: Config> Pallet<T> {
         let redeem_amount = Self::calc_underlying_amount(voucher_amount, exchange_rate)?;
         Self::ensure_enough_cash(asset_id, redeem_amount)?;
 
-        if !deposit.is_collateral {
+        if !deposit.is_collateral || !check_liquidity {
             return Ok(());
         }
 
         let market = Self::market(asset_id)?;
-        let effects_amount = market.collateral_factor.mul_ceil(redeem_amount);
+        let effects_amount = Self::effective_collateral_factor(asset_id, market.collateral_factor)
+            .mul_ceil(redeem_amount);
         let redeem_effects_value = Self::get_asset_value(asset_id, effects_amount)?;
         log::trace!(
             target: "loans::redeem_allowed",
@@ -1466,8 +2916,9 @@ impl<T: Config> Pallet<T> {
         who: &T::AccountId,
         asset_id: AssetIdOf<T>,
         voucher_amount: BalanceOf<T>,
+        check_liquidity: bool,
     ) -> Result<BalanceOf<T>, DispatchError> {
-        Self::redeem_allowed(asset_id, who, voucher_amount)?;
+        Self::redeem_allowed(asset_id, who, voucher_amount, check_liquidity)?;
         Self::update_reward_supply_index(asset_id)?;
         Self::distribute_supplier_reward(asset_id, who)?;
 
@@ -1506,19 +2957,140 @@ impl<T: Config> Pallet<T> {
         asset_id: AssetIdOf<T>,
         borrower: &T::AccountId,
         borrow_amount: BalanceOf<T>,
+        check_liquidity: bool,
     ) -> DispatchResult {
+        ensure!(
+            !T::PriceFeeder::is_suspicious(&asset_id),
+            Error::<T>::PriceIsSuspicious
+        );
         Self::ensure_under_borrow_cap(asset_id, borrow_amount)?;
         Self::ensure_enough_cash(asset_id, borrow_amount)?;
         let borrow_value = Self::get_asset_value(asset_id, borrow_amount)?;
-        Self::ensure_liquidity(
-            borrower,
-            borrow_value,
-            asset_id == T::LiquidationFreeAssetId::get(),
-        )?;
+        if let Some(collateral_id) = Self::isolated_collateral(borrower) {
+            ensure!(
+                Self::market(asset_id)?.borrowable_in_isolation,
+                Error::<T>::NotBorrowableInIsolation
+            );
+            let mut debt = borrow_value;
+            for (debt_asset_id, snapshot) in IsolatedDebt::<T>::iter_prefix(collateral_id) {
+                let debt_amount = Self::accrued_borrow_balance(debt_asset_id, &snapshot)?;
+                debt = Self::get_asset_value(debt_asset_id, debt_amount)?
+                    .checked_add(&debt)
+                    .ok_or(ArithmeticError::Overflow)?;
+            }
+            ensure!(
+                debt <= Self::market(collateral_id)?.debt_ceiling,
+                Error::<T>::DebtCeilingExceeded
+            );
+        }
+        if check_liquidity {
+            Self::ensure_liquidity(
+                borrower,
+                borrow_value,
+                asset_id == T::LiquidationFreeAssetId::get(),
+            )?;
+        }
 
         Ok(())
     }
 
+    /// The e-mode category all collateral and borrows of `account` belong to, if any.
+    pub fn account_emode_category(
+        account: &T::AccountId,
+    ) -> Option<(EModeCategoryId, EModeCategory)> {
+        Self::account_collateral(account).emode
+    }
+
+    // Leaving e-mode lowers the collateral factors of `account`, so it must not
+    // end up with a shortfall when an operation takes it out of its category.
+    fn ensure_emode_exit_allowed(account: &T::AccountId, was_in_emode: bool) -> DispatchResult {
+        if was_in_emode && Self::account_emode_category(account).is_none() {
+            let (_, shortfall, _, _) = Self::get_account_liquidity(account)?;
+            ensure!(shortfall.is_zero(), Error::<T>::InsufficientLiquidity);
+        }
+
+        Ok(())
+    }
+
+    // The collateral state of `account`, read once from all markets.
+    pub(crate) fn account_collateral(account: &T::AccountId) -> AccountCollateral<AssetIdOf<T>> {
+        let mut collateral = AccountCollateral::default();
+        let mut found = false;
+        let mut category_id = None;
+        let mut mixed = false;
+        for (asset_id, market) in Markets::<T>::iter() {
+            let deposits = Self::account_deposits(asset_id, account);
+            if deposits.is_collateral {
+                collateral.combined |= found;
+                found = true;
+                if market.isolated && collateral.isolated.is_none() {
+                    collateral.isolated = Some(asset_id);
+                }
+            }
+
+            let in_use = (deposits.is_collateral && !deposits.voucher_balance.is_zero())
+                || !Self::account_borrows(asset_id, account).principal.is_zero()
+                || AccountFixedBorrows::<T>::contains_key(asset_id, account);
+            if in_use && !mixed {
+                mixed = match Self::market_emode_category(asset_id) {
+                    Some(id) => *category_id.get_or_insert(id) != id,
+                    None => true,
+                };
+            }
+        }
+        if !mixed {
+            collateral.emode = category_id.and_then(|id| Some((id, Self::emode_categories(id)?)));
+        }
+
+        collateral
+    }
+
+    // The isolated market whose deposits `account` uses as collateral, if any.
+    fn isolated_collateral(account: &T::AccountId) -> Option<AssetIdOf<T>> {
+        Self::account_collateral(account).isolated
+    }
+
+    // Whether `account` uses the deposits of any market other than `asset_id` as collateral.
+    fn has_other_collateral(account: &T::AccountId, asset_id: AssetIdOf<T>) -> bool {
+        Markets::<T>::iter_keys()
+            .filter(|id| *id != asset_id)
+            .any(|id| Self::account_deposits(id, account).is_collateral)
+    }
+
+    // Books `amount` of `asset_id` borrowed or repaid by `borrower` against the
+    // debt of its isolated collateral, if any.
+    fn update_isolated_debt(
+        borrower: &T::AccountId,
+        asset_id: AssetIdOf<T>,
+        amount: BalanceOf<T>,
+        borrowed: bool,
+    ) -> DispatchResult {
+        let collateral_id = match Self::isolated_collateral(borrower) {
+            Some(collateral_id) => collateral_id,
+            None => return Ok(()),
+        };
+        IsolatedDebt::<T>::try_mutate_exists(
+            collateral_id,
+            asset_id,
+            |snapshot| -> DispatchResult {
+                let debt = match snapshot {
+                    Some(snapshot) => Self::accrued_borrow_balance(asset_id, snapshot)?,
+                    None => Zero::zero(),
+                };
+                let debt = if borrowed {
+                    debt.checked_add(amount).ok_or(ArithmeticError::Overflow)?
+                } else {
+                    debt.saturating_sub(amount)
+                };
+                *snapshot = (!debt.is_zero()).then(|| BorrowSnapshot {
+                    principal: debt,
+                    borrow_index: Self::borrow_index(asset_id),
+                });
+                Ok(())
+            },
+        )
+    }
+
     #[require_transactional]
     fn do_repay_borrow_with_amount(
         borrower: &T::AccountId,
@@ -1552,6 +3124,11 @@ impl<T: Config> Pallet<T> {
             },
         );
         TotalBorrows::<T>::insert(asset_id, total_borrows_new);
+        if account_borrows_new.is_zero() {
+            OverdueBorrows::<T>::remove(asset_id, borrower);
+            Self::update_borrowers(borrower);
+        }
+        Self::update_isolated_debt(borrower, asset_id, repay_amount, false)?;
 
         Ok(())
     }
@@ -1563,6 +3140,14 @@ impl<T: Config> Pallet<T> {
         asset_id: AssetIdOf<T>,
     ) -> Result<BalanceOf<T>, DispatchError> {
         let snapshot: BorrowSnapshot<BalanceOf<T>> = Self::account_borrows(asset_id, who);
+        Self::accrued_borrow_balance(asset_id, &snapshot)
+    }
+
+    // Balance of a borrow snapshot of `asset_id` with the interest accrued since.
+    fn accrued_borrow_balance(
+        asset_id: AssetIdOf<T>,
+        snapshot: &BorrowSnapshot<BalanceOf<T>>,
+    ) -> Result<BalanceOf<T>, DispatchError> {
         if snapshot.principal.is_zero() || snapshot.borrow_index.is_zero() {
             return Ok(Zero::zero());
         }
@@ -1595,16 +3180,116 @@ impl<T: Config> Pallet<T> {
             account_earned.total_earned_prior
         };
 
-        AccountEarned::<T>::insert(
-            asset_id,
-            who,
-            EarnedSnapshot {
-                exchange_rate_prior: exchange_rate,
-                total_earned_prior: total_earned_prior_new,
-            },
+        AccountEarned::<T>::insert(
+            asset_id,
+            who,
+            EarnedSnapshot {
+                exchange_rate_prior: exchange_rate,
+                total_earned_prior: total_earned_prior_new,
+            },
+        );
+
+        Ok(())
+    }
+
+    /// Checks if the borrow of `liquidation_asset_id` of `borrower` can be liquidated,
+    /// overdue borrows can be liquidated regardless of the shortfall of the borrower
+    fn is_borrow_liquidatable(
+        borrower: &T::AccountId,
+        liquidation_asset_id: AssetIdOf<T>,
+    ) -> Result<bool, DispatchError> {
+        Ok(Self::overdue_borrows(liquidation_asset_id, borrower)
+            || Self::is_liquidatable(borrower)?)
+    }
+
+    // Checks if any borrow of `borrower` can be liquidated.
+    fn is_account_liquidatable(borrower: &T::AccountId) -> Result<bool, DispatchError> {
+        for asset_id in Markets::<T>::iter_keys() {
+            let borrowing = !Self::account_borrows(asset_id, borrower)
+                .principal
+                .is_zero()
+                || AccountFixedBorrows::<T>::contains_key(asset_id, borrower);
+            if borrowing && Self::is_borrow_liquidatable(borrower, asset_id)? {
+                return Ok(true);
+            }
+        }
+        Ok(false)
+    }
+
+    // Adds `account` to `Borrowers` while it borrows from any market, removes it otherwise.
+    fn update_borrowers(account: &T::AccountId) {
+        let borrowing = Markets::<T>::iter_keys().any(|asset_id| {
+            !Self::account_borrows(asset_id, account).principal.is_zero()
+                || AccountFixedBorrows::<T>::contains_key(asset_id, account)
+        });
+        if borrowing {
+            Borrowers::<T>::insert(account, ());
+        } else {
+            Borrowers::<T>::remove(account);
+        }
+    }
+
+    /// Checks if the borrows of `borrower` can be liquidated
+    fn is_liquidatable(borrower: &T::AccountId) -> Result<bool, DispatchError> {
+        let (liquidity, shortfall, lf_liquidity, _) =
+            Self::get_account_liquidation_threshold_liquidity(borrower)?;
+
+        // C_other >= B_other + B_dot_over
+        // C_other >= B_other + max(B_dot - C_lf, 0)
+        // C_other + C_lf >= B_other + B_dot - B_dot + C_lf + max(B_dot - C_lf, 0)
+        // C_all - B_all >= max(0, C_lf - B_dot)
+        // C_all - B_all >= 0 && C_all - B_all >= max(0, C_lf - B_dot)
+        // shortfall == 0 && liquidity > lf_liquidity
+        Ok(!(shortfall.is_zero() && liquidity >= lf_liquidity))
+    }
+
+    // Ensures `collateral_asset_id` is not reserved from liquidations.
+    fn ensure_collateral_not_reserved(collateral_asset_id: AssetIdOf<T>) -> DispatchResult {
+        ensure!(
+            !Self::liquidation_free_collaterals().contains(&collateral_asset_id)
+                && !is_auxiliary_token(collateral_asset_id),
+            Error::<T>::CollateralReserved
+        );
+        Ok(())
+    }
+
+    // The collateral discount of `auction`, it grows linearly from zero up to
+    // the maximum discount of the market.
+    fn liquidation_auction_discount(
+        config: &LiquidationAuctionConfig<T::BlockNumber>,
+        auction: &LiquidationAuction<T::BlockNumber>,
+    ) -> Ratio {
+        let elapsed = <frame_system::Pallet<T>>::block_number().saturating_sub(auction.started_at);
+        if elapsed >= config.duration {
+            return config.max_discount;
+        }
+        let progress = Ratio::from_rational(
+            elapsed.saturated_into::<u64>(),
+            config.duration.saturated_into::<u64>(),
         );
+        Ratio::from_parts(progress.mul_floor(config.max_discount.deconstruct()))
+    }
 
-        Ok(())
+    // The collateral factor of a market, it is ramped down linearly to zero
+    // while the market is deprecated.
+    pub(crate) fn effective_collateral_factor(
+        asset_id: AssetIdOf<T>,
+        collateral_factor: Ratio,
+    ) -> Ratio {
+        let deprecation = match Self::market_deprecation(asset_id) {
+            Some(deprecation) => deprecation,
+            None => return collateral_factor,
+        };
+        let elapsed =
+            <frame_system::Pallet<T>>::block_number().saturating_sub(deprecation.started_at);
+        if elapsed >= deprecation.ramp_duration {
+            return Ratio::zero();
+        }
+        let remaining = Ratio::from_rational(
+            (deprecation.ramp_duration - elapsed).saturated_into::<u64>(),
+            deprecation.ramp_duration.saturated_into::<u64>(),
+        );
+        Ratio::from_parts(remaining.mul_floor(collateral_factor.deconstruct()))
     }
 
     /// Checks if the liquidation should be allowed to occur
@@ -1622,26 +3307,18 @@ impl<T: Config> Pallet<T> {
             repay_amount,
             market
         );
-        let (liquidity, shortfall, lf_liquidity, _) =
-            Self::get_account_liquidation_threshold_liquidity(borrower)?;
-
-        // C_other >= B_other + B_dot_over
-        // C_other >= B_other + max(B_dot - C_lf, 0)
-        // C_other + C_lf >= B_other + B_dot - B_dot + C_lf + max(B_dot - C_lf, 0)
-        // C_all - B_all >= max(0, C_lf - B_dot)
-        // C_all - B_all >= 0 && C_all - B_all >= max(0, C_lf - B_dot)
-        // shortfall == 0 && liquidity > lf_liquidity
-        if shortfall.is_zero() && liquidity >= lf_liquidity {
+        if !Self::is_borrow_liquidatable(borrower, liquidation_asset_id)? {
             return Err(Error::<T>::InsufficientShortfall.into());
         }
 
         // The liquidator may not repay more than 50%(close_factor) of the borrower's borrow balance.
         let account_borrows = Self::current_borrow_balance(borrower, liquidation_asset_id)?;
         let account_borrows_value: FixedU128 =
-            Self::get_asset_value(liquidation_asset_id, account_borrows)?;
-        let repay_value = Self::get_asset_value(liquidation_asset_id, repay_amount)?;
+            Self::get_liquidation_asset_value(liquidation_asset_id, account_borrows)?;
+        let repay_value = Self::get_liquidation_asset_value(liquidation_asset_id, repay_amount)?;
         let effects_borrows_value = if liquidation_asset_id == T::LiquidationFreeAssetId::get() {
-            let base_position = Self::get_lf_base_position(borrower)?;
+            let base_position =
+                Self::get_lf_base_position(borrower, true, &Self::account_collateral(borrower))?;
             if account_borrows_value > base_position {
                 account_borrows_value - base_position
             } else {
@@ -1678,6 +3355,33 @@ impl<T: Config> Pallet<T> {
         liquidation_asset_id: AssetIdOf<T>,
         repay_amount: BalanceOf<T>,
         collateral_asset_id: AssetIdOf<T>,
+    ) -> DispatchResult {
+        let market = Self::market(liquidation_asset_id)?;
+        Self::do_liquidate_borrow_with_incentive(
+            liquidator,
+            borrower,
+            liquidation_asset_id,
+            repay_amount,
+            collateral_asset_id,
+            market.liquidate_incentive,
+            false,
+        )
+    }
+
+    /// Liquidates a borrow like [`Pallet::do_liquidate_borrow`], paying
+    /// `liquidate_incentive` instead of the incentive of the market.
+    ///
+    /// The reserves take their share of the discount only if `auction` is set,
+    /// of the repaid value otherwise.
+    #[require_transactional]
+    pub fn do_liquidate_borrow_with_incentive(
+        liquidator: T::AccountId,
+        borrower: T::AccountId,
+        liquidation_asset_id: AssetIdOf<T>,
+        repay_amount: BalanceOf<T>,
+        collateral_asset_id: AssetIdOf<T>,
+        liquidate_incentive: Rate,
+        auction: bool,
     ) -> DispatchResult {
         Self::ensure_active_market(liquidation_asset_id)?;
         Self::ensure_active_market(collateral_asset_id)?;
@@ -1698,11 +3402,13 @@ impl<T: Config> Pallet<T> {
             .checked_mul_int(deposits.voucher_balance)
             .ok_or(ArithmeticError::Overflow)?;
 
-        let collateral_value = Self::get_asset_value(collateral_asset_id, borrower_deposit_amount)?;
+        let collateral_value =
+            Self::get_liquidation_asset_value(collateral_asset_id, borrower_deposit_amount)?;
         // liquidate_value contains the incentive of liquidator and the punishment of the borrower
-        let liquidate_value = Self::get_asset_value(liquidation_asset_id, repay_amount)?
-            .checked_mul(&market.liquidate_incentive)
-            .ok_or(ArithmeticError::Overflow)?;
+        let liquidate_value =
+            Self::get_liquidation_asset_value(liquidation_asset_id, repay_amount)?
+                .checked_mul(&liquidate_incentive)
+                .ok_or(ArithmeticError::Overflow)?;
 
         if collateral_value < liquidate_value {
             return Err(Error::<T>::InsufficientCollateral.into());
@@ -1717,7 +3423,7 @@ impl<T: Config> Pallet<T> {
         // if liquidate_value >= 340282366920938463463.374607431768211455,
         // FixedU128::saturating_from_integer(liquidate_value) will overflow, so we use from_inner
         // instead of saturating_from_integer, and after calculation use into_inner to get final value.
-        let collateral_token_price: FixedU128 = Self::get_price(collateral_asset_id)?;
+        let collateral_token_price: FixedU128 = Self::get_liquidation_price(collateral_asset_id)?;
         let real_collateral_underlying_amount = liquidate_value
             .checked_div(&collateral_token_price)
             .ok_or(ArithmeticError::Underflow)?
@@ -1731,8 +3437,92 @@ impl<T: Config> Pallet<T> {
             collateral_asset_id,
             repay_amount,
             real_collateral_underlying_amount,
+            liquidate_incentive,
+            auction,
             &market,
         )?;
+        Self::write_off_bad_debt(&borrower)?;
+
+        Ok(())
+    }
+
+    /// Writes off the borrows of `borrower` once all of its collateral was seized.
+    ///
+    /// The bad debt of each market is covered by its reserves first, the rest is
+    /// socialised across the suppliers if the market is configured to do so.
+    /// Nothing is written off while `borrower` has a fixed-rate borrow open.
+    #[require_transactional]
+    fn write_off_bad_debt(borrower: &T::AccountId) -> DispatchResult {
+        // collateral left in a market of any state can still be seized
+        let has_collateral_or_fixed_borrow = Markets::<T>::iter_keys().any(|asset_id| {
+            let deposits = Self::account_deposits(asset_id, borrower);
+            (deposits.is_collateral && !deposits.voucher_balance.is_zero())
+                || AccountFixedBorrows::<T>::contains_key(asset_id, borrower)
+        });
+        if has_collateral_or_fixed_borrow {
+            return Ok(());
+        }
+
+        for (asset_id, _) in Self::active_markets() {
+            if Self::account_borrows(asset_id, borrower)
+                .principal
+                .is_zero()
+            {
+                continue;
+            }
+            Self::accrue_interest(asset_id)?;
+            let bad_debt = Self::current_borrow_balance(borrower, asset_id)?;
+            let total_reserves = Self::total_reserves(asset_id);
+            let reserves_amount = bad_debt.min(total_reserves);
+            let socialised_amount = if Self::bad_debt_socialisation(asset_id) {
+                bad_debt - reserves_amount
+            } else {
+                Zero::zero()
+            };
+            let written_off = reserves_amount
+                .checked_add(socialised_amount)
+                .ok_or(ArithmeticError::Overflow)?;
+            if written_off.is_zero() {
+                continue;
+            }
+
+            Self::update_reward_borrow_index(asset_id)?;
+            Self::distribute_borrower_reward(asset_id, borrower)?;
+            AccountBorrows::<T>::insert(
+                asset_id,
+                borrower,
+                BorrowSnapshot {
+                    principal: bad_debt - written_off,
+                    borrow_index: Self::borrow_index(asset_id),
+                },
+            );
+            TotalBorrows::<T>::mutate(asset_id, |total_borrows| {
+                *total_borrows = total_borrows.saturating_sub(written_off)
+            });
+            if written_off == bad_debt {
+                OverdueBorrows::<T>::remove(asset_id, borrower);
+                Self::update_borrowers(borrower);
+            }
+            TotalReserves::<T>::insert(asset_id, total_reserves - reserves_amount);
+            TotalBadDebt::<T>::try_mutate(asset_id, |total_bad_debt| -> DispatchResult {
+                *total_bad_debt = total_bad_debt
+                    .checked_add(written_off)
+                    .ok_or(ArithmeticError::Overflow)?;
+                Ok(())
+            })?;
+            Self::update_isolated_debt(borrower, asset_id, written_off, false)?;
+            if !socialised_amount.is_zero() {
+                ExchangeRate::<T>::insert(asset_id, Self::exchange_rate_stored(asset_id)?);
+            }
+
+            Self::deposit_event(Event::<T>::BadDebtWrittenOff(
+                borrower.clone(),
+                asset_id,
+                bad_debt,
+                reserves_amount,
+                socialised_amount,
+            ));
+        }
 
         Ok(())
     }
@@ -1745,6 +3535,8 @@ impl<T: Config> Pallet<T> {
         collateral_asset_id: AssetIdOf<T>,
         repay_amount: BalanceOf<T>,
         collateral_underlying_amount: BalanceOf<T>,
+        liquidate_incentive: Rate,
+        auction: bool,
         market: &Market<BalanceOf<T>>,
     ) -> DispatchResult {
         log::trace!(
@@ -1791,6 +3583,11 @@ impl<T: Config> Pallet<T> {
             },
         );
         TotalBorrows::<T>::insert(liquidation_asset_id, total_borrows_new);
+        if account_borrows_new.is_zero() {
+            OverdueBorrows::<T>::remove(liquidation_asset_id, borrower);
+            Self::update_borrowers(borrower);
+        }
+        Self::update_isolated_debt(borrower, liquidation_asset_id, repay_amount, false)?;
 
         // update supply index before modify supply balance.
         Self::update_reward_supply_index(collateral_asset_id)?;
@@ -1816,12 +3613,20 @@ impl<T: Config> Pallet<T> {
                 Ok(())
             },
         )?;
-        let incentive_reserved_amount = market.liquidate_incentive_reserved_factor.mul_floor(
-            FixedU128::from_inner(collateral_amount)
-                .checked_div(&market.liquidate_incentive)
-                .map(|r| r.into_inner())
-                .ok_or(ArithmeticError::Underflow)?,
-        );
+        let repaid_collateral_amount = FixedU128::from_inner(collateral_amount)
+            .checked_div(&liquidate_incentive)
+            .map(|r| r.into_inner())
+            .ok_or(ArithmeticError::Underflow)?;
+        // the reserves of an auction only share the discount, which starts at zero
+        let incentive_reserved_amount = if auction {
+            market
+                .liquidate_incentive_reserved_factor
+                .mul_floor(collateral_amount.saturating_sub(repaid_collateral_amount))
+        } else {
+            market
+                .liquidate_incentive_reserved_factor
+                .mul_floor(repaid_collateral_amount)
+        };
         // increase liquidator's voucher_balance
         AccountDeposits::<T>::try_mutate(
             collateral_asset_id,
@@ -1859,7 +3664,51 @@ impl<T: Config> Pallet<T> {
         Ok(())
     }
 
-    // Ensures a given `asset_id` is an active market.
+    // Removes an open liquidation auction and updates the auction count of its market.
+    fn remove_liquidation_auction(borrower: &T::AccountId, liquidation_asset_id: AssetIdOf<T>) {
+        LiquidationAuctions::<T>::remove(borrower, liquidation_asset_id);
+        OpenLiquidationAuctions::<T>::mutate(liquidation_asset_id, |count| {
+            *count = count.saturating_sub(1)
+        });
+    }
+
+    // Clears at most `MAX_REMOVED_MARKET_KEYS` account and reward keys of a
+    // market being removed. Returns the number of keys cleared and whether all
+    // of them are gone.
+    fn clear_market_accounts(asset_id: AssetIdOf<T>) -> (u32, bool) {
+        let clears: [&dyn Fn(u32) -> sp_io::MultiRemovalResults; 14] = [
+            &|limit| AccountDeposits::<T>::clear_prefix(asset_id, limit, None),
+            &|limit| AccountBorrows::<T>::clear_prefix(asset_id, limit, None),
+            &|limit| AccountEarned::<T>::clear_prefix(asset_id, limit, None),
+            &|limit| AccountFixedBorrows::<T>::clear_prefix(asset_id, limit, None),
+            &|limit| OverdueBorrows::<T>::clear_prefix(asset_id, limit, None),
+            &|limit| RewardSupplierIndex::<T>::clear_prefix(asset_id, limit, None),
+            &|limit| RewardBorrowerIndex::<T>::clear_prefix(asset_id, limit, None),
+            &|limit| RewardTokenSupplierIndex::<T>::clear_prefix((asset_id,), limit, None),
+            &|limit| RewardTokenBorrowerIndex::<T>::clear_prefix((asset_id,), limit, None),
+            &|limit| RewardTokenConfigs::<T>::clear_prefix(asset_id, limit, None),
+            &|limit| RewardTokenSupplyState::<T>::clear_prefix(asset_id, limit, None),
+            &|limit| RewardTokenBorrowState::<T>::clear_prefix(asset_id, limit, None),
+            &|limit| IsolatedDebt::<T>::clear_prefix(asset_id, limit, None),
+            &|limit| MarketSnapshots::<T>::clear_prefix(asset_id, limit, None),
+        ];
+
+        let mut budget = MAX_REMOVED_MARKET_KEYS;
+        for clear in clears {
+            if budget.is_zero() {
+                return (MAX_REMOVED_MARKET_KEYS, false);
+            }
+            let result = clear(budget);
+            budget = budget.saturating_sub(result.unique);
+            if result.maybe_cursor.is_some() {
+                return (MAX_REMOVED_MARKET_KEYS.saturating_sub(budget), false);
+            }
+        }
+        (MAX_REMOVED_MARKET_KEYS.saturating_sub(budget), true)
+    }
+
+    // Ensures a given `asset_id` is an active market, deprecated markets
+    // included.
     fn ensure_active_market(asset_id: AssetIdOf<T>) -> Result<Market<BalanceOf<T>>, DispatchError> {
         Self::active_markets()
             .find(|(id, _)| id == &asset_id)
@@ -1867,6 +3716,16 @@ impl<T: Config> Pallet<T> {
             .ok_or_else(|| Error::<T>::MarketNotActivated.into())
     }
 
+    // Ensures a given `asset_id` is an active market that is not deprecated.
+    fn ensure_open_market(asset_id: AssetIdOf<T>) -> Result<Market<BalanceOf<T>>, DispatchError> {
+        let market = Self::ensure_active_market(asset_id)?;
+        ensure!(
+            market.state != MarketState::Deprecated,
+            Error::<T>::MarketDeprecated
+        );
+        Ok(market)
+    }
+
     /// Ensure market is enough to supply `amount` asset.
     fn ensure_under_supply_cap(asset_id: AssetIdOf<T>, amount: BalanceOf<T>) -> DispatchResult {
         let market = Self::market(asset_id)?;
@@ -1888,7 +3747,8 @@ impl<T: Config> Pallet<T> {
         let market = Self::market(asset_id)?;
         let total_borrows = Self::total_borrows(asset_id);
         let new_total_borrows = total_borrows
-            .checked_add(amount)
+            .checked_add(Self::total_fixed_borrows(asset_id))
+            .and_then(|b| b.checked_add(amount))
             .ok_or(ArithmeticError::Overflow)?;
         ensure!(
             new_total_borrows <= market.borrow_cap,
@@ -1900,7 +3760,7 @@ impl<T: Config> Pallet<T> {
 
     /// Make sure there is enough cash available in the pool
     fn ensure_enough_cash(asset_id: AssetIdOf<T>, amount: BalanceOf<T>) -> DispatchResult {
-        let reducible_cash = Self::get_total_cash(asset_id)
+        let reducible_cash = T::Assets::reducible_balance(asset_id, &Self::account_id(), false)
             .checked_sub(Self::total_reserves(asset_id))
             .ok_or(ArithmeticError::Underflow)?;
         if reducible_cash < amount {
@@ -1950,6 +3810,98 @@ impl<T: Config> Pallet<T> {
         Err(Error::<T>::InsufficientLiquidity.into())
     }
 
+    // Runs `f`, whose steps skip their liquidity checks, then applies the rule of
+    // `ensure_liquidity` to the liquidity `account` is left with.
+    // `lf_enable`: the operation borrows dot or redeems assets in `LiquidationFreeCollaterals`.
+    fn with_deferred_liquidity_check<R>(
+        account: &T::AccountId,
+        lf_enable: bool,
+        f: impl FnOnce() -> Result<R, DispatchError>,
+    ) -> Result<R, DispatchError> {
+        let was_in_emode = Self::account_emode_category(account).is_some();
+        let result = f()?;
+
+        let (total_liquidity, shortfall, lf_liquidity, lf_shortfall) =
+            Self::get_account_liquidity(account)?;
+        let allowed = if lf_enable {
+            shortfall.is_zero() || lf_shortfall.is_zero()
+        } else {
+            shortfall.is_zero() && total_liquidity >= lf_liquidity
+        };
+        ensure!(allowed, Error::<T>::InsufficientLiquidity);
+        Self::ensure_emode_exit_allowed(account, was_in_emode)?;
+
+        Ok(result)
+    }
+
+    // Borrows `amount`, `check_liquidity` is unset by the steps of composed
+    // operations whose liquidity is checked by `with_deferred_liquidity_check`.
+    fn do_borrow_with_check(
+        borrower: &AccountIdOf<T>,
+        asset_id: AssetIdOf<T>,
+        amount: BalanceOf<T>,
+        check_liquidity: bool,
+    ) -> Result<(), DispatchError> {
+        Self::ensure_open_market(asset_id)?;
+
+        Self::accrue_interest(asset_id)?;
+        Self::borrow_allowed(asset_id, borrower, amount, check_liquidity)?;
+        Self::update_isolated_debt(borrower, asset_id, amount, true)?;
+        let was_in_emode = Self::account_emode_category(borrower).is_some();
+
+        // update borrow index after accrue interest.
+        Self::update_reward_borrow_index(asset_id)?;
+        Self::distribute_borrower_reward(asset_id, borrower)?;
+
+        let account_borrows = Self::current_borrow_balance(borrower, asset_id)?;
+        let account_borrows_new = account_borrows
+            .checked_add(amount)
+            .ok_or(ArithmeticError::Overflow)?;
+        let total_borrows = Self::total_borrows(asset_id);
+        let total_borrows_new = total_borrows
+            .checked_add(amount)
+            .ok_or(ArithmeticError::Overflow)?;
+        AccountBorrows::<T>::insert(
+            asset_id,
+            borrower,
+            BorrowSnapshot {
+                principal: account_borrows_new,
+                borrow_index: Self::borrow_index(asset_id),
+            },
+        );
+        TotalBorrows::<T>::insert(asset_id, total_borrows_new);
+        Borrowers::<T>::insert(borrower, ());
+        if check_liquidity {
+            Self::ensure_emode_exit_allowed(borrower, was_in_emode)?;
+        }
+        T::Assets::transfer(asset_id, &Self::account_id(), borrower, amount, false)?;
+        Self::deposit_event(Event::<T>::Borrowed(borrower.clone(), asset_id, amount));
+        Ok(())
+    }
+
+    // Redeems `amount`, `check_liquidity` is unset by the steps of composed
+    // operations whose liquidity is checked by `with_deferred_liquidity_check`.
+    fn do_redeem_with_check(
+        supplier: &AccountIdOf<T>,
+        asset_id: AssetIdOf<T>,
+        amount: BalanceOf<T>,
+        check_liquidity: bool,
+    ) -> Result<(), DispatchError> {
+        Self::ensure_active_market(asset_id)?;
+        Self::accrue_interest(asset_id)?;
+        let exchange_rate = Self::exchange_rate_stored(asset_id)?;
+        Self::update_earned_stored(supplier, asset_id, exchange_rate)?;
+        let voucher_amount = Self::calc_collateral_amount(amount, exchange_rate)?;
+        let redeem_amount =
+            Self::do_redeem_voucher(supplier, asset_id, voucher_amount, check_liquidity)?;
+        Self::deposit_event(Event::<T>::Redeemed(
+            supplier.clone(),
+            asset_id,
+            redeem_amount,
+        ));
+        Ok(())
+    }
+
     pub fn calc_underlying_amount(
         voucher_amount: BalanceOf<T>,
         exchange_rate: Rate,
@@ -1969,8 +3921,11 @@ impl<T: Config> Pallet<T> {
             .ok_or(ArithmeticError::Underflow)?)
     }
 
+    // Cash lent out by flash loans is still counted, so that the exchange rate
+    // cannot be moved by a flash loan.
     fn get_total_cash(asset_id: AssetIdOf<T>) -> BalanceOf<T> {
         T::Assets::reducible_balance(asset_id, &Self::account_id(), false)
+            .saturating_add(Self::flash_loan_amount(asset_id))
     }
 
     // Returns the uniform format price.
@@ -1978,32 +3933,86 @@ impl<T: Config> Pallet<T> {
     // This particular price makes it easy to calculate the value ,
     // because we don't have to consider decimal for each asset. ref: get_asset_value
     //
-    // Returns `Err` if the oracle price not ready
+    // Returns `Err` if the oracle price not ready or stale
     pub fn get_price(asset_id: AssetIdOf<T>) -> Result<Price, DispatchError> {
-        let (price, _) =
+        Self::get_price_with_mode(asset_id, false)
+    }
+
+    // Same as `get_price`, but a stale price is accepted if the market of `asset_id`
+    // allows liquidations to proceed on stale prices.
+    pub fn get_liquidation_price(asset_id: AssetIdOf<T>) -> Result<Price, DispatchError> {
+        Self::get_price_with_mode(asset_id, true)
+    }
+
+    fn get_price_with_mode(
+        asset_id: AssetIdOf<T>,
+        liquidating: bool,
+    ) -> Result<Price, DispatchError> {
+        let (price, updated_at) =
             T::PriceFeeder::get_price(&asset_id).ok_or(Error::<T>::PriceOracleNotReady)?;
         if price.is_zero() {
             return Err(Error::<T>::PriceIsZero.into());
         }
         log::trace!(
-            target: "loans::get_price", "price: {:?}", price.into_inner()
+            target: "loans::get_price",
+            "price: {:?}, updated_at: {:?}",
+            price.into_inner(),
+            updated_at
         );
 
+        // Emergency prices are reported with a zero timestamp, they are set by
+        // the oracle collective on purpose and never become stale.
+        if updated_at.is_zero() {
+            return Ok(price);
+        }
+        if let Some(market) = Markets::<T>::get(asset_id) {
+            if Self::is_price_stale(&market, updated_at)
+                && !(liquidating && market.liquidate_on_stale_price)
+            {
+                return Err(Error::<T>::PriceIsStale.into());
+            }
+        }
+
         Ok(price)
     }
 
+    // Checks the age of a price updated at `updated_at` (in milliseconds, as reported
+    // by the oracle) against `max_price_age` of the market.
+    fn is_price_stale(market: &Market<BalanceOf<T>>, updated_at: Timestamp) -> bool {
+        market.max_price_age.map_or(false, |max_price_age| {
+            let now: Timestamp = T::UnixTime::now().as_millis().saturated_into();
+            now.saturating_sub(updated_at) > max_price_age.saturating_mul(1000)
+        })
+    }
+
     // Returns the value of the asset, in dollars.
     // Formula: `value = oracle_price * balance / 1e18(oracle_price_decimal) / asset_decimal`
     // As the price is a result of `oracle_price * 10.pow(18 - asset_decimal)`,
     // then `value = price * balance / 1e18`.
     // We use FixedU128::from_inner(balance) instead of `balance / 1e18`.
     //
-    // Returns `Err` if oracle price not ready or arithmetic error.
+    // Returns `Err` if oracle price not ready, stale or arithmetic error.
     pub fn get_asset_value(
         asset_id: AssetIdOf<T>,
         amount: BalanceOf<T>,
     ) -> Result<FixedU128, DispatchError> {
-        let value = Self::get_price(asset_id)?
+        Self::get_asset_value_with_mode(asset_id, amount, false)
+    }
+
+    // Same as `get_asset_value`, but priced with `get_liquidation_price`.
+    pub fn get_liquidation_asset_value(
+        asset_id: AssetIdOf<T>,
+        amount: BalanceOf<T>,
+    ) -> Result<FixedU128, DispatchError> {
+        Self::get_asset_value_with_mode(asset_id, amount, true)
+    }
+
+    fn get_asset_value_with_mode(
+        asset_id: AssetIdOf<T>,
+        amount: BalanceOf<T>,
+        liquidating: bool,
+    ) -> Result<FixedU128, DispatchError> {
+        let value = Self::get_price_with_mode(asset_id, liquidating)?
             .checked_mul(&FixedU128::from_inner(amount))
             .ok_or(ArithmeticError::Overflow)?;
 
@@ -2038,9 +4047,11 @@ impl<T: Config> Pallet<T> {
         )
     }
 
-    // All markets that are `MarketStatus::Active`.
+    // All markets that are `MarketStatus::Active` or `MarketStatus::Deprecated`.
     fn active_markets() -> impl Iterator<Item = (AssetIdOf<T>, Market<BalanceOf<T>>)> {
-        Markets::<T>::iter().filter(|(_, market)| market.state == MarketState::Active)
+        Markets::<T>::iter().filter(|(_, market)| {
+            matches!(market.state, MarketState::Active | MarketState::Deprecated)
+        })
     }
 
     // Returns a stored asset_id
@@ -2077,7 +4088,7 @@ impl<T: Config> LoansTrait<AssetIdOf<T>, AccountIdOf<T>, BalanceOf<T>> for Palle
         amount: BalanceOf<T>,
     ) -> Result<(), DispatchError> {
         ensure!(!amount.is_zero(), Error::<T>::InvalidAmount);
-        Self::ensure_active_market(asset_id)?;
+        Self::ensure_open_market(asset_id)?;
         Self::ensure_under_supply_cap(asset_id, amount)?;
 
         Self::accrue_interest(asset_id)?;
@@ -2115,21 +4126,114 @@ impl<T: Config> LoansTrait<AssetIdOf<T>, AccountIdOf<T>, BalanceOf<T>> for Palle
         asset_id: AssetIdOf<T>,
         amount: BalanceOf<T>,
     ) -> Result<(), DispatchError> {
+        Self::do_borrow_with_check(borrower, asset_id, amount, true)
+    }
+
+    fn do_fixed_borrow(
+        borrower: &AccountIdOf<T>,
+        asset_id: AssetIdOf<T>,
+        amount: BalanceOf<T>,
+        term: T::BlockNumber,
+    ) -> DispatchResult {
+        Self::ensure_open_market(asset_id)?;
+        let config = Self::fixed_rate_config(asset_id).ok_or(Error::<T>::FixedRateDisabled)?;
+        ensure!(!amount.is_zero(), Error::<T>::InvalidAmount);
+        ensure!(
+            !term.is_zero() && term <= config.max_term,
+            Error::<T>::InvalidFixedTerm
+        );
+        ensure!(
+            !AccountFixedBorrows::<T>::contains_key(asset_id, borrower),
+            Error::<T>::FixedBorrowAlreadyExists
+        );
+
+        Self::accrue_interest(asset_id)?;
+        Self::borrow_allowed(asset_id, borrower, amount, true)?;
+        Self::update_isolated_debt(borrower, asset_id, amount, true)?;
+        let was_in_emode = Self::account_emode_category(borrower).is_some();
+
+        let (borrow_rate, ..) = Self::get_market_status(asset_id)?;
+        let rate = borrow_rate
+            .checked_add(&config.rate_premium)
+            .ok_or(ArithmeticError::Overflow)?;
+        let fee = config.origination_fee.mul_ceil(amount);
+        let maturity = <frame_system::Pallet<T>>::block_number()
+            .checked_add(&term)
+            .ok_or(ArithmeticError::Overflow)?;
+        AccountFixedBorrows::<T>::insert(
+            asset_id,
+            borrower,
+            FixedBorrow {
+                principal: amount,
+                rate,
+                started_at: T::UnixTime::now().as_secs(),
+                maturity,
+            },
+        );
+        TotalFixedBorrows::<T>::try_mutate(asset_id, |total| -> DispatchResult {
+            *total = total.checked_add(amount).ok_or(ArithmeticError::Overflow)?;
+            Ok(())
+        })?;
+        Borrowers::<T>::insert(borrower, ());
+        FixedBorrowsYearlyInterest::<T>::try_mutate(asset_id, |total| -> DispatchResult {
+            *total = rate
+                .checked_mul_int(amount)
+                .and_then(|interest| total.checked_add(interest))
+                .ok_or(ArithmeticError::Overflow)?;
+            Ok(())
+        })?;
+        TotalReserves::<T>::try_mutate(asset_id, |total_reserves| -> DispatchResult {
+            *total_reserves = total_reserves
+                .checked_add(fee)
+                .ok_or(ArithmeticError::Overflow)?;
+            Ok(())
+        })?;
+        Self::ensure_emode_exit_allowed(borrower, was_in_emode)?;
+        T::Assets::transfer(asset_id, &Self::account_id(), borrower, amount - fee, false)?;
+        Self::deposit_event(Event::<T>::FixedBorrowed(
+            borrower.clone(),
+            asset_id,
+            amount,
+            rate,
+            fee,
+            maturity,
+        ));
+        Ok(())
+    }
+
+    fn do_repay_fixed_borrow(borrower: &AccountIdOf<T>, asset_id: AssetIdOf<T>) -> DispatchResult {
         Self::ensure_active_market(asset_id)?;
+        Self::accrue_interest(asset_id)?;
+        let amount = Self::close_fixed_borrow(borrower, asset_id)?;
+        Self::update_borrowers(borrower);
+        T::Assets::transfer(asset_id, borrower, &Self::account_id(), amount, false)?;
+        Self::update_isolated_debt(borrower, asset_id, amount, false)?;
+        Self::deposit_event(Event::<T>::FixedBorrowRepaid(
+            borrower.clone(),
+            asset_id,
+            amount,
+        ));
+        Ok(())
+    }
 
+    fn do_settle_fixed_borrow(borrower: &AccountIdOf<T>, asset_id: AssetIdOf<T>) -> DispatchResult {
+        Self::ensure_active_market(asset_id)?;
+        let fixed_borrow = Self::account_fixed_borrows(asset_id, borrower)
+            .ok_or(Error::<T>::FixedBorrowDoesNotExist)?;
+        let matured = <frame_system::Pallet<T>>::block_number() >= fixed_borrow.maturity;
         Self::accrue_interest(asset_id)?;
-        Self::borrow_allowed(asset_id, borrower, amount)?;
+        ensure!(
+            matured || Self::is_liquidatable(borrower)?,
+            Error::<T>::FixedBorrowNotSettleable
+        );
 
-        // update borrow index after accrue interest.
+        let amount = Self::close_fixed_borrow(borrower, asset_id)?;
         Self::update_reward_borrow_index(asset_id)?;
         Self::distribute_borrower_reward(asset_id, borrower)?;
-
-        let account_borrows = Self::current_borrow_balance(borrower, asset_id)?;
-        let account_borrows_new = account_borrows
+        let account_borrows_new = Self::current_borrow_balance(borrower, asset_id)?
             .checked_add(amount)
             .ok_or(ArithmeticError::Overflow)?;
-        let total_borrows = Self::total_borrows(asset_id);
-        let total_borrows_new = total_borrows
+        let total_borrows_new = Self::total_borrows(asset_id)
             .checked_add(amount)
             .ok_or(ArithmeticError::Overflow)?;
         AccountBorrows::<T>::insert(
@@ -2141,17 +4245,48 @@ impl<T: Config> LoansTrait<AssetIdOf<T>, AccountIdOf<T>, BalanceOf<T>> for Palle
             },
         );
         TotalBorrows::<T>::insert(asset_id, total_borrows_new);
-        T::Assets::transfer(asset_id, &Self::account_id(), borrower, amount, false)?;
-        Self::deposit_event(Event::<T>::Borrowed(borrower.clone(), asset_id, amount));
+
+        // Borrows of markets whose fixed rates were disabled since then roll over
+        let overdue =
+            matured && Self::fixed_rate_config(asset_id).map_or(false, |config| !config.rollover);
+        if overdue {
+            OverdueBorrows::<T>::insert(asset_id, borrower, true);
+        }
+        Self::deposit_event(Event::<T>::FixedBorrowSettled(
+            borrower.clone(),
+            asset_id,
+            amount,
+            overdue,
+        ));
         Ok(())
     }
 
+    // Removes the fixed borrow of `borrower` and returns the amount it owes.
+    // Its interest was accrued to the market by `accrue_interest`, which must
+    // run before.
+    #[require_transactional]
+    fn close_fixed_borrow(
+        borrower: &AccountIdOf<T>,
+        asset_id: AssetIdOf<T>,
+    ) -> Result<BalanceOf<T>, DispatchError> {
+        let fixed_borrow = AccountFixedBorrows::<T>::take(asset_id, borrower)
+            .ok_or(Error::<T>::FixedBorrowDoesNotExist)?;
+        let amount = Self::fixed_borrow_balance(&fixed_borrow)?;
+        TotalFixedBorrows::<T>::mutate(asset_id, |total| *total = total.saturating_sub(amount));
+        FixedBorrowsYearlyInterest::<T>::mutate(asset_id, |total| {
+            *total =
+                total.saturating_sub(fixed_borrow.rate.saturating_mul_int(fixed_borrow.principal))
+        });
+
+        Ok(amount)
+    }
+
     fn do_collateral_asset(
         supplier: &AccountIdOf<T>,
         asset_id: AssetIdOf<T>,
         enable: bool,
     ) -> Result<(), DispatchError> {
-        Self::ensure_active_market(asset_id)?;
+        let market = Self::ensure_active_market(asset_id)?;
         ensure!(
             AccountDeposits::<T>::contains_key(asset_id, supplier),
             Error::<T>::NoDeposit
@@ -2159,15 +4294,25 @@ impl<T: Config> LoansTrait<AssetIdOf<T>, AccountIdOf<T>, BalanceOf<T>> for Palle
         let mut deposits = Self::account_deposits(asset_id, supplier);
         // turn on the collateral button
         if enable {
+            let combined = if market.isolated {
+                Self::has_other_collateral(supplier, asset_id)
+            } else {
+                Self::isolated_collateral(supplier).is_some()
+            };
+            ensure!(!combined, Error::<T>::IsolatedCollateralCombined);
+            let was_in_emode = Self::account_emode_category(supplier).is_some();
             deposits.is_collateral = true;
             AccountDeposits::<T>::insert(asset_id, supplier, deposits);
+            Self::ensure_emode_exit_allowed(supplier, was_in_emode)?;
             Self::deposit_event(Event::<T>::CollateralAssetAdded(supplier.clone(), asset_id));
             return Ok(());
         }
         // turn off the collateral button after checking the liquidity
-        let total_collateral_value = Self::total_collateral_value(supplier)?;
-        let collateral_asset_value = Self::collateral_asset_value(supplier, asset_id)?;
-        let total_borrowed_value = Self::total_borrowed_value(supplier)?;
+        let collateral = Self::account_collateral(supplier);
+        let total_collateral_value = Self::total_collateral_value(supplier, &collateral)?;
+        let collateral_asset_value =
+            Self::collateral_asset_value(supplier, asset_id, false, &collateral)?;
+        let total_borrowed_value = Self::total_borrowed_value(supplier, false)?;
         log::trace!(
             target: "loans::collateral_asset",
             "total_collateral_value: {:?}, collateral_asset_value: {:?}, total_borrowed_value: {:?}",
@@ -2211,18 +4356,7 @@ impl<T: Config> LoansTrait<AssetIdOf<T>, AccountIdOf<T>, BalanceOf<T>> for Palle
         asset_id: AssetIdOf<T>,
         amount: BalanceOf<T>,
     ) -> Result<(), DispatchError> {
-        Self::ensure_active_market(asset_id)?;
-        Self::accrue_interest(asset_id)?;
-        let exchange_rate = Self::exchange_rate_stored(asset_id)?;
-        Self::update_earned_stored(supplier, asset_id, exchange_rate)?;
-        let voucher_amount = Self::calc_collateral_amount(amount, exchange_rate)?;
-        let redeem_amount = Self::do_redeem_voucher(supplier, asset_id, voucher_amount)?;
-        Self::deposit_event(Event::<T>::Redeemed(
-            supplier.clone(),
-            asset_id,
-            redeem_amount,
-        ));
-        Ok(())
+        Self::do_redeem_with_check(supplier, asset_id, amount, true)
     }
 }
 
@@ -2232,7 +4366,10 @@ impl<T: Config> LoansMarketDataProvider<AssetIdOf<T>, BalanceOf<T>> for Pallet<T
         let full_rate =
             Self::get_full_interest_rate(asset_id).ok_or(Error::<T>::InvalidRateModelParam)?;
         Ok(MarketInfo {
-            collateral_factor: market.collateral_factor,
+            collateral_factor: Self::effective_collateral_factor(
+                asset_id,
+                market.collateral_factor,
+            ),
             liquidation_threshold: market.liquidation_threshold,
             reserve_factor: market.reserve_factor,
             close_factor: market.close_factor,
@@ -2265,6 +4402,7 @@ impl<T: Config> LoansMarketDataProvider<AssetIdOf<T>, BalanceOf<T>> for Pallet<T
         if let Ok(market) = Self::market(asset_id) {
             let rate = match market.rate_model {
                 InterestRateModel::Jump(jump) => Some(jump.full_rate),
+                InterestRateModel::Piecewise(piecewise) => Some(piecewise.full_rate),
                 _ => None,
             };
             return rate;
@@ -2288,6 +4426,6 @@ impl<T: Config> LoansPositionDataProvider<AssetIdOf<T>, AccountIdOf<T>, BalanceO
         supplier: &AccountIdOf<T>,
         asset_id: AssetIdOf<T>,
     ) -> Result<BalanceOf<T>, DispatchError> {
-        Self::current_collateral_balance(supplier, asset_id)
+        Self::current_collateral_balance(supplier, asset_id, &Self::account_collateral(supplier))
     }
 }
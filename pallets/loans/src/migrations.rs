@@ -188,3 +188,272 @@ pub mod v3 {
         Ok(())
     }
 }
+
+pub mod v7 {
+    use super::*;
+    use crate::{pallet::StorageVersion, Config, Weight};
+    use frame_support::{log, traits::Get};
+
+    #[derive(Clone, PartialEq, Eq, codec::Decode, codec::Encode, RuntimeDebug, TypeInfo)]
+    pub struct V6Market<Balance> {
+        pub collateral_factor: Ratio,
+        pub liquidation_threshold: Ratio,
+        pub reserve_factor: Ratio,
+        pub close_factor: Ratio,
+        pub liquidate_incentive: Rate,
+        pub liquidate_incentive_reserved_factor: Ratio,
+        pub rate_model: InterestRateModel,
+        pub state: MarketState,
+        pub supply_cap: Balance,
+        pub borrow_cap: Balance,
+        pub ptoken_id: CurrencyId,
+    }
+
+    #[frame_support::storage_alias]
+    type Markets<T: Config> = StorageMap<
+        crate::Pallet<T>,
+        Blake2_128Concat,
+        AssetIdOf<T>,
+        super::v8::V7Market<BalanceOf<T>>,
+    >;
+
+    #[cfg(feature = "try-runtime")]
+    pub fn pre_migrate<T: Config>() -> Result<(), &'static str> {
+        #[frame_support::storage_alias]
+        type Markets<T: Config> =
+            StorageMap<crate::Pallet<T>, Blake2_128Concat, AssetIdOf<T>, V6Market<BalanceOf<T>>>;
+        frame_support::ensure!(
+            StorageVersion::<T>::get() == crate::Versions::V6,
+            "must upgrade linearly"
+        );
+        Markets::<T>::iter().for_each(|(asset_id, _)| {
+            log::info!("market {:#?} need to migrate", asset_id,);
+        });
+        log::info!("👜 loans v7 migration passes PRE migrate checks ✅",);
+
+        Ok(())
+    }
+
+    /// Migration adding the oracle price freshness parameters to `Market`.
+    pub fn migrate<T: Config>() -> Weight {
+        if StorageVersion::<T>::get() == crate::Versions::V6 {
+            log::info!("migrating loans to Versions::V7",);
+
+            let mut count = 0u64;
+            Markets::<T>::translate::<V6Market<BalanceOf<T>>, _>(|_key, market| {
+                count += 1;
+                Some(super::v8::V7Market {
+                    collateral_factor: market.collateral_factor,
+                    liquidation_threshold: market.liquidation_threshold,
+                    reserve_factor: market.reserve_factor,
+                    close_factor: market.close_factor,
+                    liquidate_incentive: market.liquidate_incentive,
+                    liquidate_incentive_reserved_factor: market.liquidate_incentive_reserved_factor,
+                    rate_model: market.rate_model,
+                    state: market.state,
+                    supply_cap: market.supply_cap,
+                    borrow_cap: market.borrow_cap,
+                    ptoken_id: market.ptoken_id,
+                    max_price_age: None,
+                    liquidate_on_stale_price: false,
+                })
+            });
+
+            StorageVersion::<T>::put(crate::Versions::V7);
+            log::info!("👜 completed loans migration to Versions::V7",);
+
+            T::DbWeight::get().reads_writes(count + 1, count + 1)
+        } else {
+            T::DbWeight::get().reads(1)
+        }
+    }
+
+    #[cfg(feature = "try-runtime")]
+    pub fn post_migrate<T: Config>() -> Result<(), &'static str> {
+        frame_support::ensure!(
+            StorageVersion::<T>::get() == crate::Versions::V7,
+            "must upgrade to V7"
+        );
+        Markets::<T>::iter().for_each(|(asset_id, market)| {
+            log::info!(
+                "market {:#?}, max_price_age {:?}, liquidate_on_stale_price {:?}",
+                asset_id,
+                market.max_price_age,
+                market.liquidate_on_stale_price,
+            );
+        });
+        log::info!("👜 loans v7 migration passes POST migrate checks ✅",);
+
+        Ok(())
+    }
+}
+
+pub mod v8 {
+    use super::*;
+    use crate::{pallet::StorageVersion, Config, Weight};
+    use frame_support::{log, traits::Get};
+
+    #[derive(Clone, PartialEq, Eq, codec::Decode, codec::Encode, RuntimeDebug, TypeInfo)]
+    pub struct V7Market<Balance> {
+        pub collateral_factor: Ratio,
+        pub liquidation_threshold: Ratio,
+        pub reserve_factor: Ratio,
+        pub close_factor: Ratio,
+        pub liquidate_incentive: Rate,
+        pub liquidate_incentive_reserved_factor: Ratio,
+        pub rate_model: InterestRateModel,
+        pub state: MarketState,
+        pub supply_cap: Balance,
+        pub borrow_cap: Balance,
+        pub ptoken_id: CurrencyId,
+        pub max_price_age: Option<Timestamp>,
+        pub liquidate_on_stale_price: bool,
+    }
+
+    #[cfg(feature = "try-runtime")]
+    pub fn pre_migrate<T: Config>() -> Result<(), &'static str> {
+        #[frame_support::storage_alias]
+        type Markets<T: Config> =
+            StorageMap<crate::Pallet<T>, Blake2_128Concat, AssetIdOf<T>, V7Market<BalanceOf<T>>>;
+        frame_support::ensure!(
+            StorageVersion::<T>::get() == crate::Versions::V7,
+            "must upgrade linearly"
+        );
+        Markets::<T>::iter().for_each(|(asset_id, _)| {
+            log::info!("market {:#?} need to migrate", asset_id,);
+        });
+        log::info!("👜 loans v8 migration passes PRE migrate checks ✅",);
+
+        Ok(())
+    }
+
+    /// Migration adding the isolation mode parameters to `Market`.
+    pub fn migrate<T: Config>() -> Weight {
+        if StorageVersion::<T>::get() == crate::Versions::V7 {
+            log::info!("migrating loans to Versions::V8",);
+
+            let mut count = 0u64;
+            Markets::<T>::translate::<V7Market<BalanceOf<T>>, _>(|_key, market| {
+                count += 1;
+                Some(Market {
+                    collateral_factor: market.collateral_factor,
+                    liquidation_threshold: market.liquidation_threshold,
+                    reserve_factor: market.reserve_factor,
+                    close_factor: market.close_factor,
+                    liquidate_incentive: market.liquidate_incentive,
+                    liquidate_incentive_reserved_factor: market.liquidate_incentive_reserved_factor,
+                    rate_model: market.rate_model,
+                    state: market.state,
+                    supply_cap: market.supply_cap,
+                    borrow_cap: market.borrow_cap,
+                    ptoken_id: market.ptoken_id,
+                    max_price_age: market.max_price_age,
+                    liquidate_on_stale_price: market.liquidate_on_stale_price,
+                    isolated: false,
+                    debt_ceiling: Liquidity::zero(),
+                    borrowable_in_isolation: false,
+                })
+            });
+
+            StorageVersion::<T>::put(crate::Versions::V8);
+            log::info!("👜 completed loans migration to Versions::V8",);
+
+            T::DbWeight::get().reads_writes(count + 1, count + 1)
+        } else {
+            T::DbWeight::get().reads(1)
+        }
+    }
+
+    #[cfg(feature = "try-runtime")]
+    pub fn post_migrate<T: Config>() -> Result<(), &'static str> {
+        frame_support::ensure!(
+            StorageVersion::<T>::get() == crate::Versions::V8,
+            "must upgrade to V8"
+        );
+        Markets::<T>::iter().for_each(|(asset_id, market)| {
+            log::info!(
+                "market {:#?}, isolated {:?}, borrowable_in_isolation {:?}",
+                asset_id,
+                market.isolated,
+                market.borrowable_in_isolation,
+            );
+        });
+        log::info!("👜 loans v8 migration passes POST migrate checks ✅",);
+
+        Ok(())
+    }
+}
+
+pub mod v9 {
+    use super::*;
+    use crate::{pallet::StorageVersion, Config, Weight};
+    use frame_support::{log, traits::Get};
+
+    #[cfg(feature = "try-runtime")]
+    pub fn pre_migrate<T: Config>() -> Result<(), &'static str> {
+        frame_support::ensure!(
+            StorageVersion::<T>::get() == crate::Versions::V8,
+            "must upgrade linearly"
+        );
+        log::info!("👜 loans v9 migration passes PRE migrate checks ✅",);
+
+        Ok(())
+    }
+
+    /// Migration indexing the accounts with a borrow in `Borrowers`.
+    pub fn migrate<T: Config>() -> Weight {
+        if StorageVersion::<T>::get() == crate::Versions::V8 {
+            log::info!("migrating loans to Versions::V9",);
+
+            let mut reads = 0u64;
+            let mut writes = 0u64;
+            AccountBorrows::<T>::iter().for_each(|(_, borrower, snapshot)| {
+                reads += 1;
+                if !snapshot.principal.is_zero() {
+                    writes += 1;
+                    Borrowers::<T>::insert(borrower, ());
+                }
+            });
+            AccountFixedBorrows::<T>::iter_keys().for_each(|(_, borrower)| {
+                reads += 1;
+                writes += 1;
+                Borrowers::<T>::insert(borrower, ());
+            });
+
+            StorageVersion::<T>::put(crate::Versions::V9);
+            log::info!("👜 completed loans migration to Versions::V9",);
+
+            T::DbWeight::get().reads_writes(reads + 1, writes + 1)
+        } else {
+            T::DbWeight::get().reads(1)
+        }
+    }
+
+    #[cfg(feature = "try-runtime")]
+    pub fn post_migrate<T: Config>() -> Result<(), &'static str> {
+        frame_support::ensure!(
+            StorageVersion::<T>::get() == crate::Versions::V9,
+            "must upgrade to V9"
+        );
+        let mut borrowers = sp_std::collections::btree_set::BTreeSet::new();
+        AccountBorrows::<T>::iter().for_each(|(_, borrower, snapshot)| {
+            if !snapshot.principal.is_zero() {
+                borrowers.insert(borrower);
+            }
+        });
+        AccountFixedBorrows::<T>::iter_keys().for_each(|(_, borrower)| {
+            borrowers.insert(borrower);
+        });
+        let indexed = Borrowers::<T>::iter_keys().count();
+        frame_support::ensure!(
+            indexed == borrowers.len(),
+            "borrowers index doesn't match the accounts with a borrow"
+        );
+        log::info!(
+            "👜 loans v9 migration passes POST migrate checks ✅, {:?} borrowers",
+            indexed,
+        );
+
+        Ok(())
+    }
+}
@@ -18,13 +18,14 @@ use frame_support::{
     construct_runtime, parameter_types,
     traits::SortedMembers,
     traits::{AsEnsureOriginWithArg, Everything},
+    weights::Weight,
     PalletId,
 };
 use frame_system::{EnsureRoot, EnsureSigned, EnsureSignedBy};
 use orml_traits::{DataFeeder, DataProvider, DataProviderExtended};
 use pallet_traits::{
-    DecimalProvider, ExchangeRateProvider, LiquidStakingCurrenciesProvider,
-    VaultTokenCurrenciesFilter, VaultTokenExchangeRateProvider,
+    DecimalProvider, ExchangeRateProvider, LiquidStakingCurrenciesProvider, Router,
+    VaultTokenCurrenciesFilter, VaultTokenExchangeRateProvider, AMM,
 };
 use primitives::{
     tokens::{CDOT_6_13, PCDOT_6_13},
@@ -231,6 +232,14 @@ parameter_types! {
     // pub const DefaultProtocolFee: Ratio = Ratio::from_rational(5u32, 10000u32);
     pub  DefaultLpFee: Ratio = Ratio::from_rational(25u32, 10000u32);         // 0.3%
     pub const MinimumLiquidity: u128 = 1_000u128;
+    pub const PoolCreationDeposit: Balance = 1_000_000;
+    pub MaxPermissionlessLpFee: Ratio = Ratio::from_percent(1);
+    pub const MinimumPoolSeedLiquidity: u128 = 10_000u128;
+    pub const DepositRefundThreshold: u128 = 1_000_000u128;
+    pub const DepositRefundDelay: BlockNumber = 10;
+    pub const PermissionlessLpTokenIdStart: CurrencyId = 500_000_000;
+    pub const MaxObservations: u32 = 4;
+    pub const ObservationPeriod: BlockNumber = 1;
     pub const LockAccountId: AccountId = ALICE;
     pub const MaxLengthRoute: u8 = 10;
 }
@@ -250,10 +259,23 @@ impl pallet_amm::Config for Test {
     type AMMWeightInfo = ();
     type CreatePoolOrigin = EnsureSignedBy<AliceCreatePoolOrigin, AccountId>;
     type ProtocolFeeUpdateOrigin = EnsureSignedBy<AliceCreatePoolOrigin, AccountId>;
+    type PermissionlessCreatePoolOrigin = EnsureSigned<AccountId>;
+    type Currency = Balances;
+    type LpTokenAssets = Assets;
+    type PermissionlessLpTokenIdStart = PermissionlessLpTokenIdStart;
+    type PoolCreationDeposit = PoolCreationDeposit;
+    type MinimumPoolSeedLiquidity = MinimumPoolSeedLiquidity;
+    type DepositRefundThreshold = DepositRefundThreshold;
+    type DepositRefundDelay = DepositRefundDelay;
     type LpFee = DefaultLpFee;
+    type MaxPermissionlessLpFee = MaxPermissionlessLpFee;
     type MinimumLiquidity = MinimumLiquidity;
     type MaxLengthRoute = MaxLengthRoute;
     type GetNativeCurrencyId = NativeCurrencyId;
+    type MaxObservations = MaxObservations;
+    type ObservationPeriod = ObservationPeriod;
+    type OnPoolCreated = ();
+    type OnPoolDelisted = ();
 }
 
 impl pallet_prices::Config for Test {
@@ -268,11 +290,50 @@ impl pallet_prices::Config for Test {
     type VaultLoansRateProvider = VaultLoansRateProvider;
     type RelayCurrency = RelayCurrency;
     type Decimal = Decimal;
+    type MemberSource = ();
+    type UnixTime = TimestampPallet;
+    type MaxEmergencyPriceReasonLength = frame_support::traits::ConstU32<128>;
+    type MaxDeviationLimits = frame_support::traits::ConstU32<16>;
+    type MaxEmergencyPriceExpiries = frame_support::traits::ConstU32<16>;
     type AMM = DefaultAMM;
+    type StableSwap = ();
     type Assets = CurrencyAdapter;
     type WeightInfo = ();
 }
 
+/// Swaps directly through the AMM pool of the pair
+pub struct MockRouter;
+
+impl Router<AccountId, CurrencyId, Balance> for MockRouter {
+    fn swap_exact_tokens_for_tokens(
+        who: &AccountId,
+        token_in: CurrencyId,
+        token_out: CurrencyId,
+        amount_in: Balance,
+        min_amount_out: Balance,
+    ) -> Result<Balance, sp_runtime::DispatchError> {
+        let amounts =
+            <DefaultAMM as AMM<AccountId, CurrencyId, Balance, BlockNumber>>::get_amounts_out(
+                amount_in,
+                vec![token_in, token_out],
+            )?;
+        if amounts[1] < min_amount_out {
+            return Err(sp_runtime::DispatchError::Other("MinimumAmountOutViolated"));
+        }
+        <DefaultAMM as AMM<AccountId, CurrencyId, Balance, BlockNumber>>::swap(
+            who,
+            (token_in, token_out),
+            amount_in,
+        )?;
+
+        Ok(amounts[1])
+    }
+
+    fn swap_exact_tokens_for_tokens_weight() -> Weight {
+        Weight::zero()
+    }
+}
+
 pub struct MockPriceFeeder;
 
 impl MockPriceFeeder {
@@ -285,6 +346,7 @@ impl MockPriceFeeder {
                     .collect()
             )
         };
+        pub static SUSPICIOUS: RefCell<Vec<CurrencyId>> = RefCell::new(vec![]);
     }
 
     pub fn set_price(asset_id: CurrencyId, price: Price) {
@@ -293,6 +355,24 @@ impl MockPriceFeeder {
         });
     }
 
+    pub fn set_price_detail(asset_id: CurrencyId, price: Price, timestamp: Timestamp) {
+        Self::PRICES.with(|prices| {
+            prices
+                .borrow_mut()
+                .insert(asset_id, Some((price, timestamp)));
+        });
+    }
+
+    pub fn set_suspicious(asset_id: CurrencyId, suspicious: bool) {
+        Self::SUSPICIOUS.with(|assets| {
+            let mut assets = assets.borrow_mut();
+            assets.retain(|&id| id != asset_id);
+            if suspicious {
+                assets.push(asset_id);
+            }
+        });
+    }
+
     pub fn reset() {
         Self::PRICES.with(|prices| {
             for (_, val) in prices.borrow_mut().iter_mut() {
@@ -306,6 +386,10 @@ impl PriceFeeder for MockPriceFeeder {
     fn get_price(asset_id: &CurrencyId) -> Option<PriceDetail> {
         Self::PRICES.with(|prices| *prices.borrow().get(asset_id).unwrap())
     }
+
+    fn is_suspicious(asset_id: &CurrencyId) -> bool {
+        Self::SUSPICIOUS.with(|assets| assets.borrow().contains(asset_id))
+    }
 }
 
 parameter_types! {
@@ -344,6 +428,8 @@ parameter_types! {
     pub const LoansPalletId: PalletId = PalletId(*b"par/loan");
     pub const RewardAssetId: CurrencyId = HKO;
     pub const LiquidationFreeAssetId: CurrencyId = DOT;
+    pub const MarketSnapshotInterval: u64 = 10;
+    pub const MaxMarketSnapshots: u32 = 3;
 }
 
 impl Config for Test {
@@ -357,6 +443,10 @@ impl Config for Test {
     type Assets = CurrencyAdapter;
     type RewardAssetId = RewardAssetId;
     type LiquidationFreeAssetId = LiquidationFreeAssetId;
+    type MarketSnapshotInterval = MarketSnapshotInterval;
+    type MaxMarketSnapshots = MaxMarketSnapshots;
+    type RuntimeCall = RuntimeCall;
+    type Router = MockRouter;
 }
 
 parameter_types! {
@@ -481,6 +571,11 @@ pub const fn market_mock(ptoken_id: u32) -> Market<Balance> {
         supply_cap: 1_000_000_000_000_000_000_000u128, // set to 1B
         borrow_cap: 1_000_000_000_000_000_000_000u128, // set to 1B
         ptoken_id,
+        max_price_age: None,
+        liquidate_on_stale_price: false,
+        isolated: false,
+        debt_ceiling: Liquidity::from_inner(0),
+        borrowable_in_isolation: false,
     }
 }
 
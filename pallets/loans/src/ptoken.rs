@@ -207,7 +207,12 @@ impl<T: Config> Pallet<T> {
         }
 
         let market = Self::ensure_active_market(underlying_id)?;
-        let collateral_value = Self::collateral_asset_value(who, underlying_id)?;
+        let collateral_value = Self::collateral_asset_value(
+            who,
+            underlying_id,
+            false,
+            &Self::account_collateral(who),
+        )?;
 
         // liquidity of all assets
         let (liquidity, _, _, _) = Self::get_account_liquidity(who)?;
@@ -221,7 +226,9 @@ impl<T: Config> Pallet<T> {
         let price = Self::get_price(underlying_id)?;
 
         let reducible_supply_value = liquidity
-            .checked_div(&market.collateral_factor.into())
+            .checked_div(
+                &Self::effective_collateral_factor(underlying_id, market.collateral_factor).into(),
+            )
             .ok_or(ArithmeticError::Overflow)?;
 
         let reducible_underlying_amount = reducible_supply_value
@@ -14,16 +14,19 @@
 
 use primitives::{Rate, Ratio};
 use scale_info::TypeInfo;
-use sp_runtime::traits::{CheckedAdd, CheckedDiv, CheckedSub, Saturating};
+use sp_runtime::traits::{CheckedAdd, CheckedDiv, CheckedMul, CheckedSub, Saturating};
+use sp_std::vec::Vec;
 
 use crate::*;
 
 /// Parallel interest rate model
 #[cfg_attr(feature = "std", derive(serde::Deserialize, serde::Serialize))]
-#[derive(Encode, Decode, Eq, PartialEq, Copy, Clone, RuntimeDebug, TypeInfo)]
+#[derive(Encode, Decode, Eq, PartialEq, Clone, RuntimeDebug, TypeInfo)]
 pub enum InterestRateModel {
     Jump(JumpModel),
     Curve(CurveModel),
+    Piecewise(PiecewiseModel),
+    Adaptive(AdaptiveModel),
 }
 
 impl Default for InterestRateModel {
@@ -56,18 +59,31 @@ impl InterestRateModel {
         Self::Curve(CurveModel::new_model(base_rate))
     }
 
+    pub fn new_piecewise_model(base_rate: Rate, kinks: Vec<Kink>, full_rate: Rate) -> Self {
+        Self::Piecewise(PiecewiseModel::new_model(base_rate, kinks, full_rate))
+    }
+
     pub fn check_model(&self) -> bool {
         match self {
             Self::Jump(jump) => jump.check_model(),
             Self::Curve(curve) => curve.check_model(),
+            Self::Piecewise(piecewise) => piecewise.check_model(),
+            Self::Adaptive(adaptive) => adaptive.check_model(),
         }
     }
 
-    /// Calculates the current borrow interest rate
+    /// Calculates the current borrow interest rate.
+    ///
+    /// Adaptive models are evaluated at their initial rate at target, the pallet
+    /// keeps the current rate at target of each market.
     pub fn get_borrow_rate(&self, utilization: Ratio) -> Option<Rate> {
         match self {
             Self::Jump(jump) => jump.get_borrow_rate(utilization),
             Self::Curve(curve) => curve.get_borrow_rate(utilization),
+            Self::Piecewise(piecewise) => piecewise.get_borrow_rate(utilization),
+            Self::Adaptive(adaptive) => {
+                adaptive.get_borrow_rate(utilization, adaptive.initial_rate_at_target)
+            }
         }
     }
 
@@ -187,6 +203,216 @@ impl CurveModel {
     }
 }
 
+/// A kink of the piecewise model
+#[cfg_attr(feature = "std", derive(serde::Deserialize, serde::Serialize))]
+#[derive(Encode, Decode, Eq, PartialEq, Copy, Clone, RuntimeDebug, Default, TypeInfo)]
+pub struct Kink {
+    /// The utilization point of the kink
+    pub utilization: Ratio,
+    /// The interest rate on the utilization point
+    pub rate: Rate,
+}
+
+/// The piecewise-linear interest rate model
+///
+/// The borrow rate is interpolated between `base_rate` at 0% utilization,
+/// the kinks and `full_rate` at 100% utilization.
+#[cfg_attr(feature = "std", derive(serde::Deserialize, serde::Serialize))]
+#[derive(Encode, Decode, Eq, PartialEq, Clone, RuntimeDebug, Default, TypeInfo)]
+pub struct PiecewiseModel {
+    /// The base interest rate when utilization rate is 0
+    pub base_rate: Rate,
+    /// The kinks, sorted by utilization
+    pub kinks: Vec<Kink>,
+    /// The max interest rate when utilization rate is 100%
+    pub full_rate: Rate,
+}
+
+impl PiecewiseModel {
+    pub const MAX_KINKS: usize = 8;
+    pub const MAX_BASE_RATE: Rate = Rate::from_inner(100_000_000_000_000_000); // 10%
+    pub const MAX_FULL_RATE: Rate = Rate::from_inner(500_000_000_000_000_000); // 50%
+
+    /// Create a new piecewise model
+    pub fn new_model(base_rate: Rate, kinks: Vec<Kink>, full_rate: Rate) -> PiecewiseModel {
+        Self {
+            base_rate,
+            kinks,
+            full_rate,
+        }
+    }
+
+    /// Check the piecewise model for sanity
+    pub fn check_model(&self) -> bool {
+        if self.kinks.len() > Self::MAX_KINKS
+            || self.base_rate > Self::MAX_BASE_RATE
+            || self.full_rate > Self::MAX_FULL_RATE
+        {
+            return false;
+        }
+
+        // The utilization must strictly increase and the rate must not decrease
+        let mut prev = Kink {
+            utilization: Ratio::zero(),
+            rate: self.base_rate,
+        };
+        for kink in self.kinks.iter() {
+            if kink.utilization <= prev.utilization
+                || kink.utilization >= Ratio::one()
+                || kink.rate < prev.rate
+            {
+                return false;
+            }
+            prev = *kink;
+        }
+
+        prev.rate <= self.full_rate
+    }
+
+    /// Calculates the borrow interest rate of piecewise model
+    pub fn get_borrow_rate(&self, utilization: Ratio) -> Option<Rate> {
+        let mut prev = Kink {
+            utilization: Ratio::zero(),
+            rate: self.base_rate,
+        };
+        let full = Kink {
+            utilization: Ratio::one(),
+            rate: self.full_rate,
+        };
+        for next in self.kinks.iter().chain(sp_std::iter::once(&full)) {
+            if utilization <= next.utilization {
+                // (utilization - prev_utilization) * (next_rate - prev_rate) / (next_utilization - prev_utilization) + prev_rate
+                let result = next
+                    .rate
+                    .checked_sub(&prev.rate)?
+                    .saturating_mul(utilization.saturating_sub(prev.utilization).into())
+                    .checked_div(&next.utilization.saturating_sub(prev.utilization).into())?
+                    .checked_add(&prev.rate)?;
+
+                return Some(result);
+            }
+            prev = *next;
+        }
+
+        None
+    }
+}
+
+/// The adaptive interest rate model
+///
+/// The borrow rate follows a curve around the rate at target utilization.
+/// The rate at target itself drifts up while the utilization is above
+/// `target_utilization` and down while it is below.
+#[cfg_attr(feature = "std", derive(serde::Deserialize, serde::Serialize))]
+#[derive(Encode, Decode, Eq, PartialEq, Copy, Clone, RuntimeDebug, Default, TypeInfo)]
+pub struct AdaptiveModel {
+    /// The utilization the model steers the market toward
+    pub target_utilization: Ratio,
+    /// The rate at target utilization of a market adopting the model
+    pub initial_rate_at_target: Rate,
+    /// Lower bound of the rate at target utilization
+    pub min_rate_at_target: Rate,
+    /// Upper bound of the rate at target utilization
+    pub max_rate_at_target: Rate,
+    /// The relative change per second of the rate at target, at 0% or 100% utilization
+    pub adjustment_speed: Rate,
+    /// The borrow rate at 100% utilization over the rate at target
+    pub curve_steepness: Rate,
+}
+
+impl AdaptiveModel {
+    pub const MAX_RATE_AT_TARGET: Rate = Rate::from_inner(500_000_000_000_000_000); // 50%
+    pub const MAX_ADJUSTMENT_SPEED: Rate = Rate::from_inner(Rate::DIV / 100_000); // 0.001% per second
+    pub const MAX_CURVE_STEEPNESS: Rate = Rate::from_inner(Rate::DIV * 10);
+
+    /// Check the adaptive model for sanity
+    pub fn check_model(&self) -> bool {
+        if self.target_utilization.is_zero() || self.target_utilization >= Ratio::one() {
+            return false;
+        }
+        if self.min_rate_at_target > self.initial_rate_at_target
+            || self.initial_rate_at_target > self.max_rate_at_target
+            || self.max_rate_at_target > Self::MAX_RATE_AT_TARGET
+        {
+            return false;
+        }
+
+        self.adjustment_speed <= Self::MAX_ADJUSTMENT_SPEED
+            && self.curve_steepness >= Rate::one()
+            && self.curve_steepness <= Self::MAX_CURVE_STEEPNESS
+    }
+
+    // The distance of `utilization` to the target, normalized to [0, 1] on
+    // both sides of the target. Returns whether it is above the target.
+    fn utilization_error(&self, utilization: Ratio) -> (Rate, bool) {
+        if utilization > self.target_utilization {
+            let error = Rate::checked_from_rational(
+                utilization
+                    .saturating_sub(self.target_utilization)
+                    .deconstruct(),
+                Ratio::one()
+                    .saturating_sub(self.target_utilization)
+                    .deconstruct(),
+            )
+            .unwrap_or_else(Rate::one);
+            (error, true)
+        } else {
+            let error = Rate::checked_from_rational(
+                self.target_utilization
+                    .saturating_sub(utilization)
+                    .deconstruct(),
+                self.target_utilization.deconstruct(),
+            )
+            .unwrap_or_else(Rate::one);
+            (error, false)
+        }
+    }
+
+    /// Calculates the borrow interest rate of adaptive model
+    pub fn get_borrow_rate(&self, utilization: Ratio, rate_at_target: Rate) -> Option<Rate> {
+        let (error, above_target) = self.utilization_error(utilization);
+        let multiplier = if above_target {
+            // 1 + (steepness - 1) * error
+            self.curve_steepness
+                .checked_sub(&Rate::one())?
+                .checked_mul(&error)?
+                .checked_add(&Rate::one())?
+        } else {
+            // 1 - (1 - 1 / steepness) * error
+            Rate::one()
+                .checked_sub(&Rate::one().checked_div(&self.curve_steepness)?)?
+                .checked_mul(&error)
+                .and_then(|r| Rate::one().checked_sub(&r))?
+        };
+
+        rate_at_target.checked_mul(&multiplier)
+    }
+
+    /// Calculates the rate at target after `delta_time` seconds at `utilization`
+    pub fn next_rate_at_target(
+        &self,
+        rate_at_target: Rate,
+        utilization: Ratio,
+        delta_time: Timestamp,
+    ) -> Rate {
+        let (error, above_target) = self.utilization_error(utilization);
+        let adjustment = self
+            .adjustment_speed
+            .saturating_mul(error)
+            .saturating_mul(Rate::saturating_from_integer(delta_time));
+        let rate_at_target = if above_target {
+            rate_at_target.saturating_add(rate_at_target.saturating_mul(adjustment))
+        } else {
+            rate_at_target
+                .saturating_sub(rate_at_target.saturating_mul(adjustment.min(Rate::one())))
+        };
+
+        rate_at_target
+            .max(self.min_rate_at_target)
+            .min(self.max_rate_at_target)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -270,4 +496,132 @@ mod tests {
             Rate::from_inner(154217728000000000)
         );
     }
+
+    // Test piecewise model
+    fn piecewise_model() -> PiecewiseModel {
+        PiecewiseModel::new_model(
+            Rate::saturating_from_rational(2, 100),
+            vec![
+                Kink {
+                    utilization: Ratio::from_percent(50),
+                    rate: Rate::saturating_from_rational(6, 100),
+                },
+                Kink {
+                    utilization: Ratio::from_percent(80),
+                    rate: Rate::saturating_from_rational(10, 100),
+                },
+            ],
+            Rate::saturating_from_rational(50, 100),
+        )
+    }
+
+    #[test]
+    fn piecewise_model_interpolates_between_kinks() {
+        let model = piecewise_model();
+        assert!(model.check_model());
+
+        let rate = |percent| model.get_borrow_rate(Ratio::from_percent(percent)).unwrap();
+        assert_eq!(rate(0), Rate::saturating_from_rational(2, 100));
+        assert_eq!(rate(25), Rate::saturating_from_rational(4, 100));
+        assert_eq!(rate(50), Rate::saturating_from_rational(6, 100));
+        assert_eq!(rate(65), Rate::saturating_from_rational(8, 100));
+        assert_eq!(rate(90), Rate::saturating_from_rational(30, 100));
+        assert_eq!(rate(100), Rate::saturating_from_rational(50, 100));
+    }
+
+    #[test]
+    fn piecewise_model_sanity_checks() {
+        // Unsorted kinks
+        let mut model = piecewise_model();
+        model.kinks.swap(0, 1);
+        assert!(!model.check_model());
+
+        // Decreasing rate
+        let mut model = piecewise_model();
+        model.kinks[1].rate = Rate::saturating_from_rational(5, 100);
+        assert!(!model.check_model());
+
+        // Too many kinks
+        let mut model = piecewise_model();
+        model.kinks = (1..=PiecewiseModel::MAX_KINKS as u32 + 1)
+            .map(|i| Kink {
+                utilization: Ratio::from_percent(i * 10),
+                rate: Rate::saturating_from_rational(i, 100),
+            })
+            .collect();
+        assert!(!model.check_model());
+    }
+
+    // Test adaptive model
+    fn adaptive_model() -> AdaptiveModel {
+        AdaptiveModel {
+            target_utilization: Ratio::from_percent(80),
+            initial_rate_at_target: Rate::saturating_from_rational(4, 100),
+            min_rate_at_target: Rate::saturating_from_rational(1, 1000),
+            max_rate_at_target: Rate::saturating_from_rational(20, 100),
+            adjustment_speed: Rate::saturating_from_rational(1, 1_000_000),
+            curve_steepness: Rate::saturating_from_integer(4),
+        }
+    }
+
+    #[test]
+    fn adaptive_model_follows_curve_around_target() {
+        let model = adaptive_model();
+        assert!(model.check_model());
+
+        let rate_at_target = model.initial_rate_at_target;
+        let rate = |percent| {
+            model
+                .get_borrow_rate(Ratio::from_percent(percent), rate_at_target)
+                .unwrap()
+        };
+        assert_eq!(rate(0), Rate::saturating_from_rational(1, 100));
+        assert_eq!(rate(80), rate_at_target);
+        assert_eq!(rate(90), Rate::saturating_from_rational(10, 100));
+        assert_eq!(rate(100), Rate::saturating_from_rational(16, 100));
+    }
+
+    #[test]
+    fn adaptive_model_adjusts_rate_at_target() {
+        let model = adaptive_model();
+        let rate_at_target = model.initial_rate_at_target;
+
+        // Above the target the rate at target rises, below it falls
+        assert_eq!(
+            model.next_rate_at_target(rate_at_target, Ratio::from_percent(100), 100_000),
+            Rate::saturating_from_rational(44, 1000)
+        );
+        assert_eq!(
+            model.next_rate_at_target(rate_at_target, Ratio::zero(), 100_000),
+            Rate::saturating_from_rational(36, 1000)
+        );
+        assert_eq!(
+            model.next_rate_at_target(rate_at_target, Ratio::from_percent(80), 100_000),
+            rate_at_target
+        );
+        // The rate at target stays within its bounds
+        assert_eq!(
+            model.next_rate_at_target(rate_at_target, Ratio::from_percent(100), 1_000_000_000),
+            model.max_rate_at_target
+        );
+        assert_eq!(
+            model.next_rate_at_target(rate_at_target, Ratio::zero(), 1_000_000_000),
+            model.min_rate_at_target
+        );
+    }
+
+    #[test]
+    fn adaptive_model_sanity_checks() {
+        let mut model = adaptive_model();
+        model.curve_steepness = Rate::saturating_from_rational(1, 2);
+        assert!(!model.check_model());
+
+        let mut model = adaptive_model();
+        model.target_utilization = Ratio::zero();
+        assert!(!model.check_model());
+
+        let mut model = adaptive_model();
+        model.initial_rate_at_target = Rate::saturating_from_rational(30, 100);
+        assert!(!model.check_model());
+    }
 }
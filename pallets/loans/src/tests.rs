@@ -12,11 +12,22 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+mod account_positions;
+mod bad_debt;
 mod edge_cases;
+mod emode;
+mod fixed_borrow;
+mod flash_loan;
 mod interest_rate;
+mod isolation;
+mod leverage;
 mod liquidate_borrow;
+mod liquidation_auction;
 mod market;
+mod market_deprecation;
+mod market_snapshots;
 mod ptokens;
+mod reward_tokens;
 
 use frame_support::{assert_err, assert_noop, assert_ok};
 
@@ -79,11 +90,11 @@ fn loans_native_token_works() {
 
         // Redeem 1001 HKO should cause InsufficientDeposit
         assert_noop!(
-            Loans::redeem_allowed(HKO, &DAVE, unit(50050)),
+            Loans::redeem_allowed(HKO, &DAVE, unit(50050), true),
             Error::<Test>::InsufficientDeposit
         );
         // Redeem 1000 HKO is ok
-        assert_ok!(Loans::redeem_allowed(HKO, &DAVE, unit(50000),));
+        assert_ok!(Loans::redeem_allowed(HKO, &DAVE, unit(50000), true));
 
         assert_ok!(Loans::collateral_asset(
             RuntimeOrigin::signed(DAVE),
@@ -191,16 +202,16 @@ fn redeem_allowed_works() {
         assert_ok!(Loans::mint(RuntimeOrigin::signed(ALICE), KSM, 200));
         // Redeem 201 KSM should cause InsufficientDeposit
         assert_noop!(
-            Loans::redeem_allowed(KSM, &ALICE, 10050),
+            Loans::redeem_allowed(KSM, &ALICE, 10050, true),
             Error::<Test>::InsufficientDeposit
         );
         // Redeem 1 DOT should cause InsufficientDeposit
         assert_noop!(
-            Loans::redeem_allowed(DOT, &ALICE, 50),
+            Loans::redeem_allowed(DOT, &ALICE, 50, true),
             Error::<Test>::InsufficientDeposit
         );
         // Redeem 200 KSM is ok
-        assert_ok!(Loans::redeem_allowed(KSM, &ALICE, 10000));
+        assert_ok!(Loans::redeem_allowed(KSM, &ALICE, 10000, true));
 
         assert_ok!(Loans::collateral_asset(
             RuntimeOrigin::signed(ALICE),
@@ -211,11 +222,11 @@ fn redeem_allowed_works() {
         assert_ok!(Loans::borrow(RuntimeOrigin::signed(ALICE), DOT, 50));
         // Redeem 101 KSM should cause InsufficientLiquidity
         assert_noop!(
-            Loans::redeem_allowed(KSM, &ALICE, 5050),
+            Loans::redeem_allowed(KSM, &ALICE, 5050, true),
             Error::<Test>::InsufficientLiquidity
         );
         // Redeem 100 KSM is ok
-        assert_ok!(Loans::redeem_allowed(KSM, &ALICE, 5000));
+        assert_ok!(Loans::redeem_allowed(KSM, &ALICE, 5000, true));
     })
 }
 
@@ -234,7 +245,7 @@ fn lf_redeem_allowed_works() {
         Loans::borrow(RuntimeOrigin::signed(ALICE), DOT, unit(50)).unwrap();
 
         // (200 - 100) * 50% >= 50
-        assert_ok!(Loans::redeem_allowed(CDOT_6_13, &ALICE, unit(100)));
+        assert_ok!(Loans::redeem_allowed(CDOT_6_13, &ALICE, unit(100), true));
 
         // Set KSM as collateral, and borrow USDT
         Loans::mint(RuntimeOrigin::signed(ALICE), KSM, unit(200)).unwrap();
@@ -242,16 +253,16 @@ fn lf_redeem_allowed_works() {
         Loans::borrow(RuntimeOrigin::signed(ALICE), USDT, unit(100)).unwrap();
 
         assert_err!(
-            Loans::redeem_allowed(KSM, &ALICE, unit(100)),
+            Loans::redeem_allowed(KSM, &ALICE, unit(100), true),
             Error::<Test>::InsufficientLiquidity
         );
         // But it'll success when redeem cdot
-        assert_ok!(Loans::redeem_allowed(CDOT_6_13, &ALICE, unit(100)));
+        assert_ok!(Loans::redeem_allowed(CDOT_6_13, &ALICE, unit(100), true));
 
         // Remove CDOT from lf collateral
         Loans::update_liquidation_free_collateral(RuntimeOrigin::root(), vec![]).unwrap();
         // Then it can be redeemed
-        assert_ok!(Loans::redeem_allowed(KSM, &ALICE, unit(100)));
+        assert_ok!(Loans::redeem_allowed(KSM, &ALICE, unit(100), true));
     })
 }
 
@@ -420,11 +431,11 @@ fn borrow_allowed_works() {
         ));
         // Borrow 101 DOT should cause InsufficientLiquidity
         assert_noop!(
-            Loans::borrow_allowed(DOT, &ALICE, 101),
+            Loans::borrow_allowed(DOT, &ALICE, 101, true),
             Error::<Test>::InsufficientLiquidity
         );
         // Borrow 100 DOT is ok
-        assert_ok!(Loans::borrow_allowed(DOT, &ALICE, 100));
+        assert_ok!(Loans::borrow_allowed(DOT, &ALICE, 100, true));
 
         // Set borrow limit to 10
         assert_ok!(Loans::force_update_market(
@@ -436,10 +447,10 @@ fn borrow_allowed_works() {
             },
         ));
         // Borrow 10 DOT is ok
-        assert_ok!(Loans::borrow_allowed(DOT, &ALICE, 10));
+        assert_ok!(Loans::borrow_allowed(DOT, &ALICE, 10, true));
         // Borrow 11 DOT should cause BorrowLimitExceeded
         assert_noop!(
-            Loans::borrow_allowed(DOT, &ALICE, 11),
+            Loans::borrow_allowed(DOT, &ALICE, 11, true),
             Error::<Test>::BorrowCapacityExceeded
         );
     })
@@ -515,10 +526,10 @@ fn lf_borrow_allowed_works() {
         );
 
         assert_noop!(
-            Loans::borrow_allowed(USDT, &ALICE, unit(100)),
+            Loans::borrow_allowed(USDT, &ALICE, unit(100), true),
             Error::<Test>::InsufficientLiquidity
         );
-        assert_ok!(Loans::borrow_allowed(DOT, &ALICE, unit(100)));
+        assert_ok!(Loans::borrow_allowed(DOT, &ALICE, unit(100), true));
     })
 }
 
@@ -704,7 +715,7 @@ fn total_collateral_value_works() {
             true
         ));
         assert_eq!(
-            Loans::total_collateral_value(&ALICE).unwrap(),
+            Loans::total_collateral_value(&ALICE, &Loans::account_collateral(&ALICE)).unwrap(),
             (collateral_factor.saturating_mul(FixedU128::from_inner(unit(100) + unit(200))))
         );
     })
@@ -985,6 +996,111 @@ fn get_price_works() {
     })
 }
 
+#[test]
+fn get_price_fails_when_price_is_stale() {
+    new_test_ext().execute_with(|| {
+        Loans::mutate_market(DOT, |market| {
+            market.max_price_age = Some(60);
+            market.clone()
+        })
+        .unwrap();
+        MockPriceFeeder::set_price_detail(DOT, 2.into(), 6000);
+        assert_eq!(
+            Loans::get_price(DOT).unwrap(),
+            Price::saturating_from_integer(2)
+        );
+
+        // 61 seconds later the price is stale
+        TimestampPallet::set_timestamp(6000 + 61_000);
+        assert_noop!(Loans::get_price(DOT), Error::<Test>::PriceIsStale);
+        assert_noop!(
+            Loans::get_asset_value(DOT, unit(100)),
+            Error::<Test>::PriceIsStale
+        );
+        // Liquidation price is stale as well unless the market allows it
+        assert_noop!(
+            Loans::get_liquidation_price(DOT),
+            Error::<Test>::PriceIsStale
+        );
+        Loans::mutate_market(DOT, |market| {
+            market.liquidate_on_stale_price = true;
+            market.clone()
+        })
+        .unwrap();
+        assert_eq!(
+            Loans::get_liquidation_price(DOT).unwrap(),
+            Price::saturating_from_integer(2)
+        );
+        assert_noop!(Loans::get_price(DOT), Error::<Test>::PriceIsStale);
+
+        // Emergency price never becomes stale
+        MockPriceFeeder::set_price_detail(DOT, 3.into(), 0);
+        assert_eq!(
+            Loans::get_price(DOT).unwrap(),
+            Price::saturating_from_integer(3)
+        );
+    })
+}
+
+#[test]
+fn borrow_and_redeem_fail_when_price_is_stale() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(Loans::mint(RuntimeOrigin::signed(ALICE), DOT, unit(200)));
+        assert_ok!(Loans::collateral_asset(
+            RuntimeOrigin::signed(ALICE),
+            DOT,
+            true
+        ));
+        assert_ok!(Loans::mint(RuntimeOrigin::signed(BOB), KSM, unit(200)));
+        Loans::mutate_market(KSM, |market| {
+            market.max_price_age = Some(60);
+            market.clone()
+        })
+        .unwrap();
+        MockPriceFeeder::set_price_detail(KSM, 1.into(), 6000);
+        assert_ok!(Loans::borrow(RuntimeOrigin::signed(ALICE), KSM, unit(10)));
+
+        TimestampPallet::set_timestamp(6000 + 61_000);
+        assert_noop!(
+            Loans::borrow(RuntimeOrigin::signed(ALICE), KSM, unit(10)),
+            Error::<Test>::PriceIsStale
+        );
+        // Redeeming collateral needs the stale KSM price to value the debt
+        assert_noop!(
+            Loans::redeem(RuntimeOrigin::signed(ALICE), DOT, unit(10)),
+            Error::<Test>::PriceIsStale
+        );
+        // Repay doesn't depend on prices
+        assert_ok!(Loans::repay_borrow(
+            RuntimeOrigin::signed(ALICE),
+            KSM,
+            unit(5)
+        ));
+    })
+}
+
+#[test]
+fn borrow_fails_when_price_is_suspicious() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(Loans::mint(RuntimeOrigin::signed(ALICE), DOT, unit(200)));
+        assert_ok!(Loans::collateral_asset(
+            RuntimeOrigin::signed(ALICE),
+            DOT,
+            true
+        ));
+        assert_ok!(Loans::mint(RuntimeOrigin::signed(BOB), KSM, unit(200)));
+
+        MockPriceFeeder::set_suspicious(KSM, true);
+        assert_noop!(
+            Loans::borrow(RuntimeOrigin::signed(ALICE), KSM, unit(10)),
+            Error::<Test>::PriceIsSuspicious
+        );
+
+        MockPriceFeeder::set_suspicious(KSM, false);
+        assert_ok!(Loans::borrow(RuntimeOrigin::signed(ALICE), KSM, unit(10)));
+    })
+}
+
 #[test]
 fn ensure_enough_cash_works() {
     new_test_ext().execute_with(|| {
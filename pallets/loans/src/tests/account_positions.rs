@@ -0,0 +1,122 @@
+use crate::mock::{
+    new_test_ext, unit, Loans, MockPriceFeeder, RuntimeOrigin, ALICE, BOB, CHARLIE, DAVE, DOT, KSM,
+    USDT,
+};
+use frame_support::assert_ok;
+use sp_runtime::{traits::Zero, FixedPointNumber, FixedU128};
+
+// Alice and Dave borrow 50 KSM against 200 USDT each, Bob borrows 10 DOT
+// against KSM.
+fn setup_borrowers() {
+    assert_ok!(Loans::mint(RuntimeOrigin::signed(BOB), KSM, unit(500)));
+    assert_ok!(Loans::collateral_asset(
+        RuntimeOrigin::signed(BOB),
+        KSM,
+        true
+    ));
+    assert_ok!(Loans::mint(RuntimeOrigin::signed(DAVE), DOT, unit(100)));
+    assert_ok!(Loans::borrow(RuntimeOrigin::signed(BOB), DOT, unit(10)));
+    for borrower in [ALICE, DAVE] {
+        assert_ok!(Loans::mint(
+            RuntimeOrigin::signed(borrower.clone()),
+            USDT,
+            unit(200)
+        ));
+        assert_ok!(Loans::collateral_asset(
+            RuntimeOrigin::signed(borrower.clone()),
+            USDT,
+            true
+        ));
+        assert_ok!(Loans::borrow(
+            RuntimeOrigin::signed(borrower),
+            KSM,
+            unit(50)
+        ));
+    }
+}
+
+#[test]
+fn get_accounts_with_shortfall_is_paginated() {
+    new_test_ext().execute_with(|| {
+        setup_borrowers();
+        assert_eq!(
+            Loans::get_accounts_with_shortfall(None, 10).unwrap(),
+            (vec![], None)
+        );
+
+        // Alice and Dave have a $40 shortfall: 50 * $3 - 200 * $1 * 55%
+        MockPriceFeeder::set_price(KSM, 3.into());
+        let shortfall = FixedU128::saturating_from_integer(40);
+        let mut accounts = vec![];
+        let mut pages = 0;
+        let mut cursor = None;
+        loop {
+            let (page, next) = Loans::get_accounts_with_shortfall(cursor, 1).unwrap();
+            accounts.extend(page);
+            pages += 1;
+            if next.is_none() {
+                break;
+            }
+            cursor = next;
+        }
+        assert_eq!(pages, 3);
+        accounts.sort();
+        assert_eq!(
+            accounts,
+            vec![
+                (ALICE, shortfall, FixedU128::zero()),
+                (DAVE, shortfall, FixedU128::zero())
+            ]
+        );
+    })
+}
+
+#[test]
+fn borrowers_are_indexed_until_repaid() {
+    new_test_ext().execute_with(|| {
+        setup_borrowers();
+        assert!(Loans::borrowers(ALICE).is_some());
+        assert!(Loans::borrowers(CHARLIE).is_none());
+
+        assert_ok!(Loans::repay_borrow(
+            RuntimeOrigin::signed(ALICE),
+            KSM,
+            unit(20)
+        ));
+        assert!(Loans::borrowers(ALICE).is_some());
+        assert_ok!(Loans::repay_borrow_all(RuntimeOrigin::signed(ALICE), KSM));
+        assert!(Loans::borrowers(ALICE).is_none());
+        assert_eq!(
+            Loans::get_accounts_with_shortfall(None, 10).unwrap(),
+            (vec![], None)
+        );
+    })
+}
+
+#[test]
+fn get_account_positions_works() {
+    new_test_ext().execute_with(|| {
+        setup_borrowers();
+        MockPriceFeeder::set_price(KSM, 2.into());
+
+        let positions = Loans::get_account_positions(&ALICE).unwrap();
+        assert_eq!(positions.len(), 2);
+        assert!(positions.contains(&(
+            USDT,
+            unit(200),
+            0,
+            true,
+            FixedU128::saturating_from_integer(200),
+            FixedU128::zero(),
+        )));
+        assert!(positions.contains(&(
+            KSM,
+            0,
+            unit(50),
+            false,
+            FixedU128::zero(),
+            FixedU128::saturating_from_integer(100),
+        )));
+        assert_eq!(Loans::get_account_positions(&BOB).unwrap().len(), 2);
+    })
+}
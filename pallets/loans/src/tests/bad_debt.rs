@@ -0,0 +1,136 @@
+use crate::{
+    mock::{new_test_ext, unit, Loans, MockPriceFeeder, RuntimeOrigin, ALICE, BOB, KSM, USDT},
+    FixedRateConfig,
+};
+use frame_support::assert_ok;
+use primitives::{Rate, Ratio};
+use sp_runtime::FixedPointNumber;
+
+// Alice borrows 50 KSM against 110 USDT, the KSM price then quadruples so
+// that liquidating half of the borrow seizes all of her collateral.
+fn setup_bad_debt() {
+    assert_ok!(Loans::mint(RuntimeOrigin::signed(BOB), KSM, unit(200)));
+    assert_ok!(Loans::add_reserves(
+        RuntimeOrigin::root(),
+        BOB,
+        KSM,
+        unit(10)
+    ));
+    assert_ok!(Loans::mint(RuntimeOrigin::signed(ALICE), USDT, unit(110)));
+    assert_ok!(Loans::collateral_asset(
+        RuntimeOrigin::signed(ALICE),
+        USDT,
+        true
+    ));
+    assert_ok!(Loans::borrow(RuntimeOrigin::signed(ALICE), KSM, unit(50)));
+    MockPriceFeeder::set_price(KSM, 4.into());
+}
+
+#[test]
+fn bad_debt_is_written_off_against_reserves() {
+    new_test_ext().execute_with(|| {
+        setup_bad_debt();
+        assert_ok!(Loans::liquidate_borrow(
+            RuntimeOrigin::signed(BOB),
+            ALICE,
+            KSM,
+            unit(25),
+            USDT
+        ));
+
+        assert_eq!(Loans::account_deposits(USDT, ALICE).voucher_balance, 0);
+        // The reserves cover 10 of the 25 KSM left, the rest stays in the borrow
+        assert_eq!(Loans::total_reserves(KSM), 0);
+        assert_eq!(Loans::total_bad_debt(KSM), unit(10));
+        assert_eq!(Loans::account_borrows(KSM, ALICE).principal, unit(15));
+        assert_eq!(Loans::total_borrows(KSM), unit(15));
+    })
+}
+
+#[test]
+fn bad_debt_is_socialised_when_enabled() {
+    new_test_ext().execute_with(|| {
+        setup_bad_debt();
+        assert_ok!(Loans::set_bad_debt_socialisation(
+            RuntimeOrigin::root(),
+            KSM,
+            true
+        ));
+        assert_ok!(Loans::liquidate_borrow(
+            RuntimeOrigin::signed(BOB),
+            ALICE,
+            KSM,
+            unit(25),
+            USDT
+        ));
+
+        assert_eq!(Loans::total_bad_debt(KSM), unit(25));
+        assert_eq!(Loans::account_borrows(KSM, ALICE).principal, 0);
+        assert_eq!(Loans::total_borrows(KSM), 0);
+        // cash = 200 + 10 - 50 + 25 = 185 backs 200 KSM of supply
+        assert_eq!(
+            Loans::exchange_rate(KSM),
+            Rate::saturating_from_rational(185, 10_000)
+        );
+    })
+}
+
+#[test]
+fn bad_debt_is_not_written_off_while_collateral_is_left() {
+    new_test_ext().execute_with(|| {
+        setup_bad_debt();
+        assert_ok!(Loans::set_bad_debt_socialisation(
+            RuntimeOrigin::root(),
+            KSM,
+            true
+        ));
+        assert_ok!(Loans::liquidate_borrow(
+            RuntimeOrigin::signed(BOB),
+            ALICE,
+            KSM,
+            unit(20),
+            USDT
+        ));
+
+        assert_eq!(Loans::total_bad_debt(KSM), 0);
+        assert_eq!(Loans::total_reserves(KSM), unit(10));
+        assert_eq!(Loans::account_borrows(KSM, ALICE).principal, unit(30));
+    })
+}
+
+#[test]
+fn bad_debt_is_not_written_off_while_a_fixed_borrow_is_open() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(Loans::set_fixed_rate_config(
+            RuntimeOrigin::root(),
+            KSM,
+            Some(FixedRateConfig {
+                rate_premium: Rate::from_inner(0),
+                origination_fee: Ratio::from_percent(0),
+                max_term: 100,
+                rollover: true,
+            })
+        ));
+        setup_bad_debt();
+        MockPriceFeeder::set_price(KSM, 1.into());
+        assert_ok!(Loans::fixed_borrow(
+            RuntimeOrigin::signed(ALICE),
+            KSM,
+            unit(1),
+            50
+        ));
+        MockPriceFeeder::set_price(KSM, 4.into());
+        assert_ok!(Loans::liquidate_borrow(
+            RuntimeOrigin::signed(BOB),
+            ALICE,
+            KSM,
+            unit(25),
+            USDT
+        ));
+
+        assert_eq!(Loans::account_deposits(USDT, ALICE).voucher_balance, 0);
+        assert_eq!(Loans::total_bad_debt(KSM), 0);
+        assert_eq!(Loans::total_reserves(KSM), unit(10));
+        assert_eq!(Loans::account_borrows(KSM, ALICE).principal, unit(25));
+    })
+}
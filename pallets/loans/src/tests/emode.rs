@@ -0,0 +1,112 @@
+use crate::{
+    mock::{new_test_ext, unit, Loans, RuntimeOrigin, Test, ALICE, BOB, DAVE, DOT, KSM, USDT},
+    EModeCategory, Error,
+};
+use frame_support::{assert_noop, assert_ok};
+use primitives::{Liquidity, Ratio};
+
+const CATEGORY: EModeCategory = EModeCategory {
+    collateral_factor: Ratio::from_percent(80),
+    liquidation_threshold: Ratio::from_percent(85),
+};
+
+// DOT and KSM are correlated, USDT stays outside of the category.
+fn setup_category() {
+    assert_ok!(Loans::set_emode_category(
+        RuntimeOrigin::root(),
+        1,
+        Some(CATEGORY)
+    ));
+    assert_ok!(Loans::set_market_emode_category(
+        RuntimeOrigin::root(),
+        DOT,
+        Some(1)
+    ));
+    assert_ok!(Loans::set_market_emode_category(
+        RuntimeOrigin::root(),
+        KSM,
+        Some(1)
+    ));
+    assert_ok!(Loans::mint(RuntimeOrigin::signed(BOB), KSM, unit(500)));
+    assert_ok!(Loans::mint(RuntimeOrigin::signed(DAVE), USDT, unit(500)));
+    assert_ok!(Loans::mint(RuntimeOrigin::signed(ALICE), DOT, unit(200)));
+    assert_ok!(Loans::collateral_asset(
+        RuntimeOrigin::signed(ALICE),
+        DOT,
+        true
+    ));
+}
+
+#[test]
+fn set_emode_category_should_validate_factors() {
+    new_test_ext().execute_with(|| {
+        assert_noop!(
+            Loans::set_emode_category(
+                RuntimeOrigin::root(),
+                1,
+                Some(EModeCategory {
+                    collateral_factor: Ratio::from_percent(90),
+                    liquidation_threshold: Ratio::from_percent(85),
+                })
+            ),
+            Error::<Test>::InvalidFactor
+        );
+        assert_noop!(
+            Loans::set_market_emode_category(RuntimeOrigin::root(), DOT, Some(2)),
+            Error::<Test>::EModeCategoryDoesNotExist
+        );
+    })
+}
+
+#[test]
+fn emode_category_elevates_liquidity() {
+    new_test_ext().execute_with(|| {
+        setup_category();
+        assert_eq!(Loans::account_emode_category(&ALICE), Some((1, CATEGORY)));
+        let (liquidity, _, _, _) = Loans::get_account_liquidity(&ALICE).unwrap();
+        assert_eq!(liquidity, Liquidity::from_inner(unit(160)));
+        let (liquidity, _, _, _) =
+            Loans::get_account_liquidation_threshold_liquidity(&ALICE).unwrap();
+        assert_eq!(liquidity, Liquidity::from_inner(unit(170)));
+
+        // Borrowing within the category keeps the elevated factors
+        assert_ok!(Loans::borrow(RuntimeOrigin::signed(ALICE), KSM, unit(150)));
+        let (liquidity, _, _, _) = Loans::get_account_liquidity(&ALICE).unwrap();
+        assert_eq!(liquidity, Liquidity::from_inner(unit(10)));
+    })
+}
+
+#[test]
+fn collateral_outside_category_disables_emode() {
+    new_test_ext().execute_with(|| {
+        setup_category();
+        assert_ok!(Loans::mint(RuntimeOrigin::signed(ALICE), USDT, unit(200)));
+        assert_ok!(Loans::collateral_asset(
+            RuntimeOrigin::signed(ALICE),
+            USDT,
+            true
+        ));
+        assert_eq!(Loans::account_emode_category(&ALICE), None);
+        let (liquidity, _, _, _) = Loans::get_account_liquidity(&ALICE).unwrap();
+        assert_eq!(liquidity, Liquidity::from_inner(unit(200)));
+    })
+}
+
+#[test]
+fn leaving_emode_with_shortfall_should_not_work() {
+    new_test_ext().execute_with(|| {
+        setup_category();
+        assert_ok!(Loans::borrow(RuntimeOrigin::signed(ALICE), KSM, unit(155)));
+
+        // Outside of the category the collateral only backs 100
+        assert_noop!(
+            Loans::borrow(RuntimeOrigin::signed(ALICE), USDT, unit(1)),
+            Error::<Test>::InsufficientLiquidity
+        );
+        assert_ok!(Loans::mint(RuntimeOrigin::signed(ALICE), USDT, unit(100)));
+        assert_noop!(
+            Loans::collateral_asset(RuntimeOrigin::signed(ALICE), USDT, true),
+            Error::<Test>::InsufficientLiquidity
+        );
+    })
+}
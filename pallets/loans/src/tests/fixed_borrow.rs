@@ -0,0 +1,218 @@
+use crate::{
+    mock::{
+        new_test_ext, unit, Assets, Loans, MockPriceFeeder, RuntimeOrigin, System, Test,
+        TimestampPallet, ALICE, BOB, KSM, USDT,
+    },
+    Error, FixedBorrow, FixedRateConfig,
+};
+use frame_support::{assert_noop, assert_ok};
+use primitives::{Rate, Ratio, SECONDS_PER_YEAR};
+use sp_runtime::{FixedPointNumber, FixedU128};
+
+const CONFIG: FixedRateConfig<u64> = FixedRateConfig {
+    rate_premium: Rate::from_inner(Rate::DIV / 100),
+    origination_fee: Ratio::from_percent(1),
+    max_term: 100,
+    rollover: true,
+};
+
+// Bob supplies the KSM, Alice borrows 100 KSM at a fixed rate against 200 USDT.
+fn setup_fixed_borrow(config: FixedRateConfig<u64>) {
+    assert_ok!(Loans::set_fixed_rate_config(
+        RuntimeOrigin::root(),
+        KSM,
+        Some(config)
+    ));
+    assert_ok!(Loans::mint(RuntimeOrigin::signed(BOB), KSM, unit(200)));
+    assert_ok!(Loans::mint(RuntimeOrigin::signed(ALICE), USDT, unit(200)));
+    assert_ok!(Loans::collateral_asset(
+        RuntimeOrigin::signed(ALICE),
+        USDT,
+        true
+    ));
+    assert_ok!(Loans::fixed_borrow(
+        RuntimeOrigin::signed(ALICE),
+        KSM,
+        unit(100),
+        50
+    ));
+}
+
+fn one_year_later() {
+    TimestampPallet::set_timestamp(6000 + SECONDS_PER_YEAR * 1000);
+}
+
+#[test]
+fn set_fixed_rate_config_should_validate_params() {
+    new_test_ext().execute_with(|| {
+        assert_noop!(
+            Loans::set_fixed_rate_config(
+                RuntimeOrigin::root(),
+                KSM,
+                Some(FixedRateConfig {
+                    origination_fee: Ratio::from_percent(100),
+                    ..CONFIG
+                })
+            ),
+            Error::<Test>::InvalidFixedRateConfig
+        );
+        assert_noop!(
+            Loans::set_fixed_rate_config(
+                RuntimeOrigin::root(),
+                KSM,
+                Some(FixedRateConfig {
+                    max_term: 0,
+                    ..CONFIG
+                })
+            ),
+            Error::<Test>::InvalidFixedRateConfig
+        );
+        assert_noop!(
+            Loans::fixed_borrow(RuntimeOrigin::signed(ALICE), KSM, unit(10), 50),
+            Error::<Test>::FixedRateDisabled
+        );
+    })
+}
+
+#[test]
+fn fixed_borrow_should_work() {
+    new_test_ext().execute_with(|| {
+        setup_fixed_borrow(CONFIG);
+
+        // The base rate of the empty market plus the 1% premium
+        assert_eq!(
+            Loans::account_fixed_borrows(KSM, ALICE),
+            Some(FixedBorrow {
+                principal: unit(100),
+                rate: Rate::saturating_from_rational(3, 100),
+                started_at: 6,
+                maturity: 50,
+            })
+        );
+        assert_eq!(Loans::total_fixed_borrows(KSM), unit(100));
+        assert_eq!(Loans::total_reserves(KSM), unit(1));
+        assert_eq!(Assets::balance(KSM, ALICE), unit(1099));
+        // The fixed borrow uses all of the liquidity of Alice
+        let (liquidity, _, _, _) = Loans::get_account_liquidity(&ALICE).unwrap();
+        assert_eq!(liquidity, FixedU128::from_inner(0));
+        assert_noop!(
+            Loans::borrow(RuntimeOrigin::signed(ALICE), KSM, unit(1)),
+            Error::<Test>::InsufficientLiquidity
+        );
+        assert_noop!(
+            Loans::fixed_borrow(RuntimeOrigin::signed(ALICE), KSM, unit(1), 50),
+            Error::<Test>::FixedBorrowAlreadyExists
+        );
+        assert_noop!(
+            Loans::fixed_borrow(RuntimeOrigin::signed(BOB), KSM, unit(1), 101),
+            Error::<Test>::InvalidFixedTerm
+        );
+    })
+}
+
+#[test]
+fn repay_fixed_borrow_pays_the_fixed_rate() {
+    new_test_ext().execute_with(|| {
+        setup_fixed_borrow(CONFIG);
+        one_year_later();
+        assert_eq!(
+            Loans::current_fixed_borrow_balance(&ALICE, KSM).unwrap(),
+            unit(103)
+        );
+        // The interest accrues to the market before the borrow is repaid
+        assert_ok!(Loans::accrue_interest(KSM));
+        assert_eq!(Loans::total_fixed_borrows(KSM), unit(103));
+        assert_eq!(Loans::total_reserves(KSM), unit(145) / 100);
+
+        assert_ok!(Loans::repay_fixed_borrow(RuntimeOrigin::signed(ALICE), KSM));
+        assert_eq!(Loans::account_fixed_borrows(KSM, ALICE), None);
+        assert_eq!(Loans::total_fixed_borrows(KSM), 0);
+        assert_eq!(Assets::balance(KSM, ALICE), unit(996));
+        // The origination fee and 15% of the interest
+        assert_eq!(Loans::total_reserves(KSM), unit(145) / 100);
+        assert_noop!(
+            Loans::repay_fixed_borrow(RuntimeOrigin::signed(ALICE), KSM),
+            Error::<Test>::FixedBorrowDoesNotExist
+        );
+    })
+}
+
+#[test]
+fn settle_fixed_borrow_rolls_over_at_maturity() {
+    new_test_ext().execute_with(|| {
+        setup_fixed_borrow(CONFIG);
+        one_year_later();
+        assert_noop!(
+            Loans::settle_fixed_borrow(RuntimeOrigin::signed(BOB), ALICE, KSM),
+            Error::<Test>::FixedBorrowNotSettleable
+        );
+
+        System::set_block_number(50);
+        assert_ok!(Loans::settle_fixed_borrow(
+            RuntimeOrigin::signed(BOB),
+            ALICE,
+            KSM
+        ));
+        assert_eq!(Loans::account_fixed_borrows(KSM, ALICE), None);
+        assert_eq!(Loans::account_borrows(KSM, ALICE).principal, unit(103));
+        assert_eq!(Loans::total_borrows(KSM), unit(103));
+        assert!(!Loans::overdue_borrows(KSM, ALICE));
+        assert_noop!(
+            Loans::liquidate_borrow(RuntimeOrigin::signed(BOB), ALICE, KSM, unit(10), USDT),
+            Error::<Test>::InsufficientShortfall
+        );
+    })
+}
+
+#[test]
+fn overdue_borrow_can_be_liquidated() {
+    new_test_ext().execute_with(|| {
+        setup_fixed_borrow(FixedRateConfig {
+            rollover: false,
+            ..CONFIG
+        });
+        System::set_block_number(50);
+        assert_ok!(Loans::settle_fixed_borrow(
+            RuntimeOrigin::signed(BOB),
+            ALICE,
+            KSM
+        ));
+        assert!(Loans::overdue_borrows(KSM, ALICE));
+        assert_eq!(
+            Loans::get_accounts_with_shortfall(None, 10).unwrap(),
+            (vec![(ALICE, FixedU128::zero(), FixedU128::zero())], None)
+        );
+
+        // Alice has no shortfall, the borrow is liquidatable because it is overdue
+        assert_ok!(Loans::liquidate_borrow(
+            RuntimeOrigin::signed(BOB),
+            ALICE,
+            KSM,
+            unit(10),
+            USDT
+        ));
+        assert_ok!(Loans::repay_borrow_all(RuntimeOrigin::signed(ALICE), KSM));
+        assert!(!Loans::overdue_borrows(KSM, ALICE));
+    })
+}
+
+#[test]
+fn fixed_borrow_can_be_settled_early_with_shortfall() {
+    new_test_ext().execute_with(|| {
+        setup_fixed_borrow(CONFIG);
+        MockPriceFeeder::set_price(KSM, 2.into());
+        assert_ok!(Loans::settle_fixed_borrow(
+            RuntimeOrigin::signed(BOB),
+            ALICE,
+            KSM
+        ));
+        assert!(!Loans::overdue_borrows(KSM, ALICE));
+        assert_ok!(Loans::liquidate_borrow(
+            RuntimeOrigin::signed(BOB),
+            ALICE,
+            KSM,
+            unit(10),
+            USDT
+        ));
+    })
+}
@@ -0,0 +1,107 @@
+use crate::{
+    mock::{
+        new_test_ext, unit, Assets, Loans, RuntimeCall, RuntimeOrigin, Test, ALICE, BOB, DOT, KSM,
+    },
+    AccountDeposits, Error,
+};
+use frame_support::{assert_noop, assert_ok};
+use primitives::Ratio;
+
+fn remark() -> Box<RuntimeCall> {
+    Box::new(RuntimeCall::System(frame_system::Call::remark {
+        remark: vec![],
+    }))
+}
+
+#[test]
+fn flash_loan_should_work() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(Loans::mint(RuntimeOrigin::signed(BOB), DOT, unit(100)));
+        assert_ok!(Loans::set_flash_loan_fee(
+            RuntimeOrigin::root(),
+            DOT,
+            Some(Ratio::from_percent(1))
+        ));
+
+        assert_ok!(Loans::flash_loan(
+            RuntimeOrigin::signed(ALICE),
+            DOT,
+            unit(50),
+            remark()
+        ));
+        // The fee is paid by the borrower and goes to reserves
+        assert_eq!(Assets::balance(DOT, ALICE), unit(1000) - unit(50) / 100);
+        assert_eq!(Loans::total_reserves(DOT), unit(50) / 100);
+        assert_eq!(Loans::flash_loan_amount(DOT), 0);
+    })
+}
+
+#[test]
+fn flash_loan_without_fee_should_not_work() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(Loans::mint(RuntimeOrigin::signed(BOB), DOT, unit(100)));
+        assert_noop!(
+            Loans::flash_loan(RuntimeOrigin::signed(ALICE), DOT, unit(50), remark()),
+            Error::<Test>::FlashLoanDisabled
+        );
+        assert_ok!(Loans::set_flash_loan_fee(
+            RuntimeOrigin::root(),
+            DOT,
+            Some(Ratio::from_percent(1))
+        ));
+        assert_noop!(
+            Loans::flash_loan(RuntimeOrigin::signed(ALICE), DOT, unit(101), remark()),
+            Error::<Test>::InsufficientCash
+        );
+    })
+}
+
+#[test]
+fn flash_loan_not_repaid_should_not_work() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(Loans::mint(RuntimeOrigin::signed(BOB), KSM, unit(100)));
+        assert_ok!(Loans::set_flash_loan_fee(
+            RuntimeOrigin::root(),
+            KSM,
+            Some(Ratio::from_percent(1))
+        ));
+        // Alice gives all her KSM away, so the fee cannot be paid
+        let call = Box::new(RuntimeCall::Assets(pallet_assets::Call::transfer {
+            id: KSM.into(),
+            target: BOB,
+            amount: unit(1050),
+        }));
+        assert_noop!(
+            Loans::flash_loan(RuntimeOrigin::signed(ALICE), KSM, unit(50), call),
+            Error::<Test>::FlashLoanNotRepaid
+        );
+    })
+}
+
+#[test]
+fn flash_loan_should_not_move_exchange_rate() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(Loans::mint(RuntimeOrigin::signed(BOB), DOT, unit(100)));
+        assert_ok!(Loans::set_flash_loan_fee(
+            RuntimeOrigin::root(),
+            DOT,
+            Some(Ratio::from_percent(1))
+        ));
+        let exchange_rate = Loans::exchange_rate_stored(DOT).unwrap();
+
+        let call = Box::new(RuntimeCall::Loans(crate::Call::mint {
+            asset_id: DOT,
+            mint_amount: unit(10),
+        }));
+        assert_ok!(Loans::flash_loan(
+            RuntimeOrigin::signed(ALICE),
+            DOT,
+            unit(90),
+            call
+        ));
+        assert_eq!(
+            AccountDeposits::<Test>::get(DOT, ALICE).voucher_balance,
+            Loans::calc_collateral_amount(unit(10), exchange_rate).unwrap()
+        );
+    })
+}
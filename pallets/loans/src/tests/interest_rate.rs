@@ -1,6 +1,6 @@
 use crate::tests::Loans;
-use crate::{mock::*, Markets};
-use frame_support::assert_ok;
+use crate::{mock::*, AdaptiveModel, Error, InterestRateModel, Kink, Markets};
+use frame_support::{assert_noop, assert_ok};
 use primitives::{Rate, Ratio, SECONDS_PER_YEAR};
 use sp_runtime::{
     traits::{CheckedDiv, One, Saturating},
@@ -406,3 +406,85 @@ fn a_market_can_only_accrue_interest_once_in_a_block() {
         );
     })
 }
+
+#[test]
+fn update_rate_model_accepts_piecewise_model() {
+    new_test_ext().execute_with(|| {
+        let kink = Kink {
+            utilization: Ratio::from_percent(50),
+            rate: Rate::saturating_from_rational(6, 100),
+        };
+        assert_ok!(Loans::update_rate_model(
+            RuntimeOrigin::root(),
+            DOT,
+            InterestRateModel::new_piecewise_model(
+                Rate::saturating_from_rational(2, 100),
+                vec![kink],
+                Rate::saturating_from_rational(30, 100),
+            ),
+        ));
+        // The rate must not decrease past a kink
+        assert_noop!(
+            Loans::update_rate_model(
+                RuntimeOrigin::root(),
+                DOT,
+                InterestRateModel::new_piecewise_model(
+                    Rate::saturating_from_rational(2, 100),
+                    vec![kink],
+                    Rate::saturating_from_rational(5, 100),
+                ),
+            ),
+            Error::<Test>::InvalidRateModelParam
+        );
+    })
+}
+
+#[test]
+fn adaptive_model_state_is_stored_per_market() {
+    new_test_ext().execute_with(|| {
+        let model = AdaptiveModel {
+            target_utilization: Ratio::from_percent(80),
+            initial_rate_at_target: Rate::saturating_from_rational(4, 100),
+            min_rate_at_target: Rate::saturating_from_rational(1, 1000),
+            max_rate_at_target: Rate::saturating_from_rational(20, 100),
+            adjustment_speed: Rate::saturating_from_rational(1, 1_000_000),
+            curve_steepness: Rate::saturating_from_integer(4),
+        };
+        assert_ok!(Loans::update_rate_model(
+            RuntimeOrigin::root(),
+            DOT,
+            InterestRateModel::Adaptive(model),
+        ));
+        // 50% utilization is below the 80% target
+        assert_ok!(Loans::mint(RuntimeOrigin::signed(ALICE), DOT, unit(200)));
+        assert_ok!(Loans::collateral_asset(
+            RuntimeOrigin::signed(ALICE),
+            DOT,
+            true
+        ));
+        assert_ok!(Loans::borrow(RuntimeOrigin::signed(ALICE), DOT, unit(100)));
+        assert_eq!(Loans::rate_at_target(DOT), None);
+
+        TimestampPallet::set_timestamp(6000 + 100_000 * 1000);
+        assert_ok!(Loans::accrue_interest(DOT));
+        // The interest accrues at the initial rate at target: 4% * (1 - 0.75 * 0.375)
+        assert_eq!(
+            Loans::borrow_rate(DOT),
+            Rate::saturating_from_rational(2875, 100_000)
+        );
+        // rate at target: 4% * (1 - 0.000001 * 0.375 * 100000)
+        assert_eq!(
+            Loans::rate_at_target(DOT),
+            Some(Rate::saturating_from_rational(385, 10_000))
+        );
+        assert_eq!(Loans::rate_at_target(KSM), None);
+
+        // Changing the model resets its state
+        assert_ok!(Loans::update_rate_model(
+            RuntimeOrigin::root(),
+            DOT,
+            InterestRateModel::Adaptive(model),
+        ));
+        assert_eq!(Loans::rate_at_target(DOT), None);
+    })
+}
@@ -0,0 +1,149 @@
+use crate::{
+    mock::{new_test_ext, unit, Loans, RuntimeOrigin, Test, ALICE, BOB, DAVE, DOT, KSM, USDT},
+    BorrowIndex, Error, IsolatedDebt,
+};
+use frame_support::{assert_noop, assert_ok};
+use primitives::{Liquidity, Rate};
+use sp_runtime::FixedPointNumber;
+
+// KSM becomes an isolated collateral with a debt ceiling of 80,
+// DOT is the only asset borrowable against it.
+fn isolate_ksm() {
+    let mut market = Loans::market(KSM).unwrap();
+    market.isolated = true;
+    market.debt_ceiling = Liquidity::from_inner(unit(80));
+    assert_ok!(Loans::force_update_market(
+        RuntimeOrigin::root(),
+        KSM,
+        market
+    ));
+    let mut market = Loans::market(DOT).unwrap();
+    market.borrowable_in_isolation = true;
+    assert_ok!(Loans::force_update_market(
+        RuntimeOrigin::root(),
+        DOT,
+        market
+    ));
+}
+
+#[test]
+fn isolated_collateral_cannot_be_combined() {
+    new_test_ext().execute_with(|| {
+        isolate_ksm();
+        assert_ok!(Loans::mint(RuntimeOrigin::signed(ALICE), KSM, unit(200)));
+        assert_ok!(Loans::mint(RuntimeOrigin::signed(ALICE), DOT, unit(200)));
+        assert_ok!(Loans::collateral_asset(
+            RuntimeOrigin::signed(ALICE),
+            KSM,
+            true
+        ));
+        assert_noop!(
+            Loans::collateral_asset(RuntimeOrigin::signed(ALICE), DOT, true),
+            Error::<Test>::IsolatedCollateralCombined
+        );
+
+        assert_ok!(Loans::mint(RuntimeOrigin::signed(BOB), KSM, unit(200)));
+        assert_ok!(Loans::mint(RuntimeOrigin::signed(BOB), DOT, unit(200)));
+        assert_ok!(Loans::collateral_asset(
+            RuntimeOrigin::signed(BOB),
+            DOT,
+            true
+        ));
+        assert_noop!(
+            Loans::collateral_asset(RuntimeOrigin::signed(BOB), KSM, true),
+            Error::<Test>::IsolatedCollateralCombined
+        );
+    })
+}
+
+#[test]
+fn borrow_against_isolated_collateral_is_limited() {
+    new_test_ext().execute_with(|| {
+        isolate_ksm();
+        assert_ok!(Loans::mint(RuntimeOrigin::signed(BOB), DOT, unit(200)));
+        assert_ok!(Loans::mint(RuntimeOrigin::signed(DAVE), USDT, unit(200)));
+        assert_ok!(Loans::mint(RuntimeOrigin::signed(ALICE), KSM, unit(200)));
+        assert_ok!(Loans::collateral_asset(
+            RuntimeOrigin::signed(ALICE),
+            KSM,
+            true
+        ));
+
+        // USDT is not whitelisted
+        assert_noop!(
+            Loans::borrow(RuntimeOrigin::signed(ALICE), USDT, unit(10)),
+            Error::<Test>::NotBorrowableInIsolation
+        );
+
+        assert_ok!(Loans::borrow(RuntimeOrigin::signed(ALICE), DOT, unit(50)));
+        assert_eq!(IsolatedDebt::<Test>::get(KSM, DOT).principal, unit(50));
+        // The collateral could back 100, but the ceiling is 80
+        assert_noop!(
+            Loans::borrow(RuntimeOrigin::signed(ALICE), DOT, unit(40)),
+            Error::<Test>::DebtCeilingExceeded
+        );
+        assert_ok!(Loans::borrow(RuntimeOrigin::signed(ALICE), DOT, unit(30)));
+
+        assert_ok!(Loans::repay_borrow(
+            RuntimeOrigin::signed(ALICE),
+            DOT,
+            unit(80)
+        ));
+        assert!(!IsolatedDebt::<Test>::contains_key(KSM, DOT));
+    })
+}
+
+#[test]
+fn isolated_debt_accrues_interest() {
+    new_test_ext().execute_with(|| {
+        isolate_ksm();
+        assert_ok!(Loans::mint(RuntimeOrigin::signed(BOB), DOT, unit(200)));
+        assert_ok!(Loans::mint(RuntimeOrigin::signed(ALICE), KSM, unit(200)));
+        assert_ok!(Loans::collateral_asset(
+            RuntimeOrigin::signed(ALICE),
+            KSM,
+            true
+        ));
+        assert_ok!(Loans::borrow(RuntimeOrigin::signed(ALICE), DOT, unit(75)));
+
+        // 5% interest brings the debt to 78.75, within the ceiling of 80
+        BorrowIndex::<Test>::insert(DOT, Rate::saturating_from_rational(105, 100));
+        assert_noop!(
+            Loans::borrow(RuntimeOrigin::signed(ALICE), DOT, unit(2)),
+            Error::<Test>::DebtCeilingExceeded
+        );
+        assert_ok!(Loans::borrow(RuntimeOrigin::signed(ALICE), DOT, unit(1)));
+        assert_eq!(
+            IsolatedDebt::<Test>::get(KSM, DOT).principal,
+            unit(75) * 105 / 100 + unit(1)
+        );
+
+        // the interest is repaid along with the principal
+        assert_ok!(Loans::repay_borrow_all(RuntimeOrigin::signed(ALICE), DOT));
+        assert!(!IsolatedDebt::<Test>::contains_key(KSM, DOT));
+    })
+}
+
+#[test]
+fn isolated_collateral_combined_before_isolation_is_ignored() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(Loans::mint(RuntimeOrigin::signed(ALICE), KSM, unit(200)));
+        assert_ok!(Loans::mint(RuntimeOrigin::signed(ALICE), DOT, unit(200)));
+        assert_ok!(Loans::collateral_asset(
+            RuntimeOrigin::signed(ALICE),
+            KSM,
+            true
+        ));
+        assert_ok!(Loans::collateral_asset(
+            RuntimeOrigin::signed(ALICE),
+            DOT,
+            true
+        ));
+        let (liquidity, _, _, _) = Loans::get_account_liquidity(&ALICE).unwrap();
+        assert_eq!(liquidity, Liquidity::from_inner(unit(200)));
+
+        isolate_ksm();
+        let (liquidity, _, _, _) = Loans::get_account_liquidity(&ALICE).unwrap();
+        assert_eq!(liquidity, Liquidity::from_inner(unit(100)));
+    })
+}
@@ -0,0 +1,156 @@
+use crate::{
+    mock::{
+        new_test_ext, unit, AccountId, Assets, BlockNumber, DefaultAMM, DefaultLpFee, Loans,
+        RuntimeOrigin, Test, ALICE, BOB, DAVE, DOT, USDT,
+    },
+    AccountBorrows, AccountDeposits, Error,
+};
+use frame_support::{assert_noop, assert_ok};
+use pallet_traits::AMM;
+use primitives::{tokens::LP_DOT_USDT, Balance, CurrencyId};
+use sp_runtime::DispatchError;
+
+fn amount_out(amount_in: Balance, token_in: CurrencyId, token_out: CurrencyId) -> Balance {
+    <DefaultAMM as AMM<AccountId, CurrencyId, Balance, BlockNumber>>::get_amounts_out(
+        amount_in,
+        vec![token_in, token_out],
+    )
+    .unwrap()[1]
+}
+
+// DOT and USDT are swappable 1:1, Bob supplies the DOT Dave borrows.
+fn setup_pool() {
+    assert_ok!(Assets::force_create(
+        RuntimeOrigin::root(),
+        LP_DOT_USDT.into(),
+        ALICE,
+        true,
+        1
+    ));
+    assert_ok!(DefaultAMM::create_pool(
+        RuntimeOrigin::signed(ALICE),
+        (DOT, USDT),
+        (unit(500), unit(500)),
+        ALICE,
+        LP_DOT_USDT,
+        DefaultLpFee::get(),
+    ));
+    assert_ok!(Loans::mint(RuntimeOrigin::signed(BOB), DOT, unit(500)));
+    assert_ok!(Loans::mint(RuntimeOrigin::signed(DAVE), USDT, unit(100)));
+    assert_ok!(Loans::collateral_asset(
+        RuntimeOrigin::signed(DAVE),
+        USDT,
+        true
+    ));
+}
+
+#[test]
+fn leverage_should_work() {
+    new_test_ext().execute_with(|| {
+        setup_pool();
+        let supply_amount = amount_out(unit(40), DOT, USDT);
+        assert_ok!(Loans::leverage(
+            RuntimeOrigin::signed(DAVE),
+            DOT,
+            unit(40),
+            USDT,
+            supply_amount
+        ));
+
+        assert_eq!(AccountBorrows::<Test>::get(DOT, DAVE).principal, unit(40));
+        assert_eq!(
+            Loans::calc_underlying_amount(
+                AccountDeposits::<Test>::get(USDT, DAVE).voucher_balance,
+                Loans::exchange_rate(USDT)
+            )
+            .unwrap(),
+            unit(100) + supply_amount
+        );
+        // The borrowed DOT is not kept by the sender
+        assert_eq!(Assets::balance(DOT, DAVE), unit(1000));
+    })
+}
+
+#[test]
+fn leverage_with_shortfall_should_not_work() {
+    new_test_ext().execute_with(|| {
+        setup_pool();
+        // The borrow alone exceeds the liquidity, the proceeds can't make up for it
+        assert_noop!(
+            Loans::leverage(RuntimeOrigin::signed(DAVE), DOT, unit(150), USDT, 1),
+            Error::<Test>::InsufficientLiquidity
+        );
+        assert_noop!(
+            Loans::leverage(RuntimeOrigin::signed(DAVE), DOT, unit(40), USDT, unit(40)),
+            DispatchError::Other("MinimumAmountOutViolated")
+        );
+    })
+}
+
+#[test]
+fn leverage_should_keep_liquidation_free_collateral() {
+    new_test_ext().execute_with(|| {
+        setup_pool();
+        assert_ok!(Loans::update_liquidation_free_collateral(
+            RuntimeOrigin::root(),
+            vec![USDT]
+        ));
+        // USDT only backs DOT borrows, the supplied DOT can't make up for the USDT borrowed
+        assert_noop!(
+            Loans::leverage(RuntimeOrigin::signed(DAVE), USDT, unit(10), DOT, 1),
+            Error::<Test>::InsufficientLiquidity
+        );
+        assert_ok!(Loans::leverage(
+            RuntimeOrigin::signed(DAVE),
+            DOT,
+            unit(10),
+            USDT,
+            1
+        ));
+    })
+}
+
+#[test]
+fn repay_with_collateral_should_work() {
+    new_test_ext().execute_with(|| {
+        setup_pool();
+        assert_ok!(Loans::borrow(RuntimeOrigin::signed(DAVE), DOT, unit(40)));
+        let repay_amount = amount_out(unit(30), USDT, DOT);
+        assert_ok!(Loans::repay_with_collateral(
+            RuntimeOrigin::signed(DAVE),
+            USDT,
+            unit(30),
+            DOT,
+            repay_amount
+        ));
+
+        assert_eq!(
+            Loans::current_borrow_balance(&DAVE, DOT).unwrap(),
+            unit(40) - repay_amount
+        );
+        assert_eq!(Assets::balance(DOT, DAVE), unit(1040));
+        assert_eq!(Assets::balance(USDT, DAVE), unit(900));
+    })
+}
+
+#[test]
+fn repay_with_collateral_with_invalid_params_should_not_work() {
+    new_test_ext().execute_with(|| {
+        setup_pool();
+        assert_ok!(Loans::borrow(RuntimeOrigin::signed(DAVE), DOT, unit(40)));
+        assert_noop!(
+            Loans::repay_with_collateral(
+                RuntimeOrigin::signed(DAVE),
+                USDT,
+                unit(30),
+                DOT,
+                unit(30)
+            ),
+            DispatchError::Other("MinimumAmountOutViolated")
+        );
+        assert_noop!(
+            Loans::repay_with_collateral(RuntimeOrigin::signed(DAVE), DOT, unit(30), DOT, 1),
+            Error::<Test>::InvalidCurrencyId
+        );
+    })
+}
@@ -1,7 +1,7 @@
 use crate::{
     mock::{
-        new_test_ext, Assets, Loans, MockPriceFeeder, RuntimeOrigin, Test, ALICE, BOB, DOT, KSM,
-        USDT,
+        new_test_ext, Assets, Loans, MockPriceFeeder, RuntimeOrigin, Test, TimestampPallet, ALICE,
+        BOB, DOT, KSM, USDT,
     },
     tests::unit,
     Error, MarketState,
@@ -215,6 +215,40 @@ fn full_workflow_works_as_expected() {
     })
 }
 
+#[test]
+fn liquidation_on_stale_price_is_configurable() {
+    new_test_ext().execute_with(|| {
+        initial_setup();
+        alice_borrows_100_ksm();
+        // adjust KSM price to make ALICE generate shortfall
+        MockPriceFeeder::set_price_detail(KSM, 2.into(), 6000);
+        Loans::mutate_market(KSM, |market| {
+            market.max_price_age = Some(60);
+            market.clone()
+        })
+        .unwrap();
+
+        TimestampPallet::set_timestamp(6000 + 61_000);
+        assert_noop!(
+            Loans::liquidate_borrow(RuntimeOrigin::signed(BOB), ALICE, KSM, unit(50), USDT),
+            Error::<Test>::PriceIsStale
+        );
+
+        Loans::mutate_market(KSM, |market| {
+            market.liquidate_on_stale_price = true;
+            market.clone()
+        })
+        .unwrap();
+        assert_ok!(Loans::liquidate_borrow(
+            RuntimeOrigin::signed(BOB),
+            ALICE,
+            KSM,
+            unit(50),
+            USDT
+        ));
+    })
+}
+
 #[test]
 fn liquidator_cannot_take_inactive_market_currency() {
     new_test_ext().execute_with(|| {
@@ -0,0 +1,191 @@
+use crate::{
+    mock::{
+        new_test_ext, unit, Loans, MockPriceFeeder, RuntimeOrigin, System, Test, ALICE, BOB, KSM,
+        USDT,
+    },
+    Error, LiquidationAuction, LiquidationAuctionConfig,
+};
+use frame_support::{assert_noop, assert_ok};
+use primitives::{tokens::CDOT_6_13, Ratio};
+use sp_runtime::FixedPointNumber;
+
+const CONFIG: LiquidationAuctionConfig<u64> = LiquidationAuctionConfig {
+    max_discount: Ratio::from_percent(10),
+    duration: 100,
+};
+
+// Alice borrows 100 KSM against 200 USDT and 200 CDOT, then the KSM
+// price doubles so that she can be liquidated.
+fn setup_shortfall() {
+    assert_ok!(Loans::set_liquidation_auction_config(
+        RuntimeOrigin::root(),
+        KSM,
+        Some(CONFIG)
+    ));
+    assert_ok!(Loans::mint(RuntimeOrigin::signed(BOB), KSM, unit(200)));
+    assert_ok!(Loans::mint(RuntimeOrigin::signed(ALICE), USDT, unit(200)));
+    assert_ok!(Loans::collateral_asset(
+        RuntimeOrigin::signed(ALICE),
+        USDT,
+        true
+    ));
+    assert_ok!(Loans::mint(
+        RuntimeOrigin::signed(ALICE),
+        CDOT_6_13,
+        unit(200)
+    ));
+    assert_ok!(Loans::collateral_asset(
+        RuntimeOrigin::signed(ALICE),
+        CDOT_6_13,
+        true
+    ));
+    assert_ok!(Loans::borrow(RuntimeOrigin::signed(ALICE), KSM, unit(100)));
+    MockPriceFeeder::set_price(KSM, 2.into());
+}
+
+#[test]
+fn set_liquidation_auction_config_should_validate_params() {
+    new_test_ext().execute_with(|| {
+        assert_noop!(
+            Loans::set_liquidation_auction_config(
+                RuntimeOrigin::root(),
+                KSM,
+                Some(LiquidationAuctionConfig {
+                    max_discount: Ratio::from_percent(0),
+                    duration: 100,
+                })
+            ),
+            Error::<Test>::InvalidLiquidationAuctionConfig
+        );
+        assert_noop!(
+            Loans::set_liquidation_auction_config(
+                RuntimeOrigin::root(),
+                KSM,
+                Some(LiquidationAuctionConfig {
+                    max_discount: Ratio::from_percent(10),
+                    duration: 0,
+                })
+            ),
+            Error::<Test>::InvalidLiquidationAuctionConfig
+        );
+        assert_noop!(
+            Loans::open_liquidation_auction(RuntimeOrigin::signed(BOB), ALICE, USDT),
+            Error::<Test>::LiquidationAuctionDisabled
+        );
+    })
+}
+
+#[test]
+fn open_liquidation_auction_requires_shortfall() {
+    new_test_ext().execute_with(|| {
+        setup_shortfall();
+        MockPriceFeeder::set_price(KSM, 1.into());
+        assert_noop!(
+            Loans::open_liquidation_auction(RuntimeOrigin::signed(BOB), ALICE, KSM),
+            Error::<Test>::InsufficientShortfall
+        );
+
+        MockPriceFeeder::set_price(KSM, 2.into());
+        assert_ok!(Loans::open_liquidation_auction(
+            RuntimeOrigin::signed(BOB),
+            ALICE,
+            KSM
+        ));
+        assert_eq!(
+            Loans::liquidation_auction(ALICE, KSM),
+            Some(LiquidationAuction { started_at: 0 })
+        );
+        assert_noop!(
+            Loans::open_liquidation_auction(RuntimeOrigin::signed(BOB), ALICE, KSM),
+            Error::<Test>::LiquidationAuctionAlreadyOpen
+        );
+        // The fixed incentive doesn't apply to auction markets
+        assert_noop!(
+            Loans::liquidate_borrow(RuntimeOrigin::signed(BOB), ALICE, KSM, unit(50), USDT),
+            Error::<Test>::LiquidationAuctionEnabled
+        );
+    })
+}
+
+#[test]
+fn fill_liquidation_auction_uses_growing_discount() {
+    new_test_ext().execute_with(|| {
+        setup_shortfall();
+        assert_noop!(
+            Loans::fill_liquidation_auction(RuntimeOrigin::signed(BOB), ALICE, KSM, unit(10), USDT),
+            Error::<Test>::LiquidationAuctionNotOpen
+        );
+        assert_ok!(Loans::open_liquidation_auction(
+            RuntimeOrigin::signed(BOB),
+            ALICE,
+            KSM
+        ));
+
+        // Halfway through the auction the discount is 5%
+        System::set_block_number(50);
+        assert_ok!(Loans::fill_liquidation_auction(
+            RuntimeOrigin::signed(BOB),
+            ALICE,
+            KSM,
+            unit(10),
+            USDT
+        ));
+        // seized = (10 * 2) * 1.05 = 21
+        // Bob USDT collateral: 21 - ((21 - 21 / 1.05) * 0.03) = 20.97
+        assert_eq!(
+            Loans::exchange_rate(USDT)
+                .saturating_mul_int(Loans::account_deposits(USDT, ALICE).voucher_balance),
+            unit(179),
+        );
+        assert_eq!(
+            Loans::exchange_rate(USDT)
+                .saturating_mul_int(Loans::account_deposits(USDT, BOB).voucher_balance),
+            unit(2097) / 100,
+        );
+
+        // The discount stops growing at the end of the auction
+        System::set_block_number(500);
+        assert_ok!(Loans::fill_liquidation_auction(
+            RuntimeOrigin::signed(BOB),
+            ALICE,
+            KSM,
+            unit(10),
+            USDT
+        ));
+        // seized = (10 * 2) * 1.1 = 22
+        assert_eq!(
+            Loans::exchange_rate(USDT)
+                .saturating_mul_int(Loans::account_deposits(USDT, ALICE).voucher_balance),
+            unit(157),
+        );
+        assert_eq!(Loans::account_borrows(KSM, ALICE).principal, unit(80));
+    })
+}
+
+#[test]
+fn close_liquidation_auction_requires_healthy_borrower() {
+    new_test_ext().execute_with(|| {
+        setup_shortfall();
+        assert_ok!(Loans::open_liquidation_auction(
+            RuntimeOrigin::signed(BOB),
+            ALICE,
+            KSM
+        ));
+        assert_noop!(
+            Loans::close_liquidation_auction(RuntimeOrigin::signed(BOB), ALICE, KSM),
+            Error::<Test>::LiquidationAuctionNotClosable
+        );
+
+        MockPriceFeeder::set_price(KSM, 1.into());
+        assert_ok!(Loans::close_liquidation_auction(
+            RuntimeOrigin::signed(ALICE),
+            ALICE,
+            KSM
+        ));
+        assert_eq!(Loans::liquidation_auction(ALICE, KSM), None);
+        assert_noop!(
+            Loans::close_liquidation_auction(RuntimeOrigin::signed(ALICE), ALICE, KSM),
+            Error::<Test>::LiquidationAuctionNotOpen
+        );
+    })
+}
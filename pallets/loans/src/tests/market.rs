@@ -246,6 +246,41 @@ fn update_market_works() {
     })
 }
 
+#[test]
+fn set_market_price_staleness_works() {
+    new_test_ext().execute_with(|| {
+        assert_noop!(
+            Loans::set_market_price_staleness(RuntimeOrigin::signed(ALICE), DOT, Some(60), true),
+            BadOrigin
+        );
+        assert_noop!(
+            Loans::set_market_price_staleness(RuntimeOrigin::root(), SDOT, Some(60), true),
+            Error::<Test>::MarketDoesNotExist
+        );
+
+        assert_ok!(Loans::set_market_price_staleness(
+            RuntimeOrigin::root(),
+            DOT,
+            Some(60),
+            true
+        ));
+        let market = Loans::market(DOT).unwrap();
+        assert_eq!(market.max_price_age, Some(60));
+        assert!(market.liquidate_on_stale_price);
+        assert_eq!(market.close_factor, MARKET_MOCK.close_factor);
+
+        assert_ok!(Loans::set_market_price_staleness(
+            RuntimeOrigin::root(),
+            DOT,
+            None,
+            false
+        ));
+        let market = Loans::market(DOT).unwrap();
+        assert_eq!(market.max_price_age, None);
+        assert!(!market.liquidate_on_stale_price);
+    })
+}
+
 #[test]
 fn update_market_should_not_work_if_with_invalid_params() {
     new_test_ext().execute_with(|| {
@@ -344,7 +379,7 @@ fn update_rate_model_works() {
         assert_ok!(Loans::update_rate_model(
             RuntimeOrigin::root(),
             DOT,
-            new_rate_model,
+            new_rate_model.clone(),
         ));
         assert_eq!(Loans::market(DOT).unwrap().rate_model, new_rate_model);
 
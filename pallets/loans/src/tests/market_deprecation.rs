@@ -0,0 +1,195 @@
+use crate::{
+    mock::{
+        market_mock, new_test_ext, unit, Loans, RuntimeEvent, RuntimeOrigin, System, Test, ALICE,
+        DOT, PDOT, USDT,
+    },
+    Error, Event, FlashLoanAmounts, LiquidationAuction, LiquidationAuctions, MarketState,
+    OpenLiquidationAuctions, OverdueBorrows, MAX_REMOVED_MARKET_KEYS,
+};
+use frame_support::{assert_noop, assert_ok};
+use sp_runtime::AccountId32;
+use sp_runtime::{FixedPointNumber, FixedU128};
+
+#[test]
+fn deprecated_market_only_allows_winding_down() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(Loans::mint(RuntimeOrigin::signed(ALICE), DOT, unit(200)));
+        assert_ok!(Loans::collateral_asset(
+            RuntimeOrigin::signed(ALICE),
+            DOT,
+            true
+        ));
+        assert_ok!(Loans::borrow(RuntimeOrigin::signed(ALICE), DOT, unit(50)));
+        assert_ok!(Loans::deprecate_market(RuntimeOrigin::root(), DOT, 100));
+        assert_eq!(Loans::market(DOT).unwrap().state, MarketState::Deprecated);
+        assert_noop!(
+            Loans::deprecate_market(RuntimeOrigin::root(), DOT, 100),
+            Error::<Test>::MarketNotActivated
+        );
+
+        assert_noop!(
+            Loans::mint(RuntimeOrigin::signed(ALICE), DOT, unit(10)),
+            Error::<Test>::MarketDeprecated
+        );
+        assert_noop!(
+            Loans::borrow(RuntimeOrigin::signed(ALICE), DOT, unit(10)),
+            Error::<Test>::MarketDeprecated
+        );
+        assert_ok!(Loans::repay_borrow(
+            RuntimeOrigin::signed(ALICE),
+            DOT,
+            unit(10)
+        ));
+        assert_ok!(Loans::redeem(RuntimeOrigin::signed(ALICE), DOT, unit(10)));
+    })
+}
+
+#[test]
+fn deprecated_market_collateral_factor_ramps_down() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(Loans::mint(RuntimeOrigin::signed(ALICE), USDT, unit(200)));
+        assert_ok!(Loans::collateral_asset(
+            RuntimeOrigin::signed(ALICE),
+            USDT,
+            true
+        ));
+        assert_ok!(Loans::deprecate_market(RuntimeOrigin::root(), USDT, 100));
+
+        // Halfway through the ramp the collateral factor is 25%
+        System::set_block_number(50);
+        let (liquidity, _, _, _) = Loans::get_account_liquidity(&ALICE).unwrap();
+        assert_eq!(liquidity, FixedU128::saturating_from_integer(50));
+
+        System::set_block_number(100);
+        let (liquidity, _, _, _) = Loans::get_account_liquidity(&ALICE).unwrap();
+        assert_eq!(liquidity, FixedU128::from_inner(0));
+
+        // Activating the market again cancels the deprecation
+        assert_ok!(Loans::activate_market(RuntimeOrigin::root(), USDT));
+        assert_eq!(Loans::market_deprecation(USDT), None);
+        let (liquidity, _, _, _) = Loans::get_account_liquidity(&ALICE).unwrap();
+        assert_eq!(liquidity, FixedU128::saturating_from_integer(100));
+    })
+}
+
+#[test]
+fn remove_market_requires_empty_deprecated_market() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(Loans::mint(RuntimeOrigin::signed(ALICE), DOT, unit(100)));
+        assert_noop!(
+            Loans::remove_market(RuntimeOrigin::root(), DOT),
+            Error::<Test>::MarketNotDeprecated
+        );
+        assert_ok!(Loans::deprecate_market(RuntimeOrigin::root(), DOT, 100));
+        assert_noop!(
+            Loans::remove_market(RuntimeOrigin::root(), DOT),
+            Error::<Test>::MarketNotEmpty
+        );
+
+        assert_ok!(Loans::redeem_all(RuntimeOrigin::signed(ALICE), DOT));
+        assert_ok!(Loans::remove_market(RuntimeOrigin::root(), DOT));
+        assert!(Loans::market(DOT).is_err());
+        assert!(Loans::underlying_id(PDOT).is_err());
+
+        // The ptoken id is free for a new market
+        assert_ok!(Loans::add_market(
+            RuntimeOrigin::root(),
+            DOT,
+            market_mock(PDOT)
+        ));
+    })
+}
+
+#[test]
+fn remove_market_clears_market_storage() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(Loans::set_bad_debt_socialisation(
+            RuntimeOrigin::root(),
+            DOT,
+            true
+        ));
+        assert_ok!(Loans::update_liquidation_free_collateral(
+            RuntimeOrigin::root(),
+            vec![DOT, USDT]
+        ));
+        assert_ok!(Loans::mint(RuntimeOrigin::signed(ALICE), DOT, unit(100)));
+        assert_ok!(Loans::redeem_all(RuntimeOrigin::signed(ALICE), DOT));
+        assert_ok!(Loans::deprecate_market(RuntimeOrigin::root(), DOT, 100));
+        assert_ok!(Loans::remove_market(RuntimeOrigin::root(), DOT));
+
+        assert!(!Loans::bad_debt_socialisation(DOT));
+        assert_eq!(Loans::liquidation_free_collaterals(), vec![USDT]);
+        assert!(!crate::TotalSupply::<Test>::contains_key(DOT));
+    })
+}
+
+#[test]
+fn remove_market_clears_account_storage() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(Loans::mint(RuntimeOrigin::signed(ALICE), DOT, unit(100)));
+        assert_ok!(Loans::redeem_all(RuntimeOrigin::signed(ALICE), DOT));
+        assert!(crate::AccountEarned::<Test>::contains_key(DOT, ALICE));
+        OverdueBorrows::<Test>::insert(DOT, ALICE, false);
+        FlashLoanAmounts::<Test>::insert(DOT, 0);
+        assert_ok!(Loans::deprecate_market(RuntimeOrigin::root(), DOT, 100));
+        assert_ok!(Loans::remove_market(RuntimeOrigin::root(), DOT));
+
+        assert!(!crate::AccountDeposits::<Test>::contains_key(DOT, ALICE));
+        assert!(!crate::AccountEarned::<Test>::contains_key(DOT, ALICE));
+        assert!(!OverdueBorrows::<Test>::contains_key(DOT, ALICE));
+        assert!(!FlashLoanAmounts::<Test>::contains_key(DOT));
+    })
+}
+
+#[test]
+fn remove_market_requires_closed_liquidation_auctions() {
+    new_test_ext().execute_with(|| {
+        LiquidationAuctions::<Test>::insert(ALICE, DOT, LiquidationAuction { started_at: 0 });
+        OpenLiquidationAuctions::<Test>::insert(DOT, 1);
+        assert_ok!(Loans::deprecate_market(RuntimeOrigin::root(), DOT, 100));
+        assert_noop!(
+            Loans::remove_market(RuntimeOrigin::root(), DOT),
+            Error::<Test>::MarketHasOpenAuctions
+        );
+
+        assert_ok!(Loans::close_liquidation_auction(
+            RuntimeOrigin::signed(ALICE),
+            ALICE,
+            DOT
+        ));
+        assert_eq!(Loans::open_liquidation_auctions(DOT), 0);
+        assert_ok!(Loans::remove_market(RuntimeOrigin::root(), DOT));
+    })
+}
+
+#[test]
+fn remove_market_clears_account_storage_in_batches() {
+    let mut ext = new_test_ext();
+    ext.execute_with(|| {
+        assert_ok!(Loans::deprecate_market(RuntimeOrigin::root(), DOT, 100));
+        for i in 0..MAX_REMOVED_MARKET_KEYS + 10 {
+            let mut account = [255u8; 32];
+            account[0] = i as u8;
+            OverdueBorrows::<Test>::insert(DOT, AccountId32::new(account), true);
+        }
+    });
+    ext.commit_all().unwrap();
+
+    // The first call clears a batch of the accounts and keeps the market
+    ext.execute_with(|| {
+        System::set_block_number(1);
+        assert_ok!(Loans::remove_market(RuntimeOrigin::root(), DOT));
+        System::assert_last_event(RuntimeEvent::Loans(Event::MarketRemovalIncomplete(DOT)));
+        assert!(Loans::market(DOT).is_ok());
+        assert_eq!(OverdueBorrows::<Test>::iter_prefix(DOT).count(), 10);
+    });
+    ext.commit_all().unwrap();
+
+    ext.execute_with(|| {
+        System::set_block_number(2);
+        assert_ok!(Loans::remove_market(RuntimeOrigin::root(), DOT));
+        System::assert_last_event(RuntimeEvent::Loans(Event::RemovedMarket(DOT, PDOT)));
+        assert!(Loans::market(DOT).is_err());
+        assert_eq!(OverdueBorrows::<Test>::iter_prefix(DOT).count(), 0);
+    });
+}
@@ -0,0 +1,57 @@
+use crate::mock::{_run_to_block, new_test_ext, unit, Loans, RuntimeOrigin, ALICE, DOT, KSM};
+use frame_support::assert_ok;
+
+#[test]
+fn market_snapshots_are_sampled_every_interval() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(Loans::mint(RuntimeOrigin::signed(ALICE), DOT, unit(200)));
+        assert_ok!(Loans::collateral_asset(
+            RuntimeOrigin::signed(ALICE),
+            DOT,
+            true
+        ));
+        assert_ok!(Loans::borrow(RuntimeOrigin::signed(ALICE), DOT, unit(50)));
+
+        // The interval of the mock is 10 blocks, the snapshot of block 5
+        // is skipped
+        for block in [2, 5, 12] {
+            _run_to_block(block);
+            assert_ok!(Loans::accrue_interest(DOT));
+        }
+        let snapshots = Loans::get_market_snapshots(DOT);
+        assert_eq!(
+            snapshots.iter().map(|s| s.block).collect::<Vec<_>>(),
+            vec![2, 12]
+        );
+        let last = snapshots[1];
+        assert_eq!(last.timestamp, 72);
+        assert_eq!(last.borrow_rate, Loans::borrow_rate(DOT));
+        assert_eq!(last.supply_rate, Loans::supply_rate(DOT));
+        assert_eq!(last.exchange_rate, Loans::exchange_rate(DOT));
+        assert_eq!(last.utilization, Loans::utilization_ratio(DOT));
+        assert_eq!(last.total_supply, Loans::total_supply(DOT));
+        assert_eq!(last.total_borrows, Loans::total_borrows(DOT));
+        assert_eq!(last.total_reserves, Loans::total_reserves(DOT));
+        assert!(Loans::get_market_snapshots(KSM).is_empty());
+    })
+}
+
+#[test]
+fn market_snapshots_overwrite_the_oldest_one() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(Loans::mint(RuntimeOrigin::signed(ALICE), DOT, unit(200)));
+        // The mock keeps 3 snapshots
+        for block in [2, 12, 22, 32, 42] {
+            _run_to_block(block);
+            assert_ok!(Loans::accrue_interest(DOT));
+        }
+        assert_eq!(
+            Loans::get_market_snapshots(DOT)
+                .iter()
+                .map(|s| s.block)
+                .collect::<Vec<_>>(),
+            vec![22, 32, 42]
+        );
+        assert_eq!(Loans::market_snapshot_head(DOT), 2);
+    })
+}
@@ -135,7 +135,7 @@ fn transfer_ptoken_works() {
         );
         // DAVE Redeem 51 HKO should cause InsufficientDeposit
         assert_noop!(
-            Loans::redeem_allowed(HKO, &DAVE, unit(51) * 50),
+            Loans::redeem_allowed(HKO, &DAVE, unit(51) * 50, true),
             Error::<Test>::InsufficientDeposit
         );
 
@@ -146,7 +146,7 @@ fn transfer_ptoken_works() {
             unit(50)
         );
         // ALICE Redeem 50 HKO should be succeeded
-        assert_ok!(Loans::redeem_allowed(HKO, &ALICE, unit(50) * 50));
+        assert_ok!(Loans::redeem_allowed(HKO, &ALICE, unit(50) * 50, true));
     })
 }
 
@@ -196,6 +196,6 @@ fn transfer_ptokens_under_collateral_works() {
         );
         // ALICE Redeem 20 HKO should be succeeded
         // Also means that transfer ptoken succeed
-        assert_ok!(Loans::redeem_allowed(HKO, &ALICE, unit(20) * 50,));
+        assert_ok!(Loans::redeem_allowed(HKO, &ALICE, unit(20) * 50, true));
     })
 }
@@ -0,0 +1,222 @@
+use crate::{
+    mock::{
+        _run_to_block, almost_equal, new_test_ext, unit, Assets, Balances, Loans, RuntimeOrigin,
+        Test, ALICE, BOB, DAVE, DOT, HKO, KSM, USDT,
+    },
+    Error, RewardTokenConfig, MAX_REWARD_TOKENS,
+};
+use frame_support::{assert_noop, assert_ok};
+
+// Alice is the only supplier and borrower of DOT, the market is incentivised
+// in USDT until block 30 and in KSM until block 20.
+fn setup_reward_tokens() {
+    assert_ok!(Loans::mint(RuntimeOrigin::signed(ALICE), DOT, unit(100)));
+    assert_ok!(Loans::collateral_asset(
+        RuntimeOrigin::signed(ALICE),
+        DOT,
+        true
+    ));
+    assert_ok!(Loans::borrow(RuntimeOrigin::signed(ALICE), DOT, unit(10)));
+
+    _run_to_block(10);
+    assert_ok!(Loans::update_reward_token_speed(
+        RuntimeOrigin::root(),
+        DOT,
+        USDT,
+        unit(1),
+        unit(2),
+        30
+    ));
+    assert_ok!(Loans::update_reward_token_speed(
+        RuntimeOrigin::root(),
+        DOT,
+        KSM,
+        unit(1),
+        0,
+        20
+    ));
+    assert_ok!(Loans::add_reward_token(
+        RuntimeOrigin::signed(DAVE),
+        USDT,
+        unit(100)
+    ));
+    assert_ok!(Loans::add_reward_token(
+        RuntimeOrigin::signed(BOB),
+        KSM,
+        unit(100)
+    ));
+}
+
+#[test]
+fn update_reward_token_speed_should_validate_params() {
+    new_test_ext().execute_with(|| {
+        assert_noop!(
+            Loans::update_reward_token_speed(RuntimeOrigin::root(), DOT, HKO, unit(1), 0, 30),
+            Error::<Test>::InvalidRewardToken
+        );
+
+        for reward_asset_id in 0..MAX_REWARD_TOKENS as u32 {
+            assert_ok!(Loans::update_reward_token_speed(
+                RuntimeOrigin::root(),
+                DOT,
+                1000 + reward_asset_id,
+                unit(1),
+                0,
+                30
+            ));
+        }
+        assert_noop!(
+            Loans::update_reward_token_speed(RuntimeOrigin::root(), DOT, USDT, unit(1), 0, 30),
+            Error::<Test>::TooManyRewardTokens
+        );
+        // Existing reward tokens can still be updated
+        assert_ok!(Loans::update_reward_token_speed(
+            RuntimeOrigin::root(),
+            DOT,
+            1000,
+            0,
+            unit(1),
+            40
+        ));
+        assert_eq!(
+            Loans::reward_token_config(DOT, 1000),
+            Some(RewardTokenConfig {
+                supply_speed: 0,
+                borrow_speed: unit(1),
+                end_block: 40,
+            })
+        );
+    })
+}
+
+#[test]
+fn reward_tokens_stop_accruing_at_end_block() {
+    new_test_ext().execute_with(|| {
+        setup_reward_tokens();
+
+        _run_to_block(20);
+        assert_ok!(Loans::mint(RuntimeOrigin::signed(ALICE), DOT, unit(100)));
+        // Supply reward: USDT 10, KSM 10
+        // Borrow reward: USDT 0
+        assert_eq!(Loans::reward_token_accrued(ALICE, USDT), unit(10));
+        assert_eq!(Loans::reward_token_accrued(ALICE, KSM), unit(10));
+
+        _run_to_block(50);
+        assert_ok!(Loans::mint(RuntimeOrigin::signed(ALICE), DOT, unit(100)));
+        assert_ok!(Loans::borrow(RuntimeOrigin::signed(ALICE), DOT, unit(10)));
+        // Supply reward: USDT 20, KSM 10
+        // Borrow reward: USDT 40
+        assert!(almost_equal(
+            Loans::reward_token_accrued(ALICE, USDT),
+            unit(60)
+        ));
+        assert_eq!(Loans::reward_token_accrued(ALICE, KSM), unit(10));
+    })
+}
+
+#[test]
+fn claim_reward_pays_all_reward_tokens() {
+    new_test_ext().execute_with(|| {
+        setup_reward_tokens();
+        assert_ok!(Loans::update_market_reward_speed(
+            RuntimeOrigin::root(),
+            DOT,
+            Some(unit(1)),
+            Some(0),
+        ));
+        assert_ok!(Loans::add_reward(RuntimeOrigin::signed(DAVE), unit(100)));
+
+        _run_to_block(50);
+        let pending_rewards = Loans::get_pending_rewards(&ALICE).unwrap();
+        assert_eq!(pending_rewards.len(), 3);
+        assert_eq!(pending_rewards[0], (HKO, unit(40)));
+        assert!(pending_rewards.contains(&(KSM, unit(10))));
+        // Pending rewards are not collected
+        assert_eq!(Loans::reward_token_accrued(ALICE, KSM), 0);
+
+        assert_ok!(Loans::claim_reward(RuntimeOrigin::signed(ALICE)));
+        assert_eq!(Balances::free_balance(ALICE), unit(40));
+        assert!(almost_equal(Assets::balance(USDT, ALICE), unit(1060)));
+        assert_eq!(Assets::balance(KSM, ALICE), unit(1010));
+        assert_eq!(Loans::reward_token_accrued(ALICE, USDT), 0);
+        assert_eq!(Loans::reward_token_accrued(ALICE, KSM), 0);
+    })
+}
+
+#[test]
+fn withdraw_reward_token_works() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(Loans::add_reward_token(
+            RuntimeOrigin::signed(DAVE),
+            USDT,
+            unit(100)
+        ));
+        assert_noop!(
+            Loans::withdraw_reward_token(RuntimeOrigin::signed(DAVE), USDT, DAVE, unit(40)),
+            sp_runtime::DispatchError::BadOrigin
+        );
+        assert_ok!(Loans::withdraw_reward_token(
+            RuntimeOrigin::root(),
+            USDT,
+            DAVE,
+            unit(40)
+        ));
+        assert_eq!(Assets::balance(USDT, DAVE), unit(940));
+    })
+}
+
+#[test]
+fn claim_reward_leaves_unpaid_reward_tokens_accrued() {
+    new_test_ext().execute_with(|| {
+        setup_reward_tokens();
+        // The pool can't pay the KSM reward anymore
+        assert_ok!(Loans::withdraw_reward_token(
+            RuntimeOrigin::root(),
+            KSM,
+            BOB,
+            unit(100)
+        ));
+
+        _run_to_block(50);
+        assert_ok!(Loans::claim_reward(RuntimeOrigin::signed(ALICE)));
+        assert!(almost_equal(Assets::balance(USDT, ALICE), unit(1060)));
+        assert_eq!(Loans::reward_token_accrued(ALICE, USDT), 0);
+        assert_eq!(Assets::balance(KSM, ALICE), unit(1000));
+        assert_eq!(Loans::reward_token_accrued(ALICE, KSM), unit(10));
+    })
+}
+
+#[test]
+fn remove_reward_token_works() {
+    new_test_ext().execute_with(|| {
+        setup_reward_tokens();
+        assert_noop!(
+            Loans::remove_reward_token(RuntimeOrigin::root(), DOT, KSM),
+            Error::<Test>::RewardTokenNotExpired
+        );
+        assert_noop!(
+            Loans::remove_reward_token(RuntimeOrigin::root(), DOT, HKO),
+            Error::<Test>::RewardTokenNotFound
+        );
+
+        _run_to_block(20);
+        assert_ok!(Loans::remove_reward_token(RuntimeOrigin::root(), DOT, KSM));
+        assert_eq!(Loans::reward_token_config(DOT, KSM), None);
+
+        // The slot can be taken by another reward token
+        for reward_asset_id in 0..MAX_REWARD_TOKENS as u32 - 1 {
+            assert_ok!(Loans::update_reward_token_speed(
+                RuntimeOrigin::root(),
+                DOT,
+                1000 + reward_asset_id,
+                unit(1),
+                0,
+                30
+            ));
+        }
+        assert_noop!(
+            Loans::update_reward_token_speed(RuntimeOrigin::root(), DOT, KSM, unit(1), 0, 30),
+            Error::<Test>::TooManyRewardTokens
+        );
+    })
+}
@@ -1,6 +1,6 @@
 use crate::InterestRateModel;
 use frame_support::pallet_prelude::*;
-use primitives::{CurrencyId, Rate, Ratio};
+use primitives::{CurrencyId, EModeCategoryId, Liquidity, Rate, Ratio, Timestamp};
 use scale_info::TypeInfo;
 
 /// Container for borrow balance information
@@ -37,6 +37,8 @@ pub enum MarketState {
     Active,
     Pending,
     Supervision,
+    /// Winding down, only the operations reducing positions are allowed
+    Deprecated,
 }
 
 /// Market.
@@ -68,6 +70,133 @@ pub struct Market<Balance> {
     pub borrow_cap: Balance,
     /// Ptoken asset id
     pub ptoken_id: CurrencyId,
+    /// Maximum age in seconds of the oracle price before it is considered stale.
+    /// `None` disables the check. Emergency prices never become stale.
+    pub max_price_age: Option<Timestamp>,
+    /// Whether liquidations involving this market may proceed on a stale price
+    pub liquidate_on_stale_price: bool,
+    /// Isolation mode. Collateral of an isolated market cannot be combined with
+    /// other collateral and only backs borrows of assets `borrowable_in_isolation`
+    pub isolated: bool,
+    /// Upper bound of the value borrowed against this market's collateral
+    /// while it is isolated
+    pub debt_ceiling: Liquidity,
+    /// Whether this asset can be borrowed against isolated collateral
+    pub borrowable_in_isolation: bool,
+}
+
+/// E-mode category.
+///
+/// Elevated risk parameters of correlated markets, they apply to an account
+/// whose collateral and borrows all belong to markets of the same category.
+#[cfg_attr(feature = "std", derive(serde::Deserialize, serde::Serialize))]
+#[derive(Clone, Copy, PartialEq, Eq, codec::Decode, codec::Encode, RuntimeDebug, TypeInfo)]
+pub struct EModeCategory {
+    /// The collateral utilization ratio within the category
+    pub collateral_factor: Ratio,
+    /// The liquidation threshold ratio within the category
+    pub liquidation_threshold: Ratio,
+}
+
+/// Collateral state of an account, resolved once for each computation of
+/// its liquidity instead of once for each market.
+#[derive(Clone, Copy, PartialEq, Eq, RuntimeDebug, Default)]
+pub struct AccountCollateral<AssetId> {
+    /// The isolated market whose deposits are used as collateral, if any
+    pub isolated: Option<AssetId>,
+    /// Whether the deposits of more than one market are used as collateral
+    pub combined: bool,
+    /// The e-mode category all collateral and borrows belong to, if any
+    pub emode: Option<(EModeCategoryId, EModeCategory)>,
+}
+
+/// Dutch-auction liquidation parameters of a market.
+///
+/// The discount on the seized collateral grows linearly from zero up to
+/// `max_discount` over `duration` blocks after an auction is opened.
+#[cfg_attr(feature = "std", derive(serde::Deserialize, serde::Serialize))]
+#[derive(Clone, Copy, PartialEq, Eq, codec::Decode, codec::Encode, RuntimeDebug, TypeInfo)]
+pub struct LiquidationAuctionConfig<BlockNumber> {
+    /// The discount reached at the end of the auction
+    pub max_discount: Ratio,
+    /// The number of blocks it takes to reach the maximum discount
+    pub duration: BlockNumber,
+}
+
+/// An open liquidation auction of a borrow
+#[derive(Clone, Copy, PartialEq, Eq, codec::Decode, codec::Encode, RuntimeDebug, TypeInfo)]
+pub struct LiquidationAuction<BlockNumber> {
+    /// The block the auction was opened at
+    pub started_at: BlockNumber,
+}
+
+/// Wind-down of a deprecated market
+#[derive(Clone, Copy, PartialEq, Eq, codec::Decode, codec::Encode, RuntimeDebug, TypeInfo)]
+pub struct MarketDeprecation<BlockNumber> {
+    /// The block the market was deprecated at
+    pub started_at: BlockNumber,
+    /// The number of blocks it takes for the collateral factor to reach zero
+    pub ramp_duration: BlockNumber,
+}
+
+/// Fixed-rate borrowing parameters of a market.
+///
+/// Fixed borrows pay the borrow rate of the market at origination plus
+/// `rate_premium` until they mature, the origination fee goes to the reserves.
+#[cfg_attr(feature = "std", derive(serde::Deserialize, serde::Serialize))]
+#[derive(Clone, Copy, PartialEq, Eq, codec::Decode, codec::Encode, RuntimeDebug, TypeInfo)]
+pub struct FixedRateConfig<BlockNumber> {
+    /// The rate added to the variable borrow rate at origination
+    pub rate_premium: Rate,
+    /// The fee charged on the borrowed amount at origination
+    pub origination_fee: Ratio,
+    /// The longest term of a fixed borrow, in blocks
+    pub max_term: BlockNumber,
+    /// Whether matured borrows roll over to the variable rate, they become
+    /// liquidatable at maturity otherwise
+    pub rollover: bool,
+}
+
+/// A fixed-rate borrow of an account
+#[derive(Clone, Copy, PartialEq, Eq, codec::Decode, codec::Encode, RuntimeDebug, TypeInfo)]
+pub struct FixedBorrow<Balance, BlockNumber> {
+    /// Principal borrowed, the origination fee included
+    pub principal: Balance,
+    /// Annual rate locked at origination
+    pub rate: Rate,
+    /// The timestamp the borrow was originated at
+    pub started_at: Timestamp,
+    /// The block the borrow matures at
+    pub maturity: BlockNumber,
+}
+
+/// Incentive of a market paid in a reward token besides the native reward asset
+#[cfg_attr(feature = "std", derive(serde::Deserialize, serde::Serialize))]
+#[derive(Clone, Copy, PartialEq, Eq, codec::Decode, codec::Encode, RuntimeDebug, TypeInfo)]
+pub struct RewardTokenConfig<BlockNumber, Balance> {
+    /// Reward amount per block shared by the suppliers
+    pub supply_speed: Balance,
+    /// Reward amount per block shared by the borrowers
+    pub borrow_speed: Balance,
+    /// The block the reward stops accruing at
+    pub end_block: BlockNumber,
+}
+
+/// State of a market sampled by `accrue_interest`
+#[derive(Clone, Copy, PartialEq, Eq, codec::Decode, codec::Encode, RuntimeDebug, TypeInfo)]
+pub struct MarketSnapshot<BlockNumber, Balance> {
+    /// The block the snapshot was taken at
+    pub block: BlockNumber,
+    /// The timestamp the snapshot was taken at, in seconds
+    pub timestamp: Timestamp,
+    pub borrow_rate: Rate,
+    pub supply_rate: Rate,
+    pub exchange_rate: Rate,
+    pub utilization: Ratio,
+    /// Total supply of the ptoken of the market
+    pub total_supply: Balance,
+    pub total_borrows: Balance,
+    pub total_reserves: Balance,
 }
 
 #[derive(PartialEq, Eq, Clone, Encode, Decode, RuntimeDebug, TypeInfo, Default)]
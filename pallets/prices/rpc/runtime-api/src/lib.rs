@@ -0,0 +1,32 @@
+// Copyright 2021 Parallel Finance Developer.
+// This file is part of Parallel Finance.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use codec::Codec;
+use primitives::{CurrencyId, Price, PriceDetail, PriceSource};
+use sp_std::vec::Vec;
+
+sp_api::decl_runtime_apis! {
+    /// Version 2 adds `get_price_sources`.
+    #[api_version(2)]
+    pub trait PricesApi<BlockNumber, AccountId> where
+        BlockNumber: Codec,
+        AccountId: Codec {
+        fn get_price(asset_id: CurrencyId) -> (Option<PriceDetail>, Option<PriceDetail>, Option<Price>, Option<PriceDetail>);
+        fn get_all_prices() -> Vec<(CurrencyId, Option<PriceDetail>, Option<PriceDetail>, Option<Price>, Option<PriceDetail>)>;
+        fn get_emergency_prices() -> Vec<(CurrencyId, Price, Option<BlockNumber>, Option<Vec<u8>>)>;
+        fn get_price_sources(asset_id: CurrencyId) -> Vec<PriceSource<AccountId>>;
+    }
+}
@@ -0,0 +1,215 @@
+// Copyright 2021 Parallel Finance Developer.
+// This file is part of Parallel Finance.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::sync::Arc;
+
+pub use pallet_prices_rpc_runtime_api::PricesApi as PricesRuntimeApi;
+
+use codec::Codec;
+use jsonrpsee::{
+    core::{async_trait, Error as JsonRpseeError, RpcResult},
+    proc_macros::rpc,
+    types::error::{CallError, ErrorObject},
+};
+use primitives::{CurrencyId, Price, PriceDetail, PriceSource};
+use sp_api::{ApiExt, ProvideRuntimeApi};
+use sp_blockchain::HeaderBackend;
+use sp_core::Bytes;
+use sp_runtime::{generic::BlockId, traits::Block as BlockT};
+
+#[rpc(client, server)]
+pub trait PricesApi<BlockHash, BlockNumber, AccountId> {
+    #[method(name = "prices_getPrice")]
+    fn get_price(
+        &self,
+        asset_id: CurrencyId,
+        at: Option<BlockHash>,
+    ) -> RpcResult<(
+        Option<PriceDetail>,
+        Option<PriceDetail>,
+        Option<Price>,
+        Option<PriceDetail>,
+    )>;
+    #[method(name = "prices_getAllPrices")]
+    fn get_all_prices(
+        &self,
+        at: Option<BlockHash>,
+    ) -> RpcResult<
+        Vec<(
+            CurrencyId,
+            Option<PriceDetail>,
+            Option<PriceDetail>,
+            Option<Price>,
+            Option<PriceDetail>,
+        )>,
+    >;
+    #[method(name = "prices_getEmergencyPrices")]
+    fn get_emergency_prices(
+        &self,
+        at: Option<BlockHash>,
+    ) -> RpcResult<Vec<(CurrencyId, Price, Option<BlockNumber>, Option<Bytes>)>>;
+    #[method(name = "prices_getPriceSources")]
+    fn get_price_sources(
+        &self,
+        asset_id: CurrencyId,
+        at: Option<BlockHash>,
+    ) -> RpcResult<Vec<PriceSource<AccountId>>>;
+}
+
+/// A struct that implements the [`PricesApi`].
+pub struct Prices<C, B> {
+    client: Arc<C>,
+    _marker: std::marker::PhantomData<B>,
+}
+
+impl<C, B> Prices<C, B> {
+    /// Create new `Prices` with the given reference to the client.
+    pub fn new(client: Arc<C>) -> Self {
+        Self {
+            client,
+            _marker: Default::default(),
+        }
+    }
+}
+
+pub enum Error {
+    RuntimeError,
+    UnsupportedApiVersion,
+}
+
+impl From<Error> for i32 {
+    fn from(e: Error) -> i32 {
+        match e {
+            Error::RuntimeError => 1,
+            Error::UnsupportedApiVersion => 2,
+        }
+    }
+}
+
+#[async_trait]
+impl<C, Block, BlockNumber, AccountId>
+    PricesApiServer<<Block as BlockT>::Hash, BlockNumber, AccountId> for Prices<C, Block>
+where
+    Block: BlockT,
+    C: Send + Sync + 'static,
+    C: ProvideRuntimeApi<Block>,
+    C: HeaderBackend<Block>,
+    C::Api: PricesRuntimeApi<Block, BlockNumber, AccountId>,
+    BlockNumber: Codec,
+    AccountId: Codec,
+{
+    fn get_price(
+        &self,
+        asset_id: CurrencyId,
+        at: Option<<Block as BlockT>::Hash>,
+    ) -> RpcResult<(
+        Option<PriceDetail>,
+        Option<PriceDetail>,
+        Option<Price>,
+        Option<PriceDetail>,
+    )> {
+        let api = self.client.runtime_api();
+        let at = BlockId::hash(at.unwrap_or(
+            // If the block hash is not supplied assume the best block.
+            self.client.info().best_hash,
+        ));
+        api.get_price(&at, asset_id)
+            .map_err(runtime_error_into_rpc_error)
+    }
+
+    fn get_all_prices(
+        &self,
+        at: Option<<Block as BlockT>::Hash>,
+    ) -> RpcResult<
+        Vec<(
+            CurrencyId,
+            Option<PriceDetail>,
+            Option<PriceDetail>,
+            Option<Price>,
+            Option<PriceDetail>,
+        )>,
+    > {
+        let api = self.client.runtime_api();
+        let at = BlockId::hash(at.unwrap_or(
+            // If the block hash is not supplied assume the best block.
+            self.client.info().best_hash,
+        ));
+        api.get_all_prices(&at)
+            .map_err(runtime_error_into_rpc_error)
+    }
+
+    fn get_emergency_prices(
+        &self,
+        at: Option<<Block as BlockT>::Hash>,
+    ) -> RpcResult<Vec<(CurrencyId, Price, Option<BlockNumber>, Option<Bytes>)>> {
+        let api = self.client.runtime_api();
+        let at = BlockId::hash(at.unwrap_or(
+            // If the block hash is not supplied assume the best block.
+            self.client.info().best_hash,
+        ));
+        Ok(api
+            .get_emergency_prices(&at)
+            .map_err(runtime_error_into_rpc_error)?
+            .into_iter()
+            .map(|(asset_id, price, expires_at, reason)| {
+                (asset_id, price, expires_at, reason.map(Into::into))
+            })
+            .collect())
+    }
+
+    fn get_price_sources(
+        &self,
+        asset_id: CurrencyId,
+        at: Option<<Block as BlockT>::Hash>,
+    ) -> RpcResult<Vec<PriceSource<AccountId>>> {
+        let api = self.client.runtime_api();
+        let at = BlockId::hash(at.unwrap_or(
+            // If the block hash is not supplied assume the best block.
+            self.client.info().best_hash,
+        ));
+        ensure_api_version(
+            api.has_api_with::<dyn PricesRuntimeApi<Block, BlockNumber, AccountId>, _>(&at, |v| {
+                v >= 2
+            }),
+            2,
+        )?;
+        api.get_price_sources(&at, asset_id)
+            .map_err(runtime_error_into_rpc_error)
+    }
+}
+
+/// Fails unless the runtime implements the version of the prices runtime API
+/// which added the called method.
+fn ensure_api_version(
+    supported: Result<bool, impl std::fmt::Debug>,
+    version: u32,
+) -> RpcResult<()> {
+    if supported.map_err(runtime_error_into_rpc_error)? {
+        return Ok(());
+    }
+    Err(JsonRpseeError::Call(CallError::Custom(ErrorObject::owned(
+        Error::UnsupportedApiVersion.into(),
+        "Not supported by the runtime",
+        Some(format!("requires PricesApi version {}", version)),
+    ))))
+}
+
+/// Converts a runtime trap into an RPC error.
+fn runtime_error_into_rpc_error(err: impl std::fmt::Debug) -> JsonRpseeError {
+    JsonRpseeError::Call(CallError::Custom(ErrorObject::owned(
+        Error::RuntimeError.into(),
+        "Runtime trapped",
+        Some(format!("{:?}", err)),
+    )))
+}
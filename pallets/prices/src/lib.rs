@@ -18,25 +18,47 @@
 //!
 //! This pallet provides the price from Oracle Module by implementing the
 //! `PriceFeeder` trait. In case of emergency, the price can be set directly
-//! by Oracle Collective.
+//! by Oracle Collective until a given block, it is cleared automatically once
+//! that block is reached.
+//!
+//! Oracle feeds can be guarded by a deviation limit. A feed value which moves
+//! further than the limit from the last good price is held back, the last good
+//! price is reported instead and the asset can be flagged as suspicious.
+//!
+//! An asset can also be linked to the TWAP of an AMM pool. The TWAP is used
+//! as a fallback when the asset has no oracle feed, and the oracle feed can be
+//! cross-checked against it.
+//!
+//! The price of an asset can instead be aggregated over several sources, such
+//! as the oracle feed, the raw values of oracle members, the AMM TWAP and the
+//! derivation from the relay currency. The median of the fresh values is used
+//! once a quorum of them is reached, there is no price otherwise.
+//!
+//! Liquidity tokens of AMM and StableSwap pools are priced at their fair value
+//! from the prices of both legs of the pool, so they can be used as collateral.
 
 #![cfg_attr(not(feature = "std"), no_std)]
 
 use frame_support::{
-    dispatch::DispatchClass, log, pallet_prelude::*, traits::fungibles::Inspect, transactional,
+    dispatch::DispatchClass,
+    log,
+    pallet_prelude::*,
+    traits::{fungibles::Inspect, UnixTime},
+    transactional,
 };
 use frame_system::pallet_prelude::*;
 use orml_traits::{DataFeeder, DataProvider, DataProviderExtended};
 use primitives::*;
 use sp_runtime::{
-    traits::{CheckedDiv, CheckedMul},
+    traits::{CheckedDiv, CheckedMul, SaturatedConversion, Saturating, Zero},
     FixedPointNumber, FixedU128,
 };
-use sp_std::vec::Vec;
+use sp_std::{collections::btree_set::BTreeSet, vec::Vec};
 
 pub use pallet::*;
 use pallet_traits::*;
-use sp_core::U256;
+pub use primitives::PriceSource;
+use sp_core::{U256, U512};
 
 #[cfg(test)]
 mod mock;
@@ -45,6 +67,89 @@ mod tests;
 
 pub mod weights;
 
+/// Deviation limit of an asset's oracle feed.
+#[derive(Encode, Decode, Clone, Copy, PartialEq, Eq, RuntimeDebug, TypeInfo)]
+pub struct DeviationLimit {
+    /// Maximum deviation from the last good price per window
+    pub max_deviation: Ratio,
+    /// Length of the window in milliseconds, the allowed deviation grows
+    /// with each full window elapsed since the last good price while no feed
+    /// value is held back, so that a feed resuming after a gap is not rejected.
+    /// A held back value is only accepted once it is back within the limit
+    /// of a single window, or once the limit is set again.
+    pub window: Timestamp,
+    /// Whether to flag the asset as suspicious while its feed is held back
+    pub mark_suspicious: bool,
+}
+
+/// AMM TWAP source of an asset's price.
+#[derive(Encode, Decode, Clone, Copy, PartialEq, Eq, RuntimeDebug, TypeInfo)]
+pub struct TwapSource<BlockNumber> {
+    /// Asset the AMM pool pairs with, its price must not come from a TWAP
+    pub quote_asset: CurrencyId,
+    /// Length of the TWAP window in blocks
+    pub window: BlockNumber,
+    /// Maximum deviation of the oracle feed from the TWAP before the asset is
+    /// flagged as suspicious, `None` disables the cross-check
+    pub max_deviation: Option<Ratio>,
+}
+
+/// Expiry and reason of an emergency price.
+#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug, TypeInfo)]
+pub struct EmergencyPriceInfo<BlockNumber, Reason> {
+    /// Block at which the emergency price is cleared
+    pub expires_at: BlockNumber,
+    /// Why the oracle price is overridden
+    pub reason: Option<Reason>,
+}
+
+/// Price of an asset along with the values of its sources, all normalized.
+#[derive(Clone, PartialEq, Eq, RuntimeDebug)]
+pub struct PriceBreakdown<AccountId> {
+    /// The price reported by `get_price`
+    pub price: Option<PriceDetail>,
+    /// The oracle feed of the asset, guarded by the deviation limit
+    pub oracle: Option<PriceDetail>,
+    /// The emergency price overriding the other sources
+    pub emergency: Option<Price>,
+    /// The price derived from other assets, such as the relay currency for
+    /// liquid staking and vault tokens or the legs of a pool for liquidity tokens
+    pub derived: Option<PriceDetail>,
+    /// The sources whose fresh values make up the aggregated price, empty for
+    /// assets without aggregation or below their quorum
+    pub sources: Vec<PriceSource<AccountId>>,
+}
+
+/// The maximum number of sources aggregated into an asset's price
+pub const MAX_PRICE_SOURCES: usize = 16;
+
+/// Aggregation of an asset's price over several sources.
+#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug, TypeInfo)]
+pub struct PriceAggregation<AccountId> {
+    /// Sources whose fresh values are aggregated into the median
+    pub sources: Vec<PriceSource<AccountId>>,
+    /// Minimum number of fresh values required for a price
+    pub quorum: u32,
+    /// Maximum age of a value in milliseconds for it to be fresh
+    pub max_age: Timestamp,
+}
+
+impl<AccountId: PartialEq> PriceAggregation<AccountId> {
+    /// Whether the sources are distinct and the quorum is reachable
+    pub fn check(&self) -> bool {
+        let has_duplicates = self
+            .sources
+            .iter()
+            .enumerate()
+            .any(|(i, source)| self.sources[..i].contains(source));
+        !self.sources.is_empty()
+            && self.sources.len() <= MAX_PRICE_SOURCES
+            && !has_duplicates
+            && self.quorum > 0
+            && self.quorum as usize <= self.sources.len()
+    }
+}
+
 #[frame_support::pallet]
 pub mod pallet {
     use super::*;
@@ -57,6 +162,7 @@ pub mod pallet {
         <<T as Config>::Assets as Inspect<<T as frame_system::Config>::AccountId>>::AssetId;
     pub(crate) type BalanceOf<T> =
         <<T as Config>::Assets as Inspect<<T as frame_system::Config>::AccountId>>::Balance;
+    pub type EmergencyPriceReason<T> = BoundedVec<u8, <T as Config>::MaxEmergencyPriceReasonLength>;
 
     #[pallet::config]
     pub trait Config: frame_system::Config {
@@ -93,6 +199,9 @@ pub mod pallet {
         /// Specify all the AMMs we are routing between
         type AMM: AMM<AccountIdOf<Self>, AssetIdOf<Self>, BalanceOf<Self>, Self::BlockNumber>;
 
+        /// The StableSwap pools whose liquidity tokens can be priced
+        type StableSwap: StableSwap<AccountIdOf<Self>, AssetIdOf<Self>, BalanceOf<Self>>;
+
         /// Currency type for deposit/withdraw assets to/from amm route
         /// module
         type Assets: Transfer<Self::AccountId, AssetId = CurrencyId, Balance = Balance>
@@ -106,6 +215,25 @@ pub mod pallet {
         /// Decimal provider.
         type Decimal: DecimalProvider<CurrencyId>;
 
+        /// The raw feed values of the oracle members.
+        type MemberSource: OracleMemberProvider<Self::AccountId>;
+
+        /// Current time, used to check the freshness of aggregated values.
+        type UnixTime: UnixTime;
+
+        /// The maximum length of the reason of an emergency price.
+        #[pallet::constant]
+        type MaxEmergencyPriceReasonLength: Get<u32>;
+
+        /// The maximum number of assets with a deviation limit, all of them
+        /// are checked every block.
+        #[pallet::constant]
+        type MaxDeviationLimits: Get<u32>;
+
+        /// The maximum number of emergency prices expiring at the same block.
+        #[pallet::constant]
+        type MaxEmergencyPriceExpiries: Get<u32>;
+
         /// Weight information
         type WeightInfo: WeightInfo;
     }
@@ -117,6 +245,33 @@ pub mod pallet {
         SetPrice(CurrencyId, Price),
         /// Reset emergency price. \[asset_id\]
         ResetPrice(CurrencyId),
+        /// Deviation limit is updated. \[asset_id, limit\]
+        DeviationLimitUpdated(CurrencyId, Option<DeviationLimit>),
+        /// Feed value deviates too much and is held back.
+        /// \[asset_id, last_good_price, rejected_price\]
+        PriceDeviationDetected(CurrencyId, Price, Price),
+        /// Feed value is back within the deviation limit. \[asset_id, price\]
+        PriceDeviationCleared(CurrencyId, Price),
+        /// TWAP source is updated. \[asset_id, source\]
+        TwapSourceUpdated(CurrencyId, Option<TwapSource<T::BlockNumber>>),
+        /// Price aggregation is updated. \[asset_id, aggregation\]
+        PriceAggregationUpdated(CurrencyId, Option<PriceAggregation<T::AccountId>>),
+        /// Emergency price expires at the given block. \[asset_id, expires_at, reason\]
+        EmergencyPriceExpiryUpdated(CurrencyId, T::BlockNumber, Option<EmergencyPriceReason<T>>),
+        /// Emergency price is expired and cleared. \[asset_id, price\]
+        EmergencyPriceExpired(CurrencyId, Price),
+    }
+
+    #[pallet::error]
+    pub enum Error<T> {
+        /// Price aggregation has no sources, duplicated sources or an invalid quorum
+        InvalidPriceAggregation,
+        /// Emergency price must expire after the current block
+        InvalidEmergencyPriceExpiry,
+        /// The maximum number of assets with a deviation limit is reached
+        TooManyDeviationLimits,
+        /// The maximum number of emergency prices expiring at the block is reached
+        TooManyEmergencyPriceExpiries,
     }
 
     /// Mapping from currency id to it's emergency price
@@ -125,6 +280,58 @@ pub mod pallet {
     pub type EmergencyPrice<T: Config> =
         StorageMap<_, Twox64Concat, CurrencyId, Price, OptionQuery>;
 
+    /// Mapping from currency id to the expiry and reason of its emergency price
+    #[pallet::storage]
+    #[pallet::getter(fn emergency_price_info)]
+    pub type EmergencyPriceInfos<T: Config> = StorageMap<
+        _,
+        Twox64Concat,
+        CurrencyId,
+        EmergencyPriceInfo<T::BlockNumber, EmergencyPriceReason<T>>,
+        OptionQuery,
+    >;
+
+    /// Mapping from block number to the assets whose emergency price expires at it
+    #[pallet::storage]
+    #[pallet::getter(fn emergency_price_expiries)]
+    pub type EmergencyPriceExpiries<T: Config> = StorageMap<
+        _,
+        Twox64Concat,
+        T::BlockNumber,
+        BoundedVec<CurrencyId, T::MaxEmergencyPriceExpiries>,
+        ValueQuery,
+    >;
+
+    /// Mapping from currency id to the deviation limit of its oracle feed
+    #[pallet::storage]
+    #[pallet::getter(fn deviation_limit)]
+    pub type DeviationLimits<T: Config> =
+        CountedStorageMap<_, Twox64Concat, CurrencyId, DeviationLimit, OptionQuery>;
+
+    /// Mapping from currency id to the last feed value within the deviation limit
+    #[pallet::storage]
+    #[pallet::getter(fn last_good_price)]
+    pub type LastGoodPrice<T: Config> =
+        StorageMap<_, Twox64Concat, CurrencyId, TimeStampedPrice, OptionQuery>;
+
+    /// Mapping from currency id to the feed value currently held back
+    #[pallet::storage]
+    #[pallet::getter(fn held_price)]
+    pub type HeldPrice<T: Config> =
+        StorageMap<_, Twox64Concat, CurrencyId, TimeStampedPrice, OptionQuery>;
+
+    /// Mapping from currency id to the AMM TWAP source of its price
+    #[pallet::storage]
+    #[pallet::getter(fn twap_source)]
+    pub type TwapSources<T: Config> =
+        StorageMap<_, Twox64Concat, CurrencyId, TwapSource<T::BlockNumber>, OptionQuery>;
+
+    /// Mapping from currency id to the aggregation of its price over several sources
+    #[pallet::storage]
+    #[pallet::getter(fn price_aggregation)]
+    pub type PriceAggregations<T: Config> =
+        StorageMap<_, Twox64Concat, CurrencyId, PriceAggregation<T::AccountId>, OptionQuery>;
+
     /// Mapping from foreign vault token to our's vault token
     #[pallet::storage]
     #[pallet::getter(fn foreign_to_native_asset)]
@@ -135,9 +342,36 @@ pub mod pallet {
     #[pallet::without_storage_info]
     pub struct Pallet<T>(PhantomData<T>);
 
+    #[pallet::hooks]
+    impl<T: Config> Hooks<T::BlockNumber> for Pallet<T> {
+        fn on_initialize(n: T::BlockNumber) -> Weight {
+            let mut count = 0u64;
+            for (asset_id, limit) in DeviationLimits::<T>::iter() {
+                count += 1;
+                Self::check_price_deviation(asset_id, &limit);
+            }
+            let expired = EmergencyPriceExpiries::<T>::take(n);
+            for asset_id in expired.iter() {
+                Self::expire_emergency_price(*asset_id);
+            }
+            let expired = expired.len() as u64;
+            // DeviationLimits, Source, LastGoodPrice and HeldPrice, then
+            // EmergencyPriceExpiries, EmergencyPriceInfos and EmergencyPrice
+            // of the expired ones
+            T::DbWeight::get()
+                .reads_writes(count.saturating_mul(4), count.saturating_mul(2))
+                .saturating_add(T::DbWeight::get().reads_writes(
+                    expired.saturating_add(1),
+                    expired.saturating_mul(2).saturating_add(1),
+                ))
+        }
+    }
+
     #[pallet::call]
     impl<T: Config> Pallet<T> {
-        /// Set emergency price
+        /// Set emergency price, which is cleared at the `expires_at` block
+        ///
+        /// - `reason`: why the oracle price is overridden
         #[pallet::call_index(0)]
         #[pallet::weight((<T as Config>::WeightInfo::set_price(), DispatchClass::Operational))]
         #[transactional]
@@ -145,11 +379,11 @@ pub mod pallet {
             origin: OriginFor<T>,
             asset_id: CurrencyId,
             price: Price,
+            expires_at: T::BlockNumber,
+            reason: Option<EmergencyPriceReason<T>>,
         ) -> DispatchResultWithPostInfo {
             T::FeederOrigin::ensure_origin(origin)?;
-            <Pallet<T> as EmergencyPriceFeeder<CurrencyId, Price>>::set_emergency_price(
-                asset_id, price,
-            );
+            Self::do_set_emergency_price(asset_id, price, expires_at, reason)?;
             Ok(().into())
         }
 
@@ -162,7 +396,7 @@ pub mod pallet {
             asset_id: CurrencyId,
         ) -> DispatchResultWithPostInfo {
             T::FeederOrigin::ensure_origin(origin)?;
-            <Pallet<T> as EmergencyPriceFeeder<CurrencyId, Price>>::reset_emergency_price(asset_id);
+            Self::reset_emergency_price(asset_id);
             Ok(().into())
         }
 
@@ -179,10 +413,400 @@ pub mod pallet {
             ForeignToNativeAsset::<T>::insert(foreign_asset_id, asset_id);
             Ok(().into())
         }
+
+        /// Set or remove the deviation limit of an asset's oracle feed.
+        /// Removing the limit also clears the held back price.
+        #[pallet::call_index(3)]
+        #[pallet::weight((<T as Config>::WeightInfo::set_deviation_limit(), DispatchClass::Operational))]
+        #[transactional]
+        pub fn set_deviation_limit(
+            origin: OriginFor<T>,
+            asset_id: CurrencyId,
+            limit: Option<DeviationLimit>,
+        ) -> DispatchResultWithPostInfo {
+            T::UpdateOrigin::ensure_origin(origin)?;
+            match limit {
+                Some(limit) => {
+                    ensure!(
+                        DeviationLimits::<T>::contains_key(asset_id)
+                            || DeviationLimits::<T>::count() < T::MaxDeviationLimits::get(),
+                        Error::<T>::TooManyDeviationLimits
+                    );
+                    DeviationLimits::<T>::insert(asset_id, limit);
+                    if let Some(price) = T::Source::get(&asset_id) {
+                        LastGoodPrice::<T>::insert(asset_id, price);
+                    }
+                }
+                None => {
+                    DeviationLimits::<T>::remove(asset_id);
+                    LastGoodPrice::<T>::remove(asset_id);
+                }
+            }
+            HeldPrice::<T>::remove(asset_id);
+            Self::deposit_event(Event::<T>::DeviationLimitUpdated(asset_id, limit));
+            Ok(().into())
+        }
+
+        /// Set or remove the AMM TWAP source of an asset's price.
+        #[pallet::call_index(4)]
+        #[pallet::weight((<T as Config>::WeightInfo::set_twap_source(), DispatchClass::Operational))]
+        #[transactional]
+        pub fn set_twap_source(
+            origin: OriginFor<T>,
+            asset_id: CurrencyId,
+            source: Option<TwapSource<T::BlockNumber>>,
+        ) -> DispatchResultWithPostInfo {
+            T::UpdateOrigin::ensure_origin(origin)?;
+            TwapSources::<T>::mutate_exists(asset_id, |twap_source| *twap_source = source);
+            Self::deposit_event(Event::<T>::TwapSourceUpdated(asset_id, source));
+            Ok(().into())
+        }
+
+        /// Set or remove the aggregation of an asset's price over several sources.
+        /// Once set, the asset has no price while fewer fresh values than the
+        /// quorum are available.
+        #[pallet::call_index(5)]
+        #[pallet::weight((<T as Config>::WeightInfo::set_price_aggregation(), DispatchClass::Operational))]
+        #[transactional]
+        pub fn set_price_aggregation(
+            origin: OriginFor<T>,
+            asset_id: CurrencyId,
+            aggregation: Option<PriceAggregation<T::AccountId>>,
+        ) -> DispatchResultWithPostInfo {
+            T::UpdateOrigin::ensure_origin(origin)?;
+            if let Some(aggregation) = &aggregation {
+                ensure!(aggregation.check(), Error::<T>::InvalidPriceAggregation);
+            }
+            PriceAggregations::<T>::mutate_exists(asset_id, |stored| *stored = aggregation.clone());
+            Self::deposit_event(Event::<T>::PriceAggregationUpdated(asset_id, aggregation));
+            Ok(().into())
+        }
     }
 }
 
 impl<T: Config> Pallet<T> {
+    fn do_set_emergency_price(
+        asset_id: CurrencyId,
+        price: Price,
+        expires_at: T::BlockNumber,
+        reason: Option<EmergencyPriceReason<T>>,
+    ) -> DispatchResult {
+        ensure!(
+            expires_at > frame_system::Pallet::<T>::block_number(),
+            Error::<T>::InvalidEmergencyPriceExpiry
+        );
+        Self::unschedule_emergency_price_expiry(asset_id);
+        EmergencyPriceExpiries::<T>::try_mutate(expires_at, |expiries| {
+            expiries
+                .try_push(asset_id)
+                .map_err(|_| Error::<T>::TooManyEmergencyPriceExpiries)
+        })?;
+
+        EmergencyPrice::<T>::insert(asset_id, price);
+        EmergencyPriceInfos::<T>::insert(
+            asset_id,
+            EmergencyPriceInfo {
+                expires_at,
+                reason: reason.clone(),
+            },
+        );
+        Self::deposit_event(Event::<T>::SetPrice(asset_id, price));
+        Self::deposit_event(Event::<T>::EmergencyPriceExpiryUpdated(
+            asset_id, expires_at, reason,
+        ));
+        Ok(())
+    }
+
+    // Drops the asset from the expiry queue of its current emergency price
+    fn unschedule_emergency_price_expiry(asset_id: CurrencyId) {
+        if let Some(info) = EmergencyPriceInfos::<T>::take(asset_id) {
+            EmergencyPriceExpiries::<T>::mutate_exists(info.expires_at, |expiries| {
+                if let Some(queue) = expiries {
+                    queue.retain(|id| *id != asset_id);
+                    if queue.is_empty() {
+                        *expiries = None;
+                    }
+                }
+            });
+        }
+    }
+
+    fn expire_emergency_price(asset_id: CurrencyId) {
+        EmergencyPriceInfos::<T>::remove(asset_id);
+        if let Some(price) = EmergencyPrice::<T>::take(asset_id) {
+            Self::deposit_event(Event::<T>::EmergencyPriceExpired(asset_id, price));
+        }
+    }
+
+    /// Returns all the emergency prices along with their expiry and reason,
+    /// the expiry of an emergency price set before expiries were introduced
+    /// is `None`.
+    pub fn get_emergency_prices() -> Vec<(
+        CurrencyId,
+        Price,
+        Option<EmergencyPriceInfo<T::BlockNumber, EmergencyPriceReason<T>>>,
+    )> {
+        EmergencyPrice::<T>::iter()
+            .map(|(asset_id, price)| (asset_id, price, Self::emergency_price_info(asset_id)))
+            .collect()
+    }
+
+    /// Returns the price of an asset along with the values of its sources.
+    pub fn get_price_breakdown(asset_id: &CurrencyId) -> PriceBreakdown<T::AccountId> {
+        PriceBreakdown {
+            price: Self::get_price(asset_id),
+            oracle: Self::get_source_value(asset_id, &PriceSource::Oracle),
+            emergency: Self::get_emergency_price(asset_id).map(|(price, _)| price),
+            derived: Self::get_source_value(asset_id, &PriceSource::Derived),
+            sources: Self::get_aggregated_price(asset_id)
+                .map(|(_, sources)| sources)
+                .unwrap_or_default(),
+        }
+    }
+
+    /// Returns the price breakdowns of the assets known to the pallet, which
+    /// are the ones with an oracle feed, an emergency price, a price setting or
+    /// a foreign mapping, and the liquid staking currency.
+    pub fn get_all_price_breakdowns() -> Vec<(CurrencyId, PriceBreakdown<T::AccountId>)> {
+        let mut assets: BTreeSet<CurrencyId> = T::Source::get_all_values()
+            .into_iter()
+            .map(|(asset_id, _)| asset_id)
+            .collect();
+        assets.extend(EmergencyPrice::<T>::iter_keys());
+        assets.extend(DeviationLimits::<T>::iter_keys());
+        assets.extend(TwapSources::<T>::iter_keys());
+        assets.extend(PriceAggregations::<T>::iter_keys());
+        assets.extend(ForeignToNativeAsset::<T>::iter_keys());
+        assets.extend(T::LiquidStakingCurrenciesProvider::get_liquid_currency());
+        assets
+            .into_iter()
+            .map(|asset_id| (asset_id, Self::get_price_breakdown(&asset_id)))
+            .collect()
+    }
+
+    // get the feed value of the oracle, the last good price is returned instead
+    // if the feed value exceeds the deviation limit
+    fn get_source_price(asset_id: &CurrencyId) -> Option<TimeStampedPrice> {
+        let price = T::Source::get(asset_id)?;
+        match (
+            Self::deviation_limit(asset_id),
+            Self::last_good_price(asset_id),
+        ) {
+            (Some(limit), Some(last_good_price))
+                if !Self::is_within_deviation_limit(
+                    &limit,
+                    &last_good_price,
+                    &price,
+                    HeldPrice::<T>::contains_key(asset_id),
+                ) =>
+            {
+                Some(last_good_price)
+            }
+            _ => Some(price),
+        }
+    }
+
+    // the allowed deviation no longer grows once a value is held back, otherwise
+    // any held back value would be accepted after enough windows
+    fn is_within_deviation_limit(
+        limit: &DeviationLimit,
+        last_good_price: &TimeStampedPrice,
+        price: &TimeStampedPrice,
+        is_held: bool,
+    ) -> bool {
+        if last_good_price.value.is_zero() {
+            return true;
+        }
+        let windows = if is_held {
+            1
+        } else {
+            let elapsed = price.timestamp.saturating_sub(last_good_price.timestamp);
+            elapsed.checked_div(limit.window).unwrap_or_default().max(1)
+        };
+        let max_deviation = FixedU128::from(limit.max_deviation)
+            .saturating_mul(FixedU128::saturating_from_integer(windows));
+        let deviation = if price.value > last_good_price.value {
+            price.value - last_good_price.value
+        } else {
+            last_good_price.value - price.value
+        };
+        deviation
+            .checked_div(&last_good_price.value)
+            .map_or(false, |deviation| deviation <= max_deviation)
+    }
+
+    // compares the feed value with the last good price, updates the circuit breaker
+    // state and emits events on transitions
+    fn check_price_deviation(asset_id: CurrencyId, limit: &DeviationLimit) {
+        let price = match T::Source::get(&asset_id) {
+            Some(price) => price,
+            None => return,
+        };
+        let last_good_price = match Self::last_good_price(asset_id) {
+            Some(last_good_price) => last_good_price,
+            None => {
+                LastGoodPrice::<T>::insert(asset_id, price);
+                return;
+            }
+        };
+
+        let is_held = HeldPrice::<T>::contains_key(asset_id);
+        if Self::is_within_deviation_limit(limit, &last_good_price, &price, is_held) {
+            LastGoodPrice::<T>::insert(asset_id, &price);
+            if is_held {
+                HeldPrice::<T>::remove(asset_id);
+                Self::deposit_event(Event::<T>::PriceDeviationCleared(asset_id, price.value));
+            }
+        } else if !is_held {
+            log::warn!(
+                target: "prices::check_price_deviation",
+                "asset_id: {:?}, last_good_price: {:?}, rejected_price: {:?}",
+                asset_id,
+                last_good_price,
+                price
+            );
+            HeldPrice::<T>::insert(asset_id, &price);
+            Self::deposit_event(Event::<T>::PriceDeviationDetected(
+                asset_id,
+                last_good_price.value,
+                price.value,
+            ));
+        } else {
+            HeldPrice::<T>::insert(asset_id, price);
+        }
+    }
+
+    // get the oracle price of an asset, special assets are derived from the
+    // price of the relay currency
+    fn get_oracle_price(asset_id: &CurrencyId) -> Option<PriceDetail> {
+        let mantissa = Self::get_asset_mantissa(asset_id)?;
+        Self::get_source_price(&T::RelayCurrency::get())
+            .and_then(|base_price| Self::get_special_asset_price(*asset_id, base_price))
+            .or_else(|| Self::get_source_price(asset_id))
+            .and_then(|price| Self::normalize_detail_price(price, mantissa))
+    }
+
+    // get the price from the TWAP of the asset's AMM pool, the timestamp is the
+    // one of the quote asset's price
+    //
+    // Both the TWAP and the normalized price are expressed per smallest unit, so
+    // no decimal conversion is needed.
+    fn get_twap_price(asset_id: &CurrencyId) -> Option<PriceDetail> {
+        let source = Self::twap_source(asset_id)?;
+        let (quote_price, timestamp) = Self::get_emergency_price(&source.quote_asset)
+            .or_else(|| Self::get_oracle_price(&source.quote_asset))?;
+        let twap = T::AMM::consult((*asset_id, source.quote_asset), source.window).ok()?;
+        log::trace!(
+            target: "prices::get_twap_price",
+            "asset_id: {:?}, source: {:?}, twap: {:?}",
+            asset_id,
+            source,
+            twap
+        );
+        twap.checked_mul(&quote_price)
+            .map(|price| (price, timestamp))
+    }
+
+    // whether the oracle price deviates from the TWAP more than allowed
+    fn exceeds_twap_deviation(asset_id: &CurrencyId) -> bool {
+        let max_deviation =
+            match Self::twap_source(asset_id).and_then(|source| source.max_deviation) {
+                Some(max_deviation) => max_deviation,
+                None => return false,
+            };
+        match (
+            Self::get_oracle_price(asset_id),
+            Self::get_twap_price(asset_id),
+        ) {
+            (Some((price, _)), Some((twap_price, _))) if !twap_price.is_zero() => {
+                let deviation = if price > twap_price {
+                    price - twap_price
+                } else {
+                    twap_price - price
+                };
+                deviation
+                    .checked_div(&twap_price)
+                    .map_or(true, |deviation| deviation > FixedU128::from(max_deviation))
+            }
+            _ => false,
+        }
+    }
+
+    // get the value of a single price source of an asset
+    fn get_source_value(
+        asset_id: &CurrencyId,
+        source: &PriceSource<T::AccountId>,
+    ) -> Option<PriceDetail> {
+        let mantissa = Self::get_asset_mantissa(asset_id)?;
+        match source {
+            PriceSource::Oracle => Self::get_source_price(asset_id)
+                .and_then(|price| Self::normalize_detail_price(price, mantissa)),
+            PriceSource::OracleMember(member) => {
+                T::MemberSource::get_member_value(member, asset_id)
+                    .and_then(|price| Self::normalize_detail_price(price, mantissa))
+            }
+            PriceSource::Twap => Self::get_twap_price(asset_id),
+            PriceSource::Derived => Self::get_source_price(&T::RelayCurrency::get())
+                .and_then(|base_price| Self::get_special_asset_price(*asset_id, base_price))
+                .and_then(|price| Self::normalize_detail_price(price, mantissa))
+                .or_else(|| Self::get_lp_asset_price(asset_id)),
+        }
+    }
+
+    /// Returns the median of the fresh values of an asset's price sources along
+    /// with the sources that contributed, `None` if the asset has no aggregation
+    /// or fewer fresh values than the quorum.
+    ///
+    /// The timestamp is the one of the oldest contributing value.
+    pub fn get_aggregated_price(
+        asset_id: &CurrencyId,
+    ) -> Option<(PriceDetail, Vec<PriceSource<T::AccountId>>)> {
+        let aggregation = Self::price_aggregation(asset_id)?;
+        let now: Timestamp = T::UnixTime::now().as_millis().saturated_into();
+        let mut values: Vec<(PriceDetail, PriceSource<T::AccountId>)> = aggregation
+            .sources
+            .into_iter()
+            .filter_map(|source| {
+                Self::get_source_value(asset_id, &source)
+                    .filter(|(_, timestamp)| now.saturating_sub(*timestamp) <= aggregation.max_age)
+                    .map(|value| (value, source))
+            })
+            .collect();
+        if values.len() < aggregation.quorum as usize {
+            log::trace!(
+                target: "prices::get_aggregated_price",
+                "asset_id: {:?}, fresh values: {:?}, quorum: {:?}",
+                asset_id,
+                values.len(),
+                aggregation.quorum
+            );
+            return None;
+        }
+
+        values.sort_by(|((a, _), _), ((b, _), _)| a.cmp(b));
+        let prices: Vec<Price> = values.iter().map(|((price, _), _)| *price).collect();
+        let mid = prices.len() / 2;
+        let median = if prices.len() % 2 == 0 {
+            FixedU128::from_inner(
+                prices[mid - 1]
+                    .into_inner()
+                    .saturating_add(prices[mid].into_inner())
+                    / 2,
+            )
+        } else {
+            prices[mid]
+        };
+        let timestamp = values
+            .iter()
+            .map(|((_, timestamp), _)| *timestamp)
+            .min()
+            .unwrap_or_default();
+        Some((
+            (median, timestamp),
+            values.into_iter().map(|(_, source)| source).collect(),
+        ))
+    }
+
     // get emergency price, the timestamp is zero
     fn get_emergency_price(asset_id: &CurrencyId) -> Option<PriceDetail> {
         Self::emergency_price(asset_id).and_then(|p| {
@@ -293,6 +917,75 @@ impl<T: Config> Pallet<T> {
             timestamp: vault_asset_price.timestamp,
         })
     }
+
+    // get the price of the liquidity token of an AMM or StableSwap pool, the
+    // timestamp is the older one of the prices of both legs
+    fn get_lp_asset_price(asset_id: &CurrencyId) -> Option<PriceDetail> {
+        Self::get_amm_lp_asset_price(asset_id).or_else(|| Self::get_stable_lp_asset_price(asset_id))
+    }
+
+    // Fair value of an AMM liquidity token: `2 * sqrt(k * p0 * p1) / supply`.
+    //
+    // It only depends on the invariant `k` and on the prices of both legs, so
+    // it can't be moved by skewing the reserves of the pool.
+    fn get_amm_lp_asset_price(asset_id: &CurrencyId) -> Option<PriceDetail> {
+        let (base_asset, quote_asset, pool) = T::AMM::get_pool_by_lp_asset(*asset_id)?;
+        let (base_price, base_timestamp) = Self::get_price(&base_asset)?;
+        let (quote_price, quote_timestamp) = Self::get_price(&quote_asset)?;
+        let lp_asset_total_supply = T::Assets::total_issuance(*asset_id);
+        if lp_asset_total_supply.is_zero() {
+            return None;
+        }
+        // the values of both legs keep the 18 decimals of the prices, the
+        // square root of their product keeps them too
+        let base_value =
+            U256::from(pool.base_amount).saturating_mul(U256::from(base_price.into_inner()));
+        let quote_value =
+            U256::from(pool.quote_amount).saturating_mul(U256::from(quote_price.into_inner()));
+        let lp_asset_price = base_value
+            .full_mul(quote_value)
+            .integer_sqrt()
+            .checked_mul(U512::from(2_u128))?
+            .checked_div(U512::from(lp_asset_total_supply))
+            .and_then(|r| TryInto::<u128>::try_into(r).ok())?;
+        log::trace!(
+            target: "prices::get_amm_lp_asset_price",
+            "asset_id: {:?}, pair: {:?}, lp_asset_price: {:?}",
+            asset_id,
+            (base_asset, quote_asset),
+            lp_asset_price
+        );
+        Some((
+            Price::from_inner(lp_asset_price),
+            base_timestamp.min(quote_timestamp),
+        ))
+    }
+
+    // Fair value of a StableSwap liquidity token: `D * min(p_i) / supply`.
+    //
+    // The invariant D is the value of the pool once balanced, in units of its
+    // assets, so the assets of a pool are expected to share the same decimals.
+    fn get_stable_lp_asset_price(asset_id: &CurrencyId) -> Option<PriceDetail> {
+        let (assets, d) = T::StableSwap::get_pool_by_lp_asset(*asset_id)?;
+        let prices = assets
+            .iter()
+            .map(|asset| Self::get_price(asset))
+            .collect::<Option<Vec<_>>>()?;
+        let min_price = prices.iter().map(|(price, _)| *price).min()?;
+        let timestamp = prices.iter().map(|(_, timestamp)| *timestamp).min()?;
+        let lp_asset_total_supply = T::Assets::total_issuance(*asset_id);
+        let lp_asset_rate = Price::checked_from_rational(d, lp_asset_total_supply)?;
+        let lp_asset_price = min_price.checked_mul(&lp_asset_rate)?;
+        log::trace!(
+            target: "prices::get_stable_lp_asset_price",
+            "asset_id: {:?}, assets: {:?}, d: {:?}, lp_asset_price: {:?}",
+            asset_id,
+            assets,
+            d,
+            lp_asset_price
+        );
+        Some((lp_asset_price, timestamp))
+    }
 }
 
 impl<T: Config> PriceFeeder for Pallet<T> {
@@ -305,26 +998,46 @@ impl<T: Config> PriceFeeder for Pallet<T> {
     /// Timestamp is zero means the price is emergency price
     fn get_price(asset_id: &CurrencyId) -> Option<PriceDetail> {
         // if emergency price exists, return it
-        Self::get_emergency_price(asset_id).or_else(|| {
-            let mantissa = Self::get_asset_mantissa(asset_id)?;
-            T::Source::get(&T::RelayCurrency::get())
-                .and_then(|base_price| Self::get_special_asset_price(*asset_id, base_price))
-                .or_else(|| T::Source::get(asset_id))
-                .and_then(|price| Self::normalize_detail_price(price, mantissa))
-        })
+        if let Some(price) = Self::get_emergency_price(asset_id) {
+            return Some(price);
+        }
+        // aggregated assets don't fall back to a single feed
+        if PriceAggregations::<T>::contains_key(asset_id) {
+            return Self::get_aggregated_price(asset_id).map(|(price, _)| price);
+        }
+        Self::get_oracle_price(asset_id)
+            // liquidity tokens are priced from the legs of their pool
+            .or_else(|| Self::get_lp_asset_price(asset_id))
+            // fall back to the AMM TWAP for assets without oracle feed
+            .or_else(|| Self::get_twap_price(asset_id))
+    }
+
+    /// The asset is suspicious while its feed is held back and its deviation limit
+    /// asks for it, or while its feed deviates too much from its AMM TWAP, unless
+    /// an emergency price is set.
+    fn is_suspicious(asset_id: &CurrencyId) -> bool {
+        if Self::emergency_price(asset_id).is_some() {
+            return false;
+        }
+        let is_held = HeldPrice::<T>::contains_key(asset_id)
+            && Self::deviation_limit(asset_id).map_or(false, |limit| limit.mark_suspicious);
+        is_held || Self::exceeds_twap_deviation(asset_id)
     }
 }
 
-impl<T: Config> EmergencyPriceFeeder<CurrencyId, Price> for Pallet<T> {
-    /// Set emergency price
-    fn set_emergency_price(asset_id: CurrencyId, price: Price) {
-        // set price direct
-        EmergencyPrice::<T>::insert(asset_id, price);
-        <Pallet<T>>::deposit_event(Event::SetPrice(asset_id, price));
+impl<T: Config> EmergencyPriceFeeder<CurrencyId, Price, T::BlockNumber> for Pallet<T> {
+    /// Set emergency price, which is cleared at the `expires_at` block
+    fn set_emergency_price(
+        asset_id: CurrencyId,
+        price: Price,
+        expires_at: T::BlockNumber,
+    ) -> DispatchResult {
+        Self::do_set_emergency_price(asset_id, price, expires_at, None)
     }
 
     /// Reset emergency price
     fn reset_emergency_price(asset_id: CurrencyId) {
+        Self::unschedule_emergency_price_expiry(asset_id);
         EmergencyPrice::<T>::remove(asset_id);
         <Pallet<T>>::deposit_event(Event::ResetPrice(asset_id));
     }
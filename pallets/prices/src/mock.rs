@@ -24,8 +24,11 @@ use frame_support::{
 use frame_system::{EnsureRoot, EnsureSigned, EnsureSignedBy};
 use sp_core::H256;
 use sp_runtime::{testing::Header, traits::IdentityLookup, FixedPointNumber};
+use std::{cell::RefCell, collections::HashMap};
 
-pub use primitives::tokens::{CDOT_7_14, CKSM_20_27, DOT, KSM, LP_DOT_CDOT_7_14, SDOT, SKSM};
+pub use primitives::tokens::{
+    CDOT_7_14, CKSM_20_27, DOT, KSM, LP_DOT_CDOT_7_14, LP_DOT_SDOT, PARA, SDOT, SKSM,
+};
 
 pub type AccountId = u128;
 pub type BlockNumber = u64;
@@ -67,8 +70,27 @@ impl frame_system::Config for Test {
 
 pub type TimeStampedPrice = orml_oracle::TimestampedValue<Price, Moment>;
 pub struct MockDataProvider;
+
+impl MockDataProvider {
+    thread_local! {
+        pub static PRICES: RefCell<HashMap<CurrencyId, TimeStampedPrice>> =
+            RefCell::new(HashMap::new());
+    }
+
+    pub fn set_price(asset_id: CurrencyId, value: Price, timestamp: Moment) {
+        Self::PRICES.with(|prices| {
+            prices
+                .borrow_mut()
+                .insert(asset_id, TimeStampedPrice { value, timestamp });
+        });
+    }
+}
+
 impl DataProvider<CurrencyId, TimeStampedPrice> for MockDataProvider {
     fn get(asset_id: &CurrencyId) -> Option<TimeStampedPrice> {
+        if let Some(price) = Self::PRICES.with(|prices| prices.borrow().get(asset_id).cloned()) {
+            return Some(price);
+        }
         match *asset_id {
             DOT => Some(TimeStampedPrice {
                 value: Price::saturating_from_integer(100),
@@ -109,6 +131,47 @@ impl DataFeeder<CurrencyId, TimeStampedPrice, AccountId> for MockDataProvider {
     }
 }
 
+pub struct MockMemberSource;
+
+impl MockMemberSource {
+    thread_local! {
+        pub static PRICES: RefCell<HashMap<(AccountId, CurrencyId), TimeStampedPrice>> =
+            RefCell::new(HashMap::new());
+    }
+
+    pub fn set_price(member: AccountId, asset_id: CurrencyId, value: Price, timestamp: Moment) {
+        Self::PRICES.with(|prices| {
+            prices
+                .borrow_mut()
+                .insert((member, asset_id), TimeStampedPrice { value, timestamp });
+        });
+    }
+}
+
+impl OracleMemberProvider<AccountId> for MockMemberSource {
+    fn get_member_value(member: &AccountId, asset_id: &CurrencyId) -> Option<TimeStampedPrice> {
+        Self::PRICES.with(|prices| prices.borrow().get(&(*member, *asset_id)).cloned())
+    }
+}
+
+pub struct MockUnixTime;
+
+impl MockUnixTime {
+    thread_local! {
+        pub static NOW: RefCell<Moment> = RefCell::new(0);
+    }
+
+    pub fn set_now(now: Moment) {
+        Self::NOW.with(|t| *t.borrow_mut() = now);
+    }
+}
+
+impl UnixTime for MockUnixTime {
+    fn now() -> core::time::Duration {
+        core::time::Duration::from_millis(Self::NOW.with(|t| *t.borrow()))
+    }
+}
+
 pub struct LiquidStakingExchangeRateProvider;
 impl ExchangeRateProvider<CurrencyId> for LiquidStakingExchangeRateProvider {
     fn get_exchange_rate(_: &CurrencyId) -> Option<Rate> {
@@ -137,7 +200,9 @@ impl DecimalProvider<CurrencyId> for Decimal {
             CKSM_20_27 => Some(12),
             CDOT_7_14 => Some(10),
             LP_DOT_CDOT_7_14 => Some(12),
+            LP_DOT_SDOT => Some(10),
             LC_DOT => Some(10),
+            PARA => Some(12),
             _ => None,
         }
     }
@@ -246,6 +311,14 @@ parameter_types! {
     // pub const DefaultProtocolFee: Ratio = Ratio::from_rational(5u32, 10000u32);
     pub  DefaultLpFee: Ratio = Ratio::from_rational(25u32, 10000u32);         // 0.3%
     pub const MinimumLiquidity: u128 = 1_000u128;
+    pub const PoolCreationDeposit: Balance = 1_000_000;
+    pub MaxPermissionlessLpFee: Ratio = Ratio::from_percent(1);
+    pub const MinimumPoolSeedLiquidity: u128 = 10_000u128;
+    pub const DepositRefundThreshold: u128 = 1_000_000u128;
+    pub const DepositRefundDelay: BlockNumber = 10;
+    pub const PermissionlessLpTokenIdStart: CurrencyId = 500_000_000;
+    pub const MaxObservations: u32 = 4;
+    pub const ObservationPeriod: BlockNumber = 1;
     pub const LockAccountId: AccountId = ALICE;
     pub const MaxLengthRoute: u8 = 10;
 }
@@ -265,10 +338,50 @@ impl pallet_amm::Config for Test {
     type AMMWeightInfo = ();
     type CreatePoolOrigin = EnsureSignedBy<AliceCreatePoolOrigin, AccountId>;
     type ProtocolFeeUpdateOrigin = EnsureSignedBy<AliceCreatePoolOrigin, AccountId>;
+    type PermissionlessCreatePoolOrigin = EnsureSigned<AccountId>;
+    type Currency = Balances;
+    type LpTokenAssets = Assets;
+    type PermissionlessLpTokenIdStart = PermissionlessLpTokenIdStart;
+    type PoolCreationDeposit = PoolCreationDeposit;
+    type MinimumPoolSeedLiquidity = MinimumPoolSeedLiquidity;
+    type DepositRefundThreshold = DepositRefundThreshold;
+    type DepositRefundDelay = DepositRefundDelay;
     type LpFee = DefaultLpFee;
+    type MaxPermissionlessLpFee = MaxPermissionlessLpFee;
     type MinimumLiquidity = MinimumLiquidity;
     type MaxLengthRoute = MaxLengthRoute;
     type GetNativeCurrencyId = NativeCurrencyId;
+    type MaxObservations = MaxObservations;
+    type ObservationPeriod = ObservationPeriod;
+    type OnPoolCreated = ();
+    type OnPoolDelisted = ();
+}
+
+// StableSwap instance initialization
+parameter_types! {
+    pub const StableSwapPalletId: PalletId = PalletId(*b"par/sswp");
+    pub const NumTokens: u32 = 2;
+    pub const Precision: u128 = 100;
+    pub const AmplificationCoefficient: u8 = 85;
+    pub DefaultProtocolFee: Ratio = Ratio::from_rational(5u32, 10000u32);
+    pub const DefaultProtocolFeeReceiver: AccountId = CHARLIE;
+}
+
+impl pallet_stableswap::Config for Test {
+    type RuntimeEvent = RuntimeEvent;
+    type Assets = CurrencyAdapter;
+    type WeightInfo = ();
+    type PalletId = StableSwapPalletId;
+    type NumTokens = NumTokens;
+    type Precision = Precision;
+    type AmplificationCoefficient = AmplificationCoefficient;
+    type ProtocolFeeReceiver = DefaultProtocolFeeReceiver;
+    type LpFee = DefaultLpFee;
+    type LockAccountId = LockAccountId;
+    type ProtocolFee = DefaultProtocolFee;
+    type MinimumLiquidity = MinimumLiquidity;
+    type CreatePoolOrigin = EnsureSignedBy<AliceCreatePoolOrigin, AccountId>;
+    type OnPoolCreated = ();
 }
 
 impl crate::Config for Test {
@@ -283,7 +396,13 @@ impl crate::Config for Test {
     type VaultLoansRateProvider = VaultLoansRateProvider;
     type RelayCurrency = RelayCurrency;
     type Decimal = Decimal;
+    type MemberSource = MockMemberSource;
+    type UnixTime = MockUnixTime;
+    type MaxEmergencyPriceReasonLength = frame_support::traits::ConstU32<32>;
+    type MaxDeviationLimits = frame_support::traits::ConstU32<16>;
+    type MaxEmergencyPriceExpiries = frame_support::traits::ConstU32<3>;
     type AMM = DefaultAMM;
+    type StableSwap = DefaultStableSwap;
     type Assets = CurrencyAdapter;
     type WeightInfo = ();
 }
@@ -301,6 +420,7 @@ construct_runtime!(
         Balances: pallet_balances::{Pallet, Call, Storage, Event<T>},
         Assets: pallet_assets::{Pallet, Call, Storage, Event<T>},
         DefaultAMM: pallet_amm::{Pallet, Call, Storage, Event<T>},
+        DefaultStableSwap: pallet_stableswap::{Pallet, Call, Storage, Event<T>},
         CurrencyAdapter: pallet_currency_adapter::{Pallet, Call},
         Prices: crate::{Pallet, Storage, Call, Event<T>},
     }
@@ -48,7 +48,9 @@ fn set_price_work() {
         assert_ok!(Prices::set_price(
             RuntimeOrigin::signed(ALICE),
             DOT,
-            Price::saturating_from_integer(99)
+            Price::saturating_from_integer(99),
+            100,
+            None
         ));
         assert_eq!(
             Prices::get_price(&DOT),
@@ -57,7 +59,9 @@ fn set_price_work() {
         assert_ok!(Prices::set_price(
             RuntimeOrigin::signed(ALICE),
             KSM,
-            Price::saturating_from_integer(1)
+            Price::saturating_from_integer(1),
+            100,
+            None
         ));
         assert_eq!(
             Prices::get_emergency_price(&KSM),
@@ -104,6 +108,8 @@ fn set_price_call_work() {
                 RuntimeOrigin::signed(CHARLIE),
                 DOT,
                 Price::saturating_from_integer(100),
+                100,
+                None
             ),
             BadOrigin
         );
@@ -111,6 +117,8 @@ fn set_price_call_work() {
             RuntimeOrigin::signed(ALICE),
             DOT,
             Price::saturating_from_integer(90),
+            100,
+            None
         ));
         assert_eq!(
             Prices::get_price(&DOT),
@@ -130,6 +138,8 @@ fn set_price_call_work() {
                 RuntimeOrigin::signed(ALICE),
                 DOT,
                 Price::saturating_from_integer(90),
+                100,
+                None
             ),
             Ok(().into())
         );
@@ -150,6 +160,8 @@ fn reset_price_call_work() {
             RuntimeOrigin::signed(ALICE),
             DOT,
             Price::saturating_from_integer(90),
+            100,
+            None
         ));
         assert_eq!(
             Prices::get_price(&DOT),
@@ -223,6 +235,7 @@ fn get_lp_ctoken_price_work() {
             (300 * PRICE_ONE, 100 * PRICE_ONE), //3:1
             ALICE,
             LP_DOT_CDOT_7_14,
+            DefaultLpFee::get(),
         )
         .unwrap();
 
@@ -244,6 +257,7 @@ fn get_lp_ctoken_price_with_different_exchange_rate_will_not_change() {
             (200 * PRICE_ONE, 100 * PRICE_ONE), //2:1
             ALICE,
             LP_DOT_CDOT_7_14,
+            DefaultLpFee::get(),
         )
         .unwrap();
 
@@ -273,6 +287,7 @@ fn get_lp_ctoken_no_op_price_work() {
             (300 * PRICE_ONE, 100 * PRICE_ONE),
             ALICE,
             LP_DOT_CDOT_7_14,
+            DefaultLpFee::get(),
         )
         .unwrap();
 
@@ -303,3 +318,602 @@ fn get_foreign_ctoken_price_work() {
         assert_eq!(Prices::get_price(&CDOT_7_14), Prices::get_price(&LC_DOT));
     });
 }
+
+#[test]
+fn deviation_limit_holds_last_good_price() {
+    new_test_ext().execute_with(|| {
+        System::set_block_number(1);
+        let limit = DeviationLimit {
+            max_deviation: Ratio::from_percent(10),
+            window: 60_000,
+            mark_suspicious: true,
+        };
+        MockDataProvider::set_price(KSM, Price::saturating_from_integer(500), 6_000);
+        assert_noop!(
+            Prices::set_deviation_limit(RuntimeOrigin::signed(CHARLIE), KSM, Some(limit)),
+            BadOrigin
+        );
+        assert_ok!(Prices::set_deviation_limit(
+            RuntimeOrigin::signed(ALICE),
+            KSM,
+            Some(limit)
+        ));
+        assert_eq!(
+            Prices::last_good_price(KSM).map(|price| price.timestamp),
+            Some(6_000)
+        );
+
+        // 8% move is accepted
+        MockDataProvider::set_price(KSM, Price::saturating_from_integer(540), 12_000);
+        Prices::on_initialize(1);
+        assert_eq!(Prices::get_price(&KSM).map(|price| price.1), Some(12_000));
+        assert!(!Prices::is_suspicious(&KSM));
+
+        // 48% move is held back
+        MockDataProvider::set_price(KSM, Price::saturating_from_integer(800), 18_000);
+        assert_eq!(Prices::get_price(&KSM).map(|price| price.1), Some(12_000));
+        Prices::on_initialize(2);
+        assert_eq!(Prices::get_price(&KSM).map(|price| price.1), Some(12_000));
+        assert!(Prices::is_suspicious(&KSM));
+        let detected_event = RuntimeEvent::Prices(crate::Event::PriceDeviationDetected(
+            KSM,
+            Price::saturating_from_integer(540),
+            Price::saturating_from_integer(800),
+        ));
+        assert!(System::events()
+            .iter()
+            .any(|record| record.event == detected_event));
+
+        // the allowed deviation does not grow while the feed is held back
+        MockDataProvider::set_price(KSM, Price::saturating_from_integer(800), 600_000);
+        assert_eq!(Prices::get_price(&KSM).map(|price| price.1), Some(12_000));
+        Prices::on_initialize(3);
+        assert_eq!(Prices::get_price(&KSM).map(|price| price.1), Some(12_000));
+        assert!(Prices::is_suspicious(&KSM));
+
+        // feed is back within the limit
+        MockDataProvider::set_price(KSM, Price::saturating_from_integer(560), 606_000);
+        Prices::on_initialize(4);
+        assert_eq!(Prices::get_price(&KSM).map(|price| price.1), Some(606_000));
+        assert!(!Prices::is_suspicious(&KSM));
+        let cleared_event = RuntimeEvent::Prices(crate::Event::PriceDeviationCleared(
+            KSM,
+            Price::saturating_from_integer(560),
+        ));
+        assert!(System::events()
+            .iter()
+            .any(|record| record.event == cleared_event));
+
+        // allowed deviation grows with the elapsed windows
+        MockDataProvider::set_price(KSM, Price::saturating_from_integer(640), 726_000);
+        assert_eq!(Prices::get_price(&KSM).map(|price| price.1), Some(726_000));
+
+        // removing the limit clears the state
+        assert_ok!(Prices::set_deviation_limit(
+            RuntimeOrigin::signed(ALICE),
+            KSM,
+            None
+        ));
+        assert_eq!(Prices::last_good_price(KSM), None);
+        assert_eq!(Prices::held_price(KSM), None);
+    });
+}
+
+#[test]
+fn deviation_limits_are_bounded() {
+    new_test_ext().execute_with(|| {
+        let limit = DeviationLimit {
+            max_deviation: Ratio::from_percent(10),
+            window: 60_000,
+            mark_suspicious: false,
+        };
+        let max_limits = <Test as crate::Config>::MaxDeviationLimits::get();
+        for asset_id in 0..max_limits {
+            assert_ok!(Prices::set_deviation_limit(
+                RuntimeOrigin::signed(ALICE),
+                10_000 + asset_id,
+                Some(limit)
+            ));
+        }
+        assert_noop!(
+            Prices::set_deviation_limit(RuntimeOrigin::signed(ALICE), KSM, Some(limit)),
+            Error::<Test>::TooManyDeviationLimits
+        );
+
+        // an existing limit can still be updated or removed
+        assert_ok!(Prices::set_deviation_limit(
+            RuntimeOrigin::signed(ALICE),
+            10_000,
+            Some(limit)
+        ));
+        assert_ok!(Prices::set_deviation_limit(
+            RuntimeOrigin::signed(ALICE),
+            10_000,
+            None
+        ));
+        assert_ok!(Prices::set_deviation_limit(
+            RuntimeOrigin::signed(ALICE),
+            KSM,
+            Some(limit)
+        ));
+    });
+}
+
+#[test]
+fn amm_twap_is_price_fallback_and_cross_check() {
+    new_test_ext().execute_with(|| {
+        System::set_block_number(1);
+        DefaultAMM::create_pool(
+            RuntimeOrigin::signed(ALICE),
+            (PARA, DOT),
+            (4_000_000, 1_000_000), //4:1
+            ALICE,
+            LP_DOT_CDOT_7_14,
+            DefaultLpFee::get(),
+        )
+        .unwrap();
+
+        let source = TwapSource {
+            quote_asset: DOT,
+            window: 10,
+            max_deviation: None,
+        };
+        assert_noop!(
+            Prices::set_twap_source(RuntimeOrigin::signed(CHARLIE), PARA, Some(source)),
+            BadOrigin
+        );
+        assert_ok!(Prices::set_twap_source(
+            RuntimeOrigin::signed(ALICE),
+            PARA,
+            Some(source)
+        ));
+
+        // not enough history for the window yet
+        assert_eq!(Prices::get_price(&PARA), None);
+
+        // PARA has no oracle feed, its price is derived from the TWAP
+        System::set_block_number(11);
+        let twap_price = Prices::get_price(&DOT).map(|(price, timestamp)| {
+            (
+                price.saturating_mul(Price::saturating_from_rational(1, 4)),
+                timestamp,
+            )
+        });
+        assert_eq!(Prices::get_price(&PARA), twap_price);
+
+        // the oracle feed is preferred and cross-checked against the TWAP
+        assert_ok!(Prices::set_twap_source(
+            RuntimeOrigin::signed(ALICE),
+            PARA,
+            Some(TwapSource {
+                max_deviation: Some(Ratio::from_percent(10)),
+                ..source
+            })
+        ));
+        MockDataProvider::set_price(PARA, Price::saturating_from_integer(2500), 6_000);
+        assert_eq!(
+            Prices::get_price(&PARA),
+            twap_price.map(|(price, _)| (price, 6_000))
+        );
+        assert!(!Prices::is_suspicious(&PARA));
+
+        MockDataProvider::set_price(PARA, Price::saturating_from_integer(3000), 12_000);
+        assert!(Prices::is_suspicious(&PARA));
+
+        assert_ok!(Prices::set_twap_source(
+            RuntimeOrigin::signed(ALICE),
+            PARA,
+            None
+        ));
+        assert_eq!(Prices::twap_source(PARA), None);
+        assert!(!Prices::is_suspicious(&PARA));
+        let updated_event = RuntimeEvent::Prices(crate::Event::TwapSourceUpdated(PARA, None));
+        assert!(System::events()
+            .iter()
+            .any(|record| record.event == updated_event));
+    });
+}
+
+#[test]
+fn aggregated_price_is_median_of_fresh_values() {
+    new_test_ext().execute_with(|| {
+        let aggregation = PriceAggregation {
+            sources: vec![
+                PriceSource::Oracle,
+                PriceSource::OracleMember(ALICE),
+                PriceSource::OracleMember(CHARLIE),
+            ],
+            quorum: 2,
+            max_age: 30_000,
+        };
+        assert_noop!(
+            Prices::set_price_aggregation(
+                RuntimeOrigin::signed(ALICE),
+                KSM,
+                Some(PriceAggregation {
+                    quorum: 4,
+                    ..aggregation.clone()
+                })
+            ),
+            Error::<Test>::InvalidPriceAggregation
+        );
+        assert_noop!(
+            Prices::set_price_aggregation(
+                RuntimeOrigin::signed(ALICE),
+                KSM,
+                Some(PriceAggregation {
+                    sources: vec![PriceSource::Oracle, PriceSource::Oracle],
+                    ..aggregation.clone()
+                })
+            ),
+            Error::<Test>::InvalidPriceAggregation
+        );
+        assert_ok!(Prices::set_price_aggregation(
+            RuntimeOrigin::signed(ALICE),
+            KSM,
+            Some(aggregation)
+        ));
+
+        // the value of Charlie is stale
+        MockUnixTime::set_now(60_000);
+        MockDataProvider::set_price(KSM, Price::saturating_from_integer(500), 50_000);
+        MockMemberSource::set_price(ALICE, KSM, Price::saturating_from_integer(510), 55_000);
+        MockMemberSource::set_price(CHARLIE, KSM, Price::saturating_from_integer(600), 10_000);
+        assert_eq!(
+            Prices::get_aggregated_price(&KSM),
+            Some((
+                (Price::saturating_from_integer(505_000_000), 50_000),
+                vec![PriceSource::Oracle, PriceSource::OracleMember(ALICE)]
+            ))
+        );
+        assert_eq!(
+            Prices::get_price_breakdown(&KSM).sources,
+            vec![PriceSource::Oracle, PriceSource::OracleMember(ALICE)]
+        );
+
+        MockMemberSource::set_price(CHARLIE, KSM, Price::saturating_from_integer(600), 58_000);
+        assert_eq!(
+            Prices::get_price(&KSM),
+            Some((Price::saturating_from_integer(510_000_000), 50_000))
+        );
+        assert_eq!(
+            Prices::get_aggregated_price(&KSM).map(|(_, sources)| sources.len()),
+            Some(3)
+        );
+    });
+}
+
+#[test]
+fn aggregated_price_requires_quorum() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(Prices::set_price_aggregation(
+            RuntimeOrigin::signed(ALICE),
+            KSM,
+            Some(PriceAggregation {
+                sources: vec![PriceSource::Oracle, PriceSource::OracleMember(ALICE)],
+                quorum: 2,
+                max_age: 30_000,
+            })
+        ));
+
+        // the oracle feed alone is not trusted
+        MockUnixTime::set_now(60_000);
+        MockDataProvider::set_price(KSM, Price::saturating_from_integer(500), 50_000);
+        assert_eq!(Prices::get_price(&KSM), None);
+
+        // the emergency price still takes precedence
+        assert_ok!(Prices::set_price(
+            RuntimeOrigin::signed(ALICE),
+            KSM,
+            Price::saturating_from_integer(1),
+            100,
+            None
+        ));
+        assert_eq!(Prices::get_price(&KSM), Some((1_000_000.into(), 0)));
+        assert_ok!(Prices::reset_price(RuntimeOrigin::signed(ALICE), KSM));
+
+        assert_ok!(Prices::set_price_aggregation(
+            RuntimeOrigin::signed(ALICE),
+            KSM,
+            None
+        ));
+        assert_eq!(
+            Prices::get_price(&KSM),
+            Some((Price::saturating_from_integer(500_000_000), 50_000))
+        );
+    });
+}
+
+#[test]
+fn get_amm_lp_asset_price_work() {
+    new_test_ext().execute_with(|| {
+        Assets::force_create(RuntimeOrigin::root(), LP_DOT_SDOT.into(), ALICE, true, 1).unwrap();
+        assert_eq!(Prices::get_price(&LP_DOT_SDOT), None);
+
+        // 1 SDOT = 1.5 DOT, both legs are worth the same
+        DefaultAMM::create_pool(
+            RuntimeOrigin::signed(ALICE),
+            (DOT, SDOT),
+            (150 * PRICE_ONE, 100 * PRICE_ONE),
+            ALICE,
+            LP_DOT_SDOT,
+            DefaultLpFee::get(),
+        )
+        .unwrap();
+
+        let (dot_price, _) = Prices::get_price(&DOT).unwrap();
+        let pool_value = dot_price.saturating_mul_int(300 * PRICE_ONE);
+        let (lp_price, timestamp) = Prices::get_price(&LP_DOT_SDOT).unwrap();
+        let lp_total_supply = Assets::total_issuance(LP_DOT_SDOT);
+        assert_eq!(timestamp, 0);
+        assert!(
+            lp_price
+                .saturating_mul_int(lp_total_supply)
+                .abs_diff(pool_value)
+                <= 1_000
+        );
+
+        // skewing the reserves only adds the swap fees to the fair value
+        assert_ok!(<DefaultAMM as AMM<_, _, _, _>>::swap(
+            &ALICE,
+            (DOT, SDOT),
+            100 * PRICE_ONE
+        ));
+        let (skewed_lp_price, _) = Prices::get_price(&LP_DOT_SDOT).unwrap();
+        assert!(skewed_lp_price >= lp_price);
+        assert!(
+            skewed_lp_price < lp_price.saturating_mul(Price::saturating_from_rational(101, 100))
+        );
+
+        // and follows the prices of the legs
+        assert_ok!(Prices::set_price(
+            RuntimeOrigin::signed(ALICE),
+            DOT,
+            Price::saturating_from_integer(200),
+            100,
+            None
+        ));
+        assert!(Prices::get_price(&LP_DOT_SDOT).unwrap().0 > skewed_lp_price);
+    });
+}
+
+#[test]
+fn get_stable_lp_asset_price_work() {
+    new_test_ext().execute_with(|| {
+        Assets::force_create(RuntimeOrigin::root(), LP_DOT_SDOT.into(), ALICE, true, 1).unwrap();
+        DefaultStableSwap::create_pool(
+            RuntimeOrigin::signed(ALICE),
+            vec![(DOT, 100 * PRICE_ONE), (SDOT, 100 * PRICE_ONE)],
+            ALICE,
+            LP_DOT_SDOT,
+        )
+        .unwrap();
+
+        // the pool is valued at the price of its cheapest leg
+        let (dot_price, _) = Prices::get_price(&DOT).unwrap();
+        let pool_value = dot_price.saturating_mul_int(200 * PRICE_ONE);
+        let (lp_price, timestamp) = Prices::get_price(&LP_DOT_SDOT).unwrap();
+        let lp_total_supply = Assets::total_issuance(LP_DOT_SDOT);
+        assert_eq!(timestamp, 0);
+        assert!(
+            lp_price
+                .saturating_mul_int(lp_total_supply)
+                .abs_diff(pool_value)
+                <= 1_000
+        );
+
+        // it can be one of the sources of an aggregated price
+        assert_ok!(Prices::set_price_aggregation(
+            RuntimeOrigin::signed(ALICE),
+            LP_DOT_SDOT,
+            Some(PriceAggregation {
+                sources: vec![PriceSource::Derived],
+                quorum: 1,
+                max_age: 30_000,
+            })
+        ));
+        assert_eq!(
+            Prices::get_aggregated_price(&LP_DOT_SDOT),
+            Some(((lp_price, 0), vec![PriceSource::Derived]))
+        );
+    });
+}
+
+#[test]
+fn emergency_price_expires() {
+    new_test_ext().execute_with(|| {
+        System::set_block_number(1);
+        let reason: EmergencyPriceReason<Test> = b"feed outage".to_vec().try_into().unwrap();
+
+        assert_noop!(
+            Prices::set_price(
+                RuntimeOrigin::signed(ALICE),
+                DOT,
+                Price::saturating_from_integer(90),
+                1,
+                None
+            ),
+            Error::<Test>::InvalidEmergencyPriceExpiry
+        );
+        assert_ok!(Prices::set_price(
+            RuntimeOrigin::signed(ALICE),
+            DOT,
+            Price::saturating_from_integer(90),
+            10,
+            Some(reason.clone())
+        ));
+        System::assert_last_event(RuntimeEvent::Prices(
+            crate::Event::EmergencyPriceExpiryUpdated(DOT, 10, Some(reason.clone())),
+        ));
+        assert_eq!(
+            Prices::get_emergency_prices(),
+            vec![(
+                DOT,
+                Price::saturating_from_integer(90),
+                Some(EmergencyPriceInfo {
+                    expires_at: 10,
+                    reason: Some(reason)
+                })
+            )]
+        );
+
+        Prices::on_initialize(9);
+        assert_eq!(
+            Prices::get_price(&DOT),
+            Some((FixedU128::from_inner(9_000_000_000 * PRICE_ONE), 0))
+        );
+
+        // back to the oracle price once expired
+        Prices::on_initialize(10);
+        System::assert_last_event(RuntimeEvent::Prices(crate::Event::EmergencyPriceExpired(
+            DOT,
+            Price::saturating_from_integer(90),
+        )));
+        assert_eq!(
+            Prices::get_price(&DOT),
+            Some((FixedU128::from_inner(10_000_000_000 * PRICE_ONE), 0))
+        );
+        assert_eq!(Prices::emergency_price_info(DOT), None);
+        assert!(Prices::get_emergency_prices().is_empty());
+
+        assert!(Prices::emergency_price_expiries(10).is_empty());
+
+        // an emergency price set through the feeder expires as well
+        assert_noop!(
+            <Prices as EmergencyPriceFeeder<CurrencyId, Price, BlockNumber>>::set_emergency_price(
+                KSM,
+                Price::saturating_from_integer(1),
+                1,
+            ),
+            Error::<Test>::InvalidEmergencyPriceExpiry
+        );
+        assert_ok!(<Prices as EmergencyPriceFeeder<
+            CurrencyId,
+            Price,
+            BlockNumber,
+        >>::set_emergency_price(
+            KSM, Price::saturating_from_integer(1), 20,
+        ));
+
+        // setting it again moves the expiry
+        assert_ok!(Prices::set_price(
+            RuntimeOrigin::signed(ALICE),
+            KSM,
+            Price::saturating_from_integer(2),
+            30,
+            None
+        ));
+        assert!(Prices::emergency_price_expiries(20).is_empty());
+        Prices::on_initialize(20);
+        assert_eq!(
+            Prices::emergency_price(KSM),
+            Some(Price::saturating_from_integer(2))
+        );
+        Prices::on_initialize(30);
+        assert_eq!(Prices::emergency_price(KSM), None);
+        assert!(Prices::get_emergency_prices().is_empty());
+    });
+}
+
+#[test]
+fn emergency_price_expiries_are_bounded() {
+    new_test_ext().execute_with(|| {
+        System::set_block_number(1);
+        for asset_id in [DOT, KSM, SDOT] {
+            assert_ok!(Prices::set_price(
+                RuntimeOrigin::signed(ALICE),
+                asset_id,
+                Price::saturating_from_integer(1),
+                10,
+                None
+            ));
+        }
+        assert_noop!(
+            Prices::set_price(
+                RuntimeOrigin::signed(ALICE),
+                SKSM,
+                Price::saturating_from_integer(1),
+                10,
+                None
+            ),
+            Error::<Test>::TooManyEmergencyPriceExpiries
+        );
+
+        // a reset emergency price frees its place in the queue
+        assert_ok!(Prices::reset_price(RuntimeOrigin::signed(ALICE), DOT));
+        assert_eq!(
+            Prices::emergency_price_expiries(10).to_vec(),
+            vec![KSM, SDOT]
+        );
+        assert_ok!(Prices::set_price(
+            RuntimeOrigin::signed(ALICE),
+            SKSM,
+            Price::saturating_from_integer(1),
+            10,
+            None
+        ));
+
+        Prices::on_initialize(10);
+        assert!(Prices::get_emergency_prices().is_empty());
+    });
+}
+
+#[test]
+fn get_price_breakdown_work() {
+    new_test_ext().execute_with(|| {
+        let dot_price = Prices::get_price(&DOT);
+        assert_eq!(
+            Prices::get_price_breakdown(&DOT),
+            PriceBreakdown {
+                price: dot_price,
+                oracle: dot_price,
+                emergency: None,
+                derived: None,
+                sources: vec![],
+            }
+        );
+
+        // SDOT has no oracle feed, its price is derived from DOT
+        let sdot_price = dot_price.map(|(price, timestamp)| {
+            (
+                price.saturating_mul(Price::saturating_from_rational(150, 100)),
+                timestamp,
+            )
+        });
+        assert_eq!(
+            Prices::get_price_breakdown(&SDOT),
+            PriceBreakdown {
+                price: sdot_price,
+                oracle: None,
+                emergency: None,
+                derived: sdot_price,
+                sources: vec![],
+            }
+        );
+
+        assert_ok!(Prices::set_price(
+            RuntimeOrigin::signed(ALICE),
+            SDOT,
+            Price::saturating_from_integer(200),
+            100,
+            None
+        ));
+        let emergency_price = Price::from_inner(20_000_000_000 * PRICE_ONE);
+        assert_eq!(
+            Prices::get_price_breakdown(&SDOT),
+            PriceBreakdown {
+                price: Some((emergency_price, 0)),
+                oracle: None,
+                emergency: Some(emergency_price),
+                derived: sdot_price,
+                sources: vec![],
+            }
+        );
+
+        let all_prices = Prices::get_all_price_breakdowns();
+        assert!(all_prices.contains(&(SDOT, Prices::get_price_breakdown(&SDOT))));
+        assert!(all_prices
+            .iter()
+            .any(|(asset_id, _)| asset_id == &tokens::LC_DOT));
+    });
+}
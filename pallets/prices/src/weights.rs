@@ -26,6 +26,9 @@ pub trait WeightInfo {
     fn set_price() -> Weight;
     fn reset_price() -> Weight;
     fn set_foreign_asset() -> Weight;
+    fn set_deviation_limit() -> Weight;
+    fn set_twap_source() -> Weight;
+    fn set_price_aggregation() -> Weight;
 }
 
 /// Weights for pallet_prices using the Substrate node and recommended hardware.
@@ -33,36 +36,62 @@ pub struct SubstrateWeight<T>(PhantomData<T>);
 impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
     fn set_price() -> Weight {
         Weight::from_ref_time(22_361_000 as u64)
-            .saturating_add(T::DbWeight::get().reads(1 as u64))
-            .saturating_add(T::DbWeight::get().writes(1 as u64))
+            .saturating_add(T::DbWeight::get().reads(3 as u64))
+            .saturating_add(T::DbWeight::get().writes(4 as u64))
     }
     fn reset_price() -> Weight {
         Weight::from_ref_time(21_361_000 as u64)
-            .saturating_add(T::DbWeight::get().reads(1 as u64))
-            .saturating_add(T::DbWeight::get().writes(1 as u64))
+            .saturating_add(T::DbWeight::get().reads(2 as u64))
+            .saturating_add(T::DbWeight::get().writes(3 as u64))
     }
     fn set_foreign_asset() -> Weight {
         Weight::from_ref_time(23_361_000 as u64)
             .saturating_add(T::DbWeight::get().reads(1 as u64))
             .saturating_add(T::DbWeight::get().writes(1 as u64))
     }
+    fn set_deviation_limit() -> Weight {
+        Weight::from_ref_time(24_361_000 as u64)
+            .saturating_add(T::DbWeight::get().reads(2 as u64))
+            .saturating_add(T::DbWeight::get().writes(4 as u64))
+    }
+    fn set_twap_source() -> Weight {
+        Weight::from_ref_time(22_361_000 as u64)
+            .saturating_add(T::DbWeight::get().writes(1 as u64))
+    }
+    fn set_price_aggregation() -> Weight {
+        Weight::from_ref_time(24_817_000 as u64)
+            .saturating_add(T::DbWeight::get().writes(1 as u64))
+    }
 }
 
 // For backwards compatibility and tests
 impl WeightInfo for () {
     fn set_price() -> Weight {
         Weight::from_ref_time(22_361_000 as u64)
-            .saturating_add(RocksDbWeight::get().reads(1 as u64))
-            .saturating_add(RocksDbWeight::get().writes(1 as u64))
+            .saturating_add(RocksDbWeight::get().reads(3 as u64))
+            .saturating_add(RocksDbWeight::get().writes(4 as u64))
     }
     fn reset_price() -> Weight {
         Weight::from_ref_time(21_361_000 as u64)
-            .saturating_add(RocksDbWeight::get().reads(1 as u64))
-            .saturating_add(RocksDbWeight::get().writes(1 as u64))
+            .saturating_add(RocksDbWeight::get().reads(2 as u64))
+            .saturating_add(RocksDbWeight::get().writes(3 as u64))
     }
     fn set_foreign_asset() -> Weight {
         Weight::from_ref_time(23_361_000 as u64)
             .saturating_add(RocksDbWeight::get().reads(1 as u64))
             .saturating_add(RocksDbWeight::get().writes(1 as u64))
     }
+    fn set_deviation_limit() -> Weight {
+        Weight::from_ref_time(24_361_000 as u64)
+            .saturating_add(RocksDbWeight::get().reads(2 as u64))
+            .saturating_add(RocksDbWeight::get().writes(4 as u64))
+    }
+    fn set_twap_source() -> Weight {
+        Weight::from_ref_time(22_361_000 as u64)
+            .saturating_add(RocksDbWeight::get().writes(1 as u64))
+    }
+    fn set_price_aggregation() -> Weight {
+        Weight::from_ref_time(24_817_000 as u64)
+            .saturating_add(RocksDbWeight::get().writes(1 as u64))
+    }
 }
@@ -15,18 +15,34 @@
 #![cfg_attr(not(feature = "std"), no_std)]
 
 use codec::Codec;
-use primitives::CurrencyId;
+use primitives::{CurrencyId, PoolType};
 use sp_runtime::DispatchError;
 use sp_std::vec::Vec;
 
 sp_api::decl_runtime_apis! {
+    /// Version 2 reports the pool type of each hop of a route and adds split routes
+    #[api_version(2)]
     pub trait RouterApi<Balance> where
         Balance: Codec, {
+        #[changed_in(2)]
         fn get_best_route(
             amount: Balance,
             token_in: CurrencyId,
             token_out: CurrencyId,
             reversed: bool,
         ) -> Result<(Vec<CurrencyId>, Balance), DispatchError>;
+
+        fn get_best_route(
+            amount: Balance,
+            token_in: CurrencyId,
+            token_out: CurrencyId,
+            reversed: bool,
+        ) -> Result<(Vec<CurrencyId>, Balance, Vec<PoolType>), DispatchError>;
+
+        fn get_best_split_route(
+            amount: Balance,
+            token_in: CurrencyId,
+            token_out: CurrencyId,
+        ) -> Result<Vec<(Vec<CurrencyId>, Balance, Balance, Vec<PoolType>)>, DispatchError>;
     }
 }
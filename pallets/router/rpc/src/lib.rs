@@ -22,8 +22,8 @@ use jsonrpsee::{
     proc_macros::rpc,
     types::error::{CallError, ErrorCode, ErrorObject},
 };
-use primitives::CurrencyId;
-use sp_api::ProvideRuntimeApi;
+use primitives::{CurrencyId, PoolType};
+use sp_api::{ApiExt, ProvideRuntimeApi};
 use sp_blockchain::HeaderBackend;
 use sp_rpc::number::NumberOrHex;
 use sp_runtime::{generic::BlockId, traits::Block as BlockT};
@@ -42,7 +42,16 @@ where
         token_out: CurrencyId,
         reversed: bool,
         at: Option<BlockHash>,
-    ) -> RpcResult<(Vec<CurrencyId>, NumberOrHex)>;
+    ) -> RpcResult<(Vec<CurrencyId>, NumberOrHex, Vec<PoolType>)>;
+
+    #[method(name = "router_getBestSplitRoute")]
+    fn get_best_split_route(
+        &self,
+        amount: NumberOrHex,
+        token_in: CurrencyId,
+        token_out: CurrencyId,
+        at: Option<BlockHash>,
+    ) -> RpcResult<Vec<(Vec<CurrencyId>, NumberOrHex, NumberOrHex, Vec<PoolType>)>>;
 }
 
 /// A struct that implements the [`RouteApi`].
@@ -92,20 +101,55 @@ where
         token_out: CurrencyId,
         reversed: bool,
         at: Option<<Block as BlockT>::Hash>,
-    ) -> RpcResult<(Vec<CurrencyId>, NumberOrHex)> {
+    ) -> RpcResult<(Vec<CurrencyId>, NumberOrHex, Vec<PoolType>)> {
+        let api = self.client.runtime_api();
+        let at = BlockId::hash(at.unwrap_or(self.client.info().best_hash));
+        let amount = decode_hex(amount, "balance")?;
+        let api_version = api
+            .api_version::<dyn RouterRuntimeApi<Block, Balance>>(&at)
+            .map_err(runtime_error_into_rpc_error)?
+            .unwrap_or_default();
+        let (route, amt, pool_types) = if api_version < 2 {
+            // older runtimes only route through AMM pools
+            #[allow(deprecated)]
+            let (route, amt) = api
+                .get_best_route_before_version_2(&at, amount, token_in, token_out, reversed)
+                .map_err(runtime_error_into_rpc_error)?
+                .map_err(smart_route_rpc_error)?;
+            let pool_types = vec![PoolType::AMM; route.len().saturating_sub(1)];
+            (route, amt, pool_types)
+        } else {
+            api.get_best_route(&at, amount, token_in, token_out, reversed)
+                .map_err(runtime_error_into_rpc_error)?
+                .map_err(smart_route_rpc_error)?
+        };
+        Ok((route, try_into_rpc_balance(amt)?, pool_types))
+    }
+
+    fn get_best_split_route(
+        &self,
+        amount: NumberOrHex,
+        token_in: CurrencyId,
+        token_out: CurrencyId,
+        at: Option<<Block as BlockT>::Hash>,
+    ) -> RpcResult<Vec<(Vec<CurrencyId>, NumberOrHex, NumberOrHex, Vec<PoolType>)>> {
         let api = self.client.runtime_api();
         let at = BlockId::hash(at.unwrap_or(self.client.info().best_hash));
-        let (route, amt) = api
-            .get_best_route(
-                &at,
-                decode_hex(amount, "balance")?,
-                token_in,
-                token_out,
-                reversed,
-            )
+        let split_routes = api
+            .get_best_split_route(&at, decode_hex(amount, "balance")?, token_in, token_out)
             .map_err(runtime_error_into_rpc_error)?
             .map_err(smart_route_rpc_error)?;
-        Ok((route, try_into_rpc_balance(amt)?))
+        split_routes
+            .into_iter()
+            .map(|(route, amount_in, amount_out, pool_types)| {
+                Ok((
+                    route,
+                    try_into_rpc_balance(amount_in)?,
+                    try_into_rpc_balance(amount_out)?,
+                    pool_types,
+                ))
+            })
+            .collect()
     }
 }
 
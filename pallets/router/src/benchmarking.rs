@@ -20,7 +20,7 @@ use frame_support::{
     },
 };
 use frame_system::{self, RawOrigin as SystemOrigin};
-use primitives::{tokens, Balance, CurrencyId};
+use primitives::{tokens, Balance, CurrencyId, PoolType};
 use sp_runtime::traits::{One, StaticLookup};
 use sp_std::{vec, vec::Vec};
 
@@ -81,7 +81,8 @@ fn initial_set_up<
             (DOT, SDOT),
             (100_000_000u128, 100_000_000u128),
             pool_creator.clone(),
-            ASSET_ID
+            ASSET_ID,
+            <T as pallet_amm::Config>::LpFee::get(),
         ));
     }
     assert_ok!(pallet_amm::Pallet::<T>::add_liquidity(
@@ -92,6 +93,49 @@ fn initial_set_up<
     ));
 }
 
+// adds `n - 1` routes of DOT -> X -> SDOT next to the DOT -> SDOT pool
+fn create_split_routes<
+    T: Config<I> + pallet_assets::Config<AssetId = CurrencyId, Balance = Balance> + pallet_amm::Config,
+    I: 'static,
+>(
+    n: u32,
+) {
+    let pool_creator: T::AccountId = account("pool_creator", 1, 0);
+    for i in 1..n {
+        let asset_id = ASSET_ID + 3 * i;
+        for id in asset_id..asset_id + 3 {
+            pallet_assets::Pallet::<T>::force_create(
+                SystemOrigin::Root.into(),
+                id.into(),
+                T::Lookup::unlookup(pool_creator.clone()),
+                true,
+                One::one(),
+            )
+            .ok();
+        }
+        <T as crate::Config<I>>::Assets::mint_into(asset_id, &pool_creator, INITIAL_AMOUNT).ok();
+
+        assert_ok!(pallet_amm::Pallet::<T>::create_pool(
+            T::CreatePoolOrigin::try_successful_origin()
+                .expect("No origin exists which can satisfy the guard"),
+            (DOT, asset_id),
+            (100_000_000u128, 100_000_000u128),
+            pool_creator.clone(),
+            asset_id + 1,
+            <T as pallet_amm::Config>::LpFee::get(),
+        ));
+        assert_ok!(pallet_amm::Pallet::<T>::create_pool(
+            T::CreatePoolOrigin::try_successful_origin()
+                .expect("No origin exists which can satisfy the guard"),
+            (asset_id, SDOT),
+            (100_000_000u128, 100_000_000u128),
+            pool_creator.clone(),
+            asset_id + 2,
+            <T as pallet_amm::Config>::LpFee::get(),
+        ));
+    }
+}
+
 benchmarks_instance_pallet! {
     where_clause {
         where
@@ -112,7 +156,7 @@ benchmarks_instance_pallet! {
         let expected = 996u128;
 
         assert_eq!(amount_out, expected);
-        assert_last_event::<T, I>(Event::Traded(caller, amount_in, routes, expected).into());
+        assert_last_event::<T, I>(Event::Traded(caller, amount_in, routes, expected, vec![PoolType::AMM]).into());
     }
 
     swap_tokens_for_exact_tokens {
@@ -132,7 +176,31 @@ benchmarks_instance_pallet! {
         let expected = 984u128;
 
         assert_eq!(amount_in, expected);
-        assert_last_event::<T, I>(Event::Traded(caller, expected, routes, amount_out).into());
+        assert_last_event::<T, I>(Event::Traded(caller, expected, routes, amount_out, vec![PoolType::AMM]).into());
+    }
+
+    swap_exact_tokens_for_tokens_split {
+        let n in 1 .. T::MaxSplitRoutes::get();
+        let caller: T::AccountId = whitelisted_caller();
+        initial_set_up::<T, I>(caller.clone());
+        create_split_routes::<T, I>(n);
+        let amount_in = 1_000_000u128;
+        let min_amount_out = 900_000u128;
+    }: swap_exact_tokens_for_tokens_split(SystemOrigin::Signed(caller.clone()), DOT, SDOT, amount_in, min_amount_out)
+
+    verify {
+        let amount_out: BalanceOf<T, I> = <T as crate::Config<I>>::Assets::balance(SDOT, &caller);
+        assert!(amount_out >= min_amount_out);
+    }
+
+    find_routes {
+        let p in 1 .. T::MaxPoolsVisited::get();
+        let caller: T::AccountId = whitelisted_caller();
+        initial_set_up::<T, I>(caller);
+        // the search visits three pools for each DOT -> X -> SDOT route
+        create_split_routes::<T, I>((p / 3).clamp(1, T::MaxPoolDegree::get()));
+    }: {
+        assert!(AMMRoute::<T, I>::find_routes(DOT, SDOT).is_ok());
     }
 }
 
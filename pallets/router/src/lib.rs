@@ -15,12 +15,26 @@
 //! # Router for Automatic Market Maker (AMM)
 //!
 //! Given a supported `route`, executes the indicated trades on all the available AMM(s) pool(s).
+//!
+//! Routes are searched across both the constant product pools of `T::AMM` and the
+//! pools of `T::StableSwap`. Each hop of a route trades through whichever pool
+//! type gives the better price for that hop.
+//!
+//! Large trades can be split across up to `T::MaxSplitRoutes` routes which do not
+//! share any pool, to reduce the price impact on a single route.
 
 #![cfg_attr(not(feature = "std"), no_std)]
 
+use codec::{Decode, Encode, MaxEncodedLen};
+use frame_support::{ensure, weights::Weight, RuntimeDebug};
+use pallet_traits::{OnPoolCreated, OnPoolDelisted, Router, StableSwap};
+use scale_info::TypeInfo;
+use sp_runtime::{traits::Zero, DispatchError};
+
 pub use pallet::*;
 
 mod benchmarking;
+pub mod migrations;
 
 #[cfg(test)]
 mod mock;
@@ -30,12 +44,22 @@ mod tests;
 pub mod weights;
 pub use weights::WeightInfo;
 
+/// Utility type for managing upgrades/migrations.
+#[derive(Encode, Decode, Clone, Copy, PartialEq, Eq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+pub enum Versions {
+    V0,
+    V1,
+}
+
 #[frame_support::pallet]
 pub mod pallet {
     use super::*;
     use frame_support::{
         ensure, log,
-        pallet_prelude::{DispatchResult, DispatchResultWithPostInfo},
+        pallet_prelude::{
+            Blake2_128Concat, DispatchResult, DispatchResultWithPostInfo, StorageMap, StorageValue,
+            ValueQuery,
+        },
         require_transactional,
         traits::{
             fungibles::{Inspect, Mutate, Transfer},
@@ -44,10 +68,14 @@ pub mod pallet {
         transactional, BoundedVec, PalletId,
     };
     use frame_system::{ensure_signed, pallet_prelude::OriginFor};
-    use pallet_traits::AMM;
-    use primitives::{Balance, CurrencyId};
+    use pallet_traits::{StableSwap, AMM};
+    use primitives::{Balance, CurrencyId, PoolType};
     use sp_runtime::{traits::Zero, DispatchError};
-    use sp_std::{cmp::Reverse, collections::btree_map::BTreeMap, vec::Vec};
+    use sp_std::{
+        cmp::Reverse,
+        collections::{btree_map::BTreeMap, vec_deque::VecDeque},
+        vec::Vec,
+    };
 
     pub type Route<T, I> = BoundedVec<
         (
@@ -59,6 +87,18 @@ pub mod pallet {
         <T as Config<I>>::MaxLengthRoute,
     >;
 
+    /// A route of a split trade along with its amount in, amount out and the
+    /// pool type used by each hop
+    pub type SplitRoute<T, I> = (
+        Vec<AssetIdOf<T, I>>,
+        BalanceOf<T, I>,
+        BalanceOf<T, I>,
+        Vec<PoolType>,
+    );
+
+    /// Number of chunks the amount of a split trade is handed out in
+    pub const SPLIT_STEPS: u128 = 20;
+
     pub(crate) type AccountIdOf<T> = <T as frame_system::Config>::AccountId;
     pub(crate) type AssetIdOf<T, I = ()> =
         <<T as Config<I>>::Assets as Inspect<<T as frame_system::Config>::AccountId>>::AssetId;
@@ -77,6 +117,9 @@ pub mod pallet {
         /// Specify all the AMMs we are routing between
         type AMM: AMM<AccountIdOf<Self>, AssetIdOf<Self, I>, BalanceOf<Self, I>, Self::BlockNumber>;
 
+        /// Specify the stable swap pools we are routing between
+        type StableSwap: StableSwap<AccountIdOf<Self>, AssetIdOf<Self, I>, BalanceOf<Self, I>>;
+
         /// Weight information for extrinsics in this pallet.
         type AMMRouterWeightInfo: WeightInfo;
 
@@ -84,6 +127,18 @@ pub mod pallet {
         #[pallet::constant]
         type MaxLengthRoute: Get<u32>;
 
+        /// How many routes a split trade is spread across at most
+        #[pallet::constant]
+        type MaxSplitRoutes: Get<u32>;
+
+        /// How many pools route discovery visits at most
+        #[pallet::constant]
+        type MaxPoolsVisited: Get<u32>;
+
+        /// How many assets an asset can be paired with in the cached graph
+        #[pallet::constant]
+        type MaxPoolDegree: Get<u32>;
+
         /// The asset id for native currency.
         #[pallet::constant]
         type GetNativeCurrencyId: Get<AssetIdOf<Self, I>>;
@@ -98,6 +153,28 @@ pub mod pallet {
     #[pallet::pallet]
     pub struct Pallet<T, I = ()>(_);
 
+    /// Assets each asset is paired with in a pool of any type
+    #[pallet::storage]
+    #[pallet::getter(fn adjacency)]
+    pub type Adjacency<T: Config<I>, I: 'static = ()> = StorageMap<
+        _,
+        Blake2_128Concat,
+        AssetIdOf<T, I>,
+        BoundedVec<AssetIdOf<T, I>, T::MaxPoolDegree>,
+        ValueQuery,
+    >;
+
+    /// DefaultVersion is using for initialize the StorageVersion
+    #[pallet::type_value]
+    pub(super) fn DefaultVersion<T: Config<I>, I: 'static>() -> Versions {
+        Versions::V0
+    }
+
+    /// Storage version of the pallet.
+    #[pallet::storage]
+    pub(crate) type StorageVersion<T: Config<I>, I: 'static = ()> =
+        StorageValue<_, Versions, ValueQuery, DefaultVersion<T, I>>;
+
     #[pallet::error]
     pub enum Error<T, I = ()> {
         /// Input balance must not be zero
@@ -124,12 +201,13 @@ pub mod pallet {
     #[pallet::generate_deposit(pub (crate) fn deposit_event)]
     pub enum Event<T: Config<I>, I: 'static = ()> {
         /// Event emitted when swap is successful
-        /// [sender, amount_in, route, amount_out]
+        /// [sender, amount_in, route, amount_out, pool_types]
         Traded(
             T::AccountId,
             BalanceOf<T, I>,
             Vec<AssetIdOf<T, I>>,
             BalanceOf<T, I>,
+            Vec<PoolType>,
         ),
     }
 
@@ -157,85 +235,106 @@ pub mod pallet {
         }
 
         /// Returns a sorted list of all routes and their output amounts from a
-        /// start token to end token by traversing a graph.
+        /// start token to end token by traversing the graph of pairs.
         pub fn get_all_routes(
             amount: BalanceOf<T, I>,
             token_in: AssetIdOf<T, I>,
             token_out: AssetIdOf<T, I>,
             reversed: bool,
-        ) -> Result<Vec<(Vec<AssetIdOf<T, I>>, BalanceOf<T, I>)>, DispatchError> {
-            // get all the pool asset pairs from the AMM
-            let pools = T::AMM::get_pools()?;
-
-            let mut graph: BTreeMap<u32, Vec<u32>> = BTreeMap::new();
-
-            // build a non directed graph from pool asset pairs
-            pools.into_iter().for_each(|(a, b)| {
-                graph.entry(a).or_insert_with(Vec::new).push(b);
-                graph.entry(b).or_insert_with(Vec::new).push(a);
-            });
+        ) -> Result<Vec<(Vec<AssetIdOf<T, I>>, BalanceOf<T, I>, Vec<PoolType>)>, DispatchError>
+        {
+            let paths = Self::find_routes(token_in, token_out)?;
 
-            // init mutable variables
-            let mut path = Vec::new();
-            let mut paths = Vec::new();
+            // get output amounts for all routes
+            let mut output_routes = Self::get_output_routes(amount, paths, reversed);
 
-            let mut start = token_in;
-            let mut end = token_out;
+            // sort values greatest to least
+            output_routes.sort_by_key(|k| Reverse(k.1));
 
-            let mut queue: Vec<(u32, u32, Vec<u32>)> = Vec::from([(start, end, path)]);
+            Ok(output_routes)
+        }
 
+        /// Returns all routes from `token_in` to `token_out` no longer than
+        /// `MaxLengthRoute`, searching the cached graph breadth first. The search
+        /// stops once `MaxPoolsVisited` pools have been visited.
+        pub fn find_routes(
+            token_in: AssetIdOf<T, I>,
+            token_out: AssetIdOf<T, I>,
+        ) -> Result<Vec<Vec<AssetIdOf<T, I>>>, DispatchError> {
             // check that both tokens exist in graph
             ensure!(
-                graph.contains_key(&start),
+                Adjacency::<T, I>::contains_key(token_in),
+                Error::<T, I>::TokenDoesNotExists
+            );
+            ensure!(
+                Adjacency::<T, I>::contains_key(token_out),
                 Error::<T, I>::TokenDoesNotExists
             );
-            ensure!(graph.contains_key(&end), Error::<T, I>::TokenDoesNotExists);
 
-            // iterate until we build all routes
-            while !queue.is_empty() {
-                // desugared RFC 2909-destructuring-assignment
-                let (_start, _end, _path) = queue.swap_remove(0);
-                start = _start;
-                end = _end;
-                path = _path;
+            let max_length = T::MaxLengthRoute::get() as usize;
+            let max_visited = T::MaxPoolsVisited::get();
 
-                path.push(start);
+            // each asset's adjacents are read from storage once
+            let mut adjacency: BTreeMap<
+                AssetIdOf<T, I>,
+                BoundedVec<AssetIdOf<T, I>, T::MaxPoolDegree>,
+            > = BTreeMap::new();
+            let mut visited = 0u32;
+            let mut paths = Vec::new();
+            let mut queue: VecDeque<Vec<AssetIdOf<T, I>>> = VecDeque::from([Vec::from([token_in])]);
+
+            'search: while let Some(path) = queue.pop_front() {
+                let last = path[path.len() - 1];
 
-                // exit if we reached our target
-                if start == end {
-                    paths.push(path.clone());
+                // a route ends at the target, it is not extended any further
+                if last == token_out {
+                    paths.push(path);
+                    continue;
                 }
 
-                // cant error because we fetch pools above
-                let adjacents = graph.get(&start).unwrap();
+                if path.len() >= max_length {
+                    continue;
+                }
 
-                // items that are adjacent but not already in path
-                let difference: Vec<_> = adjacents
-                    .iter()
-                    .filter(|item| !path.contains(item))
-                    .collect();
+                let adjacents = adjacency
+                    .entry(last)
+                    .or_insert_with(|| Adjacency::<T, I>::get(last));
 
-                for node in difference {
-                    queue.push((*node, end, path.clone()));
+                for node in adjacents.iter() {
+                    if visited >= max_visited {
+                        break 'search;
+                    }
+                    visited += 1;
+
+                    // items that are adjacent but not already in path
+                    if !path.contains(node) {
+                        let mut next = path.clone();
+                        next.push(*node);
+                        queue.push_back(next);
+                    }
                 }
             }
 
-            // get output amounts for all routes
-            let mut output_routes = Self::get_output_routes(amount, paths, reversed);
-
-            // sort values greatest to least
-            output_routes.sort_by_key(|k| Reverse(k.1));
+            log::trace!(
+                target: "router::find_routes",
+                "token_in: {:?}, token_out: {:?}, visited: {:?}, routes: {:?}",
+                token_in,
+                token_out,
+                visited,
+                paths.len()
+            );
 
-            Ok(output_routes)
+            Ok(paths)
         }
 
-        /// Returns the route that results in the largest amount out for amount in
+        /// Returns the route that results in the largest amount out for amount in,
+        /// along with the pool type used by each hop
         pub fn get_best_route(
             amount: BalanceOf<T, I>,
             token_in: AssetIdOf<T, I>,
             token_out: AssetIdOf<T, I>,
             reversed: bool,
-        ) -> Result<(Vec<AssetIdOf<T, I>>, BalanceOf<T, I>), DispatchError> {
+        ) -> Result<(Vec<AssetIdOf<T, I>>, BalanceOf<T, I>, Vec<PoolType>), DispatchError> {
             let mut all_routes = Self::get_all_routes(amount, token_in, token_out, reversed)?;
             ensure!(!all_routes.is_empty(), Error::<T, I>::NoPossibleRoute);
             let best_route = if reversed {
@@ -262,27 +361,266 @@ pub mod pallet {
             amount: BalanceOf<T, I>,
             routes: Vec<Vec<AssetIdOf<T, I>>>,
             reversed: bool,
-        ) -> Vec<(Vec<AssetIdOf<T, I>>, BalanceOf<T, I>)> {
+        ) -> Vec<(Vec<AssetIdOf<T, I>>, BalanceOf<T, I>, Vec<PoolType>)> {
             let mut output_routes = Vec::new();
 
             if reversed {
                 for route in routes {
-                    let amounts = T::AMM::get_amounts_in(amount, route.clone());
-                    if let Ok(amounts) = amounts {
-                        output_routes.push((route, amounts[0]));
+                    let amounts = Self::get_amounts_in(amount, &route);
+                    if let Ok((amounts, pool_types)) = amounts {
+                        output_routes.push((route, amounts[0], pool_types));
                     }
                 }
             } else {
                 for route in routes {
-                    let amounts = T::AMM::get_amounts_out(amount, route.clone());
-                    if let Ok(amounts) = amounts {
-                        output_routes.push((route, amounts[amounts.len() - 1]));
+                    let amounts = Self::get_amounts_out(amount, &route);
+                    if let Ok((amounts, pool_types)) = amounts {
+                        output_routes.push((route, amounts[amounts.len() - 1], pool_types));
                     }
                 }
             }
 
             output_routes
         }
+
+        /// Based on the route specified and the available pool balances this will
+        /// return the amounts outs when trading the specified amount in, along with
+        /// the pool type used by each hop
+        pub fn get_amounts_out(
+            amount_in: BalanceOf<T, I>,
+            route: &[AssetIdOf<T, I>],
+        ) -> Result<(Vec<BalanceOf<T, I>>, Vec<PoolType>), DispatchError> {
+            let mut amounts = Vec::from([amount_in]);
+            let mut pool_types = Vec::new();
+
+            for pair in route.windows(2) {
+                let (amount_out, pool_type) =
+                    Self::get_hop_amount_out(amounts[amounts.len() - 1], (pair[0], pair[1]))?;
+                amounts.push(amount_out);
+                pool_types.push(pool_type);
+            }
+
+            Ok((amounts, pool_types))
+        }
+
+        /// Based on the route specified and the available pool balances this will
+        /// return the amounts in needed to produce the specified amount out, along
+        /// with the pool type used by each hop
+        pub fn get_amounts_in(
+            amount_out: BalanceOf<T, I>,
+            route: &[AssetIdOf<T, I>],
+        ) -> Result<(Vec<BalanceOf<T, I>>, Vec<PoolType>), DispatchError> {
+            let mut amounts = Vec::from([amount_out]);
+            let mut pool_types = Vec::new();
+
+            for pair in route.windows(2).rev() {
+                let (amount_in, pool_type) =
+                    Self::get_hop_amount_in(amounts[amounts.len() - 1], (pair[0], pair[1]))?;
+                amounts.push(amount_in);
+                pool_types.push(pool_type);
+            }
+
+            amounts.reverse();
+            pool_types.reverse();
+
+            Ok((amounts, pool_types))
+        }
+
+        /// Splits the amount in across up to `MaxSplitRoutes` routes which share no
+        /// pool, returning the routes used along with their amounts in and out
+        pub fn get_best_split_route(
+            amount: BalanceOf<T, I>,
+            token_in: AssetIdOf<T, I>,
+            token_out: AssetIdOf<T, I>,
+        ) -> Result<Vec<SplitRoute<T, I>>, DispatchError> {
+            let all_routes = Self::get_all_routes(amount, token_in, token_out, false)?;
+
+            // pick the best routes that do not trade through the same pair, so
+            // each of them can be quoted against the current reserves
+            let mut routes: Vec<Vec<AssetIdOf<T, I>>> = Vec::new();
+            let mut used_pairs = Vec::new();
+            for (route, _, _) in all_routes {
+                if routes.len() >= T::MaxSplitRoutes::get() as usize {
+                    break;
+                }
+                let pairs: Vec<_> = route
+                    .windows(2)
+                    .map(|pair| {
+                        if pair[0] < pair[1] {
+                            (pair[0], pair[1])
+                        } else {
+                            (pair[1], pair[0])
+                        }
+                    })
+                    .collect();
+                if pairs.iter().any(|pair| used_pairs.contains(pair)) {
+                    continue;
+                }
+
+                used_pairs.extend(pairs);
+                routes.push(route);
+            }
+            ensure!(!routes.is_empty(), Error::<T, I>::NoPossibleRoute);
+
+            // hand out the amount in chunks, each one to the route with the
+            // largest marginal amount out
+            let mut amounts_in: Vec<BalanceOf<T, I>> = routes.iter().map(|_| 0).collect();
+            let mut amounts_out: Vec<BalanceOf<T, I>> = amounts_in.clone();
+            let mut pool_types: Vec<Vec<PoolType>> = routes.iter().map(|_| Vec::new()).collect();
+            let mut allocated: BalanceOf<T, I> = Zero::zero();
+
+            for step in 0..SPLIT_STEPS {
+                let chunk = if step == SPLIT_STEPS - 1 {
+                    amount - allocated
+                } else {
+                    amount / SPLIT_STEPS
+                };
+                if chunk.is_zero() {
+                    continue;
+                }
+
+                let mut best: Option<(usize, BalanceOf<T, I>, BalanceOf<T, I>, Vec<PoolType>)> =
+                    None;
+                for (i, route) in routes.iter().enumerate() {
+                    if let Ok((amounts, types)) =
+                        Self::get_amounts_out(amounts_in[i] + chunk, route)
+                    {
+                        let amount_out = amounts[amounts.len() - 1];
+                        let gain = amount_out.saturating_sub(amounts_out[i]);
+                        if best
+                            .as_ref()
+                            .map_or(true, |(_, best_gain, _, _)| gain > *best_gain)
+                        {
+                            best = Some((i, gain, amount_out, types));
+                        }
+                    }
+                }
+
+                let (i, _, amount_out, types) = best.ok_or(Error::<T, I>::NoPossibleRoute)?;
+                amounts_in[i] += chunk;
+                amounts_out[i] = amount_out;
+                pool_types[i] = types;
+                allocated += chunk;
+            }
+
+            let split_routes = routes
+                .into_iter()
+                .zip(amounts_in)
+                .zip(amounts_out)
+                .zip(pool_types)
+                .filter(|(((_, amount_in), _), _)| !amount_in.is_zero())
+                .map(|(((route, amount_in), amount_out), pool_types)| {
+                    (route, amount_in, amount_out, pool_types)
+                })
+                .collect();
+
+            log::trace!(
+                target: "router::get_best_split_route",
+                "amount: {:?}, token_in: {:?}, token_out: {:?}, split_routes: {:?}",
+                amount,
+                token_in,
+                token_out,
+                split_routes
+            );
+
+            Ok(split_routes)
+        }
+
+        // adds an edge between two assets to the cached graph, the edge is left
+        // out if either asset is already paired with `MaxPoolDegree` assets
+        pub(crate) fn add_pair((a, b): (AssetIdOf<T, I>, AssetIdOf<T, I>)) {
+            let mut adjacents_a = Adjacency::<T, I>::get(a);
+            let mut adjacents_b = Adjacency::<T, I>::get(b);
+            if adjacents_a.contains(&b) && adjacents_b.contains(&a) {
+                return;
+            }
+
+            let indexed = (adjacents_a.contains(&b) || adjacents_a.try_push(b).is_ok())
+                && (adjacents_b.contains(&a) || adjacents_b.try_push(a).is_ok());
+            if !indexed {
+                log::warn!(
+                    target: "router::add_pair",
+                    "pair not indexed, max pool degree reached: {:?}",
+                    (a, b)
+                );
+                return;
+            }
+
+            Adjacency::<T, I>::insert(a, adjacents_a);
+            Adjacency::<T, I>::insert(b, adjacents_b);
+        }
+
+        // removes the edge between two assets from the cached graph, an asset
+        // left without any adjacent is removed from the graph
+        pub(crate) fn remove_pair((a, b): (AssetIdOf<T, I>, AssetIdOf<T, I>)) {
+            for (asset, adjacent) in [(a, b), (b, a)] {
+                Adjacency::<T, I>::mutate_exists(asset, |adjacents| {
+                    if let Some(list) = adjacents {
+                        list.retain(|x| *x != adjacent);
+                        if list.is_empty() {
+                            *adjacents = None;
+                        }
+                    }
+                });
+            }
+        }
+
+        // returns the largest amount out of a single hop across all pool types
+        fn get_hop_amount_out(
+            amount_in: BalanceOf<T, I>,
+            (asset_in, asset_out): (AssetIdOf<T, I>, AssetIdOf<T, I>),
+        ) -> Result<(BalanceOf<T, I>, PoolType), DispatchError> {
+            let path = Vec::from([asset_in, asset_out]);
+            let amm = T::AMM::get_amounts_out(amount_in, path.clone())
+                .map(|amounts| (amounts[1], PoolType::AMM));
+            let stable_swap = T::StableSwap::get_amounts_out(amount_in, path)
+                .map(|amounts| (amounts[1], PoolType::StableSwap));
+
+            match (amm, stable_swap) {
+                (Ok(amm), Ok(stable_swap)) if stable_swap.0 > amm.0 => Ok(stable_swap),
+                (Ok(amm), _) => Ok(amm),
+                (Err(_), Ok(stable_swap)) => Ok(stable_swap),
+                (Err(err), Err(_)) => Err(err),
+            }
+        }
+
+        // returns the smallest amount in of a single hop across all pool types
+        fn get_hop_amount_in(
+            amount_out: BalanceOf<T, I>,
+            (asset_in, asset_out): (AssetIdOf<T, I>, AssetIdOf<T, I>),
+        ) -> Result<(BalanceOf<T, I>, PoolType), DispatchError> {
+            let path = Vec::from([asset_in, asset_out]);
+            let amm = T::AMM::get_amounts_in(amount_out, path.clone())
+                .map(|amounts| (amounts[0], PoolType::AMM));
+            let stable_swap = T::StableSwap::get_amounts_in(amount_out, path)
+                .map(|amounts| (amounts[0], PoolType::StableSwap));
+
+            match (amm, stable_swap) {
+                (Ok(amm), Ok(stable_swap)) if stable_swap.0 < amm.0 => Ok(stable_swap),
+                (Ok(amm), _) => Ok(amm),
+                (Err(_), Ok(stable_swap)) => Ok(stable_swap),
+                (Err(err), Err(_)) => Err(err),
+            }
+        }
+
+        // executes the trades of a route through the pool type of each hop
+        #[require_transactional]
+        pub(crate) fn do_swap(
+            trader: &AccountIdOf<T>,
+            route: &[AssetIdOf<T, I>],
+            amounts: &[BalanceOf<T, I>],
+            pool_types: &[PoolType],
+        ) -> DispatchResult {
+            for (i, pool_type) in pool_types.iter().enumerate() {
+                let pair = (route[i], route[i + 1]);
+                match pool_type {
+                    PoolType::AMM => T::AMM::swap(trader, pair, amounts[i])?,
+                    PoolType::StableSwap => T::StableSwap::swap(trader, pair, amounts[i])?,
+                }
+            }
+
+            Ok(())
+        }
     }
 
     #[pallet::call]
@@ -324,7 +662,7 @@ pub mod pallet {
                 Error::<T, I>::InsufficientBalance
             );
 
-            let amounts = T::AMM::get_amounts_out(amount_in, route.clone())?;
+            let (amounts, pool_types) = Self::get_amounts_out(amount_in, &route)?;
 
             // make sure the required amount in does not violate our input
             ensure!(
@@ -332,16 +670,14 @@ pub mod pallet {
                 Error::<T, I>::MinimumAmountOutViolated
             );
 
-            for i in 0..(route.len() - 1) {
-                let next_index = i + 1;
-                T::AMM::swap(&trader, (route[i], route[next_index]), amounts[i])?;
-            }
+            Self::do_swap(&trader, &route, &amounts, &pool_types)?;
 
             Self::deposit_event(Event::Traded(
                 trader,
                 amounts[0],
                 route,
                 amounts[amounts.len() - 1],
+                pool_types,
             ));
 
             Ok(().into())
@@ -374,7 +710,7 @@ pub mod pallet {
             );
 
             // calculate trading amounts
-            let amounts = T::AMM::get_amounts_in(amount_out, route.clone())?;
+            let (amounts, pool_types) = Self::get_amounts_in(amount_out, &route)?;
 
             // we need to check after calc so we know how much is expected to be input
             // Ensure the trader has enough tokens for transaction.
@@ -394,19 +730,138 @@ pub mod pallet {
                 Error::<T, I>::MaximumAmountInViolated
             );
 
-            for i in 0..(route.len() - 1) {
-                let next_index = i + 1;
-                T::AMM::swap(&trader, (route[i], route[next_index]), amounts[i])?;
-            }
+            Self::do_swap(&trader, &route, &amounts, &pool_types)?;
 
             Self::deposit_event(Event::Traded(
                 trader,
                 amounts[0],
                 route,
                 amounts[amounts.len() - 1],
+                pool_types,
             ));
 
             Ok(().into())
         }
+
+        /// Given input amount is fixed, split it across up to `MaxSplitRoutes` routes
+        /// from `token_in` to `token_out` so that the total output is the largest.
+        /// A `Traded` event is emitted for every route used.
+        ///
+        /// - `origin`: the trader.
+        /// - `token_in`: the asset the trader sells
+        /// - `token_out`: the asset the trader buys
+        /// - `amount_in`: the amount of trading assets
+        /// - `min_amount_out`: the minimum total a trader is willing to receive
+        #[pallet::call_index(2)]
+        #[pallet::weight(
+            T::AMMRouterWeightInfo::swap_exact_tokens_for_tokens_split(T::MaxSplitRoutes::get())
+                .saturating_add(T::AMMRouterWeightInfo::find_routes(T::MaxPoolsVisited::get()))
+        )]
+        #[transactional]
+        pub fn swap_exact_tokens_for_tokens_split(
+            origin: OriginFor<T>,
+            token_in: AssetIdOf<T, I>,
+            token_out: AssetIdOf<T, I>,
+            #[pallet::compact] amount_in: BalanceOf<T, I>,
+            #[pallet::compact] min_amount_out: BalanceOf<T, I>,
+        ) -> DispatchResultWithPostInfo {
+            let trader = ensure_signed(origin)?;
+
+            // Ensure balances user input is bigger than zero.
+            ensure!(amount_in > Zero::zero(), Error::<T, I>::ZeroBalance);
+
+            // Ensure the trader has enough tokens for transaction.
+            ensure!(
+                T::Assets::reducible_balance(
+                    token_in,
+                    &trader,
+                    token_in == T::GetNativeCurrencyId::get()
+                ) >= amount_in,
+                Error::<T, I>::InsufficientBalance
+            );
+
+            let split_routes = Self::get_best_split_route(amount_in, token_in, token_out)?;
+
+            // make sure the total amount out does not violate our input
+            let total_amount_out = split_routes.iter().fold(
+                Zero::zero(),
+                |acc: BalanceOf<T, I>, (_, _, amount_out, _)| acc.saturating_add(*amount_out),
+            );
+            ensure!(
+                total_amount_out >= min_amount_out,
+                Error::<T, I>::MinimumAmountOutViolated
+            );
+
+            for (route, route_amount_in, _, _) in split_routes {
+                let (amounts, pool_types) = Self::get_amounts_out(route_amount_in, &route)?;
+
+                Self::do_swap(&trader, &route, &amounts, &pool_types)?;
+
+                Self::deposit_event(Event::Traded(
+                    trader.clone(),
+                    amounts[0],
+                    route,
+                    amounts[amounts.len() - 1],
+                    pool_types,
+                ));
+            }
+
+            Ok(().into())
+        }
+    }
+}
+
+impl<T: Config<I>, I: 'static> OnPoolCreated<AssetIdOf<T, I>> for Pallet<T, I> {
+    fn on_pool_created(pair: (AssetIdOf<T, I>, AssetIdOf<T, I>)) {
+        Self::add_pair(pair);
+    }
+}
+
+impl<T: Config<I>, I: 'static> OnPoolDelisted<AssetIdOf<T, I>> for Pallet<T, I> {
+    fn on_pool_delisted((a, b): (AssetIdOf<T, I>, AssetIdOf<T, I>)) {
+        // the pair can still be traded through a stable swap pool
+        if T::StableSwap::get_reserves(a, b).is_ok() {
+            return;
+        }
+        Self::remove_pair((a, b));
+    }
+}
+
+impl<T: Config<I>, I: 'static> Router<AccountIdOf<T>, AssetIdOf<T, I>, BalanceOf<T, I>>
+    for Pallet<T, I>
+{
+    fn swap_exact_tokens_for_tokens(
+        who: &AccountIdOf<T>,
+        token_in: AssetIdOf<T, I>,
+        token_out: AssetIdOf<T, I>,
+        amount_in: BalanceOf<T, I>,
+        min_amount_out: BalanceOf<T, I>,
+    ) -> Result<BalanceOf<T, I>, DispatchError> {
+        ensure!(!amount_in.is_zero(), Error::<T, I>::ZeroBalance);
+        let (route, amount_out, pool_types) =
+            Self::get_best_route(amount_in, token_in, token_out, false)?;
+        ensure!(
+            amount_out >= min_amount_out,
+            Error::<T, I>::MinimumAmountOutViolated
+        );
+        let (amounts, _) = Self::get_amounts_out(amount_in, &route)?;
+
+        Self::do_swap(who, &route, &amounts, &pool_types)?;
+
+        Self::deposit_event(Event::Traded(
+            who.clone(),
+            amount_in,
+            route,
+            amount_out,
+            pool_types,
+        ));
+
+        Ok(amount_out)
+    }
+
+    fn swap_exact_tokens_for_tokens_weight() -> Weight {
+        T::AMMRouterWeightInfo::swap_exact_tokens_for_tokens().saturating_add(
+            T::AMMRouterWeightInfo::find_routes(T::MaxPoolsVisited::get()),
+        )
     }
 }
@@ -0,0 +1,71 @@
+// Copyright 2021 Parallel Finance Developer.
+// This file is part of Parallel Finance.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+pub mod v1 {
+    use crate::{pallet::StorageVersion, Adjacency, Config, Pallet, Versions};
+    use frame_support::{log, traits::Get, weights::Weight};
+    use pallet_traits::{StableSwap, AMM};
+
+    #[cfg(feature = "try-runtime")]
+    pub fn pre_migrate<T: Config<I>, I: 'static>() -> Result<(), &'static str> {
+        frame_support::ensure!(StorageVersion::<T, I>::get() == Versions::V0, "must be V0");
+        log::info!(
+            "pools to index: {:?}",
+            T::AMM::get_pools()?.len() + T::StableSwap::get_pools()?.len()
+        );
+        Ok(())
+    }
+
+    /// Build the cached graph of pairs from the pools that already exist
+    pub fn migrate<T: Config<I>, I: 'static>() -> Weight {
+        if StorageVersion::<T, I>::get() == Versions::V0 {
+            log::info!(
+                target: "router::migrate",
+                "migrating router to Versions::V1"
+            );
+
+            let mut pools = T::AMM::get_pools().unwrap_or_default();
+            pools.extend(T::StableSwap::get_pools().unwrap_or_default());
+            let count = pools.len() as u64;
+            for pair in pools {
+                Pallet::<T, I>::add_pair(pair);
+            }
+
+            StorageVersion::<T, I>::put(Versions::V1);
+            log::info!(
+                target: "router::migrate",
+                "completed router migration to Versions::V1"
+            );
+
+            T::DbWeight::get().reads_writes(count + 1, count * 2 + 1)
+        } else {
+            T::DbWeight::get().reads(1)
+        }
+    }
+
+    #[cfg(feature = "try-runtime")]
+    pub fn post_migrate<T: Config<I>, I: 'static>() -> Result<(), &'static str> {
+        frame_support::ensure!(StorageVersion::<T, I>::get() == Versions::V1, "must be V1");
+        for (a, b) in T::AMM::get_pools()?
+            .into_iter()
+            .chain(T::StableSwap::get_pools()?)
+        {
+            frame_support::ensure!(
+                Adjacency::<T, I>::get(a).contains(&b) && Adjacency::<T, I>::get(b).contains(&a),
+                "pair must be indexed"
+            );
+        }
+        Ok(())
+    }
+}
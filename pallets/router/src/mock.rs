@@ -131,6 +131,14 @@ parameter_types! {
     // pub const DefaultProtocolFee: Ratio = Ratio::from_rational(5u32, 10000u32);
     pub  DefaultLpFee: Ratio = Ratio::from_rational(25u32, 10000u32);         // 0.3%
     pub const MinimumLiquidity: u128 = 1_000u128;
+    pub const PoolCreationDeposit: Balance = 1_000_000;
+    pub MaxPermissionlessLpFee: Ratio = Ratio::from_percent(1);
+    pub const MinimumPoolSeedLiquidity: u128 = 10_000u128;
+    pub const DepositRefundThreshold: u128 = 1_000_000u128;
+    pub const DepositRefundDelay: BlockNumber = 10;
+    pub const PermissionlessLpTokenIdStart: CurrencyId = 500_000_000;
+    pub const MaxObservations: u32 = 4;
+    pub const ObservationPeriod: BlockNumber = 1;
     pub const LockAccountId: AccountId = ALICE;
 
 }
@@ -150,16 +158,56 @@ impl pallet_amm::Config for Runtime {
     type AMMWeightInfo = ();
     type CreatePoolOrigin = EnsureSignedBy<AliceCreatePoolOrigin, AccountId>;
     type ProtocolFeeUpdateOrigin = EnsureSignedBy<AliceCreatePoolOrigin, AccountId>;
+    type PermissionlessCreatePoolOrigin = EnsureSigned<AccountId>;
+    type Currency = Balances;
+    type LpTokenAssets = Assets;
+    type PermissionlessLpTokenIdStart = PermissionlessLpTokenIdStart;
+    type PoolCreationDeposit = PoolCreationDeposit;
+    type MinimumPoolSeedLiquidity = MinimumPoolSeedLiquidity;
+    type DepositRefundThreshold = DepositRefundThreshold;
+    type DepositRefundDelay = DepositRefundDelay;
     type LpFee = DefaultLpFee;
+    type MaxPermissionlessLpFee = MaxPermissionlessLpFee;
     type MinimumLiquidity = MinimumLiquidity;
     type MaxLengthRoute = MaxLengthRoute;
     type GetNativeCurrencyId = NativeCurrencyId;
+    type MaxObservations = MaxObservations;
+    type ObservationPeriod = ObservationPeriod;
+    type OnPoolCreated = AMMRoute;
+    type OnPoolDelisted = AMMRoute;
 }
 
 parameter_types! {
     pub const NativeCurrencyId: CurrencyId = 0;
 }
 
+// StableSwap initialization
+parameter_types! {
+    pub const StableSwapPalletId: PalletId = PalletId(*b"par/sswp");
+    pub const NumTokens: u32 = 2;
+    pub const Precision: u32 = 100;
+    pub const AmplificationCoefficient: u8 = 85;
+    pub DefaultProtocolFee: Ratio = Ratio::from_rational(5u32, 10000u32);
+    pub const DefaultProtocolFeeReceiver: AccountId = BOB;
+}
+
+impl pallet_stableswap::Config for Runtime {
+    type RuntimeEvent = RuntimeEvent;
+    type Assets = CurrencyAdapter;
+    type WeightInfo = ();
+    type PalletId = StableSwapPalletId;
+    type NumTokens = NumTokens;
+    type Precision = Precision;
+    type AmplificationCoefficient = AmplificationCoefficient;
+    type ProtocolFeeReceiver = DefaultProtocolFeeReceiver;
+    type LpFee = DefaultLpFee;
+    type LockAccountId = LockAccountId;
+    type ProtocolFee = DefaultProtocolFee;
+    type MinimumLiquidity = MinimumLiquidity;
+    type CreatePoolOrigin = EnsureSignedBy<AliceCreatePoolOrigin, AccountId>;
+    type OnPoolCreated = AMMRoute;
+}
+
 impl pallet_currency_adapter::Config for Runtime {
     type Assets = Assets;
     type Balances = Balances;
@@ -168,7 +216,10 @@ impl pallet_currency_adapter::Config for Runtime {
 }
 
 parameter_types! {
-    pub const MaxLengthRoute: u8 = 10;
+    pub static MaxLengthRoute: u8 = 10;
+    pub const MaxSplitRoutes: u32 = 3;
+    pub static MaxPoolsVisited: u32 = 64;
+    pub static MaxPoolDegree: u32 = 16;
     pub const RouterPalletId: PalletId = PalletId(*b"ammroute");
 }
 
@@ -176,8 +227,12 @@ impl Config for Runtime {
     type RuntimeEvent = RuntimeEvent;
     type PalletId = RouterPalletId;
     type AMM = DefaultAMM;
+    type StableSwap = DefaultStableSwap;
     type AMMRouterWeightInfo = ();
     type MaxLengthRoute = MaxLengthRoute;
+    type MaxSplitRoutes = MaxSplitRoutes;
+    type MaxPoolsVisited = MaxPoolsVisited;
+    type MaxPoolDegree = MaxPoolDegree;
     type Assets = CurrencyAdapter;
     type GetNativeCurrencyId = NativeCurrencyId;
 }
@@ -196,8 +251,10 @@ construct_runtime!(
         Balances: pallet_balances::{Pallet, Call, Storage, Event<T>},
         // AMM instances
         DefaultAMM: pallet_amm::{Pallet, Call, Storage, Event<T>},
+        // StableSwap instances
+        DefaultStableSwap: pallet_stableswap::{Pallet, Call, Storage, Event<T>},
         // AMM Route
-        AMMRoute: pallet_route::{Pallet, Call, Event<T>},
+        AMMRoute: pallet_route::{Pallet, Call, Storage, Event<T>},
         CurrencyAdapter: pallet_currency_adapter::{Pallet, Call},
     }
 );
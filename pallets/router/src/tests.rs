@@ -17,6 +17,7 @@
 use super::*;
 use frame_support::{assert_noop, assert_ok};
 use mock::*;
+use primitives::PoolType;
 
 #[test]
 fn too_many_routes_should_not_work() {
@@ -77,7 +78,8 @@ fn swap_exact_tokens_for_tokens_should_work() {
             (DOT, SDOT),
             (100_000_000, 100_000_000),
             DAVE,
-            SAMPLE_LP_TOKEN
+            SAMPLE_LP_TOKEN,
+            DefaultLpFee::get(),
         ));
 
         let route = vec![DOT, SDOT];
@@ -111,7 +113,8 @@ fn swap_tokens_for_exact_tokens_should_work() {
             (DOT, SDOT),
             (100_000_000, 100_000_000),
             DAVE,
-            SAMPLE_LP_TOKEN
+            SAMPLE_LP_TOKEN,
+            DefaultLpFee::get(),
         ));
 
         let route = vec![DOT, SDOT];
@@ -145,7 +148,8 @@ fn pool_as_bridge_swap_tokens_for_exact_tokens_should_work() {
             (USDT, SDOT),
             (40_000_000, 1_000_000),
             DAVE,
-            SAMPLE_LP_TOKEN
+            SAMPLE_LP_TOKEN,
+            DefaultLpFee::get(),
         ));
         // 1 SDOT ~= 40 USDT
 
@@ -155,7 +159,8 @@ fn pool_as_bridge_swap_tokens_for_exact_tokens_should_work() {
             (DOT, SDOT),
             (50_000_000, 50_000_000),
             DAVE,
-            SAMPLE_LP_TOKEN_2
+            SAMPLE_LP_TOKEN_2,
+            DefaultLpFee::get(),
         ));
         // 1 DOT == 1 SDOT
 
@@ -195,7 +200,8 @@ fn swap_exact_tokens_for_tokens_should_not_work_if_amount_less_than_min_amount_o
             (DOT, SDOT),
             (100_000_000, 100_000_000),
             DAVE,
-            SAMPLE_LP_TOKEN
+            SAMPLE_LP_TOKEN,
+            DefaultLpFee::get(),
         ));
 
         // check that pool was funded correctly
@@ -233,7 +239,8 @@ fn swap_tokens_for_exact_tokens_should_not_work_if_amount_more_than_max_amount_i
             (DOT, SDOT),
             (100_000_000, 100_000_000),
             DAVE,
-            SAMPLE_LP_TOKEN
+            SAMPLE_LP_TOKEN,
+            DefaultLpFee::get(),
         ));
 
         // check that pool was funded correctly
@@ -271,7 +278,8 @@ fn trade_should_work_more_than_one_route() {
             (DOT, SDOT),
             (100_000_000, 100_000_000),
             DAVE,
-            SAMPLE_LP_TOKEN
+            SAMPLE_LP_TOKEN,
+            DefaultLpFee::get(),
         ));
 
         // create pool and add liquidity
@@ -280,7 +288,8 @@ fn trade_should_work_more_than_one_route() {
             (SDOT, KSM),
             (100_000_000, 100_000_000),
             DAVE,
-            SAMPLE_LP_TOKEN_2
+            SAMPLE_LP_TOKEN_2,
+            DefaultLpFee::get(),
         ));
 
         // create pool and add liquidity
@@ -289,7 +298,8 @@ fn trade_should_work_more_than_one_route() {
             (USDT, KSM),
             (100_000_000, 100_000_000),
             DAVE,
-            SAMPLE_LP_TOKEN_3
+            SAMPLE_LP_TOKEN_3,
+            DefaultLpFee::get(),
         ));
 
         // CHECK POOLS
@@ -416,7 +426,8 @@ fn get_all_routes_should_work() {
             (DOT, SDOT),
             (100_000_000, 90_000_000),
             DAVE,
-            SAMPLE_LP_TOKEN
+            SAMPLE_LP_TOKEN,
+            DefaultLpFee::get(),
         ));
 
         // create pool and add liquidity
@@ -425,7 +436,8 @@ fn get_all_routes_should_work() {
             (SDOT, KSM),
             (100_000_000, 100_000_000),
             DAVE,
-            SAMPLE_LP_TOKEN_2
+            SAMPLE_LP_TOKEN_2,
+            DefaultLpFee::get(),
         ));
 
         // create pool and add liquidity
@@ -434,7 +446,8 @@ fn get_all_routes_should_work() {
             (DOT, KSM),
             (100_000_000, 70_000_000),
             DAVE,
-            SAMPLE_LP_TOKEN_3
+            SAMPLE_LP_TOKEN_3,
+            DefaultLpFee::get(),
         ));
 
         let routes = AMMRoute::get_all_routes(
@@ -448,7 +461,14 @@ fn get_all_routes_should_work() {
         // Returns descending order `highest` value first.
         assert_eq!(
             routes,
-            vec![(vec![101, 1001, 100], 893), (vec![101, 100], 697)]
+            vec![
+                (
+                    vec![101, 1001, 100],
+                    893,
+                    vec![PoolType::AMM, PoolType::AMM]
+                ),
+                (vec![101, 100], 697, vec![PoolType::AMM])
+            ]
         );
     })
 }
@@ -463,7 +483,8 @@ fn get_best_route_should_work() {
             (DOT, SDOT),
             (100_000_000, 90_000_000),
             DAVE,
-            SAMPLE_LP_TOKEN
+            SAMPLE_LP_TOKEN,
+            DefaultLpFee::get(),
         ));
 
         // create pool and add liquidity
@@ -472,7 +493,8 @@ fn get_best_route_should_work() {
             (SDOT, KSM),
             (100_000_000, 100_000_000),
             DAVE,
-            SAMPLE_LP_TOKEN_2
+            SAMPLE_LP_TOKEN_2,
+            DefaultLpFee::get(),
         ));
 
         // create pool and add liquidity
@@ -481,7 +503,8 @@ fn get_best_route_should_work() {
             (DOT, KSM),
             (100_000_000, 70_000_000),
             DAVE,
-            SAMPLE_LP_TOKEN_3
+            SAMPLE_LP_TOKEN_3,
+            DefaultLpFee::get(),
         ));
 
         let best_route = AMMRoute::get_best_route(
@@ -493,7 +516,14 @@ fn get_best_route_should_work() {
         .unwrap();
 
         // Returns descending order `highest` value first.
-        assert_eq!(best_route, (vec![101, 1001, 100], 893));
+        assert_eq!(
+            best_route,
+            (
+                vec![101, 1001, 100],
+                893,
+                vec![PoolType::AMM, PoolType::AMM]
+            )
+        );
     })
 }
 
@@ -507,7 +537,8 @@ fn get_route_for_tokens_not_in_graph_should_not_work() {
             (DOT, SDOT),
             (100_000_000, 90_000_000),
             DAVE,
-            SAMPLE_LP_TOKEN
+            SAMPLE_LP_TOKEN,
+            DefaultLpFee::get(),
         ));
 
         // create pool and add liquidity
@@ -516,7 +547,8 @@ fn get_route_for_tokens_not_in_graph_should_not_work() {
             (SDOT, KSM),
             (100_000_000, 100_000_000),
             DAVE,
-            SAMPLE_LP_TOKEN_2
+            SAMPLE_LP_TOKEN_2,
+            DefaultLpFee::get(),
         ));
 
         // create pool and add liquidity
@@ -525,7 +557,8 @@ fn get_route_for_tokens_not_in_graph_should_not_work() {
             (DOT, KSM),
             (100_000_000, 70_000_000),
             DAVE,
-            SAMPLE_LP_TOKEN_3
+            SAMPLE_LP_TOKEN_3,
+            DefaultLpFee::get(),
         ));
 
         assert_noop!(
@@ -550,7 +583,8 @@ fn get_route_for_tokens_not_possible_should_not_work() {
             (DOT, SDOT),
             (100_000_000, 90_000_000),
             DAVE,
-            SAMPLE_LP_TOKEN
+            SAMPLE_LP_TOKEN,
+            DefaultLpFee::get(),
         ));
 
         // create pool and add liquidity
@@ -559,7 +593,8 @@ fn get_route_for_tokens_not_possible_should_not_work() {
             (KSM, USDT),
             (100_000_000, 70_000_000),
             DAVE,
-            SAMPLE_LP_TOKEN_3
+            SAMPLE_LP_TOKEN_3,
+            DefaultLpFee::get(),
         ));
 
         assert_noop!(
@@ -582,7 +617,8 @@ fn get_routes_for_non_existing_pair_should_not_work() {
             (DOT, KSM),
             (100_000_000, 70_000_000),
             DAVE,
-            SAMPLE_LP_TOKEN_3
+            SAMPLE_LP_TOKEN_3,
+            DefaultLpFee::get(),
         ));
         assert_noop!(
             AMMRoute::get_best_route(
@@ -606,7 +642,8 @@ fn get_best_route_same_tokens_should_work() {
             (DOT, SDOT),
             (100_000_000, 90_000_000),
             DAVE,
-            SAMPLE_LP_TOKEN
+            SAMPLE_LP_TOKEN,
+            DefaultLpFee::get(),
         ));
 
         // create pool and add liquidity
@@ -615,7 +652,8 @@ fn get_best_route_same_tokens_should_work() {
             (SDOT, KSM),
             (100_000_000, 100_000_000),
             DAVE,
-            SAMPLE_LP_TOKEN_2
+            SAMPLE_LP_TOKEN_2,
+            DefaultLpFee::get(),
         ));
 
         // create pool and add liquidity
@@ -624,7 +662,8 @@ fn get_best_route_same_tokens_should_work() {
             (DOT, KSM),
             (100_000_000, 70_000_000),
             DAVE,
-            SAMPLE_LP_TOKEN_3
+            SAMPLE_LP_TOKEN_3,
+            DefaultLpFee::get(),
         ));
 
         let best_route = AMMRoute::get_best_route(
@@ -635,7 +674,7 @@ fn get_best_route_same_tokens_should_work() {
         )
         .unwrap();
 
-        assert_eq!(best_route, (vec![101], 1000));
+        assert_eq!(best_route, (vec![101], 1000, vec![]));
     })
 }
 
@@ -649,7 +688,8 @@ fn get_best_route_same_tokens_reversed_should_work() {
             (DOT, SDOT),
             (100_000_000, 90_000_000),
             DAVE,
-            SAMPLE_LP_TOKEN
+            SAMPLE_LP_TOKEN,
+            DefaultLpFee::get(),
         ));
 
         // create pool and add liquidity
@@ -658,7 +698,8 @@ fn get_best_route_same_tokens_reversed_should_work() {
             (SDOT, KSM),
             (100_000_000, 100_000_000),
             DAVE,
-            SAMPLE_LP_TOKEN_2
+            SAMPLE_LP_TOKEN_2,
+            DefaultLpFee::get(),
         ));
 
         // create pool and add liquidity
@@ -667,7 +708,8 @@ fn get_best_route_same_tokens_reversed_should_work() {
             (DOT, KSM),
             (100_000_000, 70_000_000),
             DAVE,
-            SAMPLE_LP_TOKEN_3
+            SAMPLE_LP_TOKEN_3,
+            DefaultLpFee::get(),
         ));
 
         let best_route = AMMRoute::get_best_route(
@@ -678,7 +720,7 @@ fn get_best_route_same_tokens_reversed_should_work() {
         )
         .unwrap();
 
-        assert_eq!(best_route, (vec![101], 1000));
+        assert_eq!(best_route, (vec![101], 1000, vec![]));
     })
 }
 
@@ -692,7 +734,8 @@ fn get_all_routes_reversed_should_work() {
             (DOT, SDOT),
             (100_000_000, 90_000_000),
             DAVE,
-            SAMPLE_LP_TOKEN
+            SAMPLE_LP_TOKEN,
+            DefaultLpFee::get(),
         ));
 
         // create pool and add liquidity
@@ -701,7 +744,8 @@ fn get_all_routes_reversed_should_work() {
             (SDOT, KSM),
             (100_000_000, 100_000_000),
             DAVE,
-            SAMPLE_LP_TOKEN_2
+            SAMPLE_LP_TOKEN_2,
+            DefaultLpFee::get(),
         ));
 
         // create pool and add liquidity
@@ -710,7 +754,8 @@ fn get_all_routes_reversed_should_work() {
             (DOT, KSM),
             (100_000_000, 70_000_000),
             DAVE,
-            SAMPLE_LP_TOKEN_3
+            SAMPLE_LP_TOKEN_3,
+            DefaultLpFee::get(),
         ));
 
         let routes = AMMRoute::get_all_routes(
@@ -724,7 +769,14 @@ fn get_all_routes_reversed_should_work() {
         // Returns descending order `highest` value first.
         assert_eq!(
             routes,
-            vec![(vec![101, 100], 1433), (vec![101, 1001, 100], 1119)]
+            vec![
+                (vec![101, 100], 1433, vec![PoolType::AMM]),
+                (
+                    vec![101, 1001, 100],
+                    1119,
+                    vec![PoolType::AMM, PoolType::AMM]
+                )
+            ]
         );
     })
 }
@@ -739,7 +791,8 @@ fn get_best_route_reversed_should_work() {
             (DOT, SDOT),
             (100_000_000, 90_000_000),
             DAVE,
-            SAMPLE_LP_TOKEN
+            SAMPLE_LP_TOKEN,
+            DefaultLpFee::get(),
         ));
 
         // create pool and add liquidity
@@ -748,7 +801,8 @@ fn get_best_route_reversed_should_work() {
             (SDOT, KSM),
             (100_000_000, 100_000_000),
             DAVE,
-            SAMPLE_LP_TOKEN_2
+            SAMPLE_LP_TOKEN_2,
+            DefaultLpFee::get(),
         ));
 
         // create pool and add liquidity
@@ -757,7 +811,8 @@ fn get_best_route_reversed_should_work() {
             (DOT, KSM),
             (100_000_000, 70_000_000),
             DAVE,
-            SAMPLE_LP_TOKEN_3
+            SAMPLE_LP_TOKEN_3,
+            DefaultLpFee::get(),
         ));
 
         let best_route = AMMRoute::get_best_route(
@@ -769,6 +824,399 @@ fn get_best_route_reversed_should_work() {
         .unwrap();
 
         // Returns descending order `highest` value first.
-        assert_eq!(best_route, (vec![101, 1001, 100], 1119));
+        assert_eq!(
+            best_route,
+            (
+                vec![101, 1001, 100],
+                1119,
+                vec![PoolType::AMM, PoolType::AMM]
+            )
+        );
+    })
+}
+
+#[test]
+fn route_across_amm_and_stable_swap_should_work() {
+    new_test_ext().execute_with(|| {
+        System::set_block_number(1);
+        let trader = DAVE;
+        let input_amount = 1_000;
+
+        // create pool and add liquidity
+        assert_ok!(DefaultAMM::create_pool(
+            RuntimeOrigin::signed(ALICE),
+            (DOT, SDOT),
+            (100_000_000, 100_000_000),
+            DAVE,
+            SAMPLE_LP_TOKEN,
+            DefaultLpFee::get(),
+        ));
+
+        // imbalanced amm pool, the stable swap pool of the same pair is cheaper
+        assert_ok!(DefaultAMM::create_pool(
+            RuntimeOrigin::signed(ALICE),
+            (SDOT, KSM),
+            (100_000_000, 50_000_000),
+            DAVE,
+            SAMPLE_LP_TOKEN_2,
+            DefaultLpFee::get(),
+        ));
+
+        assert_ok!(DefaultStableSwap::create_pool(
+            RuntimeOrigin::signed(ALICE),
+            vec![(SDOT, 100_000_000), (KSM, 100_000_000)],
+            DAVE,
+            SAMPLE_LP_TOKEN_3
+        ));
+
+        let (route, amount_out, pool_types) = AMMRoute::get_best_route(
+            input_amount, // input amount
+            DOT,          // input token
+            KSM,          // output token
+            false,
+        )
+        .unwrap();
+
+        assert_eq!(route, vec![DOT, SDOT, KSM]);
+        assert_eq!(pool_types, vec![PoolType::AMM, PoolType::StableSwap]);
+
+        let ksm_before = Assets::balance(KSM, trader);
+        assert_ok!(AMMRoute::swap_exact_tokens_for_tokens(
+            RuntimeOrigin::signed(trader),
+            route.clone(),
+            input_amount,
+            amount_out
+        ));
+        assert_eq!(Assets::balance(KSM, trader), ksm_before + amount_out);
+
+        System::assert_last_event(RuntimeEvent::AMMRoute(Event::Traded(
+            trader,
+            input_amount,
+            route,
+            amount_out,
+            pool_types,
+        )));
+    })
+}
+
+#[test]
+fn get_best_split_route_should_work() {
+    new_test_ext().execute_with(|| {
+        let input_amount = 10_000_000;
+        // create pool and add liquidity
+        assert_ok!(DefaultAMM::create_pool(
+            RuntimeOrigin::signed(ALICE),
+            (DOT, SDOT),
+            (100_000_000, 100_000_000),
+            DAVE,
+            SAMPLE_LP_TOKEN,
+            DefaultLpFee::get(),
+        ));
+
+        // create pool and add liquidity
+        assert_ok!(DefaultAMM::create_pool(
+            RuntimeOrigin::signed(ALICE),
+            (DOT, KSM),
+            (100_000_000, 100_000_000),
+            DAVE,
+            SAMPLE_LP_TOKEN_2,
+            DefaultLpFee::get(),
+        ));
+
+        // create pool and add liquidity
+        assert_ok!(DefaultAMM::create_pool(
+            RuntimeOrigin::signed(ALICE),
+            (KSM, SDOT),
+            (100_000_000, 100_000_000),
+            DAVE,
+            SAMPLE_LP_TOKEN_3,
+            DefaultLpFee::get(),
+        ));
+
+        let (_, best_amount_out, _) =
+            AMMRoute::get_best_route(input_amount, DOT, SDOT, false).unwrap();
+
+        let split_routes = AMMRoute::get_best_split_route(input_amount, DOT, SDOT).unwrap();
+
+        assert_eq!(split_routes.len(), 2);
+        assert_eq!(split_routes[0].0, vec![DOT, SDOT]);
+        assert_eq!(split_routes[1].0, vec![DOT, KSM, SDOT]);
+        assert_eq!(
+            split_routes.iter().map(|r| r.1).sum::<Balance>(),
+            input_amount
+        );
+        assert!(split_routes.iter().map(|r| r.2).sum::<Balance>() > best_amount_out);
+    })
+}
+
+#[test]
+fn swap_exact_tokens_for_tokens_split_should_work() {
+    new_test_ext().execute_with(|| {
+        System::set_block_number(1);
+        let trader = DAVE;
+        let input_amount = 10_000_000;
+        // create pool and add liquidity
+        assert_ok!(DefaultAMM::create_pool(
+            RuntimeOrigin::signed(ALICE),
+            (DOT, SDOT),
+            (100_000_000, 100_000_000),
+            DAVE,
+            SAMPLE_LP_TOKEN,
+            DefaultLpFee::get(),
+        ));
+
+        // create pool and add liquidity
+        assert_ok!(DefaultAMM::create_pool(
+            RuntimeOrigin::signed(ALICE),
+            (DOT, KSM),
+            (100_000_000, 100_000_000),
+            DAVE,
+            SAMPLE_LP_TOKEN_2,
+            DefaultLpFee::get(),
+        ));
+
+        // create pool and add liquidity
+        assert_ok!(DefaultAMM::create_pool(
+            RuntimeOrigin::signed(ALICE),
+            (KSM, SDOT),
+            (100_000_000, 100_000_000),
+            DAVE,
+            SAMPLE_LP_TOKEN_3,
+            DefaultLpFee::get(),
+        ));
+
+        let split_routes = AMMRoute::get_best_split_route(input_amount, DOT, SDOT).unwrap();
+        let total_amount_out = split_routes.iter().map(|r| r.2).sum::<Balance>();
+
+        assert_noop!(
+            AMMRoute::swap_exact_tokens_for_tokens_split(
+                RuntimeOrigin::signed(trader),
+                DOT,
+                SDOT,
+                input_amount,
+                total_amount_out + 1
+            ),
+            Error::<Runtime>::MinimumAmountOutViolated
+        );
+
+        let dot_before = Assets::balance(DOT, trader);
+        let sdot_before = Assets::balance(SDOT, trader);
+        assert_ok!(AMMRoute::swap_exact_tokens_for_tokens_split(
+            RuntimeOrigin::signed(trader),
+            DOT,
+            SDOT,
+            input_amount,
+            total_amount_out
+        ));
+        assert_eq!(Assets::balance(DOT, trader), dot_before - input_amount);
+        assert_eq!(
+            Assets::balance(SDOT, trader),
+            sdot_before + total_amount_out
+        );
+
+        // every route used emits its own trade
+        for (route, amount_in, amount_out, pool_types) in split_routes {
+            System::assert_has_event(RuntimeEvent::AMMRoute(Event::Traded(
+                trader, amount_in, route, amount_out, pool_types,
+            )));
+        }
+    })
+}
+
+#[test]
+fn create_pool_should_update_adjacency() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(DefaultAMM::create_pool(
+            RuntimeOrigin::signed(ALICE),
+            (DOT, SDOT),
+            (100_000_000, 100_000_000),
+            DAVE,
+            SAMPLE_LP_TOKEN,
+            DefaultLpFee::get(),
+        ));
+        assert_ok!(DefaultAMM::create_pool(
+            RuntimeOrigin::signed(ALICE),
+            (SDOT, KSM),
+            (100_000_000, 100_000_000),
+            DAVE,
+            SAMPLE_LP_TOKEN_2,
+            DefaultLpFee::get(),
+        ));
+
+        // a pair listed by both pool types is a single edge
+        assert_ok!(DefaultStableSwap::create_pool(
+            RuntimeOrigin::signed(ALICE),
+            vec![(KSM, 100_000_000), (SDOT, 100_000_000)],
+            DAVE,
+            SAMPLE_LP_TOKEN_3
+        ));
+
+        assert_eq!(AMMRoute::adjacency(DOT).into_inner(), vec![SDOT]);
+        assert_eq!(AMMRoute::adjacency(SDOT).into_inner(), vec![DOT, KSM]);
+        assert_eq!(AMMRoute::adjacency(KSM).into_inner(), vec![SDOT]);
+        assert!(AMMRoute::adjacency(USDT).is_empty());
+    })
+}
+
+#[test]
+fn delist_pool_should_update_adjacency() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(DefaultAMM::create_pool(
+            RuntimeOrigin::signed(ALICE),
+            (DOT, SDOT),
+            (100_000_000, 100_000_000),
+            DAVE,
+            SAMPLE_LP_TOKEN,
+            DefaultLpFee::get(),
+        ));
+        assert_ok!(DefaultAMM::create_pool(
+            RuntimeOrigin::signed(ALICE),
+            (SDOT, KSM),
+            (100_000_000, 100_000_000),
+            DAVE,
+            SAMPLE_LP_TOKEN_2,
+            DefaultLpFee::get(),
+        ));
+        assert_ok!(DefaultStableSwap::create_pool(
+            RuntimeOrigin::signed(ALICE),
+            vec![(KSM, 100_000_000), (SDOT, 100_000_000)],
+            DAVE,
+            SAMPLE_LP_TOKEN_3
+        ));
+
+        // the pair is dropped from the graph
+        assert_ok!(DefaultAMM::delist_pool(
+            RuntimeOrigin::signed(ALICE),
+            (DOT, SDOT)
+        ));
+        assert!(!Adjacency::<Runtime>::contains_key(DOT));
+        assert_eq!(AMMRoute::adjacency(SDOT).into_inner(), vec![KSM]);
+
+        // the pair is still listed by a stable swap pool
+        assert_ok!(DefaultAMM::delist_pool(
+            RuntimeOrigin::signed(ALICE),
+            (SDOT, KSM)
+        ));
+        assert_eq!(AMMRoute::adjacency(SDOT).into_inner(), vec![KSM]);
+        assert_eq!(AMMRoute::adjacency(KSM).into_inner(), vec![SDOT]);
+    })
+}
+
+#[test]
+fn create_pool_should_not_exceed_max_pool_degree() {
+    new_test_ext().execute_with(|| {
+        MaxPoolDegree::set(1);
+        assert_ok!(DefaultAMM::create_pool(
+            RuntimeOrigin::signed(ALICE),
+            (DOT, SDOT),
+            (100_000_000, 100_000_000),
+            DAVE,
+            SAMPLE_LP_TOKEN,
+            DefaultLpFee::get(),
+        ));
+        assert_ok!(DefaultAMM::create_pool(
+            RuntimeOrigin::signed(ALICE),
+            (SDOT, KSM),
+            (100_000_000, 100_000_000),
+            DAVE,
+            SAMPLE_LP_TOKEN_2,
+            DefaultLpFee::get(),
+        ));
+        MaxPoolDegree::set(16);
+
+        // the pool is created but the pair is left out of the graph
+        assert_eq!(AMMRoute::adjacency(SDOT).into_inner(), vec![DOT]);
+        assert!(AMMRoute::adjacency(KSM).is_empty());
+        assert_eq!(
+            AMMRoute::get_best_route(1_000, DOT, KSM, false),
+            Err(Error::<Runtime>::TokenDoesNotExists.into())
+        );
+    })
+}
+
+#[test]
+fn find_routes_should_be_bounded() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(DefaultAMM::create_pool(
+            RuntimeOrigin::signed(ALICE),
+            (DOT, SDOT),
+            (100_000_000, 100_000_000),
+            DAVE,
+            SAMPLE_LP_TOKEN,
+            DefaultLpFee::get(),
+        ));
+        assert_ok!(DefaultAMM::create_pool(
+            RuntimeOrigin::signed(ALICE),
+            (SDOT, KSM),
+            (100_000_000, 100_000_000),
+            DAVE,
+            SAMPLE_LP_TOKEN_2,
+            DefaultLpFee::get(),
+        ));
+        assert_ok!(DefaultAMM::create_pool(
+            RuntimeOrigin::signed(ALICE),
+            (DOT, KSM),
+            (100_000_000, 100_000_000),
+            DAVE,
+            SAMPLE_LP_TOKEN_3,
+            DefaultLpFee::get(),
+        ));
+
+        assert_eq!(
+            AMMRoute::find_routes(DOT, KSM).unwrap(),
+            vec![vec![DOT, KSM], vec![DOT, SDOT, KSM]]
+        );
+
+        // routes longer than `MaxLengthRoute` are not searched
+        MaxLengthRoute::set(2);
+        assert_eq!(
+            AMMRoute::find_routes(DOT, KSM).unwrap(),
+            vec![vec![DOT, KSM]]
+        );
+        MaxLengthRoute::set(10);
+
+        // the search stops once `MaxPoolsVisited` pools have been visited
+        MaxPoolsVisited::set(1);
+        assert!(AMMRoute::find_routes(DOT, KSM).unwrap().is_empty());
+        MaxPoolsVisited::set(64);
+    })
+}
+
+#[test]
+fn migrate_to_v1_should_build_adjacency() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(DefaultAMM::create_pool(
+            RuntimeOrigin::signed(ALICE),
+            (DOT, SDOT),
+            (100_000_000, 100_000_000),
+            DAVE,
+            SAMPLE_LP_TOKEN,
+            DefaultLpFee::get(),
+        ));
+        assert_ok!(DefaultStableSwap::create_pool(
+            RuntimeOrigin::signed(ALICE),
+            vec![(SDOT, 100_000_000), (KSM, 100_000_000)],
+            DAVE,
+            SAMPLE_LP_TOKEN_2
+        ));
+
+        // pools created before the graph was cached
+        let _ = Adjacency::<Runtime>::clear(u32::MAX, None);
+        assert_eq!(
+            AMMRoute::get_best_route(1_000, DOT, KSM, false),
+            Err(Error::<Runtime>::TokenDoesNotExists.into())
+        );
+
+        crate::migrations::v1::migrate::<Runtime, ()>();
+
+        assert_eq!(
+            crate::pallet::StorageVersion::<Runtime>::get(),
+            crate::Versions::V1
+        );
+        assert_eq!(AMMRoute::adjacency(SDOT).into_inner(), vec![DOT, KSM]);
+        assert_eq!(
+            AMMRoute::get_best_route(1_000, DOT, KSM, false).unwrap().0,
+            vec![DOT, SDOT, KSM]
+        );
     })
 }
@@ -47,6 +47,8 @@ use sp_std::marker::PhantomData;
 pub trait WeightInfo {
 	fn swap_exact_tokens_for_tokens() -> Weight;
 	fn swap_tokens_for_exact_tokens() -> Weight;
+	fn swap_exact_tokens_for_tokens_split(n: u32, ) -> Weight;
+	fn find_routes(p: u32, ) -> Weight;
 }
 
 /// Weights for pallet_router using the Substrate node and recommended hardware.
@@ -70,6 +72,29 @@ impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
 			.saturating_add(T::DbWeight::get().reads(8 as u64))
 			.saturating_add(T::DbWeight::get().writes(8 as u64))
 	}
+	// Storage: unknown [0x3a7472616e73616374696f6e5f6c6576656c3a] (r:1 w:1)
+	// Storage: AMM Pools (r:3 w:2)
+	// Storage: Assets Asset (r:3 w:3)
+	// Storage: Assets Account (r:6 w:6)
+	/// The range of component `n` is `[1, 3]`.
+	fn swap_exact_tokens_for_tokens_split(n: u32, ) -> Weight {
+		Weight::from_ref_time(187_914_000 as u64)
+			// Standard Error: 98_000
+			.saturating_add(Weight::from_ref_time(296_480_000 as u64).saturating_mul(n as u64))
+			.saturating_add(T::DbWeight::get().reads(3 as u64))
+			.saturating_add(T::DbWeight::get().reads((8 as u64).saturating_mul(n as u64)))
+			.saturating_add(T::DbWeight::get().writes(1 as u64))
+			.saturating_add(T::DbWeight::get().writes((8 as u64).saturating_mul(n as u64)))
+	}
+	// Storage: AMMRoute Adjacency (r:1 w:0)
+	/// The range of component `p` is `[1, 256]`.
+	fn find_routes(p: u32, ) -> Weight {
+		Weight::from_ref_time(14_285_000 as u64)
+			// Standard Error: 6_000
+			.saturating_add(Weight::from_ref_time(1_577_000 as u64).saturating_mul(p as u64))
+			.saturating_add(T::DbWeight::get().reads(2 as u64))
+			.saturating_add(T::DbWeight::get().reads((1 as u64).saturating_mul(p as u64)))
+	}
 }
 
 // For backwards compatibility and tests
@@ -92,4 +117,27 @@ impl WeightInfo for () {
 			.saturating_add(RocksDbWeight::get().reads(8 as u64))
 			.saturating_add(RocksDbWeight::get().writes(8 as u64))
 	}
+	// Storage: unknown [0x3a7472616e73616374696f6e5f6c6576656c3a] (r:1 w:1)
+	// Storage: AMM Pools (r:3 w:2)
+	// Storage: Assets Asset (r:3 w:3)
+	// Storage: Assets Account (r:6 w:6)
+	/// The range of component `n` is `[1, 3]`.
+	fn swap_exact_tokens_for_tokens_split(n: u32, ) -> Weight {
+		Weight::from_ref_time(187_914_000 as u64)
+			// Standard Error: 98_000
+			.saturating_add(Weight::from_ref_time(296_480_000 as u64).saturating_mul(n as u64))
+			.saturating_add(RocksDbWeight::get().reads(3 as u64))
+			.saturating_add(RocksDbWeight::get().reads((8 as u64).saturating_mul(n as u64)))
+			.saturating_add(RocksDbWeight::get().writes(1 as u64))
+			.saturating_add(RocksDbWeight::get().writes((8 as u64).saturating_mul(n as u64)))
+	}
+	// Storage: AMMRoute Adjacency (r:1 w:0)
+	/// The range of component `p` is `[1, 256]`.
+	fn find_routes(p: u32, ) -> Weight {
+		Weight::from_ref_time(14_285_000 as u64)
+			// Standard Error: 6_000
+			.saturating_add(Weight::from_ref_time(1_577_000 as u64).saturating_mul(p as u64))
+			.saturating_add(RocksDbWeight::get().reads(2 as u64))
+			.saturating_add(RocksDbWeight::get().reads((1 as u64).saturating_mul(p as u64)))
+	}
 }
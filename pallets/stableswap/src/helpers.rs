@@ -1,23 +1,31 @@
-use num_traits::{CheckedDiv, ToPrimitive};
+use num_bigint::BigUint;
+use num_traits::{CheckedDiv, CheckedSub, One, ToPrimitive, Zero};
 use pallet_traits::ConvertToBigUint;
-use sp_runtime::{biguint::BigUint, helpers_128bit::to_big_uint, ArithmeticError, DispatchError};
+use sp_runtime::{ArithmeticError, DispatchError};
 
-fn div(a: &mut BigUint, b: &mut BigUint) -> Result<BigUint, DispatchError> {
-    let _nu = u128::try_from(a.clone()).unwrap_or(0);
-    let _de = u128::try_from(b.clone()).unwrap_or(0);
+// same bound as the reference implementation, should throw error if does not converge
+const MAX_ITERATIONS: u32 = 255;
 
-    if _de == 0u128 {
-        return Ok(BigUint::from(0u128));
-    }
+fn div(a: &BigUint, b: &BigUint) -> Result<BigUint, DispatchError> {
+    a.checked_div(b)
+        .ok_or_else(|| ArithmeticError::DivisionByZero.into())
+}
+
+fn to_u128(a: &BigUint) -> Result<u128, DispatchError> {
+    a.to_u128().ok_or_else(|| ArithmeticError::Overflow.into())
+}
 
-    let x = _nu
-        .get_big_uint()
-        .checked_div(&_de.get_big_uint())
-        .ok_or(ArithmeticError::Underflow)?
-        .to_u128()
-        .unwrap();
+fn converged(a: &BigUint, b: &BigUint) -> bool {
+    if a > b {
+        a - b <= BigUint::one()
+    } else {
+        b - a <= BigUint::one()
+    }
+}
 
-    Ok(BigUint::from(x))
+// A * n^n
+fn compute_ann(amp_coeff: u128, n_coins: usize) -> BigUint {
+    amp_coeff.get_big_uint() * BigUint::from(n_coins).pow(n_coins as u32)
 }
 
 /// ```pseudocode
@@ -29,56 +37,37 @@ fn div(a: &mut BigUint, b: &mut BigUint) -> Result<BigUint, DispatchError> {
 /// ```pseudocode
 /// D[j + 1] = (A * n^n * sum(x_i) - D[j]^(n+1) / (n^n * prod(x_i))) / (A * n^n - 1)
 /// ```
-/// For two assets, n = 2 used while computation
-pub fn compute_d(
-    base_asset_aum: u128,
-    quote_asset_aum: u128,
-    amp_coeff: u128,
-) -> Result<u128, DispatchError> {
-    let base_asset_amount = to_big_uint(base_asset_aum);
-    let quote_asset_amount = to_big_uint(quote_asset_aum);
-    let amplification_coefficient = to_big_uint(amp_coeff);
-    // pool has only 2 assets
-    let n = to_big_uint(2_u128);
-    let zero = to_big_uint(0_u128);
-    let one = to_big_uint(1_u128);
-
-    let sum = base_asset_amount.clone().add(&quote_asset_amount);
-    if sum == zero {
+/// where n is the number of assets of the pool
+pub fn compute_d(balances: &[u128], amp_coeff: u128) -> Result<u128, DispatchError> {
+    let n = BigUint::from(balances.len());
+    let one = BigUint::one();
+
+    let sum = balances
+        .iter()
+        .fold(BigUint::zero(), |acc, x| acc + x.get_big_uint());
+    if sum.is_zero() {
         return Ok(0_u128);
     }
-    let ann = amplification_coefficient.mul(&n).mul(&n);
+    let ann = compute_ann(amp_coeff, balances.len());
     let mut d = sum.clone();
 
-    let mut base_n = base_asset_amount.mul(&n);
-    let mut quote_n = quote_asset_amount.mul(&n);
-    for _ in 0..255 {
+    for _ in 0..MAX_ITERATIONS {
+        // d_p = d^(n+1) / (n^n * prod(x_i))
         let mut d_p = d.clone();
-        let ann_d = ann.clone().mul(&d);
-        // d_p = d_p * d / (x * n)
-
-        let mut d_p_d = d_p.mul(&d);
-        d_p = div(&mut d_p_d, &mut base_n)?;
-        let mut d_p_d = d_p.mul(&d);
-        d_p = div(&mut d_p_d, &mut quote_n)?;
+        for x in balances {
+            d_p = div(&(d_p * &d), &(x.get_big_uint() * &n))?;
+        }
 
         let d_prev = d.clone();
         // d = (ann * sum + d_p * n) * d / (ann * d + (n + 1) * d_p - d)
-        let mut numerator = ann.clone().mul(&sum).add(&d_p.clone().mul(&n)).mul(&d);
-        let mut denominator = ann_d
-            .add(&n.clone().add(&one).mul(&d_p))
-            .sub(&d)
-            .map_err(|_| ArithmeticError::Underflow)?;
-        d = div(&mut numerator, &mut denominator)?;
-
-        if d.clone() > d_prev {
-            if d.clone() - d_prev <= one {
-                d.lstrip();
-                return Ok(d.try_into().map_err(|_| ArithmeticError::Overflow)?);
-            }
-        } else if d_prev - d.clone() <= one {
-            d.lstrip();
-            return Ok(d.try_into().map_err(|_| ArithmeticError::Overflow)?);
+        let numerator = (&ann * &sum + &d_p * &n) * &d;
+        let denominator = (&ann * &d + (&n + &one) * &d_p)
+            .checked_sub(&d)
+            .ok_or(ArithmeticError::Underflow)?;
+        d = div(&numerator, &denominator)?;
+
+        if converged(&d, &d_prev) {
+            return to_u128(&d);
         }
     }
     Err(DispatchError::Other("could not compute d"))
@@ -89,55 +78,53 @@ pub fn compute_d(
 /// Done by solving quadratic equation iteratively.
 ///
 /// ```pseudocode
-/// x_1^2 + x_1 * (sum' - (A * n^n - 1) * D / (A * n^n)) = D^(n+1) / (n^2n * prod' * A)
-/// x_1^2 + b * x_1 = c
+/// x_j^2 + x_j * (sum' - (A * n^n - 1) * D / (A * n^n)) = D^(n+1) / (n^2n * prod' * A)
+/// x_j^2 + b * x_j = c
 ///
-/// x_1 = (x_1^2 + c) / (2 * x_1 + b)
+/// x_j = (x_j^2 + c) / (2 * x_j + b)
 /// ```
-/// For two assets, n = 2 used while computation
-pub fn compute_base(new_quote: u128, amp_coeff: u128, d: u128) -> Result<u128, DispatchError> {
-    let mut n = to_big_uint(2_u128);
-    let two = to_big_uint(2_u128);
-    let one = to_big_uint(1_u128);
-    let mut d = to_big_uint(d);
-    let amplification_coefficient = to_big_uint(amp_coeff);
-    let ann = amplification_coefficient.mul(&n).mul(&n);
-
-    // s and p are same as input base amount as pool supports only 2 assets.
-    let s = to_big_uint(new_quote);
-    let mut p = to_big_uint(new_quote);
-
-    // term1 = d^(n + 1) / n^n * p
-    // term2 = 2*y + s - d
-
-    let d_n = div(&mut d, &mut n)?;
-    let mut c = d_n.clone().mul(&d_n).mul(&d);
-    let term1 = div(&mut c, &mut p)?;
+/// Returns the reserve of the asset `j` which keeps the invariant `d` given
+/// the reserves of the other assets, `balances[j]` itself being ignored.
+pub fn compute_y(
+    balances: &[u128],
+    j: usize,
+    amp_coeff: u128,
+    d: u128,
+) -> Result<u128, DispatchError> {
+    if j >= balances.len() {
+        return Err(DispatchError::Other("asset out of range"));
+    }
+    let n = BigUint::from(balances.len());
+    let two = BigUint::from(2_u8);
+    let d = d.get_big_uint();
+    let ann = compute_ann(amp_coeff, balances.len());
+
+    // c = d^(n+1) / (n^n * prod' * ann)
+    let mut c = d.clone();
+    let mut s = BigUint::zero();
+    for (k, x) in balances.iter().enumerate() {
+        if k == j {
+            continue;
+        }
+        s += x.get_big_uint();
+        c = div(&(c * &d), &(x.get_big_uint() * &n))?;
+    }
+    c = div(&(c * &d), &(&ann * &n))?;
+    let b = s + div(&d, &ann)?;
 
     let mut y = d.clone();
-
-    // y = (y^2 * ann + term1) / (ann * term2 + d)
-    for _ in 0..255 {
+    for _ in 0..MAX_ITERATIONS {
         let y_prev = y.clone();
-        let term2 = two
-            .clone()
-            .mul(&y)
-            .add(&s)
-            .sub(&d)
-            .map_err(|_| ArithmeticError::Underflow)?;
-        let mut numerator = ann.clone().mul(&y).mul(&y).add(&term1);
-        let mut denominator = ann.clone().mul(&term2).add(&d);
-
-        y = div(&mut numerator, &mut denominator)?;
-        if y.clone() > y_prev {
-            if y.clone() - y_prev <= one {
-                y.lstrip();
-                return Ok(y.try_into().map_err(|_| ArithmeticError::Overflow)?);
-            }
-        } else if y_prev - y.clone() <= one {
-            y.lstrip();
-            return Ok(y.try_into().map_err(|_| ArithmeticError::Overflow)?);
+        // y = (y^2 + c) / (2 * y + b - d)
+        let numerator = &y * &y + &c;
+        let denominator = (&two * &y + &b)
+            .checked_sub(&d)
+            .ok_or(ArithmeticError::Underflow)?;
+        y = div(&numerator, &denominator)?;
+
+        if converged(&y, &y_prev) {
+            return to_u128(&y);
         }
     }
-    Err(DispatchError::Other("Error computing d"))
+    Err(DispatchError::Other("could not compute y"))
 }
@@ -15,6 +15,12 @@
 //! # Stable Swap
 //!
 //! Provide low slippage and low fees when trading stablecoins
+//!
+//! A pool holds between two and `NumTokens` assets and is identified by its
+//! liquidity token. Liquidity can be added with any amounts of the assets of
+//! a pool, removed proportionally or as a single asset, and any asset of a
+//! pool can be traded for any other one. Each pair of assets is traded by a
+//! single pool, which is the one used by the router.
 
 #![cfg_attr(not(feature = "std"), no_std)]
 pub use pallet::*;
@@ -22,6 +28,7 @@ use types::Pool;
 extern crate alloc;
 
 mod helpers;
+pub mod migrations;
 #[cfg(test)]
 mod mock;
 #[cfg(test)]
@@ -40,15 +47,15 @@ use frame_support::{
     transactional, Blake2_128Concat, PalletId,
 };
 
-use pallet_traits::ConvertToBigUint;
+use pallet_traits::{ConvertToBigUint, OnPoolCreated};
 use primitives::{Balance, CurrencyId, Ratio};
 use sp_runtime::{
     traits::{AccountIdConversion, CheckedAdd, CheckedSub, One, Saturating, Zero},
     ArithmeticError, DispatchError, FixedPointNumber, FixedU128, SaturatedConversion,
 };
-use sp_std::{cmp::min, ops::Div, result::Result, vec::Vec};
+use sp_std::{ops::Div, result::Result, vec::Vec};
 
-use crate::helpers::{compute_base, compute_d};
+use crate::helpers::{compute_d, compute_y};
 use num_traits::{CheckedDiv, CheckedMul, ToPrimitive};
 
 pub type AccountIdOf<T> = <T as frame_system::Config>::AccountId;
@@ -56,6 +63,15 @@ pub type AssetIdOf<T, I = ()> =
     <<T as Config<I>>::Assets as Inspect<<T as frame_system::Config>::AccountId>>::AssetId;
 pub type BalanceOf<T, I = ()> =
     <<T as Config<I>>::Assets as Inspect<<T as frame_system::Config>::AccountId>>::Balance;
+pub type PoolOf<T, I = ()> =
+    Pool<AssetIdOf<T, I>, BalanceOf<T, I>, <T as frame_system::Config>::BlockNumber>;
+
+/// Utility type for managing upgrades/migrations.
+#[derive(Encode, Decode, Clone, Copy, PartialEq, Eq, RuntimeDebug, TypeInfo)]
+pub enum Versions {
+    V0,
+    V1,
+}
 
 #[frame_support::pallet]
 pub mod pallet {
@@ -97,8 +113,9 @@ pub mod pallet {
         #[pallet::constant]
         type MinimumLiquidity: Get<BalanceOf<Self, I>>;
 
+        /// Maximum number of assets in a pool
         #[pallet::constant]
-        type NumTokens: Get<u8>;
+        type NumTokens: Get<u32>;
 
         /// Precision
         #[pallet::constant]
@@ -110,6 +127,9 @@ pub mod pallet {
 
         /// Specify which origin is allowed to create new pools.
         type CreatePoolOrigin: EnsureOrigin<Self::RuntimeOrigin>;
+
+        /// Notified of every pool created, e.g. by the router to extend its graph
+        type OnPoolCreated: OnPoolCreated<AssetIdOf<Self, I>>;
     }
 
     #[pallet::error]
@@ -130,20 +150,23 @@ pub mod pallet {
         InvalidInvariant,
         /// LP token has already been minted
         LpTokenAlreadyExists,
-        /// Pool does not exist
+        /// Pool already exists, or one of its pairs is traded by another pool
         PoolAlreadyExists,
         /// Identical assets
         IdenticalAssets,
-        /// Not an ideal price ratio
-        NotAnIdealPrice,
+        /// A pool needs at least two and at most `NumTokens` assets
+        InvalidNumberOfAssets,
+        /// Asset is not traded by the pool
+        AssetNotInPool,
+        /// Less liquidity than the minimum would be minted
+        InsufficientMintAmount,
     }
 
     #[pallet::event]
     #[pallet::generate_deposit(pub (crate) fn deposit_event)]
     pub enum Event<T: Config<I>, I: 'static = ()> {
-        /// Delta Calculated
-        DeltaCalculated(AssetIdOf<T, I>, AssetIdOf<T, I>, u128),
-
+        /// Trade using liquidity
+        /// [trader, currency_id_in, currency_id_out, amount_in, amount_out, lp_token_id, new_balance_in, new_balance_out]
         Traded(
             T::AccountId,
             AssetIdOf<T, I>,
@@ -154,216 +177,201 @@ pub mod pallet {
             BalanceOf<T, I>,
             BalanceOf<T, I>,
         ),
-        PoolCreated(
-            T::AccountId,
-            AssetIdOf<T, I>,
-            AssetIdOf<T, I>,
-            AssetIdOf<T, I>,
-        ),
+        /// Create new pool
+        /// [lptoken_receiver, assets, lp_token_id]
+        PoolCreated(T::AccountId, Vec<AssetIdOf<T, I>>, AssetIdOf<T, I>),
         /// Add liquidity into pool
-        /// [sender, base_currency_id, quote_currency_id, base_amount_added, quote_amount_added, lp_token_id, new_base_amount, new_quote_amount]
+        /// [sender, lp_token_id, amounts_added, liquidity, new_balances]
         LiquidityAdded(
             T::AccountId,
             AssetIdOf<T, I>,
-            AssetIdOf<T, I>,
-            BalanceOf<T, I>,
-            BalanceOf<T, I>,
-            AssetIdOf<T, I>,
-            BalanceOf<T, I>,
+            Amounts<T, I>,
             BalanceOf<T, I>,
+            Amounts<T, I>,
         ),
         /// Remove liquidity from pool
-        /// [sender, base_currency_id, quote_currency_id, liquidity, base_amount_removed, quote_amount_removed, lp_token_id, new_base_amount, new_quote_amount]
+        /// [sender, lp_token_id, liquidity, amounts_removed, new_balances]
         LiquidityRemoved(
             T::AccountId,
             AssetIdOf<T, I>,
-            AssetIdOf<T, I>,
-            BalanceOf<T, I>,
-            BalanceOf<T, I>,
-            BalanceOf<T, I>,
-            AssetIdOf<T, I>,
-            BalanceOf<T, I>,
             BalanceOf<T, I>,
+            Amounts<T, I>,
+            Amounts<T, I>,
         ),
     }
 
     #[pallet::pallet]
+    #[pallet::without_storage_info]
     pub struct Pallet<T, I = ()>(_);
 
-    /// A bag of liquidity composed by two different assets
+    /// A bag of liquidity composed by two or more different assets, by its
+    /// liquidity token
     #[pallet::storage]
     #[pallet::getter(fn pools)]
-    pub type Pools<T: Config<I>, I: 'static = ()> = StorageDoubleMap<
+    pub type Pools<T: Config<I>, I: 'static = ()> =
+        StorageMap<_, Blake2_128Concat, AssetIdOf<T, I>, PoolOf<T, I>, OptionQuery>;
+
+    /// Liquidity token of the pool trading a pair of assets, keyed by the
+    /// sorted pair
+    #[pallet::storage]
+    #[pallet::getter(fn pair_pools)]
+    pub type PairPools<T: Config<I>, I: 'static = ()> = StorageDoubleMap<
         _,
         Blake2_128Concat,
         AssetIdOf<T, I>,
         Blake2_128Concat,
         AssetIdOf<T, I>,
-        Pool<AssetIdOf<T, I>, BalanceOf<T, I>, T::BlockNumber>,
+        AssetIdOf<T, I>,
         OptionQuery,
     >;
 
-    // No Extrinsic Calls
+    /// DefaultVersion is using for initialize the StorageVersion
+    #[pallet::type_value]
+    pub(super) fn DefaultVersion<T: Config<I>, I: 'static>() -> Versions {
+        Versions::V0
+    }
+
+    /// Storage version of the pallet.
+    #[pallet::storage]
+    pub(crate) type StorageVersion<T: Config<I>, I: 'static = ()> =
+        StorageValue<_, Versions, ValueQuery, DefaultVersion<T, I>>;
+
     #[pallet::call]
     impl<T: Config<I>, I: 'static> Pallet<T, I> {
-        // Allow users to add liquidity to a given pool
+        /// Allow users to add liquidity to a given pool
+        ///
+        /// Amounts don't need to follow the ratio of the pool, a single asset
+        /// can be deposited too. The share of a deposit which unbalances the
+        /// pool is charged the swap fees.
         ///
-        /// - `pool`: Currency pool, in which liquidity will be added
-        /// - `liquidity_amounts`: Liquidity amounts to be added in pool
-        /// - `minimum_amounts`: specifying its "worst case" ratio when pool already exists
+        /// - `pool_id`: liquidity token of the pool
+        /// - `amounts`: liquidity amounts of the assets to be added in pool
+        /// - `min_mint_amount`: the minimum liquidity the user is willing to receive
         #[pallet::call_index(0)]
         #[pallet::weight(T::WeightInfo::add_liquidity())]
         #[transactional]
         pub fn add_liquidity(
             origin: OriginFor<T>,
-            pair: (AssetIdOf<T, I>, AssetIdOf<T, I>),
-            desired_amounts: (BalanceOf<T, I>, BalanceOf<T, I>),
-            minimum_amounts: (BalanceOf<T, I>, BalanceOf<T, I>),
+            pool_id: AssetIdOf<T, I>,
+            amounts: Vec<(AssetIdOf<T, I>, BalanceOf<T, I>)>,
+            min_mint_amount: BalanceOf<T, I>,
         ) -> DispatchResultWithPostInfo {
             let who = ensure_signed(origin)?;
-            let (is_inverted, base_asset, quote_asset) = Self::sort_assets(pair)?;
 
-            let (base_amount, quote_amount) = if is_inverted {
-                (desired_amounts.1, desired_amounts.0)
-            } else {
-                (desired_amounts.0, desired_amounts.1)
-            };
+            Pools::<T, I>::try_mutate(pool_id, |pool| -> DispatchResultWithPostInfo {
+                let pool = pool.as_mut().ok_or(Error::<T, I>::PoolDoesNotExist)?;
 
-            let (minimum_base_amount, minimum_quote_amount) = if is_inverted {
-                (minimum_amounts.1, minimum_amounts.0)
-            } else {
-                (minimum_amounts.0, minimum_amounts.1)
-            };
-
-            Pools::<T, I>::try_mutate(
-                base_asset,
-                quote_asset,
-                |pool| -> DispatchResultWithPostInfo {
-                    let pool = pool.as_mut().ok_or(Error::<T, I>::PoolDoesNotExist)?;
-
-                    let (ideal_base_amount, ideal_quote_amount) =
-                        Self::get_ideal_amounts(pool, (base_amount, quote_amount))?;
-
-                    ensure!(
-                        ideal_base_amount <= base_amount && ideal_quote_amount <= quote_amount,
-                        Error::<T, I>::InsufficientAmountIn
-                    );
-
-                    ensure!(
-                        ideal_base_amount >= minimum_base_amount
-                            && ideal_quote_amount >= minimum_quote_amount,
-                        Error::<T, I>::NotAnIdealPrice
-                    );
-
-                    Self::do_mint_protocol_fee(pool)?;
-
-                    // Adds liquidity
-                    Self::do_add_liquidity(
-                        &who,
-                        pool,
-                        (ideal_base_amount, ideal_quote_amount),
-                        (base_asset, quote_asset),
-                    )?;
-
-                    log::trace!(
-                        target: "stableswap::add_liquidity",
-                        "who: {:?}, base_asset: {:?}, quote_asset: {:?}, ideal_amounts: {:?},\
-                        desired_amounts: {:?}, minimum_amounts: {:?}",
-                        &who,
-                        &base_asset,
-                        &quote_asset,
-                        &(ideal_base_amount, ideal_quote_amount),
-                        &desired_amounts,
-                        &minimum_amounts
-                    );
-
-                    Self::deposit_event(Event::<T, I>::LiquidityAdded(
-                        who,
-                        base_asset,
-                        quote_asset,
-                        ideal_base_amount,
-                        ideal_quote_amount,
-                        pool.lp_token_id,
-                        pool.base_amount,
-                        pool.quote_amount,
-                    ));
-
-                    Ok(().into())
-                },
-            )
+                let amounts = Self::get_pool_amounts(pool, &amounts)?;
+                ensure!(
+                    amounts.iter().any(|amount| !amount.is_zero()),
+                    Error::<T, I>::InsufficientAmountIn
+                );
+
+                Self::do_mint_protocol_fee(pool)?;
+
+                // Adds liquidity
+                let liquidity = Self::do_add_liquidity(&who, pool, &amounts)?;
+                ensure!(
+                    liquidity >= min_mint_amount,
+                    Error::<T, I>::InsufficientMintAmount
+                );
+
+                log::trace!(
+                    target: "stableswap::add_liquidity",
+                    "who: {:?}, pool_id: {:?}, amounts: {:?}, liquidity: {:?}, min_mint_amount: {:?}",
+                    &who,
+                    &pool_id,
+                    &amounts,
+                    &liquidity,
+                    &min_mint_amount
+                );
+
+                Self::deposit_event(Event::<T, I>::LiquidityAdded(
+                    who,
+                    pool_id,
+                    amounts,
+                    liquidity,
+                    pool.balances.clone(),
+                ));
+
+                Ok(().into())
+            })
         }
-        /// Allow users to remove liquidity from a given pool
+
+        /// Allow users to remove liquidity from a given pool, in proportion
+        /// of the reserves of the pool
         ///
-        /// - `pair`: Currency pool, in which liquidity will be removed
+        /// - `pool_id`: liquidity token of the pool
         /// - `liquidity`: liquidity to be removed from user's liquidity
         #[pallet::call_index(1)]
         #[pallet::weight(T::WeightInfo::remove_liquidity())]
         #[transactional]
         pub fn remove_liquidity(
             origin: OriginFor<T>,
-            pair: (AssetIdOf<T, I>, AssetIdOf<T, I>),
+            pool_id: AssetIdOf<T, I>,
             #[pallet::compact] liquidity: BalanceOf<T, I>,
         ) -> DispatchResult {
             let who = ensure_signed(origin)?;
 
-            let (_, base_asset, quote_asset) = Self::sort_assets(pair)?;
-
-            Pools::<T, I>::try_mutate(base_asset, quote_asset, |pool| -> DispatchResult {
+            Pools::<T, I>::try_mutate(pool_id, |pool| -> DispatchResult {
                 let pool = pool.as_mut().ok_or(Error::<T, I>::PoolDoesNotExist)?;
 
                 Self::do_mint_protocol_fee(pool)?;
 
-                let (base_amount_removed, quote_amount_removed) =
-                    Self::do_remove_liquidity(&who, pool, liquidity, (base_asset, quote_asset))?;
+                let amounts_removed = Self::do_remove_liquidity(&who, pool, liquidity)?;
 
                 log::trace!(
                     target: "stableswap::remove_liquidity",
-                    "who: {:?}, base_asset: {:?}, quote_asset: {:?}, liquidity: {:?}",
+                    "who: {:?}, pool_id: {:?}, liquidity: {:?}, amounts_removed: {:?}",
                     &who,
-                    &base_asset,
-                    &quote_asset,
-                    &liquidity
+                    &pool_id,
+                    &liquidity,
+                    &amounts_removed
                 );
 
                 Self::deposit_event(Event::<T, I>::LiquidityRemoved(
                     who,
-                    base_asset,
-                    quote_asset,
+                    pool_id,
                     liquidity,
-                    base_amount_removed,
-                    quote_amount_removed,
-                    pool.lp_token_id,
-                    pool.base_amount,
-                    pool.quote_amount,
+                    amounts_removed,
+                    pool.balances.clone(),
                 ));
 
                 Ok(())
             })
         }
 
+        /// Create a pool of two or more assets
+        ///
+        /// - `liquidity_amounts`: assets of the pool and initial liquidity amounts
+        /// - `lptoken_receiver`: account funding the pool and receiving the liquidity
+        /// - `lp_token_id`: liquidity pool share representative token, which also
+        ///   identifies the pool
         #[pallet::call_index(2)]
         #[pallet::weight(T::WeightInfo::create_pool())]
         #[transactional]
         pub fn create_pool(
             origin: OriginFor<T>,
-            pair: (AssetIdOf<T, I>, AssetIdOf<T, I>),
-            liquidity_amounts: (BalanceOf<T, I>, BalanceOf<T, I>),
+            liquidity_amounts: Vec<(AssetIdOf<T, I>, BalanceOf<T, I>)>,
             lptoken_receiver: T::AccountId,
             lp_token_id: AssetIdOf<T, I>,
         ) -> DispatchResultWithPostInfo {
             T::CreatePoolOrigin::ensure_origin(origin)?;
 
-            let (is_inverted, base_asset, quote_asset) = Self::sort_assets(pair)?;
+            let (assets, amounts) = Self::sort_pool_assets(liquidity_amounts)?;
+            let pairs = Self::get_pairs(&assets);
             ensure!(
-                !Pools::<T, I>::contains_key(base_asset, quote_asset),
+                !Pools::<T, I>::contains_key(lp_token_id)
+                    && pairs
+                        .iter()
+                        .all(|(base, quote)| !PairPools::<T, I>::contains_key(base, quote)),
                 Error::<T, I>::PoolAlreadyExists
             );
-
-            let (base_amount, quote_amount) = if is_inverted {
-                (liquidity_amounts.1, liquidity_amounts.0)
-            } else {
-                (liquidity_amounts.0, liquidity_amounts.1)
-            };
+            // the invariant can only be computed if every reserve is funded
+            ensure!(
+                amounts.iter().all(|amount| !amount.is_zero()),
+                Error::<T, I>::InsufficientAmountIn
+            );
 
             // check that this is a new asset to avoid using an asset that
             // already has tokens minted
@@ -372,49 +380,122 @@ pub mod pallet {
                 Error::<T, I>::LpTokenAlreadyExists
             );
 
-            let mut pool = Pool::new(lp_token_id);
+            let mut pool = Pool::new(assets.clone(), lp_token_id);
 
             Self::deposit_event(Event::<T, I>::PoolCreated(
                 lptoken_receiver.clone(),
-                base_asset,
-                quote_asset,
+                assets,
                 lp_token_id,
             ));
 
-            Self::do_add_liquidity(
-                &lptoken_receiver,
-                &mut pool,
-                (base_amount, quote_amount),
-                (base_asset, quote_asset),
-            )?;
+            let liquidity = Self::do_add_liquidity(&lptoken_receiver, &mut pool, &amounts)?;
 
-            Pools::<T, I>::insert(base_asset, quote_asset, pool);
+            let balances = pool.balances.clone();
+            Pools::<T, I>::insert(lp_token_id, pool);
+            for (base_asset, quote_asset) in pairs {
+                PairPools::<T, I>::insert(base_asset, quote_asset, lp_token_id);
+                T::OnPoolCreated::on_pool_created((base_asset, quote_asset));
+            }
 
             log::trace!(
                 target: "stableswap::create_pool",
-                "lptoken_receiver: {:?}, base_asset: {:?}, quote_asset: {:?}, base_amount: {:?}, quote_amount: {:?},\
-                 liquidity_amounts: {:?}",
+                "lptoken_receiver: {:?}, lp_token_id: {:?}, amounts: {:?}, liquidity: {:?}",
                 &lptoken_receiver,
-                &base_asset,
-                &quote_asset,
-                &base_amount,
-                &quote_amount,
-                &liquidity_amounts
+                &lp_token_id,
+                &amounts,
+                &liquidity
             );
 
             Self::deposit_event(Event::<T, I>::LiquidityAdded(
                 lptoken_receiver,
-                base_asset,
-                quote_asset,
-                base_amount,
-                quote_amount,
-                pool.lp_token_id,
-                pool.base_amount,
-                pool.quote_amount,
+                lp_token_id,
+                amounts,
+                liquidity,
+                balances,
             ));
 
             Ok(().into())
         }
+
+        /// Allow users to remove liquidity from a given pool as a single asset
+        ///
+        /// - `pool_id`: liquidity token of the pool
+        /// - `liquidity`: liquidity to be removed from user's liquidity
+        /// - `asset_id`: asset to be received
+        /// - `min_amount`: the minimum amount of asset the user is willing to receive
+        #[pallet::call_index(3)]
+        #[pallet::weight(T::WeightInfo::remove_liquidity_one_asset())]
+        #[transactional]
+        pub fn remove_liquidity_one_asset(
+            origin: OriginFor<T>,
+            pool_id: AssetIdOf<T, I>,
+            #[pallet::compact] liquidity: BalanceOf<T, I>,
+            asset_id: AssetIdOf<T, I>,
+            #[pallet::compact] min_amount: BalanceOf<T, I>,
+        ) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+
+            Pools::<T, I>::try_mutate(pool_id, |pool| -> DispatchResult {
+                let pool = pool.as_mut().ok_or(Error::<T, I>::PoolDoesNotExist)?;
+                let index = pool
+                    .index_of(&asset_id)
+                    .ok_or(Error::<T, I>::AssetNotInPool)?;
+
+                Self::do_mint_protocol_fee(pool)?;
+
+                let amount = Self::do_remove_liquidity_one_asset(&who, pool, liquidity, index)?;
+                ensure!(amount >= min_amount, Error::<T, I>::InsufficientAmountOut);
+
+                log::trace!(
+                    target: "stableswap::remove_liquidity_one_asset",
+                    "who: {:?}, pool_id: {:?}, liquidity: {:?}, asset_id: {:?}, amount: {:?}",
+                    &who,
+                    &pool_id,
+                    &liquidity,
+                    &asset_id,
+                    &amount
+                );
+
+                let mut amounts_removed: Amounts<T, I> = Vec::new();
+                amounts_removed.resize(pool.assets.len(), 0u128);
+                amounts_removed[index] = amount;
+
+                Self::deposit_event(Event::<T, I>::LiquidityRemoved(
+                    who,
+                    pool_id,
+                    liquidity,
+                    amounts_removed,
+                    pool.balances.clone(),
+                ));
+
+                Ok(())
+            })
+        }
+
+        /// Trade any asset of a pool for any other asset of the same pool
+        ///
+        /// - `pair`: assets traded in and out
+        /// - `amount_in`: the amount of asset traded in
+        /// - `min_amount_out`: the minimum a trader is willing to receive
+        #[pallet::call_index(4)]
+        #[pallet::weight(T::WeightInfo::swap())]
+        #[transactional]
+        pub fn swap(
+            origin: OriginFor<T>,
+            pair: (AssetIdOf<T, I>, AssetIdOf<T, I>),
+            #[pallet::compact] amount_in: BalanceOf<T, I>,
+            #[pallet::compact] min_amount_out: BalanceOf<T, I>,
+        ) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+
+            let amount_out = Self::do_swap(&who, pair, amount_in)?;
+            ensure!(
+                amount_out >= min_amount_out,
+                Error::<T, I>::InsufficientAmountOut
+            );
+
+            Ok(())
+        }
     }
 }
 
@@ -431,18 +512,22 @@ impl<T: Config<I>, I: 'static> Pallet<T, I> {
 
         amounts_in[amount_len - 1] = amount_out;
         for i in (1..(path.len())).rev() {
-            let (reserve_in, reserve_out) = Self::get_reserves(path[i - 1], path[i])?;
-            let amount_in = Self::get_amount_in(amounts_in[i], reserve_in, reserve_out)?;
+            let (pool, index_in, index_out) = Self::get_pool_of_pair(path[i - 1], path[i])?;
+            let amount_in =
+                Self::get_amount_in(amounts_in[i], &pool.balances, index_in, index_out)?;
             amounts_in[i - 1] = amount_in;
         }
 
         Ok(amounts_in)
     }
 
+    // given an input amount of an asset and the reserves of a pool, returns the
+    // amount out of another asset of the pool which keeps its invariant
     fn get_amount_out(
         amount_in: BalanceOf<T, I>,
-        pool_base_aum: BalanceOf<T, I>,
-        pool_quote_aum: BalanceOf<T, I>,
+        balances: &[BalanceOf<T, I>],
+        index_in: usize,
+        index_out: usize,
     ) -> Result<BalanceOf<T, I>, DispatchError> {
         let fees = T::LpFee::get()
             .checked_add(&T::ProtocolFee::get())
@@ -453,98 +538,35 @@ impl<T: Config<I>, I: 'static> Pallet<T, I> {
             .checked_sub(fees)
             .ok_or(ArithmeticError::Underflow)?;
 
-        let amp = T::AmplificationCoefficient::get() as u128;
-        // d = 2000000
-        // poolbaseamount = 1000000
-        // amountin = 997
-        // new quote amount = 1000000 + 997
-        let d = Self::delta_util(pool_base_aum, pool_quote_aum)?;
-        let new_quote_amount = pool_quote_aum
-            .checked_add(amount_in)
-            .ok_or(ArithmeticError::Overflow)?;
-
-        let new_base_amount = Self::get_base(new_quote_amount, amp, d)?;
-
-        // pool base amount = 1000000
-        // new base amount =  999003
-
-        // TODO: Have a check here
-        let amount_out = pool_base_aum
-            .checked_sub(new_base_amount)
-            .ok_or(ArithmeticError::Underflow)?;
-
-        Ok(amount_out)
-    }
+        let d = Self::delta_util(balances)?;
 
-    // given an input amount of an asset and pair reserves, returns the maximum output amount of the other asset
-    //
-    // reserveIn * reserveOut = (reserveIn + amountIn) * (reserveOut - amountOut)
-    // reserveIn * reserveOut = reserveIn * reserveOut + amountIn * reserveOut - (reserveIn + amountIn) * amountOut
-    // amountIn * reserveOut = (reserveIn + amountIn) * amountOut
-    //
-    // amountOut = amountIn * reserveOut / (reserveIn + amountIn)
-    // amountOut  = amountOut * (1 - fee_percent)
-    #[allow(dead_code)]
-    fn get_amount_out_1(
-        amount_in: BalanceOf<T, I>,
-        reserve_in: BalanceOf<T, I>,
-        reserve_out: BalanceOf<T, I>,
-    ) -> Result<BalanceOf<T, I>, DispatchError> {
-        let fees = T::LpFee::get()
-            .checked_add(&T::ProtocolFee::get())
-            .map(|r| r.mul_floor(amount_in))
-            .ok_or(ArithmeticError::Overflow)?;
-
-        let amount_in = amount_in
-            .checked_sub(fees)
-            .ok_or(ArithmeticError::Underflow)?;
-        let numerator = amount_in
-            .checked_mul(reserve_out)
-            .ok_or(ArithmeticError::Overflow)?;
-
-        let denominator = reserve_in
+        let mut new_balances = balances.to_vec();
+        new_balances[index_in] = balances[index_in]
             .checked_add(amount_in)
             .ok_or(ArithmeticError::Overflow)?;
 
-        let amount_out = numerator
-            .checked_div(denominator)
+        let new_balance_out = Self::get_y(&new_balances, index_out, d)?;
+
+        let amount_out = balances[index_out]
+            .checked_sub(new_balance_out)
             .ok_or(ArithmeticError::Underflow)?;
 
         log::trace!(
             target: "stableswap::get_amount_out",
-            "amount_in: {:?}, reserve_in: {:?}, reserve_out: {:?}, fees: {:?}, numerator: {:?}, denominator: {:?},\
+            "amount_in: {:?}, balances: {:?}, index_in: {:?}, index_out: {:?}, fees: {:?}, d: {:?},\
              amount_out: {:?}",
             &amount_in,
-            &reserve_in,
-            &reserve_out,
+            &balances,
+            &index_in,
+            &index_out,
             &fees,
-            &numerator,
-            &denominator,
+            &d,
             &amount_out
         );
 
         Ok(amount_out)
     }
 
-    fn quote(
-        base_amount: BalanceOf<T, I>,
-        base_pool: BalanceOf<T, I>,
-        quote_pool: BalanceOf<T, I>,
-    ) -> Result<BalanceOf<T, I>, DispatchError> {
-        log::trace!(
-            target: "stableswap::quote",
-            "base_amount: {:?}, base_pool: {:?}, quote_pool: {:?}",
-            &base_amount,
-            &base_pool,
-            &quote_pool
-        );
-
-        Ok(base_amount
-            .checked_mul(quote_pool)
-            .and_then(|r| r.checked_div(base_pool))
-            .ok_or(ArithmeticError::Underflow)?)
-    }
-
     fn sort_assets(
         (curr_a, curr_b): (AssetIdOf<T, I>, AssetIdOf<T, I>),
     ) -> Result<(bool, AssetIdOf<T, I>, AssetIdOf<T, I>), DispatchError> {
@@ -565,14 +587,133 @@ impl<T: Config<I>, I: 'static> Pallet<T, I> {
         Err(Error::<T, I>::IdenticalAssets.into())
     }
 
-    // Returns liquidity for a given 2 assets
+    // sorts the assets of a new pool from the largest id to the smallest, the
+    // same order as the pairs of `sort_assets`
+    fn sort_pool_assets(
+        mut liquidity_amounts: Vec<(AssetIdOf<T, I>, BalanceOf<T, I>)>,
+    ) -> Result<(Vec<AssetIdOf<T, I>>, Amounts<T, I>), DispatchError> {
+        ensure!(
+            liquidity_amounts.len() >= 2 && liquidity_amounts.len() <= T::NumTokens::get() as usize,
+            Error::<T, I>::InvalidNumberOfAssets
+        );
+
+        liquidity_amounts.sort_by(|(a, _), (b, _)| b.cmp(a));
+        ensure!(
+            liquidity_amounts.windows(2).all(|w| w[0].0 != w[1].0),
+            Error::<T, I>::IdenticalAssets
+        );
+
+        Ok(liquidity_amounts.into_iter().unzip())
+    }
+
+    // every sorted pair of the assets of a pool
+    fn get_pairs(assets: &[AssetIdOf<T, I>]) -> Vec<(AssetIdOf<T, I>, AssetIdOf<T, I>)> {
+        let mut pairs = Vec::new();
+        for (i, base_asset) in assets.iter().enumerate() {
+            for quote_asset in assets.iter().skip(i + 1) {
+                pairs.push((*base_asset, *quote_asset));
+            }
+        }
+        pairs
+    }
+
+    // amounts of each asset of the pool, in the order of the pool
+    fn get_pool_amounts(
+        pool: &PoolOf<T, I>,
+        amounts: &[(AssetIdOf<T, I>, BalanceOf<T, I>)],
+    ) -> Result<Amounts<T, I>, DispatchError> {
+        let mut pool_amounts: Amounts<T, I> = Vec::new();
+        pool_amounts.resize(pool.assets.len(), 0u128);
+        let mut visited = Vec::new();
+        visited.resize(pool.assets.len(), false);
+
+        for (asset_id, amount) in amounts {
+            let index = pool
+                .index_of(asset_id)
+                .ok_or(Error::<T, I>::AssetNotInPool)?;
+            ensure!(!visited[index], Error::<T, I>::IdenticalAssets);
+            visited[index] = true;
+            pool_amounts[index] = *amount;
+        }
+
+        Ok(pool_amounts)
+    }
+
+    // the pool trading a pair and the positions of both assets in the pool
+    fn get_pool_of_pair(
+        asset_in: AssetIdOf<T, I>,
+        asset_out: AssetIdOf<T, I>,
+    ) -> Result<(PoolOf<T, I>, usize, usize), DispatchError> {
+        let pool_id = Self::get_pool_id((asset_in, asset_out))?;
+        let pool = Pools::<T, I>::get(pool_id).ok_or(Error::<T, I>::PoolDoesNotExist)?;
+        let index_in = pool
+            .index_of(&asset_in)
+            .ok_or(Error::<T, I>::AssetNotInPool)?;
+        let index_out = pool
+            .index_of(&asset_out)
+            .ok_or(Error::<T, I>::AssetNotInPool)?;
+
+        Ok((pool, index_in, index_out))
+    }
+
+    fn get_pool_id(
+        pair: (AssetIdOf<T, I>, AssetIdOf<T, I>),
+    ) -> Result<AssetIdOf<T, I>, DispatchError> {
+        let (_, base_asset, quote_asset) = Self::sort_assets(pair)?;
+        PairPools::<T, I>::get(base_asset, quote_asset)
+            .ok_or_else(|| Error::<T, I>::PoolDoesNotExist.into())
+    }
+
+    // a * b / c without intermediate overflow
+    fn mul_div(
+        a: BalanceOf<T, I>,
+        b: BalanceOf<T, I>,
+        c: BalanceOf<T, I>,
+    ) -> Result<BalanceOf<T, I>, DispatchError> {
+        Ok(a.get_big_uint()
+            .checked_mul(&b.get_big_uint())
+            .and_then(|r| r.checked_div(&c.get_big_uint()))
+            .ok_or(Error::<T, I>::ConversionToU128Failed)?
+            .to_u128()
+            .ok_or(ArithmeticError::Overflow)?)
+    }
+
+    // fee charged on the share of a liquidity change which unbalances the
+    // pool, so that it costs as much as a swap
+    fn get_imbalance_fee(num_tokens: usize) -> Result<Ratio, DispatchError> {
+        let fee = T::LpFee::get()
+            .checked_add(&T::ProtocolFee::get())
+            .ok_or(ArithmeticError::Overflow)?;
+        let num_tokens = num_tokens as u32;
+
+        Ok(fee.saturating_mul(Ratio::from_rational(
+            num_tokens,
+            num_tokens.saturating_sub(1).saturating_mul(4),
+        )))
+    }
+
     #[require_transactional]
-    fn do_get_liquidity(
-        total_supply: BalanceOf<T, I>,
-        pool: &mut Pool<AssetIdOf<T, I>, BalanceOf<T, I>, T::BlockNumber>,
-        (ideal_base_amount, ideal_quote_amount): (BalanceOf<T, I>, BalanceOf<T, I>),
+    fn do_add_liquidity(
+        who: &T::AccountId,
+        pool: &mut PoolOf<T, I>,
+        amounts: &[BalanceOf<T, I>],
     ) -> Result<BalanceOf<T, I>, DispatchError> {
-        // Extract to different functionality
+        // Initial invariant
+        let d0 = Self::delta_util(&pool.balances)?;
+
+        let new_balances = pool
+            .balances
+            .iter()
+            .zip(amounts)
+            .map(|(balance, amount)| balance.checked_add(*amount))
+            .collect::<Option<Amounts<T, I>>>()
+            .ok_or(ArithmeticError::Overflow)?;
+
+        let d1 = Self::delta_util(&new_balances)?;
+        ensure!(d1 > d0, Error::<T, I>::InvalidInvariant);
+
+        let total_supply = T::Assets::total_issuance(pool.lp_token_id);
+
         let liquidity = if total_supply.is_zero() {
             T::Assets::mint_into(
                 pool.lp_token_id,
@@ -580,240 +721,206 @@ impl<T: Config<I>, I: 'static> Pallet<T, I> {
                 T::MinimumLiquidity::get(),
             )?;
 
-            ideal_base_amount
-                .get_big_uint()
-                .checked_mul(&ideal_quote_amount.get_big_uint())
-                // loss of precision due to truncated sqrt
-                .map(|r| r.sqrt())
-                .and_then(|r| r.checked_sub(&T::MinimumLiquidity::get().get_big_uint()))
-                .ok_or(Error::<T, I>::ConversionToU128Failed)?
-                .to_u128()
-                .ok_or(ArithmeticError::Underflow)?
+            d1.checked_sub(T::MinimumLiquidity::get())
+                .ok_or(Error::<T, I>::InsufficientLiquidity)?
         } else {
-            min(
-                ideal_base_amount
-                    .get_big_uint()
-                    .checked_mul(&total_supply.get_big_uint())
-                    .and_then(|r| r.checked_div(&pool.base_amount.get_big_uint()))
-                    .ok_or(Error::<T, I>::ConversionToU128Failed)?
-                    .to_u128()
-                    .ok_or(ArithmeticError::Underflow)?,
-                ideal_quote_amount
-                    .get_big_uint()
-                    .checked_mul(&total_supply.get_big_uint())
-                    .and_then(|r| r.checked_div(&pool.quote_amount.get_big_uint()))
-                    .ok_or(Error::<T, I>::ConversionToU128Failed)?
-                    .to_u128()
-                    .ok_or(ArithmeticError::Underflow)?,
-            )
+            // the fees only apply to how far the deposit is from the ratio of the pool
+            let fee = Self::get_imbalance_fee(pool.assets.len())?;
+            let mut fee_balances = new_balances.clone();
+            for (k, balance) in fee_balances.iter_mut().enumerate() {
+                let ideal_balance = Self::mul_div(d1, pool.balances[k], d0)?;
+                let difference = ideal_balance.abs_diff(new_balances[k]);
+                *balance = balance
+                    .checked_sub(fee.mul_floor(difference))
+                    .ok_or(ArithmeticError::Underflow)?;
+            }
+            let d2 = Self::delta_util(&fee_balances)?;
+
+            Self::mul_div(
+                total_supply,
+                d2.checked_sub(d0).ok_or(Error::<T, I>::InvalidInvariant)?,
+                d0,
+            )?
         };
-        Ok(liquidity)
-    }
+        ensure!(!liquidity.is_zero(), Error::<T, I>::InsufficientMintAmount);
 
-    #[require_transactional]
-    fn do_add_liquidity(
-        who: &T::AccountId,
-        pool: &mut Pool<AssetIdOf<T, I>, BalanceOf<T, I>, T::BlockNumber>,
-        (ideal_base_amount, ideal_quote_amount): (BalanceOf<T, I>, BalanceOf<T, I>),
-        (base_asset, quote_asset): (AssetIdOf<T, I>, AssetIdOf<T, I>),
-    ) -> Result<(), DispatchError> {
-        // Initial invariant
-        let mut d0 = 0u128;
-        let mut d1 = 0u128;
-        if Pools::<T, I>::contains_key(base_asset, quote_asset) {
-            // d0 = Self::do_get_delta((base_asset, quote_asset)).unwrap();
-            let (tot_base_amount, tot_quote_amount) =
-                Self::get_reserves(base_asset, quote_asset).unwrap();
-            d0 = Self::delta_util(tot_base_amount, tot_quote_amount).unwrap()
+        // fees are left in the pool
+        pool.balances = new_balances;
+
+        T::Assets::mint_into(pool.lp_token_id, who, liquidity)?;
+
+        for (asset_id, amount) in pool.assets.iter().zip(amounts) {
+            if !amount.is_zero() {
+                T::Assets::transfer(*asset_id, who, &Self::account_id(), *amount, true)?;
+            }
         }
 
-        let total_supply = T::Assets::total_issuance(pool.lp_token_id);
+        if Self::protocol_fee_on() {
+            pool.d_last = d1;
+        }
 
-        // Extract to different functionality
-        let mut liquidity =
-            Self::do_get_liquidity(total_supply, pool, (ideal_base_amount, ideal_quote_amount))
-                .unwrap();
+        log::trace!(
+            target: "stableswap::do_add_liquidity",
+            "who: {:?}, total_supply: {:?}, liquidity: {:?}, lp_token_id: {:?}, amounts: {:?}, d0: {:?}, d1: {:?}",
+            &who,
+            &total_supply,
+            &liquidity,
+            &pool.lp_token_id,
+            &amounts,
+            &d0,
+            &d1
+        );
 
-        // update reserves after liquidity calculation
-        pool.base_amount = pool
-            .base_amount
-            .checked_add(ideal_base_amount)
-            .ok_or(ArithmeticError::Overflow)?;
-        pool.quote_amount = pool
-            .quote_amount
-            .checked_add(ideal_quote_amount)
-            .ok_or(ArithmeticError::Overflow)?;
+        Ok(liquidity)
+    }
 
-        let new_base_amount = pool.base_amount;
-        let new_quote_amount = pool.quote_amount;
+    fn calculate_reserves_to_remove(
+        pool: &PoolOf<T, I>,
+        liquidity: BalanceOf<T, I>,
+    ) -> Result<Amounts<T, I>, DispatchError> {
+        let total_supply = T::Assets::total_issuance(pool.lp_token_id);
+        pool.balances
+            .iter()
+            .map(|balance| Self::mul_div(liquidity, *balance, total_supply))
+            .collect()
+    }
 
-        if Pools::<T, I>::contains_key(base_asset, quote_asset) {
-            d1 = Self::do_get_delta_on_the_fly((new_base_amount, new_quote_amount)).unwrap();
+    #[require_transactional]
+    fn do_remove_liquidity(
+        who: &T::AccountId,
+        pool: &mut PoolOf<T, I>,
+        liquidity: BalanceOf<T, I>,
+    ) -> Result<Amounts<T, I>, DispatchError> {
+        let amounts = Self::calculate_reserves_to_remove(pool, liquidity)?;
 
-            ensure!(d1 >= d0, Error::<T, I>::InvalidInvariant);
-        }
-        // TODO: the following may not required since fee is -> 0
-        // let ideal_base_balance = D1.clone() * pool.base_amount / D0.clone();
-        // let ideal_base_new_balance = ideal_base_amount;
-        //
-        // if ideal_base_balance >
-        // let ideal_base_new_balance_difference = (ideal_base_balance - ideal_base_new_balance).abs();
-        //
-        // let ideal_quote_balance = D1.clone() * pool.base_amount / D0;
-        // let ideal_quote_new_balance = ideal_quote_balance;
-        // let ideal_quote_new_balance_difference =
-        //     (ideal_base_balance - ideal_base_new_balance).abs();
-
-        // let D2 = Self::do_get_delta((base_asset, quote_asset)).unwrap();
-        // D2 and D1 in here will be the same
-        if total_supply > 0 {
-            liquidity = liquidity + liquidity * (d1 - d0) / d0;
-        } else {
-            liquidity += d1;
+        for (balance, amount) in pool.balances.iter_mut().zip(amounts.iter()) {
+            *balance = balance
+                .checked_sub(*amount)
+                .ok_or(Error::<T, I>::InsufficientLiquidity)?;
         }
 
-        T::Assets::mint_into(pool.lp_token_id, who, liquidity)?;
+        T::Assets::burn_from(pool.lp_token_id, who, liquidity)?;
 
-        T::Assets::transfer(
-            base_asset,
-            who,
-            &Self::account_id(),
-            ideal_base_amount,
-            true,
-        )?;
-        T::Assets::transfer(
-            quote_asset,
-            who,
-            &Self::account_id(),
-            ideal_quote_amount,
-            true,
-        )?;
+        for (asset_id, amount) in pool.assets.iter().zip(amounts.iter()) {
+            if !amount.is_zero() {
+                T::Assets::transfer(*asset_id, &Self::account_id(), who, *amount, false)?;
+            }
+        }
 
         if Self::protocol_fee_on() {
-            // we cannot hold k_last for really large values
-            // we can hold two u128s instead
-            pool.base_amount_last = pool.base_amount;
-            pool.quote_amount_last = pool.quote_amount;
+            pool.d_last = Self::delta_util(&pool.balances)?;
         }
 
         log::trace!(
-            target: "stableswap::do_add_liquidity",
-            "who: {:?}, total_supply: {:?}, liquidity: {:?}, base_asset: {:?}, quote_asset: {:?}, ideal_base_amount: {:?},\
-             ideal_quote_amount: {:?}",
+            target: "stableswap::do_remove_liquidity",
+            "who: {:?}, liquidity: {:?}, lp_token_id: {:?}, amounts: {:?}",
             &who,
-            &total_supply,
             &liquidity,
-            &base_asset,
-            &quote_asset,
-            &ideal_base_amount,
-            &ideal_quote_amount
+            &pool.lp_token_id,
+            &amounts
         );
 
-        Ok(())
+        Ok(amounts)
     }
 
-    fn calculate_reserves_to_remove(
-        pool: &mut Pool<AssetIdOf<T, I>, BalanceOf<T, I>, T::BlockNumber>,
+    // amount of the asset at `index` which keeps the invariant of the pool
+    // once `liquidity` is burnt, minus the fees of the imbalance it causes
+    fn calculate_one_asset_to_remove(
+        pool: &PoolOf<T, I>,
         liquidity: BalanceOf<T, I>,
-    ) -> Result<(BalanceOf<T, I>, BalanceOf<T, I>), DispatchError> {
+        index: usize,
+    ) -> Result<BalanceOf<T, I>, DispatchError> {
         let total_supply = T::Assets::total_issuance(pool.lp_token_id);
-        let base_amount = liquidity
-            .get_big_uint()
-            .checked_mul(&pool.base_amount.get_big_uint())
-            .and_then(|r| r.checked_div(&total_supply.get_big_uint()))
-            .ok_or(Error::<T, I>::ConversionToU128Failed)?
-            .to_u128()
+        ensure!(
+            liquidity < total_supply,
+            Error::<T, I>::InsufficientLiquidity
+        );
+
+        let d0 = Self::delta_util(&pool.balances)?;
+        let d1 = d0
+            .checked_sub(Self::mul_div(liquidity, d0, total_supply)?)
             .ok_or(ArithmeticError::Underflow)?;
-        let quote_amount = liquidity
-            .get_big_uint()
-            .checked_mul(&pool.quote_amount.get_big_uint())
-            .and_then(|r| r.checked_div(&total_supply.get_big_uint()))
-            .ok_or(Error::<T, I>::ConversionToU128Failed)?
-            .to_u128()
+        let new_balance = Self::get_y(&pool.balances, index, d1)?;
+
+        let fee = Self::get_imbalance_fee(pool.assets.len())?;
+        let mut fee_balances = pool.balances.clone();
+        for (k, balance) in fee_balances.iter_mut().enumerate() {
+            let ideal_balance = Self::mul_div(pool.balances[k], d1, d0)?;
+            let difference = if k == index {
+                ideal_balance.checked_sub(new_balance)
+            } else {
+                pool.balances[k].checked_sub(ideal_balance)
+            }
             .ok_or(ArithmeticError::Underflow)?;
+            *balance = balance
+                .checked_sub(fee.mul_floor(difference))
+                .ok_or(ArithmeticError::Underflow)?;
+        }
 
-        Ok((base_amount, quote_amount))
+        let new_fee_balance = Self::get_y(&fee_balances, index, d1)?;
+        Ok(fee_balances[index]
+            .checked_sub(new_fee_balance)
+            .ok_or(ArithmeticError::Underflow)?)
     }
 
     #[require_transactional]
-    fn do_remove_liquidity(
+    fn do_remove_liquidity_one_asset(
         who: &T::AccountId,
-        pool: &mut Pool<AssetIdOf<T, I>, BalanceOf<T, I>, T::BlockNumber>,
+        pool: &mut PoolOf<T, I>,
         liquidity: BalanceOf<T, I>,
-        (base_asset, quote_asset): (AssetIdOf<T, I>, AssetIdOf<T, I>),
-    ) -> Result<(BalanceOf<T, I>, BalanceOf<T, I>), DispatchError> {
-        let (base_amount, quote_amount) = Self::calculate_reserves_to_remove(pool, liquidity)?;
-
-        pool.base_amount = pool
-            .base_amount
-            .checked_sub(base_amount)
-            .ok_or(Error::<T, I>::InsufficientLiquidity)?;
+        index: usize,
+    ) -> Result<BalanceOf<T, I>, DispatchError> {
+        let amount = Self::calculate_one_asset_to_remove(pool, liquidity, index)?;
 
-        pool.quote_amount = pool
-            .quote_amount
-            .checked_sub(quote_amount)
+        pool.balances[index] = pool.balances[index]
+            .checked_sub(amount)
             .ok_or(Error::<T, I>::InsufficientLiquidity)?;
 
         T::Assets::burn_from(pool.lp_token_id, who, liquidity)?;
 
-        T::Assets::transfer(base_asset, &Self::account_id(), who, base_amount, false)?;
-        T::Assets::transfer(quote_asset, &Self::account_id(), who, quote_amount, false)?;
+        T::Assets::transfer(pool.assets[index], &Self::account_id(), who, amount, false)?;
 
         if Self::protocol_fee_on() {
-            // we cannot hold k_last for really large values
-            // we can hold two u128s instead
-            pool.base_amount_last = pool.base_amount;
-            pool.quote_amount_last = pool.quote_amount;
+            pool.d_last = Self::delta_util(&pool.balances)?;
         }
 
         log::trace!(
-            target: "stableswap::do_remove_liquidity",
-            "who: {:?}, liquidity: {:?}, base_asset: {:?}, quote_asset: {:?}, base_amount: {:?}, quote_amount: {:?}",
+            target: "stableswap::do_remove_liquidity_one_asset",
+            "who: {:?}, liquidity: {:?}, lp_token_id: {:?}, asset_id: {:?}, amount: {:?}",
             &who,
             &liquidity,
-            &base_asset,
-            &quote_asset,
-            &base_amount,
-            &quote_amount
+            &pool.lp_token_id,
+            &pool.assets[index],
+            &amount
         );
 
-        Ok((base_amount, quote_amount))
+        Ok(amount)
     }
 
     #[require_transactional]
-    pub fn do_mint_protocol_fee(
-        pool: &mut Pool<AssetIdOf<T, I>, BalanceOf<T, I>, T::BlockNumber>,
-    ) -> Result<BalanceOf<T, I>, DispatchError> {
+    pub fn do_mint_protocol_fee(pool: &mut PoolOf<T, I>) -> Result<BalanceOf<T, I>, DispatchError> {
         // TODO: If we turn off protocol_fee later in runtime upgrade
         // this will reset root_k_last to zero which may not be good
-        let k_last = pool
-            .base_amount_last
-            .get_big_uint()
-            .checked_mul(&pool.quote_amount_last.get_big_uint())
-            .ok_or(ArithmeticError::Overflow)?;
-
         if !Self::protocol_fee_on() {
-            // if fees are off and k_last is a value we need to reset it
-            if !k_last.is_zero() {
-                pool.base_amount_last = Zero::zero();
-                pool.quote_amount_last = Zero::zero();
-                return Ok(Zero::zero());
+            // if fees are off and d_last is a value we need to reset it
+            if !pool.d_last.is_zero() {
+                pool.d_last = Zero::zero();
             }
 
-            // if fees are off and k_last is zero return
             return Ok(Zero::zero());
         }
 
-        let root_k_last = Self::delta_util(pool.base_amount_last, pool.quote_amount_last)
-            .unwrap()
-            .get_big_uint();
+        // fees are on but no liquidity change has been recorded yet
+        if pool.d_last.is_zero() {
+            return Ok(Zero::zero());
+        }
 
-        // if the early exits do not return we know that k_last is not zero
+        let root_k_last = pool.d_last.get_big_uint();
+
+        // if the early exits do not return we know that d_last is not zero
         // and that protocol fees are on
 
-        let root_k = Self::delta_util(pool.base_amount, pool.quote_amount)
-            .unwrap()
-            .get_big_uint();
+        let root_k = Self::delta_util(&pool.balances)?.get_big_uint();
 
         if root_k <= root_k_last {
             return Ok(Zero::zero());
@@ -866,335 +973,126 @@ impl<T: Config<I>, I: 'static> Pallet<T, I> {
         (asset_in, asset_out): (AssetIdOf<T, I>, AssetIdOf<T, I>),
         amount_in: BalanceOf<T, I>,
     ) -> Result<BalanceOf<T, I>, DispatchError> {
-        let (is_inverted, base_asset, quote_asset) = Self::sort_assets((asset_in, asset_out))?;
-
-        Pools::<T, I>::try_mutate(
-            base_asset,
-            quote_asset,
-            |pool| -> Result<BalanceOf<T, I>, DispatchError> {
-                let pool = pool.as_mut().ok_or(Error::<T, I>::PoolDoesNotExist)?;
+        let pool_id = Self::get_pool_id((asset_in, asset_out))?;
 
-                let (supply_in, supply_out) = if is_inverted {
-                    (pool.quote_amount, pool.base_amount)
-                } else {
-                    (pool.base_amount, pool.quote_amount)
-                };
+        Pools::<T, I>::try_mutate(pool_id, |pool| -> Result<BalanceOf<T, I>, DispatchError> {
+            let pool = pool.as_mut().ok_or(Error::<T, I>::PoolDoesNotExist)?;
+            let index_in = pool
+                .index_of(&asset_in)
+                .ok_or(Error::<T, I>::AssetNotInPool)?;
+            let index_out = pool
+                .index_of(&asset_out)
+                .ok_or(Error::<T, I>::AssetNotInPool)?;
 
-                ensure!(
-                    amount_in >= T::LpFee::get().saturating_reciprocal_mul_floor(One::one()),
-                    Error::<T, I>::InsufficientAmountIn
-                );
-                ensure!(!supply_out.is_zero(), Error::<T, I>::InsufficientAmountOut);
-
-                //let amount_out = Self::get_amount_out(amount_in, supply_in, supply_out)?;
-                let amount_out = Self::get_amount_out(amount_in, supply_in, supply_out)?;
+            ensure!(
+                amount_in >= T::LpFee::get().saturating_reciprocal_mul_floor(One::one()),
+                Error::<T, I>::InsufficientAmountIn
+            );
+            ensure!(
+                !pool.balances[index_out].is_zero(),
+                Error::<T, I>::InsufficientAmountOut
+            );
 
-                let (new_supply_in, new_supply_out) = (
-                    supply_in
-                        .checked_add(amount_in)
-                        .ok_or(ArithmeticError::Overflow)?,
-                    supply_out
-                        .checked_sub(amount_out)
-                        .ok_or(ArithmeticError::Underflow)?,
-                );
+            let amount_out = Self::get_amount_out(amount_in, &pool.balances, index_in, index_out)?;
 
-                if is_inverted {
-                    pool.quote_amount = new_supply_in;
-                    pool.base_amount = new_supply_out;
-                } else {
-                    pool.base_amount = new_supply_in;
-                    pool.quote_amount = new_supply_out;
-                }
+            pool.balances[index_in] = pool.balances[index_in]
+                .checked_add(amount_in)
+                .ok_or(ArithmeticError::Overflow)?;
+            pool.balances[index_out] = pool.balances[index_out]
+                .checked_sub(amount_out)
+                .ok_or(ArithmeticError::Underflow)?;
 
-                Self::do_update_oracle(pool)?;
+            Self::do_update_oracle(pool)?;
 
-                T::Assets::transfer(asset_in, who, &Self::account_id(), amount_in, true)?;
-                T::Assets::transfer(asset_out, &Self::account_id(), who, amount_out, false)?;
+            T::Assets::transfer(asset_in, who, &Self::account_id(), amount_in, true)?;
+            T::Assets::transfer(asset_out, &Self::account_id(), who, amount_out, false)?;
 
-                log::trace!(
-                    target: "stableswap::do_trade",
-                    "who: {:?}, asset_in: {:?}, asset_out: {:?}, amount_in: {:?}, amount_out: {:?}",
-                    &who,
-                    &asset_in,
-                    &asset_out,
-                    &amount_in,
-                    &amount_out,
-                );
+            log::trace!(
+                target: "stableswap::do_trade",
+                "who: {:?}, asset_in: {:?}, asset_out: {:?}, amount_in: {:?}, amount_out: {:?}",
+                &who,
+                &asset_in,
+                &asset_out,
+                &amount_in,
+                &amount_out,
+            );
 
-                Self::deposit_event(Event::<T, I>::Traded(
-                    who.clone(),
-                    asset_in,
-                    asset_out,
-                    amount_in,
-                    amount_out,
-                    pool.lp_token_id,
-                    pool.quote_amount,
-                    pool.base_amount,
-                ));
+            Self::deposit_event(Event::<T, I>::Traded(
+                who.clone(),
+                asset_in,
+                asset_out,
+                amount_in,
+                amount_out,
+                pool.lp_token_id,
+                pool.balances[index_in],
+                pool.balances[index_out],
+            ));
 
-                Ok(amount_out)
-            },
-        )
+            Ok(amount_out)
+        })
     }
     // https://miguelmota.com/blog/understanding-stableswap-curve/
     // https://github.com/curvefi/curve-contract/blob/master/contracts/pool-templates/base/SwapTemplateBase.vy
     // https://github.com/parallel-finance/amm-formula/blob/master/src/formula.rs
     // https://curve.fi/files/stableswap-paper.pdf
-    // Calculates delta based on already persisted assets
-    #[allow(dead_code)]
-    pub fn do_get_delta(
-        (asset_in, asset_out): (AssetIdOf<T, I>, AssetIdOf<T, I>),
-    ) -> Result<Balance, DispatchError> {
-        let (tot_base_amount, tot_quote_amount) = Self::get_reserves(asset_in, asset_out).unwrap();
-
-        let d = Self::delta_util(tot_base_amount, tot_quote_amount).unwrap();
-
-        Self::deposit_event(Event::<T, I>::DeltaCalculated(asset_in, asset_out, d));
-
-        log::trace!(
-            target: "stableSwap::do_get_delta",
-            "asset_in: {:?}, asset_out: {:?}, delta: {:?}",
-            &asset_in,
-            &asset_out,
-            &d
-        );
-
-        Ok(d)
-    }
-
     // Calculates delta based on amounts
-    fn delta_util(
-        tot_base_amount: BalanceOf<T, I>,
-        tot_quote_amount: BalanceOf<T, I>,
-    ) -> Result<Balance, DispatchError> {
-        let d = compute_d(
-            tot_base_amount,
-            tot_quote_amount,
-            T::AmplificationCoefficient::get() as u128,
-        )?;
+    fn delta_util(balances: &[BalanceOf<T, I>]) -> Result<Balance, DispatchError> {
+        let d = compute_d(balances, T::AmplificationCoefficient::get() as u128)?;
 
         Ok(d)
     }
 
-    pub fn get_base(
-        new_quote: BalanceOf<T, I>,
-        amp_coeff: BalanceOf<T, I>,
+    // Reserve of the asset at `index` which keeps the invariant `d`
+    pub fn get_y(
+        balances: &[BalanceOf<T, I>],
+        index: usize,
         d: BalanceOf<T, I>,
     ) -> Result<Balance, DispatchError> {
-        let base = compute_base(new_quote, amp_coeff, d)?;
-        Ok(base)
-    }
-
-    #[allow(dead_code)]
-    // Responsible to get ratio
-    fn get_exchange_value(
-        pair: (AssetIdOf<T, I>, AssetIdOf<T, I>),
-        _asset_id: AssetIdOf<T, I>,
-        amount: BalanceOf<T, I>,
-    ) -> Result<Balance, DispatchError> {
-        let (_, base_asset, quote_asset) = Self::sort_assets(pair)?;
-        let pool = Pools::<T, I>::try_get(base_asset, quote_asset)
-            .map_err(|_err| Error::<T, I>::PoolDoesNotExist)?;
-        let amp = T::AmplificationCoefficient::get() as u128;
-        let pool_base_aum = pool.base_amount;
-        let pool_quote_aum = pool.quote_amount;
-        let d = Self::delta_util(pool_base_aum, pool_quote_aum)?;
-        let new_quote_amount = pool_quote_aum
-            .checked_add(amount)
-            .ok_or(ArithmeticError::Underflow)?;
-        let new_base_amount = Self::get_base(new_quote_amount, amp, d)?;
-        let exchange_value = pool_base_aum
-            .checked_sub(new_base_amount)
-            .ok_or(ArithmeticError::Underflow)?;
-        Ok(exchange_value)
-    }
-
-    // Calculates delta on the fly
-    #[allow(dead_code)]
-    pub fn do_get_delta_on_the_fly(
-        (tot_base_amount, tot_quote_amount): (Balance, Balance),
-    ) -> Result<Balance, DispatchError> {
-        let d = Self::delta_util(tot_base_amount, tot_quote_amount).unwrap();
-
-        log::trace!(
-            target: "stableSwap::do_get_delta_on_the_fly",
-            "tot_base_amount: {:?}, tot_quote_amount: {:?}, delta: {:?}",
-            &tot_base_amount,
-            &tot_quote_amount,
-            &d
-        );
-
-        Ok(d)
-    }
-
-    #[allow(dead_code)]
-    pub fn do_get_alternative_var(
-        mut autonomous_var: BalanceOf<T, I>,
-        (asset_in, asset_out): (AssetIdOf<T, I>, AssetIdOf<T, I>),
-    ) -> Result<Balance, DispatchError> {
-        let (resx, _) = Self::get_reserves(asset_in, asset_out).unwrap();
-        autonomous_var = autonomous_var
-            .get_big_uint()
-            .checked_add(&resx.get_big_uint())
-            .ok_or(Error::<T, I>::ConversionToU128Failed)?
-            .to_u128()
-            .ok_or(ArithmeticError::Overflow)?;
-
-        // passes asset in and asset out
-        let (tot_base_amount, tot_quote_amount) = Self::get_reserves(asset_in, asset_out).unwrap();
-        let d = Self::delta_util(tot_base_amount, tot_quote_amount).unwrap();
-
-        let mut c = d;
-        let mut s = 0u128;
-
-        let a = (T::AmplificationCoefficient::get() as u128)
-            .get_big_uint()
-            .checked_mul(&T::Precision::get().get_big_uint())
-            .ok_or(Error::<T, I>::ConversionToU128Failed)?
-            .to_u128()
-            .ok_or(ArithmeticError::Underflow)?;
-
-        let n_a = (T::NumTokens::get() as u128)
-            .get_big_uint()
-            .checked_mul(&a.get_big_uint())
-            .ok_or(Error::<T, I>::ConversionToU128Failed)?
-            .to_u128()
-            .ok_or(ArithmeticError::Underflow)?;
-
-        let _x = 0u128;
-
-        s = s
-            .get_big_uint()
-            .checked_add(&autonomous_var.get_big_uint())
-            .ok_or(Error::<T, I>::ConversionToU128Failed)?
-            .to_u128()
-            .ok_or(ArithmeticError::Underflow)?;
-
-        c = (c.get_big_uint().checked_mul(&d.get_big_uint()))
-            .ok_or(Error::<T, I>::ConversionToU128Failed)?
-            .to_u128()
-            .ok_or(ArithmeticError::Underflow)?
-            .checked_div(
-                autonomous_var
-                    .get_big_uint()
-                    .checked_mul(&(T::NumTokens::get() as u128).get_big_uint())
-                    .ok_or(Error::<T, I>::ConversionToU128Failed)?
-                    .to_u128()
-                    .ok_or(ArithmeticError::Underflow)?,
-            )
-            .ok_or(Error::<T, I>::ConversionToU128Failed)?
-            .to_u128()
-            .ok_or(ArithmeticError::Underflow)?;
-
-        c = (c
-            .get_big_uint()
-            .checked_mul(&d.get_big_uint())
-            .and_then(|r| r.checked_mul(&T::Precision::get().get_big_uint())))
-        .ok_or(Error::<T, I>::ConversionToU128Failed)?
-        .to_u128()
-        .ok_or(ArithmeticError::Underflow)?
-        .checked_div(
-            n_a.get_big_uint()
-                .checked_mul(&(T::NumTokens::get() as u128).get_big_uint())
-                .ok_or(Error::<T, I>::ConversionToU128Failed)?
-                .to_u128()
-                .ok_or(ArithmeticError::Underflow)?,
-        )
-        .ok_or(Error::<T, I>::ConversionToU128Failed)?
-        .to_u128()
-        .ok_or(ArithmeticError::Underflow)?;
-
-        let b = s
-            .get_big_uint()
-            .checked_add(
-                &d.get_big_uint()
-                    .checked_mul(&T::Precision::get().get_big_uint())
-                    .and_then(|r| r.checked_div(&n_a.get_big_uint()))
-                    .ok_or(Error::<T, I>::ConversionToU128Failed)?,
-            )
-            .ok_or(Error::<T, I>::ConversionToU128Failed)?
-            .to_u128()
-            .ok_or(ArithmeticError::Underflow)?;
-
-        let mut y_prev;
-        let mut y = d;
-        // 255 is a max number of loops
-        // should throw error if does not converge
-        for _ in 0..255 {
-            y_prev = y;
-
-            y = (y
-                .get_big_uint()
-                .checked_mul(&y.get_big_uint())
-                .and_then(|r| r.checked_add(&c.get_big_uint()))
-                .ok_or(Error::<T, I>::ConversionToU128Failed)?
-                .to_u128()
-                .ok_or(ArithmeticError::Underflow)?)
-            .checked_div(
-                y.get_big_uint()
-                    .checked_mul(&2u128.get_big_uint())
-                    .and_then(|r| r.checked_add(&b.get_big_uint()))
-                    .and_then(|r| r.checked_sub(&d.get_big_uint()))
-                    .ok_or(Error::<T, I>::ConversionToU128Failed)?
-                    .to_u128()
-                    .ok_or(ArithmeticError::Underflow)?,
-            )
-            .ok_or(Error::<T, I>::ConversionToU128Failed)?
-            .to_u128()
-            .ok_or(ArithmeticError::Underflow)?;
-
-            if y.eq(&y_prev) {
-                break;
-            }
-        }
-
+        let y = compute_y(
+            balances,
+            index,
+            T::AmplificationCoefficient::get() as u128,
+            d,
+        )?;
         Ok(y)
-        // throw new Error('Approximation did not converge')
     }
-    // extract the reserves from a pool after sorting assets
+
+    // extract the reserves of a pair from the pool trading it
     fn get_reserves(
         asset_in: AssetIdOf<T, I>,
         asset_out: AssetIdOf<T, I>,
     ) -> Result<(BalanceOf<T, I>, BalanceOf<T, I>), DispatchError> {
-        let (is_inverted, base_asset, quote_asset) = Self::sort_assets((asset_in, asset_out))?;
-
-        let pool = Pools::<T, I>::try_get(base_asset, quote_asset)
-            .map_err(|_err| Error::<T, I>::PoolDoesNotExist)?;
+        let (pool, index_in, index_out) = Self::get_pool_of_pair(asset_in, asset_out)?;
 
-        if is_inverted {
-            Ok((pool.quote_amount, pool.base_amount))
-        } else {
-            Ok((pool.base_amount, pool.quote_amount))
-        }
+        Ok((pool.balances[index_in], pool.balances[index_out]))
     }
 
-    // given an output amount of an asset and pair reserves, returns a required input amount of the other asset
-    //
-    // amountOut = amountIn * reserveOut / reserveIn + amountIn
-    // amountOut * reserveIn + amountOut * amountIn  = amountIn * reserveOut
-    // amountOut * reserveIn = amountIn * (reserveOut - amountOut)
+    // given an output amount of an asset and the reserves of a pool, returns the
+    // required input amount of another asset of the pool which keeps its invariant
     //
-    // amountIn = amountOut * reserveIn / (reserveOut - amountOut)
     // amountIn = (amountIn / (1 - fee_percent)) + 1
     fn get_amount_in(
         amount_out: BalanceOf<T, I>,
-        reserve_in: BalanceOf<T, I>,
-        reserve_out: BalanceOf<T, I>,
+        balances: &[BalanceOf<T, I>],
+        index_in: usize,
+        index_out: usize,
     ) -> Result<BalanceOf<T, I>, DispatchError> {
         ensure!(
-            amount_out < reserve_out,
+            amount_out < balances[index_out],
             Error::<T, I>::InsufficientSupplyOut
         );
-        let numerator = reserve_in
-            .checked_mul(amount_out)
-            .ok_or(ArithmeticError::Overflow)?;
 
-        let denominator = reserve_out
+        let d = Self::delta_util(balances)?;
+
+        let mut new_balances = balances.to_vec();
+        new_balances[index_out] = balances[index_out]
             .checked_sub(amount_out)
             .ok_or(ArithmeticError::Underflow)?;
 
-        let amount_in = numerator
-            .checked_div(denominator)
+        let new_balance_in = Self::get_y(&new_balances, index_in, d)?;
+
+        let amount_in = new_balance_in
+            .checked_sub(balances[index_in])
             .ok_or(ArithmeticError::Underflow)?;
 
         let fee_percent = T::LpFee::get()
@@ -1204,12 +1102,12 @@ impl<T: Config<I>, I: 'static> Pallet<T, I> {
 
         log::trace!(
             target: "stableswap::get_amount_in",
-            "amount_out: {:?}, reserve_in: {:?}, reserve_out: {:?}, numerator: {:?}, denominator: {:?}, amount_in: {:?}",
+            "amount_out: {:?}, balances: {:?}, index_in: {:?}, index_out: {:?}, d: {:?}, amount_in: {:?}",
             &amount_out,
-            &reserve_in,
-            &reserve_out,
-            &numerator,
-            &denominator,
+            &balances,
+            &index_in,
+            &index_out,
+            &d,
             &amount_in
         );
 
@@ -1219,9 +1117,7 @@ impl<T: Config<I>, I: 'static> Pallet<T, I> {
             .ok_or(ArithmeticError::Overflow)?)
     }
 
-    fn do_update_oracle(
-        pool: &mut Pool<AssetIdOf<T, I>, BalanceOf<T, I>, T::BlockNumber>,
-    ) -> Result<(), DispatchError> {
+    fn do_update_oracle(pool: &mut PoolOf<T, I>) -> Result<(), DispatchError> {
         let block_timestamp = frame_system::Pallet::<T>::block_number();
 
         if pool.block_timestamp_last != block_timestamp {
@@ -1229,36 +1125,26 @@ impl<T: Config<I>, I: 'static> Pallet<T, I> {
                 .saturating_sub(pool.block_timestamp_last)
                 .saturated_into();
 
-            // compute by multiplying the numerator with the time elapsed
-            let price0_fraction = FixedU128::saturating_from_rational(
-                time_elapsed
-                    .get_big_uint()
-                    .checked_mul(&pool.quote_amount.get_big_uint())
-                    .ok_or(Error::<T, I>::ConversionToU128Failed)?
-                    .to_u128()
-                    .ok_or(ArithmeticError::Overflow)?,
-                pool.base_amount,
-            );
-            let price1_fraction = FixedU128::saturating_from_rational(
-                time_elapsed
-                    .get_big_uint()
-                    .checked_mul(&pool.base_amount.get_big_uint())
-                    .ok_or(Error::<T, I>::ConversionToU128Failed)?
-                    .to_u128()
-                    .ok_or(ArithmeticError::Overflow)?,
-                pool.quote_amount,
-            );
-
-            // convert stored u128 into FixedU128 before add
-            pool.price_0_cumulative_last = FixedU128::from_inner(pool.price_0_cumulative_last)
-                .checked_add(&price0_fraction)
-                .ok_or(ArithmeticError::Overflow)?
-                .into_inner();
+            let num_tokens = pool.assets.len();
+            for k in 0..num_tokens {
+                // compute by multiplying the numerator with the time elapsed
+                let price_fraction = FixedU128::saturating_from_rational(
+                    time_elapsed
+                        .get_big_uint()
+                        .checked_mul(&pool.balances[(k + 1) % num_tokens].get_big_uint())
+                        .ok_or(Error::<T, I>::ConversionToU128Failed)?
+                        .to_u128()
+                        .ok_or(ArithmeticError::Overflow)?,
+                    pool.balances[k],
+                );
 
-            pool.price_1_cumulative_last = FixedU128::from_inner(pool.price_1_cumulative_last)
-                .checked_add(&price1_fraction)
-                .ok_or(ArithmeticError::Overflow)?
-                .into_inner();
+                // convert stored u128 into FixedU128 before add
+                pool.price_cumulative_last[k] =
+                    FixedU128::from_inner(pool.price_cumulative_last[k])
+                        .checked_add(&price_fraction)
+                        .ok_or(ArithmeticError::Overflow)?
+                        .into_inner();
+            }
 
             // updates timestamp last so `time_elapsed` is correctly calculated
             pool.block_timestamp_last = block_timestamp;
@@ -1266,30 +1152,6 @@ impl<T: Config<I>, I: 'static> Pallet<T, I> {
 
         Ok(())
     }
-    // given a pool, calculate the ideal liquidity amounts as a function of the current
-    // pool reserves ratio
-    fn get_ideal_amounts(
-        pool: &Pool<AssetIdOf<T, I>, BalanceOf<T, I>, T::BlockNumber>,
-        (base_amount, quote_amount): (BalanceOf<T, I>, BalanceOf<T, I>),
-    ) -> Result<(BalanceOf<T, I>, BalanceOf<T, I>), DispatchError> {
-        log::trace!(
-            target: "stableswap::get_ideal_amounts",
-            "pair: {:?}",
-            &(base_amount, quote_amount)
-        );
-
-        if pool.is_empty() {
-            return Ok((base_amount, quote_amount));
-        }
-
-        let ideal_quote_amount = Self::quote(base_amount, pool.base_amount, pool.quote_amount)?;
-        if ideal_quote_amount <= quote_amount {
-            Ok((base_amount, ideal_quote_amount))
-        } else {
-            let ideal_base_amount = Self::quote(quote_amount, pool.quote_amount, pool.base_amount)?;
-            Ok((ideal_base_amount, quote_amount))
-        }
-    }
     fn protocol_fee_on() -> bool {
         !T::ProtocolFee::get().is_zero()
     }
@@ -1310,8 +1172,9 @@ impl<T: Config<I>, I: 'static> Pallet<T, I> {
 
         amounts_out[0] = amount_in;
         for i in 0..(path.len() - 1) {
-            let (reserve_in, reserve_out) = Self::get_reserves(path[i], path[i + 1])?;
-            let amount_out = Self::get_amount_out(amounts_out[i], reserve_in, reserve_out)?;
+            let (pool, index_in, index_out) = Self::get_pool_of_pair(path[i], path[i + 1])?;
+            let amount_out =
+                Self::get_amount_out(amounts_out[i], &pool.balances, index_in, index_out)?;
             amounts_out[i + 1] = amount_out;
         }
 
@@ -1365,9 +1228,9 @@ impl<T: Config<I>, I: 'static>
         Ok(())
     }
 
-    /// Returns a vector of all of the pools in storage
+    /// Returns a vector of all of the pairs traded by the pools
     fn get_pools() -> Result<Vec<(AssetIdOf<T, I>, AssetIdOf<T, I>)>, DispatchError> {
-        Ok(Pools::<T, I>::iter_keys().collect())
+        Ok(PairPools::<T, I>::iter_keys().collect())
     }
 
     fn get_reserves(
@@ -1377,4 +1240,13 @@ impl<T: Config<I>, I: 'static>
         let (amount_x, amount_y) = Self::get_reserves(asset_in, asset_out)?;
         Ok((amount_x, amount_y))
     }
+
+    /// Returns the assets and the invariant D of the pool by lp_asset
+    fn get_pool_by_lp_asset(
+        asset_id: AssetIdOf<T, I>,
+    ) -> Option<(Vec<AssetIdOf<T, I>>, BalanceOf<T, I>)> {
+        let pool = Pools::<T, I>::get(asset_id)?;
+        let d = Self::delta_util(&pool.balances).ok()?;
+        Some((pool.assets, d))
+    }
 }
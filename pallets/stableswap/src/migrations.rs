@@ -0,0 +1,112 @@
+// Copyright 2021 Parallel Finance Developer.
+// This file is part of Parallel Finance.
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+// http://www.apache.org/licenses/LICENSE-2.0
+
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+pub mod v1 {
+    use crate::{
+        helpers::compute_d, pallet::StorageVersion, types::Pool, AssetIdOf, BalanceOf, Config,
+        PairPools, Pallet, Versions,
+    };
+    use codec::{Decode, Encode};
+    use frame_support::{log, storage_alias, traits::Get, weights::Weight, Blake2_128Concat};
+    use scale_info::TypeInfo;
+    use sp_runtime::RuntimeDebug;
+    use sp_std::{vec, vec::Vec};
+
+    #[derive(Clone, PartialEq, Eq, Encode, Decode, RuntimeDebug, TypeInfo)]
+    pub struct V0Pool<CurrencyId, Balance, BlockNumber> {
+        pub base_amount: Balance,
+        pub quote_amount: Balance,
+        pub base_amount_last: Balance,
+        pub quote_amount_last: Balance,
+        pub lp_token_id: CurrencyId,
+        pub block_timestamp_last: BlockNumber,
+        pub price_0_cumulative_last: Balance,
+        pub price_1_cumulative_last: Balance,
+    }
+
+    /// Two-sided pools keyed by their sorted pair
+    #[storage_alias]
+    type Pools<T: Config<I>, I: 'static> = StorageDoubleMap<
+        Pallet<T, I>,
+        Blake2_128Concat,
+        AssetIdOf<T, I>,
+        Blake2_128Concat,
+        AssetIdOf<T, I>,
+        V0Pool<AssetIdOf<T, I>, BalanceOf<T, I>, <T as frame_system::Config>::BlockNumber>,
+    >;
+
+    #[cfg(feature = "try-runtime")]
+    pub fn pre_migrate<T: Config<I>, I: 'static>() -> Result<(), &'static str> {
+        frame_support::ensure!(StorageVersion::<T, I>::get() == Versions::V0, "must be V0");
+        log::info!("pools count: {:?}", Pools::<T, I>::iter_keys().count());
+        Ok(())
+    }
+
+    /// Key the existing 2-token pools by their liquidity token, with vector reserves
+    pub fn migrate<T: Config<I>, I: 'static>() -> Weight {
+        if StorageVersion::<T, I>::get() == Versions::V0 {
+            log::info!(
+                target: "stableswap::migrate",
+                "migrating stableswap to Versions::V1"
+            );
+
+            let amp = T::AmplificationCoefficient::get() as u128;
+            // both layouts share the same prefix, drain the old one before writing
+            let pools: Vec<_> = Pools::<T, I>::drain().collect();
+            let count = pools.len() as u64;
+            for (base_asset, quote_asset, pool) in pools {
+                let d_last = compute_d(&[pool.base_amount_last, pool.quote_amount_last], amp)
+                    .unwrap_or_default();
+                PairPools::<T, I>::insert(base_asset, quote_asset, pool.lp_token_id);
+                crate::Pools::<T, I>::insert(
+                    pool.lp_token_id,
+                    Pool {
+                        assets: vec![base_asset, quote_asset],
+                        balances: vec![pool.base_amount, pool.quote_amount],
+                        d_last,
+                        lp_token_id: pool.lp_token_id,
+                        block_timestamp_last: pool.block_timestamp_last,
+                        price_cumulative_last: vec![
+                            pool.price_0_cumulative_last,
+                            pool.price_1_cumulative_last,
+                        ],
+                    },
+                );
+            }
+
+            StorageVersion::<T, I>::put(Versions::V1);
+            log::info!(
+                target: "stableswap::migrate",
+                "completed stableswap migration to Versions::V1"
+            );
+
+            T::DbWeight::get().reads_writes(count + 1, count * 3 + 1)
+        } else {
+            T::DbWeight::get().reads(1)
+        }
+    }
+
+    #[cfg(feature = "try-runtime")]
+    pub fn post_migrate<T: Config<I>, I: 'static>() -> Result<(), &'static str> {
+        frame_support::ensure!(StorageVersion::<T, I>::get() == Versions::V1, "must be V1");
+        for (lp_token_id, pool) in crate::Pools::<T, I>::iter() {
+            frame_support::ensure!(pool.assets.len() == 2, "pool must have two assets");
+            frame_support::ensure!(
+                PairPools::<T, I>::get(pool.assets[0], pool.assets[1]) == Some(lp_token_id),
+                "pair must be indexed"
+            );
+        }
+        Ok(())
+    }
+}
@@ -115,7 +115,7 @@ impl pallet_balances::Config for Test {
 
 parameter_types! {
     pub const StableSwapPalletId: PalletId = PalletId(*b"par/sswp");
-    pub const NumTokens: u8 = 2;
+    pub const NumTokens: u32 = 3;
     pub const Precision: u32 = 100;
     pub const AmplificationCoefficient: u8 = 85;
     //
@@ -139,6 +139,7 @@ impl pallet_stableswap::Config for Test {
     type ProtocolFee = DefaultProtocolFee;
     type MinimumLiquidity = MinimumLiquidity;
     type CreatePoolOrigin = EnsureSignedBy<AliceCreatePoolOrigin, AccountId>;
+    type OnPoolCreated = ();
 }
 
 parameter_types! {
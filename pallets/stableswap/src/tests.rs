@@ -1,8 +1,8 @@
 use super::*;
 use crate::mock::*;
-use frame_support::{assert_noop, assert_ok};
+use frame_support::{assert_noop, assert_ok, StorageHasher};
 use frame_system::RawOrigin;
-use pallet_traits::StableSwap as _;
+use pallet_traits::StableSwap;
 use primitives::tokens;
 
 const MINIMUM_LIQUIDITY: u128 = 1_000;
@@ -11,20 +11,114 @@ const MINIMUM_LIQUIDITY: u128 = 1_000;
 fn create_pool_should_work() {
     new_test_ext().execute_with(|| {
         assert_ok!(DefaultStableSwap::create_pool(
-            RawOrigin::Signed(ALICE).into(), // Origin
-            (DOT, SDOT),                     // Currency pool, in which liquidity will be added
-            (1_000, 2_000),                  // Liquidity amounts to be added in pool
-            BOB,                             // LPToken receiver
-            SAMPLE_LP_TOKEN,                 // Liquidity pool share representative token
+            RawOrigin::Signed(ALICE).into(),   // Origin
+            vec![(DOT, 1_000), (SDOT, 2_000)], // Liquidity amounts to be added in pool
+            BOB,                               // LPToken receiver
+            SAMPLE_LP_TOKEN,                   // Liquidity pool share representative token
         ));
 
+        // assets are sorted from the largest id to the smallest
+        let pool = DefaultStableSwap::pools(SAMPLE_LP_TOKEN).unwrap();
+        assert_eq!(pool.assets, vec![SDOT, DOT]);
+        assert_eq!(pool.balances, vec![2_000, 1_000]);
         assert_eq!(
-            DefaultStableSwap::pools(SDOT, DOT).unwrap().base_amount,
-            2_000
+            DefaultStableSwap::pair_pools(SDOT, DOT),
+            Some(SAMPLE_LP_TOKEN)
         );
-        assert_eq!(Assets::total_issuance(SAMPLE_LP_TOKEN), 1_414);
+
+        // the liquidity issued is the invariant of the pool
+        assert_eq!(Assets::total_issuance(SAMPLE_LP_TOKEN), 2_998);
         // should be issuance minus the min liq locked
-        assert_eq!(Assets::balance(SAMPLE_LP_TOKEN, BOB), 414);
+        assert_eq!(
+            Assets::balance(SAMPLE_LP_TOKEN, BOB),
+            2_998 - MINIMUM_LIQUIDITY
+        );
+    })
+}
+
+#[test]
+fn create_pool_with_three_assets_should_work() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(DefaultStableSwap::create_pool(
+            RawOrigin::Signed(ALICE).into(),
+            vec![(DOT, 1_000_000), (SDOT, 1_000_000), (KSM, 1_000_000)],
+            ALICE,
+            SAMPLE_LP_TOKEN,
+        ));
+
+        let pool = DefaultStableSwap::pools(SAMPLE_LP_TOKEN).unwrap();
+        assert_eq!(pool.assets, vec![SDOT, DOT, KSM]);
+        assert_eq!(pool.balances, vec![1_000_000, 1_000_000, 1_000_000]);
+        assert_eq!(Assets::total_issuance(SAMPLE_LP_TOKEN), 3_000_000);
+
+        // every pair of the pool is traded by it
+        assert_eq!(
+            DefaultStableSwap::pair_pools(SDOT, DOT),
+            Some(SAMPLE_LP_TOKEN)
+        );
+        assert_eq!(
+            DefaultStableSwap::pair_pools(SDOT, KSM),
+            Some(SAMPLE_LP_TOKEN)
+        );
+        assert_eq!(
+            DefaultStableSwap::pair_pools(DOT, KSM),
+            Some(SAMPLE_LP_TOKEN)
+        );
+        let mut pairs = <DefaultStableSwap as StableSwap<_, _, _>>::get_pools().unwrap();
+        pairs.sort();
+        assert_eq!(pairs, vec![(DOT, KSM), (SDOT, KSM), (SDOT, DOT)]);
+    })
+}
+
+#[test]
+fn create_pool_with_invalid_assets_should_not_work() {
+    new_test_ext().execute_with(|| {
+        assert_noop!(
+            DefaultStableSwap::create_pool(
+                RawOrigin::Signed(ALICE).into(),
+                vec![(DOT, 1_000)],
+                ALICE,
+                SAMPLE_LP_TOKEN,
+            ),
+            Error::<Test>::InvalidNumberOfAssets
+        );
+
+        // more assets than `NumTokens`
+        assert_noop!(
+            DefaultStableSwap::create_pool(
+                RawOrigin::Signed(ALICE).into(),
+                vec![
+                    (DOT, 1_000),
+                    (SDOT, 1_000),
+                    (KSM, 1_000),
+                    (tokens::USDT, 1_000)
+                ],
+                ALICE,
+                SAMPLE_LP_TOKEN,
+            ),
+            Error::<Test>::InvalidNumberOfAssets
+        );
+
+        assert_noop!(
+            DefaultStableSwap::create_pool(
+                RawOrigin::Signed(ALICE).into(),
+                vec![(DOT, 1_000), (SDOT, 1_000), (DOT, 1_000)],
+                ALICE,
+                SAMPLE_LP_TOKEN,
+            ),
+            Error::<Test>::IdenticalAssets
+        );
+
+        // every reserve must be funded
+        assert_noop!(
+            DefaultStableSwap::create_pool(
+                RawOrigin::Signed(ALICE).into(),
+                vec![(DOT, 1_000), (SDOT, 0)],
+                ALICE,
+                SAMPLE_LP_TOKEN,
+            ),
+            Error::<Test>::InsufficientAmountIn
+        );
     })
 }
 
@@ -32,31 +126,28 @@ fn create_pool_should_work() {
 fn double_liquidity_correct_liq_ratio_should_work() {
     new_test_ext().execute_with(|| {
         assert_ok!(DefaultStableSwap::create_pool(
-            RawOrigin::Signed(ALICE).into(),              // Origin
-            (DOT, KSM), // Currency pool, in which liquidity will be added
-            (15_000_000_000_000, 50_000_000_000_000_000), // Liquidity amounts to be added in pool
-            FRANK,      // LPToken receiver
+            RawOrigin::Signed(ALICE).into(), // Origin
+            vec![(DOT, 15_000_000_000_000), (KSM, 50_000_000_000_000_000)], // Liquidity amounts to be added in pool
+            FRANK,                                                          // LPToken receiver
             SAMPLE_LP_TOKEN, // Liquidity pool share representative token
         ));
 
         // total liquidity after pool created
         let total_liquidity_tokens = Assets::total_issuance(SAMPLE_LP_TOKEN);
+        assert_eq!(total_liquidity_tokens, 28_173_572_004_314_980);
 
         assert_ok!(DefaultStableSwap::add_liquidity(
-            RawOrigin::Signed(FRANK).into(),              // Origin
-            (DOT, KSM), // Currency pool, in which liquidity will be added
-            (15_000_000_000_000, 50_000_000_000_000_000), // Liquidity amounts to be added in pool
-            (15_000_000_000_000, 50_000_000_000_000_000), // specifying its worst case ratio when pool already
+            RawOrigin::Signed(FRANK).into(), // Origin
+            SAMPLE_LP_TOKEN,                 // Pool in which liquidity will be added
+            vec![(DOT, 15_000_000_000_000), (KSM, 50_000_000_000_000_000)], // Liquidity amounts to be added in pool
+            0, // Minimum liquidity to be received
         ));
 
         let total_liquidity_tokens_after_double = Assets::total_issuance(SAMPLE_LP_TOKEN);
         let liquidity_received = total_liquidity_tokens_after_double - total_liquidity_tokens;
 
         // received liquidity should be half of total liquidity
-        assert_eq!(
-            liquidity_received as f64 / total_liquidity_tokens_after_double as f64,
-            0.6666666666666666
-        );
+        assert_eq!(liquidity_received, total_liquidity_tokens);
     })
 }
 
@@ -64,18 +155,22 @@ fn double_liquidity_correct_liq_ratio_should_work() {
 fn stable_swap_amount_out_should_work() {
     new_test_ext().execute_with(|| {
         assert_ok!(DefaultStableSwap::create_pool(
-            RawOrigin::Signed(ALICE).into(), // Origin
-            (DOT, SDOT),                     // Currency pool, in which liquidity will be added
-            (1_000_000, 1_000_000),          // Liquidity amounts to be added in pool
-            BOB,                             // LPToken receiver
-            SAMPLE_LP_TOKEN,                 // Liquidity pool share representative token
+            RawOrigin::Signed(ALICE).into(),
+            vec![(DOT, 1_000_000), (SDOT, 1_000_000)],
+            BOB,
+            SAMPLE_LP_TOKEN,
         ));
 
-        let y = DefaultStableSwap::do_get_alternative_var(10_000, (DOT, SDOT)).unwrap();
+        let amount_in = 10_000;
+        // DOT is at index 1 and SDOT at index 0
+        let mut balances = DefaultStableSwap::pools(SAMPLE_LP_TOKEN).unwrap().balances;
+        let d = DefaultStableSwap::delta_util(&balances).unwrap();
+        balances[1] += amount_in;
+        let y = DefaultStableSwap::get_y(&balances, 0, d).unwrap();
 
         let dy = 1_000_000u128.checked_sub(y).unwrap();
 
-        assert_eq!(dy, 9999);
+        assert_eq!(dy, 10_000);
     })
 }
 
@@ -83,29 +178,24 @@ fn stable_swap_amount_out_should_work() {
 fn small_stable_swap_amount_out_should_work() {
     new_test_ext().execute_with(|| {
         assert_ok!(DefaultStableSwap::create_pool(
-            RawOrigin::Signed(ALICE).into(), // Origin
-            (DOT, SDOT),                     // Currency pool, in which liquidity will be added
-            (1_000_000, 1_000_000),          // Liquidity amounts to be added in pool
-            BOB,                             // LPToken receiver
-            SAMPLE_LP_TOKEN,                 // Liquidity pool share representative token
+            RawOrigin::Signed(ALICE).into(),
+            vec![(DOT, 1_000_000), (SDOT, 1_000_000)],
+            BOB,
+            SAMPLE_LP_TOKEN,
         ));
 
         let amount_in = 10;
-        let val = 1_000_000;
-        let y = DefaultStableSwap::do_get_alternative_var(amount_in, (DOT, SDOT)).unwrap();
-
-        let dy: u128;
-
-        if val > y {
-            dy = val - y;
-        } else {
-            dy = y - val;
-        }
+        // DOT is at index 1 and SDOT at index 0
+        let mut balances = DefaultStableSwap::pools(SAMPLE_LP_TOKEN).unwrap().balances;
+        let d = DefaultStableSwap::delta_util(&balances).unwrap();
+        balances[1] += amount_in;
+        let y = DefaultStableSwap::get_y(&balances, 0, d).unwrap();
 
+        let dy = 1_000_000u128.checked_sub(y).unwrap();
         let ex_ratio = dy.checked_div(amount_in).unwrap();
 
-        assert_eq!(ex_ratio, 1); // 33202
-        assert_eq!(dy, 10); // 332027
+        assert_eq!(ex_ratio, 1);
+        assert_eq!(dy, 10);
     })
 }
 
@@ -113,21 +203,24 @@ fn small_stable_swap_amount_out_should_work() {
 fn large_stable_swap_amount_out_should_work() {
     new_test_ext().execute_with(|| {
         assert_ok!(DefaultStableSwap::create_pool(
-            RawOrigin::Signed(ALICE).into(), // Origin
-            (DOT, SDOT),                     // Currency pool, in which liquidity will be added
-            (1_000_000, 1_000_000),          // Liquidity amounts to be added in pool
-            BOB,                             // LPToken receiver
-            SAMPLE_LP_TOKEN,                 // Liquidity pool share representative token
+            RawOrigin::Signed(ALICE).into(),
+            vec![(DOT, 1_000_000), (SDOT, 1_000_000)],
+            BOB,
+            SAMPLE_LP_TOKEN,
         ));
 
         let amount_in = 999_999;
-        let y = DefaultStableSwap::do_get_alternative_var(amount_in, (DOT, SDOT)).unwrap();
+        // DOT is at index 1 and SDOT at index 0
+        let mut balances = DefaultStableSwap::pools(SAMPLE_LP_TOKEN).unwrap().balances;
+        let d = DefaultStableSwap::delta_util(&balances).unwrap();
+        balances[1] += amount_in;
+        let y = DefaultStableSwap::get_y(&balances, 0, d).unwrap();
 
         let dy = 1_000_000u128.checked_sub(y).unwrap();
         let ex_ratio = dy.checked_div(amount_in).unwrap();
 
         assert_eq!(ex_ratio, 0);
-        assert_eq!(dy, 928960);
+        assert_eq!(dy, 948_629);
     })
 }
 
@@ -135,48 +228,51 @@ fn large_stable_swap_amount_out_should_work() {
 fn unbalanced_stable_swap_amount_out_should_work() {
     new_test_ext().execute_with(|| {
         assert_ok!(DefaultStableSwap::create_pool(
-            RawOrigin::Signed(ALICE).into(), // Origin
-            (DOT, SDOT),                     // Currency pool, in which liquidity will be added
-            (10_000, 1_000_000),             // Liquidity amounts to be added in pool
-            BOB,                             // LPToken receiver
-            SAMPLE_LP_TOKEN,                 // Liquidity pool share representative token
+            RawOrigin::Signed(ALICE).into(),
+            vec![(DOT, 10_000), (SDOT, 1_000_000)],
+            BOB,
+            SAMPLE_LP_TOKEN,
         ));
 
         let amount_in = 500;
-        let y = DefaultStableSwap::do_get_alternative_var(amount_in, (DOT, SDOT)).unwrap();
-        // y = 1048189
-        // TODO: Fix this scenario since it returns more value
-        // Correct Test
-        let dy = y.checked_sub(1_000_000u128).unwrap();
+        // DOT is at index 1 and SDOT at index 0
+        let mut balances = DefaultStableSwap::pools(SAMPLE_LP_TOKEN).unwrap().balances;
+        let d = DefaultStableSwap::delta_util(&balances).unwrap();
+        balances[1] += amount_in;
+        let y = DefaultStableSwap::get_y(&balances, 0, d).unwrap();
+
+        assert_eq!(y, 996_698);
+        let dy = 1_000_000u128.checked_sub(y).unwrap();
         let ex_ratio = dy.checked_div(amount_in).unwrap();
 
-        assert_eq!(ex_ratio, 96);
-        assert_eq!(dy, 48189);
+        assert_eq!(ex_ratio, 6);
+        assert_eq!(dy, 3_302);
     })
 }
 
 #[test]
 fn unbalanced_small_stable_swap_amount_out_should_work() {
-    // y = 1051916
     new_test_ext().execute_with(|| {
         assert_ok!(DefaultStableSwap::create_pool(
-            RawOrigin::Signed(ALICE).into(), // Origin
-            (DOT, SDOT),                     // Currency pool, in which liquidity will be added
-            (10_000, 1_000_000),             // Liquidity amounts to be added in pool
-            BOB,                             // LPToken receiver
-            SAMPLE_LP_TOKEN,                 // Liquidity pool share representative token
+            RawOrigin::Signed(ALICE).into(),
+            vec![(DOT, 10_000), (SDOT, 1_000_000)],
+            BOB,
+            SAMPLE_LP_TOKEN,
         ));
 
         let amount_in = 162;
-        let y = DefaultStableSwap::do_get_alternative_var(amount_in, (DOT, SDOT)).unwrap();
-        assert_eq!(y, 1_051_916);
-        let dy = y.checked_sub(1_000_000u128).unwrap();
+        // DOT is at index 1 and SDOT at index 0
+        let mut balances = DefaultStableSwap::pools(SAMPLE_LP_TOKEN).unwrap().balances;
+        let d = DefaultStableSwap::delta_util(&balances).unwrap();
+        balances[1] += amount_in;
+        let y = DefaultStableSwap::get_y(&balances, 0, d).unwrap();
+
+        assert_eq!(y, 998_901);
+        let dy = 1_000_000u128.checked_sub(y).unwrap();
         let ex_ratio = dy.checked_div(amount_in).unwrap();
 
-        // assert_eq!(ex_ratio, 10);
-        assert_eq!(ex_ratio, 320);
-        // assert_eq!(dy, 1720);
-        assert_eq!(dy, 51916)
+        assert_eq!(ex_ratio, 6);
+        assert_eq!(dy, 1_099);
     })
 }
 
@@ -184,23 +280,24 @@ fn unbalanced_small_stable_swap_amount_out_should_work() {
 fn close_unbalanced_small_stable_swap_amount_out_should_work() {
     new_test_ext().execute_with(|| {
         assert_ok!(DefaultStableSwap::create_pool(
-            RawOrigin::Signed(ALICE).into(), // Origin
-            (DOT, SDOT),                     // Currency pool, in which liquidity will be added
-            (900_000, 1_000_000),            // Liquidity amounts to be added in pool
-            BOB,                             // LPToken receiver
-            SAMPLE_LP_TOKEN,                 // Liquidity pool share representative token
+            RawOrigin::Signed(ALICE).into(),
+            vec![(DOT, 900_000), (SDOT, 1_000_000)],
+            BOB,
+            SAMPLE_LP_TOKEN,
         ));
 
         let amount_in = 10_000;
-        let y = DefaultStableSwap::do_get_alternative_var(amount_in, (DOT, SDOT)).unwrap();
+        // DOT is at index 1 and SDOT at index 0
+        let mut balances = DefaultStableSwap::pools(SAMPLE_LP_TOKEN).unwrap().balances;
+        let d = DefaultStableSwap::delta_util(&balances).unwrap();
+        balances[1] += amount_in;
+        let y = DefaultStableSwap::get_y(&balances, 0, d).unwrap();
 
         let dy = 1_000_000u128.checked_sub(y).unwrap();
         let ex_ratio = dy.checked_div(amount_in).unwrap();
 
-        // assert_eq!(ex_ratio, 1);
-        assert_eq!(ex_ratio, 0);
-        // assert_eq!(dy, 10012);
-        assert_eq!(dy, 9997);
+        assert_eq!(ex_ratio, 1);
+        assert_eq!(dy, 10_006);
     })
 }
 
@@ -208,24 +305,24 @@ fn close_unbalanced_small_stable_swap_amount_out_should_work() {
 fn add_liquidity_with_variant_should_work() {
     new_test_ext().execute_with(|| {
         assert_ok!(DefaultStableSwap::create_pool(
-            RawOrigin::Signed(ALICE).into(), // Origin
-            (DOT, SDOT),                     // Currency pool, in which liquidity will be added
-            (1_000, 2_000),                  // Liquidity amounts to be added in pool
-            ALICE,                           // LPToken receiver
-            SAMPLE_LP_TOKEN,                 // Liquidity pool share representative token
+            RawOrigin::Signed(ALICE).into(),
+            vec![(DOT, 1_000), (SDOT, 2_000)],
+            ALICE,
+            SAMPLE_LP_TOKEN,
         ));
-        assert_eq!(Assets::total_issuance(SAMPLE_LP_TOKEN), 1_414);
+        assert_eq!(Assets::total_issuance(SAMPLE_LP_TOKEN), 2_998);
+
         assert_ok!(DefaultStableSwap::add_liquidity(
-            RawOrigin::Signed(ALICE).into(), // Origin
-            (DOT, SDOT),                     // Currency pool, in which liquidity will be added
-            (1_000_000, 2_000_000),          // Liquidity amounts to be added in pool
-            (5, 5),                          // specifying its worst case ratio when pool already
+            RawOrigin::Signed(ALICE).into(),
+            SAMPLE_LP_TOKEN,
+            vec![(DOT, 1_000_000), (SDOT, 2_000_000)],
+            0,
         ));
-        // assert_eq!(Assets::total_issuance(SAMPLE_LP_TOKEN), 1414390653);
-        assert_eq!(Assets::total_issuance(SAMPLE_LP_TOKEN), 1415842255);
+
+        assert_eq!(Assets::total_issuance(SAMPLE_LP_TOKEN), 3_001_903);
         assert_eq!(
-            DefaultStableSwap::pools(SDOT, DOT).unwrap().base_amount,
-            2002000
+            DefaultStableSwap::pools(SAMPLE_LP_TOKEN).unwrap().balances,
+            vec![2_002_000, 1_001_000]
         );
     })
 }
@@ -234,137 +331,228 @@ fn add_liquidity_with_variant_should_work() {
 fn add_liquidity_should_work() {
     new_test_ext().execute_with(|| {
         assert_ok!(DefaultStableSwap::create_pool(
-            RawOrigin::Signed(ALICE).into(), // Origin
-            (DOT, SDOT),                     // Currency pool, in which liquidity will be added
-            (1_000, 2_000),                  // Liquidity amounts to be added in pool
-            ALICE,                           // LPToken receiver
-            SAMPLE_LP_TOKEN,                 // Liquidity pool share representative token
+            RawOrigin::Signed(ALICE).into(),
+            vec![(DOT, 1_000), (SDOT, 2_000)],
+            ALICE,
+            SAMPLE_LP_TOKEN,
         ));
+
         assert_ok!(DefaultStableSwap::add_liquidity(
-            RawOrigin::Signed(ALICE).into(), // Origin
-            (DOT, SDOT),                     // Currency pool, in which liquidity will be added
-            (1_000, 2_000),                  // Liquidity amounts to be added in pool
-            (5, 5),                          // specifying its worst case ratio when pool already
+            RawOrigin::Signed(ALICE).into(),
+            SAMPLE_LP_TOKEN,
+            vec![(SDOT, 2_000), (DOT, 1_000)],
+            2_999,
         ));
 
         assert_eq!(
-            DefaultStableSwap::pools(SDOT, DOT).unwrap().base_amount,
+            DefaultStableSwap::pools(SAMPLE_LP_TOKEN).unwrap().balances,
+            vec![4_000, 2_000]
+        );
+        assert_eq!(Assets::total_issuance(SAMPLE_LP_TOKEN), 2_998 + 2_999);
+        // the lock account is ALICE in the mock
+        assert_eq!(Assets::balance(SAMPLE_LP_TOKEN, ALICE), 2_998 + 2_999);
+        assert_eq!(Assets::balance(DOT, DefaultStableSwap::account_id()), 2_000);
+        assert_eq!(
+            Assets::balance(SDOT, DefaultStableSwap::account_id()),
             4_000
         );
     })
 }
 
 #[test]
-fn add_more_liquidity_should_work() {
+fn add_liquidity_with_a_single_asset_should_work() {
     new_test_ext().execute_with(|| {
         assert_ok!(DefaultStableSwap::create_pool(
-            RawOrigin::Signed(ALICE).into(), // Origin
-            (DOT, SDOT),                     // Currency pool, in which liquidity will be added
-            (1_000, 2_000),                  // Liquidity amounts to be added in pool
-            ALICE,                           // LPToken receiver
-            SAMPLE_LP_TOKEN                  // Liquidity pool share representative token
+            RawOrigin::Signed(ALICE).into(),
+            vec![(DOT, 1_000_000), (SDOT, 1_000_000)],
+            ALICE,
+            SAMPLE_LP_TOKEN,
         ));
 
         assert_ok!(DefaultStableSwap::add_liquidity(
-            RawOrigin::Signed(ALICE).into(), // Origin
-            (DOT, SDOT),                     // Currency pool, in which liquidity will be added
-            (3_000, 4_000),                  // Liquidity amounts to be added in pool
-            (5, 5), // specifying its worst case ratio when pool already exists
+            RawOrigin::Signed(BOB).into(),
+            SAMPLE_LP_TOKEN,
+            vec![(DOT, 10_000)],
+            0,
         ));
 
         assert_eq!(
-            DefaultStableSwap::pools(SDOT, DOT).unwrap().base_amount,
-            6_000
+            DefaultStableSwap::pools(SAMPLE_LP_TOKEN).unwrap().balances,
+            vec![1_000_000, 1_010_000]
         );
+        // the imbalance is charged the swap fees
+        assert_eq!(Assets::balance(SAMPLE_LP_TOKEN, BOB), 9_985);
         assert_eq!(
-            DefaultStableSwap::pools(SDOT, DOT).unwrap().quote_amount,
-            3_000
+            Assets::balance(DOT, BOB),
+            100_000_000_000_000_000_000 - 10_000
         );
+        assert_eq!(Assets::balance(SDOT, BOB), 100_000_000_000_000_000_000);
     })
 }
 
 #[test]
-fn add_more_liquidity_should_not_work_if_minimum_base_amount_is_higher() {
+fn add_liquidity_should_not_work_if_min_mint_amount_is_higher() {
     new_test_ext().execute_with(|| {
         assert_ok!(DefaultStableSwap::create_pool(
-            RawOrigin::Signed(ALICE).into(), // Origin
-            (DOT, SDOT),                     // Currency pool, in which liquidity will be added
-            (1_000, 2_000),                  // Liquidity amounts to be added in pool
-            ALICE,                           // LPToken receiver
-            SAMPLE_LP_TOKEN                  // Liquidity pool share representative token
+            RawOrigin::Signed(ALICE).into(),
+            vec![(DOT, 1_000_000), (SDOT, 1_000_000)],
+            ALICE,
+            SAMPLE_LP_TOKEN,
         ));
 
         assert_noop!(
             DefaultStableSwap::add_liquidity(
-                RawOrigin::Signed(ALICE).into(), // Origin
-                (DOT, SDOT),                     // Currency pool, in which liquidity will be added
-                (3_000, 4_000),                  // Liquidity amounts to be added in pool
-                (5_500, 5_00)                    // specifying its worst case ratio when pool already
+                RawOrigin::Signed(BOB).into(),
+                SAMPLE_LP_TOKEN,
+                vec![(DOT, 10_000)],
+                9_986,
             ),
-            Error::<Test>::NotAnIdealPrice // Not an ideal price ratio
+            Error::<Test>::InsufficientMintAmount
         );
     })
 }
 
 #[test]
-fn add_more_liquidity_with_low_balance_should_not_work() {
+fn add_liquidity_with_invalid_amounts_should_not_work() {
     new_test_ext().execute_with(|| {
         assert_ok!(DefaultStableSwap::create_pool(
-            RawOrigin::Signed(ALICE).into(), // Origin
-            (DOT, SDOT),                     // Currency pool, in which liquidity will be added
-            (1_000, 2_000),                  // Liquidity amounts to be added in pool
-            ALICE,                           // LPToken receiver
-            SAMPLE_LP_TOKEN                  // Liquidity pool share representative token
+            RawOrigin::Signed(ALICE).into(),
+            vec![(DOT, 1_000), (SDOT, 2_000)],
+            ALICE,
+            SAMPLE_LP_TOKEN,
         ));
 
+        assert_noop!(
+            DefaultStableSwap::add_liquidity(
+                RawOrigin::Signed(ALICE).into(),
+                SAMPLE_LP_TOKEN_2,
+                vec![(DOT, 1_000)],
+                0,
+            ),
+            Error::<Test>::PoolDoesNotExist
+        );
+        assert_noop!(
+            DefaultStableSwap::add_liquidity(
+                RawOrigin::Signed(ALICE).into(),
+                SAMPLE_LP_TOKEN,
+                vec![(KSM, 1_000)],
+                0,
+            ),
+            Error::<Test>::AssetNotInPool
+        );
+        assert_noop!(
+            DefaultStableSwap::add_liquidity(
+                RawOrigin::Signed(ALICE).into(),
+                SAMPLE_LP_TOKEN,
+                vec![(DOT, 1_000), (DOT, 1_000)],
+                0,
+            ),
+            Error::<Test>::IdenticalAssets
+        );
+        assert_noop!(
+            DefaultStableSwap::add_liquidity(
+                RawOrigin::Signed(ALICE).into(),
+                SAMPLE_LP_TOKEN,
+                vec![(DOT, 0), (SDOT, 0)],
+                0,
+            ),
+            Error::<Test>::InsufficientAmountIn
+        );
+    })
+}
+
+#[test]
+fn add_more_liquidity_should_work() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(DefaultStableSwap::create_pool(
+            RawOrigin::Signed(ALICE).into(),
+            vec![(DOT, 1_000), (SDOT, 2_000)],
+            ALICE,
+            SAMPLE_LP_TOKEN,
+        ));
+
+        // amounts in the ratio of the pool
         assert_ok!(DefaultStableSwap::add_liquidity(
-            RawOrigin::Signed(ALICE).into(), // Origin
-            (DOT, SDOT),                     // Currency pool, in which liquidity will be added
-            (3_000, 4_000),                  // Liquidity amounts to be added in pool
-            (1, 1),                          // specifying its worst case ratio when pool already
+            RawOrigin::Signed(ALICE).into(),
+            SAMPLE_LP_TOKEN,
+            vec![(DOT, 2_000), (SDOT, 4_000)],
+            5_998,
+        ));
+
+        assert_eq!(
+            DefaultStableSwap::pools(SAMPLE_LP_TOKEN).unwrap().balances,
+            vec![6_000, 3_000]
+        );
+        assert_eq!(Assets::total_issuance(SAMPLE_LP_TOKEN), 2_998 + 5_998);
+    })
+}
+
+#[test]
+fn add_more_liquidity_should_not_work_if_minimum_base_amount_is_higher() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(DefaultStableSwap::create_pool(
+            RawOrigin::Signed(ALICE).into(),
+            vec![(DOT, 1_000), (SDOT, 2_000)],
+            ALICE,
+            SAMPLE_LP_TOKEN,
         ));
 
         assert_noop!(
             DefaultStableSwap::add_liquidity(
-                RawOrigin::Signed(ALICE).into(), // Origin
-                (DOT, SDOT),                     // Currency pool, in which liquidity will be added
-                (5000_000_000, 6000_000_000),    // Liquidity amounts to be added in pool
-                (5, 5), // specifying its worst case ratio when pool already
+                RawOrigin::Signed(ALICE).into(),
+                SAMPLE_LP_TOKEN,
+                vec![(DOT, 2_000), (SDOT, 4_000)],
+                5_999,
             ),
-            pallet_assets::Error::<Test>::BalanceLow
+            Error::<Test>::InsufficientMintAmount
         );
     })
 }
 
 #[test]
-fn add_liquidity_by_another_user_should_work() {
+fn add_more_liquidity_with_low_balance_should_not_work() {
     new_test_ext().execute_with(|| {
         assert_ok!(DefaultStableSwap::create_pool(
-            RawOrigin::Signed(ALICE).into(), // Origin
-            (DOT, SDOT),                     // Currency pool, in which liquidity will be added
-            (1_000, 2_000),                  // Liquidity amounts to be added in pool
-            ALICE,                           // LPToken receiver
-            SAMPLE_LP_TOKEN                  // Liquidity pool share representative token
+            RawOrigin::Signed(ALICE).into(),
+            vec![(DOT, 1_000), (SDOT, 2_000)],
+            ALICE,
+            SAMPLE_LP_TOKEN,
         ));
 
-        assert_ok!(DefaultStableSwap::add_liquidity(
-            RawOrigin::Signed(ALICE).into(), // Origin
-            (DOT, SDOT),                     // Currency pool, in which liquidity will be added
-            (3_000, 4_000),                  // Liquidity amounts to be added in pool
-            (5, 5),                          // specifying its worst case ratio when pool already
+        assert_noop!(
+            DefaultStableSwap::add_liquidity(
+                RawOrigin::Signed(ALICE).into(),
+                SAMPLE_LP_TOKEN,
+                vec![(DOT, 5_000_000_000), (SDOT, 10_000_000_000)],
+                0,
+            ),
+            pallet_assets::Error::<Test>::BalanceLow
+        );
+    })
+}
+
+#[test]
+fn add_liquidity_by_another_user_should_work() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(DefaultStableSwap::create_pool(
+            RawOrigin::Signed(ALICE).into(),
+            vec![(DOT, 1_000), (SDOT, 2_000)],
+            ALICE,
+            SAMPLE_LP_TOKEN,
         ));
 
         assert_ok!(DefaultStableSwap::add_liquidity(
-            RawOrigin::Signed(BOB).into(), // Origin
-            (DOT, SDOT),                   // Currency pool, in which liquidity will be added
-            (500, 1_000),                  // Liquidity amounts to be added in pool
-            (5, 5),                        // specifying its worst case ratio when pool already
+            RawOrigin::Signed(BOB).into(),
+            SAMPLE_LP_TOKEN,
+            vec![(DOT, 3_000), (SDOT, 4_000)],
+            0,
         ));
 
         assert_eq!(
-            DefaultStableSwap::pools(SDOT, DOT).unwrap().base_amount,
-            7_000
+            DefaultStableSwap::pools(SAMPLE_LP_TOKEN).unwrap().balances,
+            vec![6_000, 4_000]
         );
+        assert_eq!(Assets::balance(SAMPLE_LP_TOKEN, BOB), 6_999);
     })
 }
 
@@ -372,22 +560,40 @@ fn add_liquidity_by_another_user_should_work() {
 fn cannot_create_pool_twice() {
     new_test_ext().execute_with(|| {
         assert_ok!(DefaultStableSwap::create_pool(
-            RawOrigin::Signed(ALICE).into(), // Origin
-            (DOT, SDOT),                     // Currency pool, in which liquidity will be added
-            (1_000, 2_000),                  // Liquidity amounts to be added in pool
-            ALICE,                           // LPToken receiver
-            SAMPLE_LP_TOKEN                  // Liquidity pool share representative token
+            RawOrigin::Signed(ALICE).into(),
+            vec![(DOT, 1_000), (SDOT, 2_000)],
+            ALICE,
+            SAMPLE_LP_TOKEN,
         ));
 
         assert_noop!(
             DefaultStableSwap::create_pool(
-                RawOrigin::Signed(ALICE).into(), // Origin
-                (DOT, SDOT),                     // Currency pool, in which liquidity will be added
-                (1_000, 2_000),                  // Liquidity amounts to be added in pool
-                ALICE,                           // LPToken receiver
-                SAMPLE_LP_TOKEN                  // Liquidity pool share representative token
+                RawOrigin::Signed(ALICE).into(),
+                vec![(DOT, 1_000), (KSM, 2_000)],
+                ALICE,
+                SAMPLE_LP_TOKEN,
+            ),
+            Error::<Test>::PoolAlreadyExists
+        );
+
+        // a pair can only be traded by a single pool
+        assert_noop!(
+            DefaultStableSwap::create_pool(
+                RawOrigin::Signed(ALICE).into(),
+                vec![(SDOT, 1_000), (DOT, 2_000)],
+                ALICE,
+                SAMPLE_LP_TOKEN_2,
+            ),
+            Error::<Test>::PoolAlreadyExists
+        );
+        assert_noop!(
+            DefaultStableSwap::create_pool(
+                RawOrigin::Signed(ALICE).into(),
+                vec![(DOT, 1_000), (SDOT, 1_000), (KSM, 1_000)],
+                ALICE,
+                SAMPLE_LP_TOKEN_2,
             ),
-            Error::<Test>::PoolAlreadyExists, // Pool already not exist
+            Error::<Test>::PoolAlreadyExists
         );
     })
 }
@@ -395,63 +601,55 @@ fn cannot_create_pool_twice() {
 #[test]
 fn remove_liquidity_whole_share_should_work() {
     new_test_ext().execute_with(|| {
-        // A pool with a single LP provider
-        // who deposit tokens and withdraws their whole share
-        // (most simple case)
+        assert_ok!(DefaultStableSwap::create_pool(
+            RawOrigin::Signed(ALICE).into(),
+            vec![(DOT, 1_000), (SDOT, 9_000)],
+            BOB,
+            SAMPLE_LP_TOKEN,
+        ));
 
-        let _ = DefaultStableSwap::create_pool(
-            RawOrigin::Signed(ALICE).into(), // Origin
-            (DOT, SDOT),                     // Currency pool, in which liquidity will be added
-            (1_000, 9_000),                  // Liquidity amounts to be added in pool
-            ALICE,                           // LPToken receiver
-            SAMPLE_LP_TOKEN,                 // Liquidity pool share representative token
-        );
+        assert_eq!(Assets::balance(SAMPLE_LP_TOKEN, BOB), 8_948);
 
         assert_ok!(DefaultStableSwap::remove_liquidity(
-            RawOrigin::Signed(ALICE).into(), // Origin
-            (DOT, SDOT),                     // Currency pool, in which liquidity will be removed
-            3_000 - MINIMUM_LIQUIDITY        // liquidity to be removed from user's liquidity
+            RawOrigin::Signed(BOB).into(),
+            SAMPLE_LP_TOKEN,
+            8_948
         ));
+
+        assert_eq!(Assets::balance(SAMPLE_LP_TOKEN, BOB), 0);
+        assert_eq!(Assets::total_issuance(SAMPLE_LP_TOKEN), MINIMUM_LIQUIDITY);
+        assert_eq!(
+            DefaultStableSwap::pools(SAMPLE_LP_TOKEN).unwrap().balances,
+            vec![905, 101]
+        );
     })
 }
 
 #[test]
 fn remove_liquidity_only_portion_should_work() {
     new_test_ext().execute_with(|| {
-        // A pool with a single LP provider who
-        // deposit tokens and withdraws
-        // a portion of their total shares (simple case)
-
-        let _ = DefaultStableSwap::create_pool(
-            RawOrigin::Signed(ALICE).into(), // Origin
-            (DOT, SDOT),                     // Currency pool, in which liquidity will be added
-            (1_000, 9_000),                  // Liquidity amounts to be added in pool
-            ALICE,                           // LPToken receiver
-            SAMPLE_LP_TOKEN,                 // Liquidity pool share representative token
-        );
-
-        assert_eq!(
-            DefaultStableSwap::pools(SDOT, DOT).unwrap().base_amount,
-            9_000
-        );
-        assert_eq!(
-            DefaultStableSwap::pools(SDOT, DOT).unwrap().quote_amount,
-            1_000
-        );
+        assert_ok!(DefaultStableSwap::create_pool(
+            RawOrigin::Signed(ALICE).into(),
+            vec![(DOT, 1_000), (SDOT, 9_000)],
+            BOB,
+            SAMPLE_LP_TOKEN,
+        ));
 
         assert_ok!(DefaultStableSwap::remove_liquidity(
-            RawOrigin::Signed(ALICE).into(), // Origin
-            (DOT, SDOT),                     // Currency pool, in which liquidity will be removed
-            1_500                            // Liquidity to be removed from user's liquidity
+            RawOrigin::Signed(BOB).into(),
+            SAMPLE_LP_TOKEN,
+            1_500
         ));
 
+        assert_eq!(Assets::balance(SAMPLE_LP_TOKEN, BOB), 8_948 - 1_500);
         assert_eq!(
-            DefaultStableSwap::pools(SDOT, DOT).unwrap().base_amount,
-            4_500
+            DefaultStableSwap::pools(SAMPLE_LP_TOKEN).unwrap().balances,
+            vec![9_000 - 1_357, 1_000 - 150]
         );
+        assert_eq!(Assets::balance(DOT, BOB), 100_000_000_000_000_000_000 + 150);
         assert_eq!(
-            DefaultStableSwap::pools(SDOT, DOT).unwrap().quote_amount,
-            500
+            Assets::balance(SDOT, BOB),
+            100_000_000_000_000_000_000 + 1_357
         );
     })
 }
@@ -460,24 +658,30 @@ fn remove_liquidity_only_portion_should_work() {
 fn remove_liquidity_user_more_liquidity_should_work() {
     new_test_ext().execute_with(|| {
         assert_ok!(DefaultStableSwap::create_pool(
-            RawOrigin::Signed(ALICE).into(), // Origin
-            (DOT, SDOT),                     // Currency pool, in which liquidity will be added
-            (1_000, 2_500),                  // Liquidity amounts to be added in pool
-            ALICE,                           // LPToken receiver
-            SAMPLE_LP_TOKEN                  // Liquidity pool share representative token
+            RawOrigin::Signed(ALICE).into(),
+            vec![(DOT, 1_000), (SDOT, 2_500)],
+            ALICE,
+            SAMPLE_LP_TOKEN,
         ));
         assert_ok!(DefaultStableSwap::add_liquidity(
-            RawOrigin::Signed(ALICE).into(), // Origin
-            (DOT, SDOT),                     // Currency pool, in which liquidity will be added
-            (1_500, 3_000),                  // Liquidity amounts to be added in pool
-            (5, 5),                          // specifying its worst case ratio when pool already
+            RawOrigin::Signed(ALICE).into(),
+            SAMPLE_LP_TOKEN,
+            vec![(DOT, 1_500), (SDOT, 3_000)],
+            0,
         ));
+        assert_eq!(Assets::total_issuance(SAMPLE_LP_TOKEN), 7_996);
 
         assert_ok!(DefaultStableSwap::remove_liquidity(
-            RawOrigin::Signed(ALICE).into(), // Origin
-            (DOT, SDOT),                     // Currency pool, in which liquidity will be removed
-            1_500                            // Liquidity to be removed from user's liquidity
+            RawOrigin::Signed(ALICE).into(),
+            SAMPLE_LP_TOKEN,
+            1_500
         ));
+
+        assert_eq!(Assets::balance(SAMPLE_LP_TOKEN, ALICE), 7_996 - 1_500);
+        assert_eq!(
+            DefaultStableSwap::pools(SAMPLE_LP_TOKEN).unwrap().balances,
+            vec![5_500 - 1_031, 2_500 - 468]
+        );
     })
 }
 
@@ -485,34 +689,191 @@ fn remove_liquidity_user_more_liquidity_should_work() {
 fn remove_liquidity_when_pool_does_not_exist_should_not_work() {
     new_test_ext().execute_with(|| {
         assert_noop!(
-            DefaultStableSwap::remove_liquidity(RawOrigin::Signed(ALICE).into(), (DOT, SDOT), 15),
+            DefaultStableSwap::remove_liquidity(
+                RawOrigin::Signed(ALICE).into(),
+                SAMPLE_LP_TOKEN,
+                15
+            ),
             Error::<Test>::PoolDoesNotExist
         );
     })
 }
 
 #[test]
-fn remove_liquidity_with_more_liquidity_should_not_work() {
+fn remove_liquidity_with_more_liquidity_should_not_work() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(DefaultStableSwap::create_pool(
+            RawOrigin::Signed(ALICE).into(),
+            vec![(DOT, 1_000), (SDOT, 9_000)],
+            ALICE,
+            SAMPLE_LP_TOKEN,
+        ));
+
+        assert_noop!(
+            DefaultStableSwap::remove_liquidity(
+                RawOrigin::Signed(ALICE).into(),
+                SAMPLE_LP_TOKEN,
+                30_000
+            ),
+            Error::<Test>::InsufficientLiquidity
+        );
+    })
+}
+
+#[test]
+fn remove_liquidity_one_asset_should_work() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(DefaultStableSwap::create_pool(
+            RawOrigin::Signed(ALICE).into(),
+            vec![(DOT, 1_000_000), (SDOT, 1_000_000)],
+            ALICE,
+            SAMPLE_LP_TOKEN,
+        ));
+
+        assert_ok!(DefaultStableSwap::remove_liquidity_one_asset(
+            RawOrigin::Signed(ALICE).into(),
+            SAMPLE_LP_TOKEN,
+            10_000,
+            DOT,
+            9_985,
+        ));
+
+        // the imbalance is charged the swap fees
+        assert_eq!(
+            DefaultStableSwap::pools(SAMPLE_LP_TOKEN).unwrap().balances,
+            vec![1_000_000, 1_000_000 - 9_985]
+        );
+        assert_eq!(Assets::balance(DOT, ALICE), 100_000_000 - 1_000_000 + 9_985);
+        assert_eq!(Assets::total_issuance(SAMPLE_LP_TOKEN), 2_000_000 - 10_000);
+    })
+}
+
+#[test]
+fn remove_liquidity_one_asset_should_not_work() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(DefaultStableSwap::create_pool(
+            RawOrigin::Signed(ALICE).into(),
+            vec![(DOT, 1_000_000), (SDOT, 1_000_000)],
+            ALICE,
+            SAMPLE_LP_TOKEN,
+        ));
+
+        assert_noop!(
+            DefaultStableSwap::remove_liquidity_one_asset(
+                RawOrigin::Signed(ALICE).into(),
+                SAMPLE_LP_TOKEN,
+                10_000,
+                DOT,
+                9_986,
+            ),
+            Error::<Test>::InsufficientAmountOut
+        );
+        assert_noop!(
+            DefaultStableSwap::remove_liquidity_one_asset(
+                RawOrigin::Signed(ALICE).into(),
+                SAMPLE_LP_TOKEN,
+                10_000,
+                KSM,
+                0,
+            ),
+            Error::<Test>::AssetNotInPool
+        );
+        assert_noop!(
+            DefaultStableSwap::remove_liquidity_one_asset(
+                RawOrigin::Signed(ALICE).into(),
+                SAMPLE_LP_TOKEN,
+                2_000_000,
+                DOT,
+                0,
+            ),
+            Error::<Test>::InsufficientLiquidity
+        );
+    })
+}
+
+#[test]
+fn add_and_remove_a_single_asset_of_three_should_work() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(DefaultStableSwap::create_pool(
+            RawOrigin::Signed(ALICE).into(),
+            vec![(DOT, 1_000_000), (SDOT, 1_000_000), (KSM, 1_000_000)],
+            ALICE,
+            SAMPLE_LP_TOKEN,
+        ));
+
+        assert_ok!(DefaultStableSwap::add_liquidity(
+            RawOrigin::Signed(BOB).into(),
+            SAMPLE_LP_TOKEN,
+            vec![(KSM, 10_000)],
+            0,
+        ));
+        assert_eq!(Assets::balance(SAMPLE_LP_TOKEN, BOB), 9_986);
+
+        assert_ok!(DefaultStableSwap::remove_liquidity_one_asset(
+            RawOrigin::Signed(BOB).into(),
+            SAMPLE_LP_TOKEN,
+            9_986,
+            KSM,
+            0,
+        ));
+
+        // the round trip is charged the fees twice, which are left in the pool
+        assert_eq!(Assets::balance(SAMPLE_LP_TOKEN, BOB), 0);
+        assert_eq!(Assets::balance(KSM, BOB), 100_000_000 - 10_000 + 9_974);
+        assert_eq!(
+            DefaultStableSwap::pools(SAMPLE_LP_TOKEN).unwrap().balances,
+            vec![1_000_000, 1_000_000, 1_000_026]
+        );
+    })
+}
+
+#[test]
+fn swap_any_assets_of_a_pool_should_work() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(DefaultStableSwap::create_pool(
+            RawOrigin::Signed(ALICE).into(),
+            vec![(DOT, 1_000_000), (SDOT, 1_000_000), (KSM, 1_000_000)],
+            ALICE,
+            SAMPLE_LP_TOKEN,
+        ));
+
+        assert_eq!(
+            DefaultStableSwap::get_amounts_out(10_000, vec![DOT, KSM]).unwrap(),
+            vec![10_000, 9_970]
+        );
+
+        assert_ok!(DefaultStableSwap::swap(
+            RawOrigin::Signed(BOB).into(),
+            (DOT, KSM),
+            10_000,
+            9_970,
+        ));
+
+        assert_eq!(
+            DefaultStableSwap::pools(SAMPLE_LP_TOKEN).unwrap().balances,
+            vec![1_000_000, 1_010_000, 990_030]
+        );
+        assert_eq!(
+            Assets::balance(DOT, BOB),
+            100_000_000_000_000_000_000 - 10_000
+        );
+        assert_eq!(Assets::balance(KSM, BOB), 100_000_000 + 9_970);
+    })
+}
+
+#[test]
+fn swap_should_not_work_if_insufficient_amount_out() {
     new_test_ext().execute_with(|| {
-        // A pool with a single LP provider
-        // who deposit tokens and withdraws their whole share
-        // (most simple case)
-
-        let _ = DefaultStableSwap::create_pool(
-            RawOrigin::Signed(ALICE).into(), // Origin
-            (DOT, SDOT),                     // Currency pool, in which liquidity will be added
-            (1_000, 9_000),                  // Liquidity amounts to be added in pool
-            ALICE,                           // LPToken receiver
-            SAMPLE_LP_TOKEN,                 // Liquidity pool share representative token
-        );
+        assert_ok!(DefaultStableSwap::create_pool(
+            RawOrigin::Signed(ALICE).into(),
+            vec![(DOT, 1_000_000), (SDOT, 1_000_000), (KSM, 1_000_000)],
+            ALICE,
+            SAMPLE_LP_TOKEN,
+        ));
 
         assert_noop!(
-            DefaultStableSwap::remove_liquidity(
-                RawOrigin::Signed(ALICE).into(),
-                (DOT, SDOT),
-                3_0000
-            ),
-            Error::<Test>::InsufficientLiquidity
+            DefaultStableSwap::swap(RawOrigin::Signed(BOB).into(), (DOT, KSM), 10_000, 9_971),
+            Error::<Test>::InsufficientAmountOut
         );
     })
 }
@@ -524,26 +885,12 @@ fn swap_should_work_base_to_quote() {
 
         // create pool and add liquidity
         assert_ok!(DefaultStableSwap::create_pool(
-            RawOrigin::Signed(ALICE).into(), // Origin
-            (DOT, SDOT),                     // Currency pool, in which liquidity will be added
-            (100_000_000, 100_000_000),      // Liquidity amounts to be added in pool
-            CHARLIE,                         // LPToken receiver
-            SAMPLE_LP_TOKEN,                 // Liquidity pool share representative token
+            RawOrigin::Signed(ALICE).into(),
+            vec![(DOT, 100_000_000), (SDOT, 100_000_000)],
+            CHARLIE,
+            SAMPLE_LP_TOKEN,
         ));
 
-        // SDOT is base_asset 1001
-        // DOT is quote_asset 101
-
-        // check that pool was funded correctly
-        assert_eq!(
-            DefaultStableSwap::pools(SDOT, DOT).unwrap().base_amount,
-            100_000_000
-        ); // SDOT
-        assert_eq!(
-            DefaultStableSwap::pools(SDOT, DOT).unwrap().quote_amount,
-            100_000_000
-        ); // DOT
-
         let path = vec![DOT, SDOT];
 
         let amount_in = 1_000;
@@ -554,7 +901,7 @@ fn swap_should_work_base_to_quote() {
         assert_eq!(Assets::balance(DOT, trader), 1_000_000_000);
         assert_eq!(Assets::balance(SDOT, trader), 1_000_000_000);
 
-        assert_ok!(DefaultStableSwap::swap(
+        assert_ok!(<DefaultStableSwap as StableSwap<_, _, _>>::swap(
             &trader,
             (DOT, SDOT),
             amounts_out[0]
@@ -567,7 +914,7 @@ fn swap_should_work_base_to_quote() {
 
         assert_eq!(
             Assets::balance(SDOT, trader),
-            1_000_000_000 + amounts_out[1] // 1_000_000_996
+            1_000_000_000 + amounts_out[1] // 1_000_000_997
         );
     })
 }
@@ -579,25 +926,17 @@ fn swap_should_work_different_ratio_base_to_quote() {
 
         // create pool and add liquidity
         assert_ok!(DefaultStableSwap::create_pool(
-            RawOrigin::Signed(ALICE).into(), // Origin
-            (DOT, SDOT),                     // Currency pool, in which liquidity will be added
-            (100_000_000, 50_000_000),       // Liquidity amounts to be added in pool
-            CHARLIE,                         // LPToken receiver
-            SAMPLE_LP_TOKEN,                 // Liquidity pool share representative token
+            RawOrigin::Signed(ALICE).into(),
+            vec![(DOT, 100_000_000), (SDOT, 50_000_000)],
+            CHARLIE,
+            SAMPLE_LP_TOKEN,
         ));
 
-        // SDOT is base_asset 1001
-        // DOT is quote_asset 101
-
         // check that pool was funded correctly
         assert_eq!(
-            DefaultStableSwap::pools(SDOT, DOT).unwrap().base_amount,
-            50_000_000
-        ); // SDOT
-        assert_eq!(
-            DefaultStableSwap::pools(SDOT, DOT).unwrap().quote_amount,
-            100_000_000
-        ); // DOT
+            DefaultStableSwap::pools(SAMPLE_LP_TOKEN).unwrap().balances,
+            vec![50_000_000, 100_000_000]
+        );
 
         let path = vec![DOT, SDOT];
 
@@ -609,10 +948,10 @@ fn swap_should_work_different_ratio_base_to_quote() {
         assert_eq!(Assets::balance(DOT, trader), 1_000_000_000);
         assert_eq!(Assets::balance(SDOT, trader), 1_000_000_000);
 
-        assert_ok!(DefaultStableSwap::swap(
+        assert_ok!(<DefaultStableSwap as StableSwap<_, _, _>>::swap(
             &trader,
             (DOT, SDOT),
-            amounts_out[0],
+            amounts_out[0]
         ));
 
         assert_eq!(
@@ -622,7 +961,7 @@ fn swap_should_work_different_ratio_base_to_quote() {
 
         assert_eq!(
             Assets::balance(SDOT, trader),
-            1_000_000_000 + amounts_out[1] // 1_000_000_996
+            1_000_000_000 + amounts_out[1] // 1_000_000_993
         );
     })
 }
@@ -634,25 +973,17 @@ fn swap_should_work_quote_to_base() {
 
         // create pool and add liquidity
         assert_ok!(DefaultStableSwap::create_pool(
-            RawOrigin::Signed(ALICE).into(), // Origin
-            (SDOT, DOT),                     // Currency pool, in which liquidity will be added
-            (50_000_000, 100_000_000),       // Liquidity amounts to be added in pool
-            CHARLIE,                         // LPToken receiver
-            SAMPLE_LP_TOKEN,                 // Liquidity pool share representative token
+            RawOrigin::Signed(ALICE).into(),
+            vec![(SDOT, 50_000_000), (DOT, 100_000_000)],
+            CHARLIE,
+            SAMPLE_LP_TOKEN,
         ));
 
-        // SDOT is base_asset 1001
-        // DOT is quote_asset 101
-
         // check that pool was funded correctly
         assert_eq!(
-            DefaultStableSwap::pools(SDOT, DOT).unwrap().base_amount,
-            50_000_000
-        ); // SDOT
-        assert_eq!(
-            DefaultStableSwap::pools(SDOT, DOT).unwrap().quote_amount,
-            100_000_000
-        ); // DOT
+            DefaultStableSwap::pools(SAMPLE_LP_TOKEN).unwrap().balances,
+            vec![50_000_000, 100_000_000]
+        );
 
         let path = vec![DOT, SDOT];
 
@@ -664,10 +995,10 @@ fn swap_should_work_quote_to_base() {
         assert_eq!(Assets::balance(DOT, trader), 1_000_000_000);
         assert_eq!(Assets::balance(SDOT, trader), 1_000_000_000);
 
-        assert_ok!(DefaultStableSwap::swap(
+        assert_ok!(<DefaultStableSwap as StableSwap<_, _, _>>::swap(
             &trader,
             (DOT, SDOT),
-            amounts_out[0],
+            amounts_out[0]
         ));
 
         assert_eq!(
@@ -677,7 +1008,7 @@ fn swap_should_work_quote_to_base() {
 
         assert_eq!(
             Assets::balance(SDOT, trader),
-            1_000_000_000 + amounts_out[1] // 1_000_000_996
+            1_000_000_000 + amounts_out[1] // 1_000_000_993
         );
     })
 }
@@ -687,57 +1018,25 @@ fn trade_should_work_base_to_quote_flipped_currencies_on_pool_creation() {
     new_test_ext().execute_with(|| {
         let trader = EVE;
 
-        // create pool and add liquidity
         assert_ok!(DefaultStableSwap::create_pool(
-            RawOrigin::Signed(ALICE).into(), // Origin
-            (SDOT, DOT),                     // Currency pool, in which liquidity will be added
-            (100_000_000, 100_000_000),      // Liquidity amounts to be added in pool
-            CHARLIE,                         // LPToken receiver
-            SAMPLE_LP_TOKEN,                 // Liquidity pool share representative token
+            RawOrigin::Signed(ALICE).into(),
+            vec![(SDOT, 100_000_000), (DOT, 100_000_000)],
+            CHARLIE,
+            SAMPLE_LP_TOKEN,
         ));
 
-        // SDOT is base_asset 1001
-        // DOT is quote_asset 101
-
-        // check that pool was funded correctly
-        assert_eq!(
-            DefaultStableSwap::pools(SDOT, DOT).unwrap().base_amount,
-            100_000_000
-        ); // SDOT
-        assert_eq!(
-            DefaultStableSwap::pools(SDOT, DOT).unwrap().quote_amount,
-            100_000_000
-        ); // DOT
-
-        // calculate amount out
-        assert_ok!(DefaultStableSwap::swap(&trader, (DOT, SDOT), 1_000));
-
-        // old
-        // assert_eq!(
-        //     Assets::balance(SDOT, trader),
-        //     1_000_000_000 + 996 // 1_000_000_996
-        // );
-
-        // new
-        assert_eq!(
-            Assets::balance(SDOT, trader),
-            1_000_000_000 + 997 // 1_000_000_996
-        );
+        assert_ok!(<DefaultStableSwap as StableSwap<_, _, _>>::swap(
+            &trader,
+            (DOT, SDOT),
+            1_000
+        ));
 
-        // pools values should be updated - we should have less SDOT
-        // assert_eq!(
-        //     DefaultStableSwap::pools(SDOT, DOT).unwrap().base_amount,
-        //     99_999_004
-        // );
-        assert_eq!(
-            DefaultStableSwap::pools(SDOT, DOT).unwrap().base_amount,
-            99999003
-        );
+        assert_eq!(Assets::balance(SDOT, trader), 1_000_000_000 + 997);
 
-        // pools values should be updated - we should have more DOT in the pool
+        // we should have less SDOT and more DOT in the pool
         assert_eq!(
-            DefaultStableSwap::pools(SDOT, DOT).unwrap().quote_amount,
-            100_001_000
+            DefaultStableSwap::pools(SAMPLE_LP_TOKEN).unwrap().balances,
+            vec![99_999_003, 100_001_000]
         );
     })
 }
@@ -747,96 +1046,71 @@ fn trade_should_work_quote_to_base() {
     new_test_ext().execute_with(|| {
         let trader = EVE;
 
-        // create pool and add liquidity
         assert_ok!(DefaultStableSwap::create_pool(
-            RawOrigin::Signed(ALICE).into(), // Origin
-            (DOT, SDOT),                     // Currency pool, in which liquidity will be added
-            (100_000_000, 100_000_000),      // Liquidity amounts to be added in pool
-            CHARLIE,                         // LPToken receiver
-            SAMPLE_LP_TOKEN,                 // Liquidity pool share representative token
+            RawOrigin::Signed(ALICE).into(),
+            vec![(DOT, 100_000_000), (SDOT, 100_000_000)],
+            CHARLIE,
+            SAMPLE_LP_TOKEN,
         ));
 
-        // SDOT is base_asset 1001
-        // DOT is quote_asset 101
-
-        // check that pool was funded correctly
-        assert_eq!(
-            DefaultStableSwap::pools(SDOT, DOT).unwrap().base_amount,
-            100_000_000
-        ); // SDOT
-        assert_eq!(
-            DefaultStableSwap::pools(SDOT, DOT).unwrap().quote_amount,
-            100_000_000
-        ); // DOT
-
-        // calculate amount out
-        // trade base for quote
-        assert_ok!(DefaultStableSwap::swap(&trader, (DOT, SDOT), 1_000));
-
-        // Old
-        // assert_eq!(
-        //     Assets::balance(SDOT, trader),
-        //     1_000_000_000 + 996 // 1_000_000_996
-        // );
-
-        // New
-        assert_eq!(
-            Assets::balance(SDOT, trader),
-            1_000_000_000 + 997 // 1_000_000_996
-        );
+        // trade quote for base
+        assert_ok!(DefaultStableSwap::swap(
+            RawOrigin::Signed(trader).into(),
+            (DOT, SDOT),
+            1_000,
+            0
+        ));
 
-        // we should have more DOT in the pool since were trading it for DOT
-        assert_eq!(
-            DefaultStableSwap::pools(SDOT, DOT).unwrap().quote_amount,
-            100_001_000
-        );
+        assert_eq!(Assets::balance(SDOT, trader), 1_000_000_000 + 997);
 
-        // we should have less SDOT since we traded it for SDOT
-        // assert_eq!(
-        //     DefaultStableSwap::pools(SDOT, DOT).unwrap().base_amount,
-        //     99_999_004
-        // );
+        // we should have more DOT and less SDOT in the pool
         assert_eq!(
-            DefaultStableSwap::pools(SDOT, DOT).unwrap().base_amount,
-            99_999_003
+            DefaultStableSwap::pools(SAMPLE_LP_TOKEN).unwrap().balances,
+            vec![99_999_003, 100_001_000]
         );
     })
 }
 
 #[test]
-fn trade_should_not_work_if_insufficient_amount_in() {
+fn trade_should_work_different_ratio() {
     new_test_ext().execute_with(|| {
         let trader = EVE;
 
         assert_ok!(DefaultStableSwap::create_pool(
-            RawOrigin::Signed(ALICE).into(), // Origin
-            (DOT, SDOT),                     // Currency pool, in which liquidity will be added
-            (100_000, 100_000),              // Liquidity amounts to be added in pool
-            CHARLIE,                         // LPToken receiver
-            SAMPLE_LP_TOKEN,                 // Liquidity pool share representative token
+            RawOrigin::Signed(ALICE).into(),
+            vec![(DOT, 100_000), (SDOT, 50_000)],
+            CHARLIE,
+            SAMPLE_LP_TOKEN,
         ));
 
-        // create pool and add liquidity
-        assert_ok!(DefaultStableSwap::add_liquidity(
-            RawOrigin::Signed(CHARLIE).into(), // Origin
-            (DOT, SDOT),                       // Currency pool, in which liquidity will be added
-            (100_000, 100_000),                // Liquidity amounts to be added in pool
-            (99_999, 99_999),                  // specifying its worst case ratio when pool already
+        assert_ok!(DefaultStableSwap::swap(
+            RawOrigin::Signed(trader).into(),
+            (DOT, SDOT),
+            500,
+            0
         ));
 
-        // check that pool was funded correctly
+        assert_eq!(Assets::balance(SDOT, trader), 1_000_000_000 + 497);
         assert_eq!(
-            DefaultStableSwap::pools(SDOT, DOT).unwrap().base_amount,
-            200_000
-        ); // SDOT
-        assert_eq!(
-            DefaultStableSwap::pools(SDOT, DOT).unwrap().quote_amount,
-            200_000
-        ); // DOT
+            DefaultStableSwap::pools(SAMPLE_LP_TOKEN).unwrap().balances,
+            vec![50_000 - 497, 100_000 + 500]
+        );
+    })
+}
+
+#[test]
+fn trade_should_not_work_if_insufficient_amount_in() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(DefaultStableSwap::create_pool(
+            RawOrigin::Signed(ALICE).into(),
+            vec![(DOT, 100_000), (SDOT, 100_000)],
+            CHARLIE,
+            SAMPLE_LP_TOKEN,
+        ));
 
-        // amount out is less than minimum_amount_out
+        // the fees would be rounded down to zero
         assert_noop!(
-            DefaultStableSwap::swap(&trader, (DOT, SDOT), 332),
+            DefaultStableSwap::swap(RawOrigin::Signed(EVE).into(), (DOT, SDOT), 332, 0),
             Error::<Test>::InsufficientAmountIn
         );
     })
@@ -847,52 +1121,25 @@ fn trade_should_work_flipped_currencies() {
     new_test_ext().execute_with(|| {
         let trader = EVE;
 
-        // create pool and add liquidity
         assert_ok!(DefaultStableSwap::create_pool(
-            RawOrigin::Signed(ALICE).into(), // Origin
-            (DOT, SDOT),                     // Currency pool, in which liquidity will be added
-            (100_000, 50_000),               // Liquidity amounts to be added in pool
-            CHARLIE,                         // LPToken receiver
-            SAMPLE_LP_TOKEN                  // Liquidity pool share representative token
+            RawOrigin::Signed(ALICE).into(),
+            vec![(SDOT, 50_000), (DOT, 100_000)],
+            CHARLIE,
+            SAMPLE_LP_TOKEN,
         ));
 
-        // check that pool was funded correctly
-        assert_eq!(
-            DefaultStableSwap::pools(SDOT, DOT).unwrap().quote_amount,
-            100_000
-        ); // DOT
-        assert_eq!(
-            DefaultStableSwap::pools(SDOT, DOT).unwrap().base_amount,
-            50_000
-        ); // SDOT
-
-        // calculate amount out
-        assert_ok!(DefaultStableSwap::swap(&trader, (DOT, SDOT), 500));
-        // Old
-        // assert_eq!(
-        //     Assets::balance(SDOT, trader),
-        //     1_000_000_000 + 248 //
-        // );
-        // New
-        assert_eq!(
-            Assets::balance(SDOT, trader),
-            1_000_000_000 + 502 //
-        );
+        assert_ok!(<DefaultStableSwap as StableSwap<_, _, _>>::swap(
+            &trader,
+            (DOT, SDOT),
+            500
+        ));
 
-        // pools values should be updated - we should have less DOT in the pool
-        assert_eq!(
-            DefaultStableSwap::pools(SDOT, DOT).unwrap().quote_amount,
-            100_000 + 500
-        );
+        assert_eq!(Assets::balance(SDOT, trader), 1_000_000_000 + 497);
 
-        // pools values should be updated - we should have more SDOT
-        // assert_eq!(
-        //     DefaultStableSwap::pools(SDOT, DOT).unwrap().base_amount,
-        //     50_000 - 248
-        // );
+        // we should have less SDOT and more DOT in the pool
         assert_eq!(
-            DefaultStableSwap::pools(SDOT, DOT).unwrap().base_amount,
-            49498
+            DefaultStableSwap::pools(SAMPLE_LP_TOKEN).unwrap().balances,
+            vec![50_000 - 497, 100_000 + 500]
         );
     })
 }
@@ -900,20 +1147,15 @@ fn trade_should_work_flipped_currencies() {
 #[test]
 fn trade_should_not_work_if_amount_in_is_zero() {
     new_test_ext().execute_with(|| {
-        let trader = EVE;
-
-        // create pool and add liquidity
         assert_ok!(DefaultStableSwap::create_pool(
-            RawOrigin::Signed(ALICE).into(), // Origin
-            (DOT, SDOT),                     // Currency pool, in which liquidity will be added
-            (1_000, 1_000),                  // Liquidity amounts to be added in pool
-            ALICE,                           // LPToken receiver
-            SAMPLE_LP_TOKEN                  // Liquidity pool share representative token
+            RawOrigin::Signed(ALICE).into(),
+            vec![(DOT, 1_000), (SDOT, 1_000)],
+            ALICE,
+            SAMPLE_LP_TOKEN,
         ));
 
-        // fail if amount_in is zero
         assert_noop!(
-            DefaultStableSwap::swap(&trader, (DOT, SDOT), 0),
+            DefaultStableSwap::swap(RawOrigin::Signed(EVE).into(), (DOT, SDOT), 0, 0),
             Error::<Test>::InsufficientAmountIn
         );
     })
@@ -922,64 +1164,58 @@ fn trade_should_not_work_if_amount_in_is_zero() {
 #[test]
 fn trade_should_not_work_if_pool_does_not_exist() {
     new_test_ext().execute_with(|| {
-        let trader = EVE;
-
-        // try to trade in pool with no liquidity
         assert_noop!(
-            DefaultStableSwap::swap(&trader, (DOT, SDOT), 10),
+            DefaultStableSwap::swap(RawOrigin::Signed(EVE).into(), (DOT, SDOT), 10, 0),
             Error::<Test>::PoolDoesNotExist
         );
+        assert_noop!(
+            DefaultStableSwap::swap(RawOrigin::Signed(EVE).into(), (DOT, DOT), 10, 0),
+            Error::<Test>::IdenticalAssets
+        );
     })
 }
 
 #[test]
 fn amount_out_should_work() {
     new_test_ext().execute_with(|| {
-        let amount_in = 1_000;
-        let supply_in = 100_000_000;
-        let supply_out = 100_000_000;
-
         let amount_out =
-            DefaultStableSwap::get_amount_out(amount_in, supply_in, supply_out).unwrap();
-
-        // actual value == 996.9900600091017
-        // TODO: assumes we round down to int
-        // old
-        // assert_eq!(amount_out, 996);
-        // new
+            DefaultStableSwap::get_amount_out(1_000, &[100_000_000, 100_000_000], 0, 1).unwrap();
         assert_eq!(amount_out, 997);
     })
 }
 
+#[test]
+fn amount_out_should_work_simple() {
+    new_test_ext().execute_with(|| {
+        let amount_out =
+            DefaultStableSwap::get_amount_out(1_000_000, &[1_000_000_000, 1_000_000_000], 0, 1)
+                .unwrap();
+        assert_eq!(amount_out, 996_994);
+    })
+}
+
 #[test]
 fn amounts_out_should_work() {
     new_test_ext().execute_with(|| {
         assert_ok!(DefaultStableSwap::create_pool(
-            RawOrigin::Signed(ALICE).into(), // Origin
-            (DOT, SDOT),                     // Currency pool, in which liquidity will be added
-            (1_000, 2_000),                  // Liquidity amounts to be added in pool
-            BOB,                             // LPToken receiver
-            SAMPLE_LP_TOKEN,                 // Liquidity pool share representative token
+            RawOrigin::Signed(ALICE).into(),
+            vec![(DOT, 1_000), (SDOT, 2_000)],
+            BOB,
+            SAMPLE_LP_TOKEN,
         ));
 
         assert_ok!(DefaultStableSwap::create_pool(
-            RawOrigin::Signed(ALICE).into(), // Origin
-            (KSM, DOT),                      // Currency pool, in which liquidity will be added
-            (1_000, 1_000),                  // Liquidity amounts to be added in pool
-            BOB,                             // LPToken receiver
-            SAMPLE_LP_TOKEN_2,               // Liquidity pool share representative token
+            RawOrigin::Signed(ALICE).into(),
+            vec![(KSM, 1_000), (DOT, 1_000)],
+            BOB,
+            SAMPLE_LP_TOKEN_2,
         ));
 
         let path = vec![SDOT, DOT, KSM];
 
-        let amount_in = 1_000;
-
-        let amounts_out = DefaultStableSwap::get_amounts_out(amount_in, path).unwrap();
-        // Old
-        // assert_eq!(amounts_out, [1000, 332, 249]);
+        let amounts_out = DefaultStableSwap::get_amounts_out(1_000, path).unwrap();
 
-        // New
-        assert_eq!(amounts_out, [1000, 998, 947]);
+        assert_eq!(amounts_out, [1_000, 923, 897]);
     })
 }
 
@@ -987,28 +1223,24 @@ fn amounts_out_should_work() {
 fn long_route_amounts_in_should_work() {
     new_test_ext().execute_with(|| {
         assert_ok!(DefaultStableSwap::create_pool(
-            RawOrigin::Signed(ALICE).into(), // Origin
-            (DOT, SDOT),                     // Currency pool, in which liquidity will be added
-            (10_000, 20_000),                // Liquidity amounts to be added in pool
-            BOB,                             // LPToken receiver
-            SAMPLE_LP_TOKEN,                 // Liquidity pool share representative token
+            RawOrigin::Signed(ALICE).into(),
+            vec![(DOT, 10_000), (SDOT, 20_000)],
+            BOB,
+            SAMPLE_LP_TOKEN,
         ));
 
         assert_ok!(DefaultStableSwap::create_pool(
-            RawOrigin::Signed(ALICE).into(), // Origin
-            (KSM, DOT),                      // Currency pool, in which liquidity will be added
-            (10_000, 10_000),                // Liquidity amounts to be added in pool
-            BOB,                             // LPToken receiver
-            SAMPLE_LP_TOKEN_2,               // Liquidity pool share representative token
+            RawOrigin::Signed(ALICE).into(),
+            vec![(KSM, 10_000), (DOT, 10_000)],
+            BOB,
+            SAMPLE_LP_TOKEN_2,
         ));
 
         let path = vec![SDOT, DOT, KSM];
 
-        let amount_out = 1_000;
-
-        let amounts_in = DefaultStableSwap::get_amounts_in(amount_out, path).unwrap();
+        let amounts_in = DefaultStableSwap::get_amounts_in(1_000, path).unwrap();
 
-        assert_eq!(amounts_in, [2517, 1115, 1000]);
+        assert_eq!(amounts_in, [1_014, 1_005, 1_000]);
     })
 }
 
@@ -1016,63 +1248,43 @@ fn long_route_amounts_in_should_work() {
 fn short_route_amounts_in_should_work() {
     new_test_ext().execute_with(|| {
         assert_ok!(DefaultStableSwap::create_pool(
-            RawOrigin::Signed(ALICE).into(), // Origin
-            (DOT, SDOT),                     // Currency pool, in which liquidity will be added
-            (10_000_000, 10_000_000),        // Liquidity amounts to be added in pool
-            BOB,                             // LPToken receiver
-            SAMPLE_LP_TOKEN,                 // Liquidity pool share representative token
+            RawOrigin::Signed(ALICE).into(),
+            vec![(DOT, 10_000_000), (SDOT, 10_000_000)],
+            BOB,
+            SAMPLE_LP_TOKEN,
         ));
 
         let path = vec![DOT, SDOT];
 
-        let amount_out = 1_000;
+        let amounts_in = DefaultStableSwap::get_amounts_in(1_000, path).unwrap();
 
-        let amounts_in = DefaultStableSwap::get_amounts_in(amount_out, path).unwrap();
-
-        assert_eq!(amounts_in, [1004, 1000]);
+        assert_eq!(amounts_in, [1_004, 1_000]);
     })
 }
 
 #[test]
 fn amount_in_should_work() {
     new_test_ext().execute_with(|| {
-        let amount_out = 1_000;
-        let supply_in = 100_000_000;
-        let supply_out = 100_000_000;
-
         let amount_in =
-            DefaultStableSwap::get_amount_in(amount_out, supply_in, supply_out).unwrap();
-
-        // actual value == 1004.0190572718165
-        // TODO: assumes we round down to int
-        assert_eq!(amount_in, 1004);
+            DefaultStableSwap::get_amount_in(1_000, &[100_000_000, 100_000_000], 0, 1).unwrap();
+        assert_eq!(amount_in, 1_004);
     })
 }
 
 #[test]
 fn amount_in_uneven_should_work() {
     new_test_ext().execute_with(|| {
-        let amount_out = 1_000;
-        let supply_in = 100_000_000;
-        let supply_out = 1_344_312_043;
-
         let amount_in =
-            DefaultStableSwap::get_amount_in(amount_out, supply_in, supply_out).unwrap();
-
-        assert_eq!(amount_in, 75);
+            DefaultStableSwap::get_amount_in(1_000, &[100_000_000, 1_344_312_043], 0, 1).unwrap();
+        assert_eq!(amount_in, 874);
     })
 }
 
 #[test]
 fn supply_out_should_larger_than_amount_out() {
-    // Test case for Panic when amount_out >= supply_out
     new_test_ext().execute_with(|| {
-        let amount_out = 100_00;
-        let supply_in = 100_000;
-        let supply_out = 100_00;
-
         assert_noop!(
-            DefaultStableSwap::get_amount_in(amount_out, supply_in, supply_out),
+            DefaultStableSwap::get_amount_in(10_000, &[100_000, 10_000], 0, 1),
             Error::<Test>::InsufficientSupplyOut
         );
     })
@@ -1081,23 +1293,13 @@ fn supply_out_should_larger_than_amount_out() {
 #[test]
 fn amount_out_and_in_should_work() {
     new_test_ext().execute_with(|| {
-        let amount_out = 1_000;
-        let supply_in = 100_000_000;
-        let supply_out = 100_000_000;
-
-        let amount_in =
-            DefaultStableSwap::get_amount_in(amount_out, supply_in, supply_out).unwrap();
-
-        assert_eq!(amount_in, 1004);
-
-        let amount_out =
-            DefaultStableSwap::get_amount_out(amount_in, supply_in, supply_out).unwrap();
+        let balances = [100_000_000, 100_000_000];
 
-        // old
-        // assert_eq!(amount_out, 1000);
+        let amount_in = DefaultStableSwap::get_amount_in(1_000, &balances, 0, 1).unwrap();
+        assert_eq!(amount_in, 1_004);
 
-        // new
-        assert_eq!(amount_out, 1001);
+        let amount_out = DefaultStableSwap::get_amount_out(amount_in, &balances, 0, 1).unwrap();
+        assert_eq!(amount_out, 1_001);
     })
 }
 
@@ -1107,134 +1309,68 @@ fn update_oracle_should_work() {
         let trader = EVE;
 
         assert_ok!(DefaultStableSwap::create_pool(
-            RawOrigin::Signed(ALICE).into(), // Origin
-            (SDOT, DOT),                     // Currency pool, in which liquidity will be added
-            (100_000, 100_000),              // Liquidity amounts to be added in pool
-            BOB,                             // LPToken receiver
-            SAMPLE_LP_TOKEN,                 // Liquidity pool share representative token
+            RawOrigin::Signed(ALICE).into(),
+            vec![(SDOT, 100_000), (DOT, 100_000)],
+            BOB,
+            SAMPLE_LP_TOKEN,
         ));
 
-        assert_eq!(
-            DefaultStableSwap::pools(SDOT, DOT)
-                .unwrap()
-                .block_timestamp_last,
-            0
-        );
-        assert_eq!(
-            DefaultStableSwap::pools(SDOT, DOT)
-                .unwrap()
-                .price_0_cumulative_last,
-            0
-        );
-        assert_eq!(
-            DefaultStableSwap::pools(SDOT, DOT)
-                .unwrap()
-                .price_1_cumulative_last,
-            0
-        );
+        let pool = DefaultStableSwap::pools(SAMPLE_LP_TOKEN).unwrap();
+        assert_eq!(pool.block_timestamp_last, 0);
+        assert_eq!(pool.price_cumulative_last, vec![0, 0]);
 
         run_to_block(2);
 
-        assert_ok!(DefaultStableSwap::swap(&trader, (DOT, SDOT), 1_000));
+        assert_ok!(DefaultStableSwap::swap(
+            RawOrigin::Signed(trader).into(),
+            (DOT, SDOT),
+            1_000,
+            0
+        ));
 
+        let pool = DefaultStableSwap::pools(SAMPLE_LP_TOKEN).unwrap();
+        assert_eq!(pool.block_timestamp_last, 2);
         assert_eq!(
-            DefaultStableSwap::pools(SDOT, DOT)
-                .unwrap()
-                .block_timestamp_last,
-            2
-        );
-        // old
-        // assert_eq!(
-        //     DefaultStableSwap::pools(SDOT, DOT)
-        //         .unwrap()
-        //         .price_0_cumulative_last,
-        //     2_040136143738700978
-        // );
-
-        // new
-        assert_eq!(
-            DefaultStableSwap::pools(SDOT, DOT)
-                .unwrap()
-                .price_0_cumulative_last,
-            2_040342211852166095
-        );
-        assert_eq!(
-            DefaultStableSwap::pools(SDOT, DOT)
-                .unwrap()
-                .price_1_cumulative_last,
-            1_960455445544554455
+            pool.price_cumulative_last,
+            vec![2_040342211852166095, 1_960455445544554455]
         );
 
         run_to_block(4);
 
-        assert_ok!(DefaultStableSwap::swap(&trader, (DOT, SDOT), 1_000));
+        assert_ok!(DefaultStableSwap::swap(
+            RawOrigin::Signed(trader).into(),
+            (DOT, SDOT),
+            1_000,
+            0
+        ));
 
+        let pool = DefaultStableSwap::pools(SAMPLE_LP_TOKEN).unwrap();
+        assert_eq!(pool.block_timestamp_last, 4);
         assert_eq!(
-            DefaultStableSwap::pools(SDOT, DOT)
-                .unwrap()
-                .block_timestamp_last,
-            4
-        );
-        // assert_eq!(
-        //     DefaultStableSwap::pools(SDOT, DOT)
-        //         .unwrap()
-        //         .price_0_cumulative_last,
-        //     4_120792162342213614
-        // );
-        assert_eq!(
-            DefaultStableSwap::pools(SDOT, DOT)
-                .unwrap()
-                .price_0_cumulative_last,
-            4_121868664584169564
-        );
-        // assert_eq!(
-        //     DefaultStableSwap::pools(SDOT, DOT)
-        //         .unwrap()
-        //         .price_1_cumulative_last,
-        //     3_883124053581828770
-        // );
-        assert_eq!(
-            DefaultStableSwap::pools(SDOT, DOT)
-                .unwrap()
-                .price_1_cumulative_last,
-            3_882122112211221121
+            pool.price_cumulative_last,
+            vec![4_121868664584169564, 3_882122112211221121]
         );
     })
 }
 
-// TODO: Fix this scenario
-
 #[test]
 fn oracle_big_block_no_overflow() {
     new_test_ext().execute_with(|| {
         let trader = FRANK;
 
         assert_ok!(DefaultStableSwap::create_pool(
-            RawOrigin::Signed(ALICE).into(),                     // Origin
-            (DOT, KSM), // Currency pool, in which liquidity will be added
-            (9_999_650_729_873_433, 30_001_051_000_000_000_000), // Liquidity amounts to be added in pool
-            FRANK,                                               // LPToken receiver
-            SAMPLE_LP_TOKEN, // Liquidity pool share representative token
+            RawOrigin::Signed(ALICE).into(),
+            vec![
+                (DOT, 9_999_650_729_873_433),
+                (KSM, 30_001_051_000_000_000_000)
+            ],
+            FRANK,
+            SAMPLE_LP_TOKEN,
         ));
 
-        assert_eq!(
-            DefaultStableSwap::pools(DOT, KSM)
-                .unwrap()
-                .block_timestamp_last,
-            0
-        );
-        assert_eq!(
-            DefaultStableSwap::pools(DOT, KSM)
-                .unwrap()
-                .price_0_cumulative_last,
-            0
-        );
-        assert_eq!(
-            DefaultStableSwap::pools(DOT, KSM)
-                .unwrap()
-                .price_1_cumulative_last,
-            0
-        );
+        let pool = DefaultStableSwap::pools(SAMPLE_LP_TOKEN).unwrap();
+        assert_eq!(pool.block_timestamp_last, 0);
+        assert_eq!(pool.price_cumulative_last, vec![0, 0]);
 
         let mut big_block = 30_000;
         run_to_block(big_block);
@@ -1242,27 +1378,19 @@ fn oracle_big_block_no_overflow() {
         for _ in 0..5 {
             big_block += 1000;
             run_to_block(big_block);
-            assert_ok!(DefaultStableSwap::swap(&trader, (DOT, KSM), 1000));
+            assert_ok!(DefaultStableSwap::swap(
+                RawOrigin::Signed(trader).into(),
+                (DOT, KSM),
+                1000,
+                0
+            ));
         }
 
+        let pool = DefaultStableSwap::pools(SAMPLE_LP_TOKEN).unwrap();
+        assert_eq!(pool.block_timestamp_last, big_block);
         assert_eq!(
-            DefaultStableSwap::pools(DOT, KSM)
-                .unwrap()
-                .block_timestamp_last,
-            big_block
-        );
-
-        assert_eq!(
-            DefaultStableSwap::pools(DOT, KSM)
-                .unwrap()
-                .price_0_cumulative_last,
-            104962346092892538490488111 //105007346_092879071079611686
-        );
-        assert_eq!(
-            DefaultStableSwap::pools(DOT, KSM)
-                .unwrap()
-                .price_1_cumulative_last,
-            11670852942309388101 // 11_665850491226458031
+            pool.price_cumulative_last,
+            vec![105007346_092888516557714015, 11_665850491225408680]
         );
 
         // increment a block
@@ -1270,16 +1398,17 @@ fn oracle_big_block_no_overflow() {
         run_to_block(big_block);
 
         // this would swap used to overflow
-        assert_ok!(DefaultStableSwap::swap(&trader, (DOT, KSM), 10_000_000_000));
+        assert_ok!(DefaultStableSwap::swap(
+            RawOrigin::Signed(trader).into(),
+            (DOT, KSM),
+            10_000_000_000,
+            0
+        ));
     })
 }
 
 #[test]
 fn create_pool_large_amount_should_work() {
-    /*
-    With ample supplies
-    Recheck values
-    */
     new_test_ext().execute_with(|| {
         Assets::mint(
             RawOrigin::Signed(ALICE).into(),
@@ -1297,36 +1426,33 @@ fn create_pool_large_amount_should_work() {
         .ok();
 
         assert_ok!(DefaultStableSwap::create_pool(
-            RawOrigin::Signed(ALICE).into(),                            // Origin
-            (DOT, SDOT), // Currency pool, in which liquidity will be added
-            (1_000_000_000_000_000_000, 2_000_000_000_000_000_000_000), // Liquidity amounts to be added in pool
-            ALICE,                                                      // LPToken receiver
-            SAMPLE_LP_TOKEN, // Liquidity pool share representative token
+            RawOrigin::Signed(ALICE).into(),
+            vec![
+                (DOT, 1_000_000_000_000_000_000),
+                (SDOT, 2_000_000_000_000_000_000_000)
+            ],
+            ALICE,
+            SAMPLE_LP_TOKEN,
         ));
 
         assert_eq!(
-            DefaultStableSwap::pools(SDOT, DOT).unwrap().base_amount,
-            2_000_000_000_000_000_000_000
+            DefaultStableSwap::pools(SAMPLE_LP_TOKEN).unwrap().balances,
+            vec![2_000_000_000_000_000_000_000, 1_000_000_000_000_000_000]
         );
         assert_eq!(
             Assets::total_issuance(SAMPLE_LP_TOKEN),
-            447_213_595_499_957_939_28
+            1_263_368_794_268_672_775_924
         );
-        // should be issuance minus the min liq locked
+        // the lock account is ALICE in the mock
         assert_eq!(
             Assets::balance(SAMPLE_LP_TOKEN, ALICE),
-            447_213_595_499_957_939_28
+            1_263_368_794_268_672_775_924
         );
     })
 }
 
 #[test]
 fn create_pool_large_amount_from_an_account_without_sufficient_amount_of_tokens_should_not_panic() {
-    /*
-    With ample supplies for Alice and less for Bob :'(
-    `create_pool` with Large amount panic for Bob
-    Recheck values
-    */
     new_test_ext().execute_with(|| {
         Assets::mint(
             RawOrigin::Signed(ALICE).into(),
@@ -1343,37 +1469,31 @@ fn create_pool_large_amount_from_an_account_without_sufficient_amount_of_tokens_
         )
         .ok();
 
-        // Creating for BOB
-        // This Panics!
+        // BOB funds the pool and has less than the amounts
         assert_noop!(
             DefaultStableSwap::create_pool(
-                RawOrigin::Signed(ALICE).into(),                            // Origin
-                (DOT, SDOT), // Currency pool, in which liquidity will be added
-                (1_000_000_000_000_000_000, 2_000_000_000_000_000_000_000), // Liquidity amounts to be added in pool
-                BOB,                                                        // LPToken receiver
-                SAMPLE_LP_TOKEN, // Liquidity pool share representative token
+                RawOrigin::Signed(ALICE).into(),
+                vec![
+                    (DOT, 1_000_000_000_000_000_000),
+                    (SDOT, 2_000_000_000_000_000_000_000)
+                ],
+                BOB,
+                SAMPLE_LP_TOKEN,
             ),
             pallet_assets::Error::<Test>::BalanceLow
         );
     })
 }
 
-#[ignore]
 #[test]
 fn do_add_liquidity_exact_amounts_should_work() {
-    /*
-    substrate->frame->assets->src->functions.rs
-    ensure!(f.best_effort || actual >= amount, Error::<T, I>::BalanceLow);   // Fails here
-    replica of `add_liquidity_should_work` with larger values
-    Loss of precision?
-    */
     new_test_ext().execute_with(|| {
         // Already deposited 100000000
         Assets::mint(
             RawOrigin::Signed(ALICE).into(),
             tokens::DOT.into(),
             ALICE,
-            999_999_999_999_900_000_000,
+            1_999_999_999_900_000_000,
         )
         .ok();
 
@@ -1382,37 +1502,40 @@ fn do_add_liquidity_exact_amounts_should_work() {
             RawOrigin::Signed(ALICE).into(),
             tokens::SDOT.into(),
             ALICE,
-            199_999_999_999_990_000_000_0,
+            3_999_999_999_999_900_000_000,
         )
         .ok();
 
         assert_ok!(DefaultStableSwap::create_pool(
-            RawOrigin::Signed(ALICE).into(),                            // Origin
-            (DOT, SDOT), // Currency pool, in which liquidity will be added
-            (1_000_000_000_000_000_000, 2_000_000_000_000_000_000_000), // Liquidity amounts to be added in pool
-            ALICE,                                                      // LPToken receiver
-            SAMPLE_LP_TOKEN, // Liquidity pool share representative token
+            RawOrigin::Signed(ALICE).into(),
+            vec![
+                (DOT, 1_000_000_000_000_000_000),
+                (SDOT, 2_000_000_000_000_000_000_000)
+            ],
+            ALICE,
+            SAMPLE_LP_TOKEN,
         ));
         assert_ok!(DefaultStableSwap::add_liquidity(
-            RawOrigin::Signed(ALICE).into(),                            // Origin
-            (DOT, SDOT), // Currency pool, in which liquidity will be added
-            (1_000_000_000_000_000_000, 2_000_000_000_000_000_000_000), // Liquidity amounts to be added in pool
-            (5, 5), // specifying its worst case ratio when pool already
+            RawOrigin::Signed(ALICE).into(),
+            SAMPLE_LP_TOKEN,
+            vec![
+                (DOT, 1_000_000_000_000_000_000),
+                (SDOT, 2_000_000_000_000_000_000_000)
+            ],
+            0,
         ));
 
         assert_eq!(
-            DefaultStableSwap::pools(SDOT, DOT).unwrap().base_amount,
-            4_000
+            DefaultStableSwap::pools(SAMPLE_LP_TOKEN).unwrap().balances,
+            vec![4_000_000_000_000_000_000_000, 2_000_000_000_000_000_000]
         );
+        assert_eq!(Assets::balance(DOT, ALICE), 100_000_000);
+        assert_eq!(Assets::balance(SDOT, ALICE), 100_000_000);
     })
 }
 
 #[test]
 fn do_add_liquidity_large_amounts_should_work() {
-    /*
-    With ample supplies
-     */
-
     new_test_ext().execute_with(|| {
         Assets::mint(
             RawOrigin::Signed(ALICE).into(),
@@ -1430,15 +1553,19 @@ fn do_add_liquidity_large_amounts_should_work() {
         .ok();
 
         assert_ok!(DefaultStableSwap::create_pool(
-            RawOrigin::Signed(ALICE).into(), // Origin
-            (DOT, SDOT),                     // Currency pool, in which liquidity will be added
-            (
-                1_000_000_000_000_000_000_000, // Either base amount or quote amount
-                2_000_000_000_000_000_000_000
-            ), // Liquidity amounts to be added in pool
-            ALICE,                           // LPToken receiver
-            SAMPLE_LP_TOKEN,                 // Liquidity pool share representative token
+            RawOrigin::Signed(ALICE).into(),
+            vec![
+                (DOT, 1_000_000_000_000_000_000_000),
+                (SDOT, 2_000_000_000_000_000_000_000)
+            ],
+            ALICE,
+            SAMPLE_LP_TOKEN,
         ));
+
+        assert_eq!(
+            Assets::total_issuance(SAMPLE_LP_TOKEN),
+            2_998_904_705_806_604_476_130
+        );
     })
 }
 
@@ -1446,93 +1573,55 @@ fn do_add_liquidity_large_amounts_should_work() {
 fn handling_fees_should_work() {
     new_test_ext().execute_with(|| {
         // Pool gets created and BOB should receive all of the LP tokens (minus the min amount)
-        // Created Pool for Bob
         assert_ok!(DefaultStableSwap::create_pool(
-            RawOrigin::Signed(ALICE).into(),    // Origin
-            (DOT, SDOT),                        // Currency pool, in which liquidity will be added
-            (100_000_000_000, 100_000_000_000), // Liquidity amounts to be added in pool
-            BOB,                                // LPToken receiver
-            SAMPLE_LP_TOKEN                     // Liquidity pool share representative token
+            RawOrigin::Signed(ALICE).into(),
+            vec![(DOT, 100_000_000_000), (SDOT, 100_000_000_000)],
+            BOB,
+            SAMPLE_LP_TOKEN
         ));
 
         // Another user makes a swap that should generate fees for the LP provider and the protocol
-        assert_ok!(DefaultStableSwap::swap(&FRANK, (DOT, SDOT), 6_000_000));
-        assert_eq!(Assets::balance(SDOT, FRANK), 5_981_998); // 18_002
+        assert_ok!(DefaultStableSwap::swap(
+            RawOrigin::Signed(FRANK).into(),
+            (DOT, SDOT),
+            6_000_000,
+            0
+        ));
+        assert_eq!(Assets::balance(SDOT, FRANK), 5_981_998);
 
-        // we can check the total balance
-        //
         // no extra fees should be minted because liquid has not been added or removed
-        //
-        assert_eq!(Assets::total_issuance(SAMPLE_LP_TOKEN), 100_000_000_000);
-
-        // bob should have all of the fees minus the min amount burned/locked
+        assert_eq!(Assets::total_issuance(SAMPLE_LP_TOKEN), 200_000_000_000);
         assert_eq!(
             Assets::balance(SAMPLE_LP_TOKEN, BOB),
-            100_000_000_000 - MINIMUM_LIQUIDITY
+            200_000_000_000 - MINIMUM_LIQUIDITY
         );
 
         // now we withdraw the fees and at this point we should mint tokens
         // for the protocol proportional to 1/6 of the total fees generated
-
-        // we know that 18_000 fees should be collected and ~3_000 are for the protocol
-        let total_fees_collected = 6_000_000.0 * 0.003;
-        let fees_to_be_collected_by_protocol = total_fees_collected * (1.0 / 6.0);
-        assert_eq!(fees_to_be_collected_by_protocol, 3000.0);
-
-        // expand the math to calculate exact amount of fees to dilute lp total supply
-        let prop_of_total_fee = 1.0 / 6.0;
-        let scalar = (1.0 / prop_of_total_fee) - 1.0;
-        assert_eq!(scalar, 5.0);
-
-        let total_lp_token_supply = 100_000_000_000.0;
-        let old_root_k =
-            DefaultStableSwap::delta_util(100_000_000_000, 100_000_000_000).unwrap() as f64;
-        let new_root_k =
-            DefaultStableSwap::delta_util(100_000_000_000 - 5_981_998, 100_000_000_000 + 6_000_000)
-                .unwrap() as f64;
-        let root_k_growth = new_root_k - old_root_k;
-
-        let numerator = total_lp_token_supply * root_k_growth;
-        let denominator = new_root_k * scalar + old_root_k;
-        let rewards_to_mint = numerator / denominator;
-
-        assert_eq!(old_root_k, 200_000_000_000.0); // 200_000_000_000
-        assert_eq!(new_root_k, 200_000_017_999.0); // 200_000_017_999
-        assert_eq!(root_k_growth, 17_999.0); // 17_999
-        assert_eq!(numerator, 1_799_900_000_000_000.0); // 1_799_900_000_000_000
-        assert_eq!(denominator, 1_200_000_089_995.0); // 1_200_000_089_995
-        assert_eq!(rewards_to_mint, 1499.9165541791747); // 1499
+        let old_d = DefaultStableSwap::delta_util(&[100_000_000_000, 100_000_000_000]).unwrap();
+        let new_d = DefaultStableSwap::delta_util(&[
+            100_000_000_000 - 5_981_998,
+            100_000_000_000 + 6_000_000,
+        ])
+        .unwrap();
+        assert_eq!(old_d, 200_000_000_000);
+        assert_eq!(new_d, 200_000_017_999);
+
+        // supply * (new_d - old_d) / (new_d * 5 + old_d)
+        let scalar = 5;
+        let rewards_to_mint = 200_000_000_000 * (new_d - old_d) / (new_d * scalar + old_d);
+        assert_eq!(rewards_to_mint, 2_999);
 
         assert_ok!(DefaultStableSwap::remove_liquidity(
             RawOrigin::Signed(PROTOCOL_FEE_RECEIVER).into(),
-            (DOT, SDOT),
-            1_499,
+            SAMPLE_LP_TOKEN,
+            2_999,
         ));
 
         // PROTOCOL_FEE_RECEIVER should have slightly less then 3_000 total rewards
-        // split between the two pools - the small difference is due to rounding errors
-        assert_eq!(Assets::balance(DOT, PROTOCOL_FEE_RECEIVER), 1499);
-        assert_eq!(Assets::balance(SDOT, PROTOCOL_FEE_RECEIVER), 1498);
-    })
-}
-
-#[test]
-fn amount_out_should_work_simple() {
-    new_test_ext().execute_with(|| {
-        let amount_in = 1_000_000;
-        let supply_in = 1_000_000_000;
-        let supply_out = 1_000_000_000;
-
-        // assert_eq!(amount_in, 1004);
-
-        let amount_out =
-            DefaultStableSwap::get_amount_out(amount_in, supply_in, supply_out).unwrap();
-
-        // old
-        // assert_eq!(amount_out, 1000);
-
-        // new
-        assert_eq!(amount_out, 996_995); // currently 999_995
+        // split between the two assets - the small difference is due to rounding errors
+        assert_eq!(Assets::balance(DOT, PROTOCOL_FEE_RECEIVER), 1_499);
+        assert_eq!(Assets::balance(SDOT, PROTOCOL_FEE_RECEIVER), 1_499);
     })
 }
 
@@ -1540,37 +1629,74 @@ fn amount_out_should_work_simple() {
 fn swap_stable_tokens() {
     new_test_ext().execute_with(|| {
         assert_ok!(DefaultStableSwap::create_pool(
-            RawOrigin::Signed(ALICE).into(), // Origin
-            (DOT, SDOT),                     // Currency pool, in which liquidity will be added
-            (1000000, 1000000),              // Liquidity amounts to be added in pool
-            ALICE,                           // LPToken receiver
-            SAMPLE_LP_TOKEN,                 // Liquidity pool share representative token
+            RawOrigin::Signed(ALICE).into(),
+            vec![(DOT, 1_000_000), (SDOT, 1_000_000)],
+            ALICE,
+            SAMPLE_LP_TOKEN,
         ));
 
-        let amount_in = 1000;
         let trader = EVE;
 
-        let bal_dot_before = Assets::balance(DOT, trader);
-        let bal_sdot_before = Assets::balance(SDOT, trader);
-
-        assert_eq!(bal_dot_before, 1_000000000);
-        assert_eq!(bal_sdot_before, 1_000000000);
-        // println!("DOT Balance Before\t{:?}", bal_dot_before);
-        // println!("SDOT Balance Before\t{:?}", bal_sdot_before);
+        assert_eq!(Assets::balance(DOT, trader), 1_000_000_000);
+        assert_eq!(Assets::balance(SDOT, trader), 1_000_000_000);
 
         // Swapping 1000 DOTs to SDOTs
-        assert_ok!(DefaultStableSwap::swap(&trader, (DOT, SDOT), amount_in));
-
-        let bal_dot_after = Assets::balance(DOT, trader);
-        let bal_sdot_after = Assets::balance(SDOT, trader);
-
-        assert_eq!(bal_dot_after, 999999000);
-        assert_eq!(bal_sdot_after, 1000000997);
+        assert_ok!(DefaultStableSwap::swap(
+            RawOrigin::Signed(trader).into(),
+            (DOT, SDOT),
+            1_000,
+            0
+        ));
 
-        // println!("DOT Balance After\t{:?}", bal_dot_after);
-        // println!("SDOT Balance After\t{:?}", bal_sdot_after);
+        assert_eq!(Assets::balance(DOT, trader), 999_999_000);
+        assert_eq!(Assets::balance(SDOT, trader), 1_000_000_997);
+    })
+}
 
-        // println!("DOT Diff\t{:?}", bal_dot_before - bal_dot_after);
-        // println!("SDOT Diff\t{:?}", bal_sdot_after - bal_sdot_before);
+#[test]
+fn migrate_to_v1_should_work() {
+    new_test_ext().execute_with(|| {
+        let pool = crate::migrations::v1::V0Pool {
+            base_amount: 2_000u128,
+            quote_amount: 1_000u128,
+            base_amount_last: 2_000u128,
+            quote_amount_last: 1_000u128,
+            lp_token_id: SAMPLE_LP_TOKEN,
+            block_timestamp_last: 5u64,
+            price_0_cumulative_last: 7u128,
+            price_1_cumulative_last: 9u128,
+        };
+        // the two-sided pools were keyed by their sorted pair
+        let key = [
+            frame_support::storage::storage_prefix(b"DefaultStableSwap", b"Pools").to_vec(),
+            Blake2_128Concat::hash(&SDOT.encode()),
+            Blake2_128Concat::hash(&DOT.encode()),
+        ]
+        .concat();
+        frame_support::storage::unhashed::put(&key, &pool);
+        assert_eq!(crate::pallet::StorageVersion::<Test>::get(), Versions::V0);
+
+        crate::migrations::v1::migrate::<Test, ()>();
+
+        assert_eq!(crate::pallet::StorageVersion::<Test>::get(), Versions::V1);
+        assert_eq!(
+            DefaultStableSwap::pools(SAMPLE_LP_TOKEN),
+            Some(Pool {
+                assets: vec![SDOT, DOT],
+                balances: vec![2_000, 1_000],
+                d_last: 2_998,
+                lp_token_id: SAMPLE_LP_TOKEN,
+                block_timestamp_last: 5,
+                price_cumulative_last: vec![7, 9],
+            })
+        );
+        assert_eq!(
+            DefaultStableSwap::pair_pools(SDOT, DOT),
+            Some(SAMPLE_LP_TOKEN)
+        );
+        assert_eq!(
+            DefaultStableSwap::get_reserves(DOT, SDOT).unwrap(),
+            (1_000, 2_000)
+        );
     })
 }
@@ -1,48 +1,46 @@
-use codec::{Decode, Encode, MaxEncodedLen};
+use codec::{Decode, Encode};
 use frame_support::traits::tokens::Balance as BalanceT;
 use scale_info::TypeInfo;
 use sp_runtime::{traits::Zero, RuntimeDebug};
+use sp_std::{vec, vec::Vec};
 
-#[derive(
-    Encode,
-    Decode,
-    Eq,
-    PartialEq,
-    Copy,
-    Clone,
-    RuntimeDebug,
-    PartialOrd,
-    Ord,
-    TypeInfo,
-    MaxEncodedLen,
-)]
+#[derive(Encode, Decode, Eq, PartialEq, Clone, RuntimeDebug, TypeInfo)]
 #[cfg_attr(feature = "std", derive(serde::Serialize, serde::Deserialize))]
 pub struct Pool<CurrencyId, Balance, BlockNumber> {
-    pub base_amount: Balance,
-    pub quote_amount: Balance,
-    pub base_amount_last: Balance,
-    pub quote_amount_last: Balance,
+    /// Assets of the pool, sorted from the largest id to the smallest
+    pub assets: Vec<CurrencyId>,
+    /// Reserve of each asset, in the order of `assets`
+    pub balances: Vec<Balance>,
+    /// Invariant D after the last liquidity change, used to mint the protocol fee
+    pub d_last: Balance,
     pub lp_token_id: CurrencyId,
     pub block_timestamp_last: BlockNumber,
-    pub price_0_cumulative_last: Balance,
-    pub price_1_cumulative_last: Balance,
+    /// Cumulative price of each asset in units of the next one, the last
+    /// asset being priced in units of the first
+    pub price_cumulative_last: Vec<Balance>,
 }
 
-impl<CurrencyId, Balance: BalanceT, BlockNumber: BalanceT> Pool<CurrencyId, Balance, BlockNumber> {
-    pub fn new(lp_token_id: CurrencyId) -> Self {
+impl<CurrencyId: PartialEq, Balance: BalanceT, BlockNumber: BalanceT>
+    Pool<CurrencyId, Balance, BlockNumber>
+{
+    pub fn new(assets: Vec<CurrencyId>, lp_token_id: CurrencyId) -> Self {
+        let len = assets.len();
         Self {
-            base_amount: Zero::zero(),
-            quote_amount: Zero::zero(),
-            base_amount_last: Zero::zero(),
-            quote_amount_last: Zero::zero(),
+            assets,
+            balances: vec![Zero::zero(); len],
+            d_last: Zero::zero(),
             lp_token_id,
             block_timestamp_last: Zero::zero(),
-            price_0_cumulative_last: Zero::zero(),
-            price_1_cumulative_last: Zero::zero(),
+            price_cumulative_last: vec![Zero::zero(); len],
         }
     }
 
     pub fn is_empty(&self) -> bool {
-        self.base_amount.is_zero() && self.quote_amount.is_zero()
+        self.balances.iter().all(|balance| balance.is_zero())
+    }
+
+    /// Position of `asset_id` in the pool
+    pub fn index_of(&self, asset_id: &CurrencyId) -> Option<usize> {
+        self.assets.iter().position(|asset| asset == asset_id)
     }
 }
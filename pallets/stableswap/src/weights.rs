@@ -23,48 +23,48 @@ use sp_std::marker::PhantomData;
 
 /// Weight functions needed for pallet_stableswap
 pub trait WeightInfo {
-    fn get_delta() -> Weight;
-    fn get_alternative_var() -> Weight;
     fn add_liquidity() -> Weight;
     fn remove_liquidity() -> Weight;
     fn create_pool() -> Weight;
+    fn remove_liquidity_one_asset() -> Weight;
+    fn swap() -> Weight;
 }
 
 /// Weights for stableswap using the Substrate node and recommended hardware.
 pub struct SubstrateWeight<T>(PhantomData<T>);
 impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
-    fn get_delta() -> Weight {
+    fn add_liquidity() -> Weight {
         Weight::from_ref_time(10_000 as u64)
     }
-    fn get_alternative_var() -> Weight {
+    fn remove_liquidity() -> Weight {
         Weight::from_ref_time(10_000 as u64)
     }
-    fn add_liquidity() -> Weight {
+    fn create_pool() -> Weight {
         Weight::from_ref_time(10_000 as u64)
     }
-    fn remove_liquidity() -> Weight {
+    fn remove_liquidity_one_asset() -> Weight {
         Weight::from_ref_time(10_000 as u64)
     }
-    fn create_pool() -> Weight {
+    fn swap() -> Weight {
         Weight::from_ref_time(10_000 as u64)
     }
 }
 
 // For backwards compatibility and tests
 impl WeightInfo for () {
-    fn get_delta() -> Weight {
+    fn add_liquidity() -> Weight {
         Weight::from_ref_time(10_000 as u64)
     }
-    fn get_alternative_var() -> Weight {
+    fn remove_liquidity() -> Weight {
         Weight::from_ref_time(10_000 as u64)
     }
-    fn add_liquidity() -> Weight {
+    fn create_pool() -> Weight {
         Weight::from_ref_time(10_000 as u64)
     }
-    fn remove_liquidity() -> Weight {
+    fn remove_liquidity_one_asset() -> Weight {
         Weight::from_ref_time(10_000 as u64)
     }
-    fn create_pool() -> Weight {
+    fn swap() -> Weight {
         Weight::from_ref_time(10_000 as u64)
     }
 }
@@ -1,14 +1,17 @@
 #![cfg_attr(not(feature = "std"), no_std)]
 
 use codec::{Decode, Encode, MaxEncodedLen};
-use frame_support::{dispatch::DispatchError, traits::tokens::Balance as BalanceT};
+use frame_support::{
+    dispatch::DispatchError, traits::tokens::Balance as BalanceT, weights::Weight,
+};
 use num_bigint::{BigUint, ToBigUint};
 use scale_info::TypeInfo;
 use sp_runtime::{traits::Zero, RuntimeDebug};
 use sp_std::prelude::*;
 
 use primitives::{
-    CurrencyId, DerivativeIndex, PersistedValidationData, PriceDetail, Rate, Timestamp,
+    CurrencyId, DerivativeIndex, PersistedValidationData, PriceDetail, Rate, Ratio,
+    TimeStampedPrice, Timestamp,
 };
 
 pub mod loans;
@@ -22,14 +25,34 @@ pub trait EmergencyCallFilter<Call> {
 
 pub trait PriceFeeder {
     fn get_price(asset_id: &CurrencyId) -> Option<PriceDetail>;
+
+    /// Whether the price feed of `asset_id` is currently considered suspicious.
+    fn is_suspicious(_asset_id: &CurrencyId) -> bool {
+        false
+    }
+}
+
+/// Raw feed values of the individual members of an oracle.
+pub trait OracleMemberProvider<AccountId> {
+    fn get_member_value(member: &AccountId, asset_id: &CurrencyId) -> Option<TimeStampedPrice>;
+}
+
+impl<AccountId> OracleMemberProvider<AccountId> for () {
+    fn get_member_value(_member: &AccountId, _asset_id: &CurrencyId) -> Option<TimeStampedPrice> {
+        None
+    }
 }
 
 pub trait DecimalProvider<CurrencyId> {
     fn get_decimal(asset_id: &CurrencyId) -> Option<u8>;
 }
 
-pub trait EmergencyPriceFeeder<CurrencyId, Price> {
-    fn set_emergency_price(asset_id: CurrencyId, price: Price);
+pub trait EmergencyPriceFeeder<CurrencyId, Price, BlockNumber> {
+    fn set_emergency_price(
+        asset_id: CurrencyId,
+        price: Price,
+        expires_at: BlockNumber,
+    ) -> Result<(), DispatchError>;
     fn reset_emergency_price(asset_id: CurrencyId);
 }
 
@@ -86,10 +109,12 @@ pub struct Pool<CurrencyId, Balance, BlockNumber> {
     pub block_timestamp_last: BlockNumber,
     pub price_0_cumulative_last: Balance,
     pub price_1_cumulative_last: Balance,
+    /// Fee taken out of each trade and left in the pool
+    pub lp_fee: Ratio,
 }
 
 impl<CurrencyId, Balance: BalanceT, BlockNumber: BalanceT> Pool<CurrencyId, Balance, BlockNumber> {
-    pub fn new(lp_token_id: CurrencyId) -> Self {
+    pub fn new(lp_token_id: CurrencyId, lp_fee: Ratio) -> Self {
         Self {
             base_amount: Zero::zero(),
             quote_amount: Zero::zero(),
@@ -99,6 +124,7 @@ impl<CurrencyId, Balance: BalanceT, BlockNumber: BalanceT> Pool<CurrencyId, Bala
             block_timestamp_last: Zero::zero(),
             price_0_cumulative_last: Zero::zero(),
             price_1_cumulative_last: Zero::zero(),
+            lp_fee,
         }
     }
 
@@ -152,6 +178,10 @@ pub trait AMM<AccountId, CurrencyId, Balance, BlockNumber> {
     fn get_pool_by_asset_pair(
         pair: (CurrencyId, CurrencyId),
     ) -> Option<Pool<CurrencyId, Balance, BlockNumber>>;
+
+    /// Returns the time weighted average price of `pair.0` in units of `pair.1`
+    /// over at least the last `window` blocks
+    fn consult(pair: (CurrencyId, CurrencyId), window: BlockNumber) -> Result<Rate, DispatchError>;
 }
 
 /// Exported traits from StableSwap pallet. These functions are to be used
@@ -189,6 +219,102 @@ pub trait StableSwap<AccountId, CurrencyId, Balance> {
         asset_in: CurrencyId,
         asset_out: CurrencyId,
     ) -> Result<(Balance, Balance), DispatchError>;
+
+    /// Returns the assets and the invariant D of the pool by lp_asset
+    fn get_pool_by_lp_asset(asset_id: CurrencyId) -> Option<(Vec<CurrencyId>, Balance)>;
+}
+
+impl<AccountId, CurrencyId, Balance> StableSwap<AccountId, CurrencyId, Balance> for () {
+    fn get_amounts_out(
+        _amount_in: Balance,
+        _path: Vec<CurrencyId>,
+    ) -> Result<Vec<Balance>, DispatchError> {
+        Err(DispatchError::Other("No stable swap pools"))
+    }
+
+    fn get_amounts_in(
+        _amount_out: Balance,
+        _path: Vec<CurrencyId>,
+    ) -> Result<Vec<Balance>, DispatchError> {
+        Err(DispatchError::Other("No stable swap pools"))
+    }
+
+    fn swap(
+        _who: &AccountId,
+        _pair: (CurrencyId, CurrencyId),
+        _amount_in: Balance,
+    ) -> Result<(), DispatchError> {
+        Err(DispatchError::Other("No stable swap pools"))
+    }
+
+    fn get_pools() -> Result<Vec<(CurrencyId, CurrencyId)>, DispatchError> {
+        Ok(Vec::new())
+    }
+
+    fn get_reserves(
+        _asset_in: CurrencyId,
+        _asset_out: CurrencyId,
+    ) -> Result<(Balance, Balance), DispatchError> {
+        Err(DispatchError::Other("No stable swap pools"))
+    }
+
+    fn get_pool_by_lp_asset(_asset_id: CurrencyId) -> Option<(Vec<CurrencyId>, Balance)> {
+        None
+    }
+}
+
+/// Exported traits from our router pallet. These functions are to be used
+/// by other pallets to trade through the best route.
+pub trait Router<AccountId, CurrencyId, Balance> {
+    /// Trades exactly `amount_in` of `token_in` for at least `min_amount_out`
+    /// of `token_out` through the best route, returning the amount received.
+    fn swap_exact_tokens_for_tokens(
+        who: &AccountId,
+        token_in: CurrencyId,
+        token_out: CurrencyId,
+        amount_in: Balance,
+        min_amount_out: Balance,
+    ) -> Result<Balance, DispatchError>;
+
+    /// The weight of `swap_exact_tokens_for_tokens`, the discovery of the
+    /// best route included.
+    fn swap_exact_tokens_for_tokens_weight() -> Weight;
+}
+
+impl<AccountId, CurrencyId, Balance> Router<AccountId, CurrencyId, Balance> for () {
+    fn swap_exact_tokens_for_tokens(
+        _who: &AccountId,
+        _token_in: CurrencyId,
+        _token_out: CurrencyId,
+        _amount_in: Balance,
+        _min_amount_out: Balance,
+    ) -> Result<Balance, DispatchError> {
+        Err(DispatchError::Other("No router"))
+    }
+
+    fn swap_exact_tokens_for_tokens_weight() -> Weight {
+        Weight::zero()
+    }
+}
+
+/// Notified by the AMM and StableSwap pallets whenever a pool is listed,
+/// so the router can keep its graph of pairs up to date
+pub trait OnPoolCreated<CurrencyId> {
+    fn on_pool_created(pair: (CurrencyId, CurrencyId));
+}
+
+impl<CurrencyId> OnPoolCreated<CurrencyId> for () {
+    fn on_pool_created(_pair: (CurrencyId, CurrencyId)) {}
+}
+
+/// Notified by the AMM pallet whenever a pool is delisted, so the router can
+/// stop routing through it
+pub trait OnPoolDelisted<CurrencyId> {
+    fn on_pool_delisted(pair: (CurrencyId, CurrencyId));
+}
+
+impl<CurrencyId> OnPoolDelisted<CurrencyId> for () {
+    fn on_pool_delisted(_pair: (CurrencyId, CurrencyId)) {}
 }
 
 pub trait ConvertToBigUint {
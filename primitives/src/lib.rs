@@ -16,6 +16,7 @@
 
 use codec::{Decode, Encode};
 pub use cumulus_primitives_core::{ParaId, PersistedValidationData};
+use scale_info::TypeInfo;
 #[cfg(feature = "std")]
 use serde::{Deserialize, Serialize};
 pub use sp_consensus_aura::sr25519::AuthorityId as AuraId;
@@ -91,6 +92,8 @@ pub type Shortfall = FixedU128;
 
 pub type Price = FixedU128;
 
+pub type EModeCategoryId = u8;
+
 pub type Timestamp = u64;
 
 pub type CurrencyId = u32;
@@ -126,6 +129,31 @@ pub enum DataProviderId {
     Aggregated = 0isize,
 }
 
+/// Kind of pool a router hop trades through
+#[derive(Encode, Decode, Eq, PartialEq, Copy, Clone, RuntimeDebug, TypeInfo)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+pub enum PoolType {
+    /// Constant product pool of the AMM pallet
+    AMM,
+    /// Pool of the StableSwap pallet
+    StableSwap,
+}
+
+/// A source of an asset's aggregated price
+#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug, TypeInfo)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+pub enum PriceSource<AccountId> {
+    /// The combined oracle feed, guarded by the deviation limit
+    Oracle,
+    /// The raw feed value of an oracle member
+    OracleMember(AccountId),
+    /// The TWAP of the asset's AMM pool
+    Twap,
+    /// The relay currency price scaled by the exchange rate of liquid staking
+    /// and vault tokens, or the fair value of a liquidity token
+    Derived,
+}
+
 ////////////////////////////////////////////////////////////////////////////////
 
 #[derive(PartialEq, Eq, Copy, Clone, Encode, Decode, RuntimeDebug)]
@@ -47,4 +47,27 @@ impl<T: frame_system::Config> pallet_router::WeightInfo for WeightInfo<T> {
 			.saturating_add(T::DbWeight::get().reads(7))
 			.saturating_add(T::DbWeight::get().writes(7))
 	}
+	// Storage: AMM Pools (r:3 w:2)
+	// Storage: Assets Asset (r:3 w:3)
+	// Storage: Assets Account (r:6 w:6)
+	/// The range of component `n` is `[1, 3]`.
+	fn swap_exact_tokens_for_tokens_split(n: u32, ) -> Weight {
+		// Minimum execution time: 389_102 nanoseconds.
+		Weight::from_ref_time(142_377_000)
+			// Standard Error: 71_564
+			.saturating_add(Weight::from_ref_time(238_915_316).saturating_mul(n.into()))
+			.saturating_add(T::DbWeight::get().reads(2))
+			.saturating_add(T::DbWeight::get().reads((7_u64).saturating_mul(n.into())))
+			.saturating_add(T::DbWeight::get().writes((7_u64).saturating_mul(n.into())))
+	}
+	// Storage: AMMRoute Adjacency (r:1 w:0)
+	/// The range of component `p` is `[1, 10]`.
+	fn find_routes(p: u32, ) -> Weight {
+		// Minimum execution time: 18_961 nanoseconds.
+		Weight::from_ref_time(12_804_221)
+			// Standard Error: 5_127
+			.saturating_add(Weight::from_ref_time(4_103_870).saturating_mul(p.into()))
+			.saturating_add(T::DbWeight::get().reads(2))
+			.saturating_add(T::DbWeight::get().reads((1_u64).saturating_mul(p.into())))
+	}
 }
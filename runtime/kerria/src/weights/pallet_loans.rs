@@ -147,10 +147,12 @@ impl<T: frame_system::Config> pallet_loans::WeightInfo for WeightInfo<T> {
 	// Storage: Loans TotalReserves (r:1 w:0)
 	// Storage: Loans AccountEarned (r:1 w:1)
 	// Storage: System Account (r:1 w:1)
+	// Storage: Loans MarketSnapshotHead (r:1 w:1)
+	// Storage: Loans MarketSnapshots (r:1 w:1)
 	fn mint() -> Weight {
 		Weight::from_ref_time(244_041_000 as u64)
-			.saturating_add(T::DbWeight::get().reads(18 as u64))
-			.saturating_add(T::DbWeight::get().writes(12 as u64))
+			.saturating_add(T::DbWeight::get().reads(20 as u64))
+			.saturating_add(T::DbWeight::get().writes(14 as u64))
 	}
 	// Storage: unknown [0x3a7472616e73616374696f6e5f6c6576656c3a] (r:1 w:1)
 	// Storage: Loans Markets (r:2 w:0)
@@ -171,10 +173,12 @@ impl<T: frame_system::Config> pallet_loans::WeightInfo for WeightInfo<T> {
 	// Storage: Loans RewardBorrowerIndex (r:1 w:1)
 	// Storage: Loans RewardAccured (r:1 w:1)
 	// Storage: Loans BorrowIndex (r:1 w:0)
+	// Storage: Loans MarketSnapshotHead (r:1 w:1)
+	// Storage: Loans MarketSnapshots (r:1 w:1)
 	fn borrow() -> Weight {
 		Weight::from_ref_time(341_042_000 as u64)
-			.saturating_add(T::DbWeight::get().reads(24 as u64))
-			.saturating_add(T::DbWeight::get().writes(10 as u64))
+			.saturating_add(T::DbWeight::get().reads(26 as u64))
+			.saturating_add(T::DbWeight::get().writes(12 as u64))
 	}
 	// Storage: unknown [0x3a7472616e73616374696f6e5f6c6576656c3a] (r:1 w:1)
 	// Storage: Loans Markets (r:2 w:0)
@@ -191,10 +195,12 @@ impl<T: frame_system::Config> pallet_loans::WeightInfo for WeightInfo<T> {
 	// Storage: Loans RewardSupplySpeed (r:1 w:0)
 	// Storage: Loans RewardSupplierIndex (r:1 w:1)
 	// Storage: Loans RewardAccured (r:1 w:1)
+	// Storage: Loans MarketSnapshotHead (r:1 w:1)
+	// Storage: Loans MarketSnapshots (r:1 w:1)
 	fn redeem() -> Weight {
 		Weight::from_ref_time(253_173_000 as u64)
-			.saturating_add(T::DbWeight::get().reads(17 as u64))
-			.saturating_add(T::DbWeight::get().writes(11 as u64))
+			.saturating_add(T::DbWeight::get().reads(19 as u64))
+			.saturating_add(T::DbWeight::get().writes(13 as u64))
 	}
 	// Storage: unknown [0x3a7472616e73616374696f6e5f6c6576656c3a] (r:1 w:1)
 	// Storage: Loans Markets (r:2 w:0)
@@ -212,10 +218,12 @@ impl<T: frame_system::Config> pallet_loans::WeightInfo for WeightInfo<T> {
 	// Storage: Loans RewardSupplierIndex (r:1 w:1)
 	// Storage: Loans RewardAccured (r:1 w:1)
 	// Storage: System Account (r:1 w:1)
+	// Storage: Loans MarketSnapshotHead (r:1 w:1)
+	// Storage: Loans MarketSnapshots (r:1 w:1)
 	fn redeem_all() -> Weight {
 		Weight::from_ref_time(272_126_000 as u64)
-			.saturating_add(T::DbWeight::get().reads(18 as u64))
-			.saturating_add(T::DbWeight::get().writes(12 as u64))
+			.saturating_add(T::DbWeight::get().reads(20 as u64))
+			.saturating_add(T::DbWeight::get().writes(14 as u64))
 	}
 	// Storage: unknown [0x3a7472616e73616374696f6e5f6c6576656c3a] (r:1 w:1)
 	// Storage: Loans Markets (r:2 w:0)
@@ -230,10 +238,12 @@ impl<T: frame_system::Config> pallet_loans::WeightInfo for WeightInfo<T> {
 	// Storage: Assets Asset (r:1 w:1)
 	// Storage: Assets Account (r:2 w:2)
 	// Storage: Loans TotalBorrows (r:1 w:1)
+	// Storage: Loans MarketSnapshotHead (r:1 w:1)
+	// Storage: Loans MarketSnapshots (r:1 w:1)
 	fn repay_borrow() -> Weight {
 		Weight::from_ref_time(222_396_000 as u64)
-			.saturating_add(T::DbWeight::get().reads(15 as u64))
-			.saturating_add(T::DbWeight::get().writes(10 as u64))
+			.saturating_add(T::DbWeight::get().reads(17 as u64))
+			.saturating_add(T::DbWeight::get().writes(12 as u64))
 	}
 	// Storage: unknown [0x3a7472616e73616374696f6e5f6c6576656c3a] (r:1 w:1)
 	// Storage: Loans Markets (r:2 w:0)
@@ -248,10 +258,12 @@ impl<T: frame_system::Config> pallet_loans::WeightInfo for WeightInfo<T> {
 	// Storage: Assets Asset (r:1 w:1)
 	// Storage: Assets Account (r:2 w:2)
 	// Storage: Loans TotalBorrows (r:1 w:1)
+	// Storage: Loans MarketSnapshotHead (r:1 w:1)
+	// Storage: Loans MarketSnapshots (r:1 w:1)
 	fn repay_borrow_all() -> Weight {
 		Weight::from_ref_time(218_486_000 as u64)
-			.saturating_add(T::DbWeight::get().reads(15 as u64))
-			.saturating_add(T::DbWeight::get().writes(10 as u64))
+			.saturating_add(T::DbWeight::get().reads(17 as u64))
+			.saturating_add(T::DbWeight::get().writes(12 as u64))
 	}
 	// Storage: unknown [0x3a7472616e73616374696f6e5f6c6576656c3a] (r:1 w:1)
 	// Storage: Loans Markets (r:2 w:0)
@@ -283,10 +295,12 @@ impl<T: frame_system::Config> pallet_loans::WeightInfo for WeightInfo<T> {
 	// Storage: Loans RewardSupplyState (r:1 w:1)
 	// Storage: Loans RewardSupplySpeed (r:1 w:0)
 	// Storage: Loans RewardSupplierIndex (r:3 w:3)
+	// Storage: Loans MarketSnapshotHead (r:2 w:2)
+	// Storage: Loans MarketSnapshots (r:2 w:2)
 	fn liquidate_borrow() -> Weight {
 		Weight::from_ref_time(631_581_000 as u64)
-			.saturating_add(T::DbWeight::get().reads(40 as u64))
-			.saturating_add(T::DbWeight::get().writes(20 as u64))
+			.saturating_add(T::DbWeight::get().reads(44 as u64))
+			.saturating_add(T::DbWeight::get().writes(24 as u64))
 	}
 	// Storage: unknown [0x3a7472616e73616374696f6e5f6c6576656c3a] (r:1 w:1)
 	// Storage: Loans Markets (r:2 w:0)
@@ -316,4 +330,312 @@ impl<T: frame_system::Config> pallet_loans::WeightInfo for WeightInfo<T> {
 			.saturating_add(T::DbWeight::get().reads(2 as u64))
 			.saturating_add(T::DbWeight::get().writes(2 as u64))
 	}
+	// Storage: Loans EModeCategories (r:0 w:1)
+	fn set_emode_category() -> Weight {
+		Weight::from_ref_time(28_172_000 as u64)
+			.saturating_add(T::DbWeight::get().writes(1 as u64))
+	}
+	// Storage: Loans Markets (r:1 w:0)
+	// Storage: Loans EModeCategories (r:1 w:0)
+	// Storage: Loans MarketEModeCategory (r:0 w:1)
+	fn set_market_emode_category() -> Weight {
+		Weight::from_ref_time(35_841_000 as u64)
+			.saturating_add(T::DbWeight::get().reads(2 as u64))
+			.saturating_add(T::DbWeight::get().writes(1 as u64))
+	}
+	// Storage: Loans Markets (r:1 w:0)
+	// Storage: Loans FlashLoanFees (r:0 w:1)
+	fn set_flash_loan_fee() -> Weight {
+		Weight::from_ref_time(31_250_000 as u64)
+			.saturating_add(T::DbWeight::get().reads(1 as u64))
+			.saturating_add(T::DbWeight::get().writes(1 as u64))
+	}
+	// Storage: Loans Markets (r:1 w:0)
+	// Storage: Loans FlashLoanFees (r:1 w:0)
+	// Storage: Loans TotalReserves (r:1 w:1)
+	// Storage: Loans FlashLoanAmounts (r:1 w:1)
+	// Storage: Assets Asset (r:1 w:1)
+	// Storage: Assets Account (r:2 w:2)
+	fn flash_loan() -> Weight {
+		Weight::from_ref_time(98_412_000 as u64)
+			.saturating_add(T::DbWeight::get().reads(6 as u64))
+			.saturating_add(T::DbWeight::get().writes(6 as u64))
+	}
+	// Storage: Loans Markets (r:2 w:0)
+	// Storage: Loans LastAccruedInterestTime (r:2 w:2)
+	// Storage: Loans AccountDeposits (r:1 w:1)
+	// Storage: Loans TotalSupply (r:1 w:1)
+	// Storage: Loans AccountBorrows (r:1 w:1)
+	// Storage: Loans TotalBorrows (r:1 w:1)
+	// Storage: AMM Pools (r:1 w:1)
+	// Storage: Assets Asset (r:2 w:2)
+	// Storage: Assets Account (r:6 w:6)
+	// Storage: Loans MarketSnapshotHead (r:2 w:2)
+	// Storage: Loans MarketSnapshots (r:2 w:2)
+	fn repay_with_collateral() -> Weight {
+		Weight::from_ref_time(312_845_000 as u64)
+			.saturating_add(T::DbWeight::get().reads(28 as u64))
+			.saturating_add(T::DbWeight::get().writes(21 as u64))
+	}
+	// Storage: Loans Markets (r:2 w:0)
+	// Storage: Loans LastAccruedInterestTime (r:2 w:2)
+	// Storage: Loans AccountDeposits (r:1 w:1)
+	// Storage: Loans TotalSupply (r:1 w:1)
+	// Storage: Loans AccountBorrows (r:1 w:1)
+	// Storage: Loans TotalBorrows (r:1 w:1)
+	// Storage: AMM Pools (r:1 w:1)
+	// Storage: Assets Asset (r:2 w:2)
+	// Storage: Assets Account (r:6 w:6)
+	// Storage: Loans MarketSnapshotHead (r:2 w:2)
+	// Storage: Loans MarketSnapshots (r:2 w:2)
+	fn leverage() -> Weight {
+		Weight::from_ref_time(335_107_000 as u64)
+			.saturating_add(T::DbWeight::get().reads(30 as u64))
+			.saturating_add(T::DbWeight::get().writes(22 as u64))
+	}
+	// Storage: Loans Markets (r:1 w:0)
+	// Storage: Loans LiquidationAuctionConfigs (r:0 w:1)
+	fn set_liquidation_auction_config() -> Weight {
+		Weight::from_ref_time(31_874_000 as u64)
+			.saturating_add(T::DbWeight::get().reads(1 as u64))
+			.saturating_add(T::DbWeight::get().writes(1 as u64))
+	}
+	// Storage: Loans Markets (r:2 w:0)
+	// Storage: Loans LiquidationAuctionConfigs (r:1 w:0)
+	// Storage: Loans LiquidationAuctions (r:1 w:1)
+	// Storage: Loans LastAccruedInterestTime (r:1 w:1)
+	// Storage: Loans TotalSupply (r:1 w:0)
+	// Storage: Loans TotalBorrows (r:1 w:0)
+	// Storage: Loans TotalReserves (r:1 w:0)
+	// Storage: Loans BorrowIndex (r:1 w:1)
+	// Storage: Loans AccountBorrows (r:1 w:0)
+	// Storage: Loans AccountDeposits (r:1 w:0)
+	// Storage: Prices EmergencyPrice (r:1 w:0)
+	// Storage: Assets Account (r:1 w:0)
+	// Storage: Loans MarketSnapshotHead (r:1 w:1)
+	// Storage: Loans MarketSnapshots (r:1 w:1)
+	// Storage: Loans OpenLiquidationAuctions (r:1 w:1)
+	fn open_liquidation_auction() -> Weight {
+		Weight::from_ref_time(96_503_000 as u64)
+			.saturating_add(T::DbWeight::get().reads(16 as u64))
+			.saturating_add(T::DbWeight::get().writes(6 as u64))
+	}
+	// Storage: Loans LiquidationAuctionConfigs (r:1 w:0)
+	// Storage: Loans LiquidationAuctions (r:1 w:1)
+	// Storage: Loans LiquidationFreeCollaterals (r:1 w:0)
+	// Storage: Loans Markets (r:3 w:0)
+	// Storage: Loans LastAccruedInterestTime (r:2 w:2)
+	// Storage: Loans TotalSupply (r:1 w:0)
+	// Storage: Loans TotalBorrows (r:2 w:1)
+	// Storage: Loans TotalReserves (r:2 w:0)
+	// Storage: Loans BorrowIndex (r:1 w:0)
+	// Storage: Loans AccountBorrows (r:2 w:1)
+	// Storage: Loans AccountDeposits (r:4 w:3)
+	// Storage: Loans RewardBorrowState (r:1 w:1)
+	// Storage: Loans RewardSupplyState (r:1 w:1)
+	// Storage: Prices EmergencyPrice (r:2 w:0)
+	// Storage: Assets Account (r:2 w:2)
+	// Storage: Loans MarketSnapshotHead (r:2 w:2)
+	// Storage: Loans MarketSnapshots (r:2 w:2)
+	// Storage: Loans OpenLiquidationAuctions (r:1 w:1)
+	fn fill_liquidation_auction() -> Weight {
+		Weight::from_ref_time(248_612_000 as u64)
+			.saturating_add(T::DbWeight::get().reads(29 as u64))
+			.saturating_add(T::DbWeight::get().writes(19 as u64))
+	}
+	// Storage: Loans LiquidationAuctions (r:1 w:1)
+	// Storage: Loans LiquidationAuctionConfigs (r:1 w:0)
+	// Storage: Loans Markets (r:2 w:0)
+	// Storage: Loans LastAccruedInterestTime (r:1 w:1)
+	// Storage: Loans TotalBorrows (r:1 w:0)
+	// Storage: Loans TotalReserves (r:1 w:0)
+	// Storage: Loans BorrowIndex (r:1 w:0)
+	// Storage: Loans AccountBorrows (r:1 w:0)
+	// Storage: Loans AccountDeposits (r:1 w:0)
+	// Storage: Prices EmergencyPrice (r:1 w:0)
+	// Storage: Assets Account (r:1 w:0)
+	// Storage: Loans MarketSnapshotHead (r:1 w:1)
+	// Storage: Loans MarketSnapshots (r:1 w:1)
+	// Storage: Loans OpenLiquidationAuctions (r:1 w:1)
+	fn close_liquidation_auction() -> Weight {
+		Weight::from_ref_time(84_296_000 as u64)
+			.saturating_add(T::DbWeight::get().reads(15 as u64))
+			.saturating_add(T::DbWeight::get().writes(5 as u64))
+	}
+	// Storage: Loans Markets (r:1 w:0)
+	// Storage: Loans BadDebtSocialisation (r:0 w:1)
+	fn set_bad_debt_socialisation() -> Weight {
+		Weight::from_ref_time(30_962_000 as u64)
+			.saturating_add(T::DbWeight::get().reads(1 as u64))
+			.saturating_add(T::DbWeight::get().writes(1 as u64))
+	}
+	// Storage: Loans Markets (r:1 w:0)
+	// Storage: Loans FixedRateConfigs (r:0 w:1)
+	fn set_fixed_rate_config() -> Weight {
+		Weight::from_ref_time(31_284_000 as u64)
+			.saturating_add(T::DbWeight::get().reads(1 as u64))
+			.saturating_add(T::DbWeight::get().writes(1 as u64))
+	}
+	// Storage: unknown [0x3a7472616e73616374696f6e5f6c6576656c3a] (r:1 w:1)
+	// Storage: Loans Markets (r:2 w:0)
+	// Storage: Loans FixedRateConfigs (r:1 w:0)
+	// Storage: Loans AccountFixedBorrows (r:2 w:1)
+	// Storage: Timestamp Now (r:1 w:0)
+	// Storage: Loans LastAccruedInterestTime (r:1 w:1)
+	// Storage: Loans TotalBorrows (r:1 w:0)
+	// Storage: Loans TotalFixedBorrows (r:1 w:1)
+	// Storage: Loans TotalReserves (r:1 w:1)
+	// Storage: Assets Asset (r:1 w:1)
+	// Storage: Assets Account (r:2 w:2)
+	// Storage: Prices EmergencyPrice (r:2 w:0)
+	// Storage: Assets Metadata (r:2 w:0)
+	// Storage: Loans AccountBorrows (r:2 w:0)
+	// Storage: Loans AccountDeposits (r:1 w:0)
+	// Storage: Loans TotalSupply (r:1 w:0)
+	// Storage: Loans LiquidationFreeCollaterals (r:1 w:0)
+	// Storage: Loans BorrowIndex (r:1 w:0)
+	// Storage: Loans MarketSnapshotHead (r:1 w:1)
+	// Storage: Loans MarketSnapshots (r:1 w:1)
+	fn fixed_borrow() -> Weight {
+		Weight::from_ref_time(318_592_000 as u64)
+			.saturating_add(T::DbWeight::get().reads(27 as u64))
+			.saturating_add(T::DbWeight::get().writes(11 as u64))
+	}
+	// Storage: unknown [0x3a7472616e73616374696f6e5f6c6576656c3a] (r:1 w:1)
+	// Storage: Loans Markets (r:2 w:0)
+	// Storage: Loans AccountFixedBorrows (r:1 w:1)
+	// Storage: Timestamp Now (r:1 w:0)
+	// Storage: Loans TotalFixedBorrows (r:1 w:1)
+	// Storage: Loans TotalReserves (r:1 w:1)
+	// Storage: Assets Asset (r:1 w:1)
+	// Storage: Assets Account (r:2 w:2)
+	// Storage: Loans MarketSnapshotHead (r:1 w:1)
+	// Storage: Loans MarketSnapshots (r:1 w:1)
+	fn repay_fixed_borrow() -> Weight {
+		Weight::from_ref_time(112_847_000 as u64)
+			.saturating_add(T::DbWeight::get().reads(12 as u64))
+			.saturating_add(T::DbWeight::get().writes(9 as u64))
+	}
+	// Storage: unknown [0x3a7472616e73616374696f6e5f6c6576656c3a] (r:1 w:1)
+	// Storage: Loans Markets (r:2 w:0)
+	// Storage: Loans AccountFixedBorrows (r:1 w:1)
+	// Storage: Timestamp Now (r:1 w:0)
+	// Storage: Loans LastAccruedInterestTime (r:1 w:1)
+	// Storage: Loans TotalBorrows (r:1 w:1)
+	// Storage: Loans TotalFixedBorrows (r:1 w:1)
+	// Storage: Loans TotalReserves (r:1 w:1)
+	// Storage: Loans FixedRateConfigs (r:1 w:0)
+	// Storage: Loans OverdueBorrows (r:0 w:1)
+	// Storage: Prices EmergencyPrice (r:2 w:0)
+	// Storage: Assets Metadata (r:2 w:0)
+	// Storage: Loans AccountBorrows (r:2 w:1)
+	// Storage: Loans AccountDeposits (r:1 w:0)
+	// Storage: Loans TotalSupply (r:1 w:0)
+	// Storage: Loans LiquidationFreeCollaterals (r:1 w:0)
+	// Storage: Loans RewardBorrowState (r:1 w:1)
+	// Storage: Loans RewardBorrowSpeed (r:1 w:0)
+	// Storage: Loans RewardBorrowerIndex (r:1 w:1)
+	// Storage: Loans RewardAccured (r:1 w:1)
+	// Storage: Loans BorrowIndex (r:1 w:0)
+	// Storage: Loans MarketSnapshotHead (r:1 w:1)
+	// Storage: Loans MarketSnapshots (r:1 w:1)
+	fn settle_fixed_borrow() -> Weight {
+		Weight::from_ref_time(301_775_000 as u64)
+			.saturating_add(T::DbWeight::get().reads(28 as u64))
+			.saturating_add(T::DbWeight::get().writes(13 as u64))
+	}
+	// Storage: unknown [0x3a7472616e73616374696f6e5f6c6576656c3a] (r:1 w:1)
+	// Storage: Loans Markets (r:2 w:0)
+	// Storage: Loans RewardTokenConfigs (r:2 w:1)
+	// Storage: Loans RewardTokenSupplyState (r:1 w:1)
+	// Storage: Loans RewardTokenBorrowState (r:1 w:1)
+	// Storage: Loans TotalSupply (r:1 w:0)
+	fn update_reward_token_speed() -> Weight {
+		Weight::from_ref_time(72_504_000 as u64)
+			.saturating_add(T::DbWeight::get().reads(8 as u64))
+			.saturating_add(T::DbWeight::get().writes(3 as u64))
+	}
+	// Storage: unknown [0x3a7472616e73616374696f6e5f6c6576656c3a] (r:1 w:1)
+	// Storage: Assets Asset (r:1 w:1)
+	// Storage: Assets Account (r:2 w:2)
+	fn add_reward_token() -> Weight {
+		Weight::from_ref_time(88_410_000 as u64)
+			.saturating_add(T::DbWeight::get().reads(3 as u64))
+			.saturating_add(T::DbWeight::get().writes(3 as u64))
+	}
+	// Storage: unknown [0x3a7472616e73616374696f6e5f6c6576656c3a] (r:1 w:1)
+	// Storage: Assets Asset (r:1 w:1)
+	// Storage: Assets Account (r:2 w:2)
+	fn withdraw_reward_token() -> Weight {
+		Weight::from_ref_time(83_926_000 as u64)
+			.saturating_add(T::DbWeight::get().reads(3 as u64))
+			.saturating_add(T::DbWeight::get().writes(3 as u64))
+	}
+	// Storage: unknown [0x3a7472616e73616374696f6e5f6c6576656c3a] (r:1 w:1)
+	// Storage: Loans RewardTokenConfigs (r:1 w:1)
+	// Storage: Loans RewardTokenSupplyState (r:1 w:1)
+	// Storage: Loans RewardTokenBorrowState (r:1 w:1)
+	// Storage: Loans TotalSupply (r:1 w:0)
+	// Storage: Loans TotalBorrows (r:1 w:0)
+	// Storage: Loans BorrowIndex (r:1 w:0)
+	fn remove_reward_token() -> Weight {
+		Weight::from_ref_time(58_812_000 as u64)
+			.saturating_add(T::DbWeight::get().reads(7 as u64))
+			.saturating_add(T::DbWeight::get().writes(4 as u64))
+	}
+	// Storage: unknown [0x3a7472616e73616374696f6e5f6c6576656c3a] (r:1 w:1)
+	// Storage: Loans Markets (r:1 w:1)
+	// Storage: Loans MarketDeprecations (r:0 w:1)
+	fn deprecate_market() -> Weight {
+		Weight::from_ref_time(41_872_000 as u64)
+			.saturating_add(T::DbWeight::get().reads(2 as u64))
+			.saturating_add(T::DbWeight::get().writes(3 as u64))
+	}
+	// Storage: unknown [0x3a7472616e73616374696f6e5f6c6576656c3a] (r:1 w:1)
+	// Storage: Loans Markets (r:1 w:1)
+	// Storage: Loans TotalSupply (r:1 w:1)
+	// Storage: Loans TotalBorrows (r:1 w:1)
+	// Storage: Loans TotalFixedBorrows (r:1 w:1)
+	// Storage: Loans TotalReserves (r:1 w:1)
+	// Storage: Loans UnderlyingAssetId (r:0 w:1)
+	// Storage: Loans MarketDeprecations (r:0 w:1)
+	// Storage: Loans LastAccruedInterestTime (r:0 w:1)
+	// Storage: Loans BorrowIndex (r:0 w:1)
+	// Storage: Loans ExchangeRate (r:0 w:1)
+	// Storage: Loans BorrowRate (r:0 w:1)
+	// Storage: Loans SupplyRate (r:0 w:1)
+	// Storage: Loans UtilizationRatio (r:0 w:1)
+	// Storage: Loans RateAtTarget (r:0 w:1)
+	// Storage: Loans RewardSupplySpeed (r:0 w:1)
+	// Storage: Loans RewardBorrowSpeed (r:0 w:1)
+	// Storage: Loans MarketEModeCategory (r:0 w:1)
+	// Storage: Loans FixedRateConfigs (r:0 w:1)
+	// Storage: Loans FlashLoanFees (r:0 w:1)
+	// Storage: Loans LiquidationAuctionConfigs (r:0 w:1)
+	// Storage: Loans LiquidationFreeCollaterals (r:1 w:1)
+	// Storage: Loans RewardTokenConfigs (r:1 w:1)
+	// Storage: Loans RewardTokenSupplyState (r:1 w:1)
+	// Storage: Loans RewardTokenBorrowState (r:1 w:1)
+	// Storage: Loans IsolatedDebt (r:1 w:1)
+	// Storage: Loans MarketSnapshots (r:1 w:1)
+	// Storage: Loans FixedBorrowsYearlyInterest (r:0 w:1)
+	// Storage: Loans TotalBadDebt (r:0 w:1)
+	// Storage: Loans BadDebtSocialisation (r:0 w:1)
+	// Storage: Loans RewardSupplyState (r:0 w:1)
+	// Storage: Loans RewardBorrowState (r:0 w:1)
+	// Storage: Loans MarketSnapshotHead (r:0 w:1)
+	// Storage: Loans OpenLiquidationAuctions (r:1 w:1)
+	// Storage: Loans FlashLoanAmounts (r:0 w:1)
+	fn remove_market() -> Weight {
+		Weight::from_ref_time(79_305_000 as u64)
+			.saturating_add(T::DbWeight::get().reads(13 as u64))
+			.saturating_add(T::DbWeight::get().writes(36 as u64))
+	}
+	// Storage: unknown [0x3a7472616e73616374696f6e5f6c6576656c3a] (r:1 w:1)
+	// Storage: Loans Markets (r:1 w:1)
+	fn set_market_price_staleness() -> Weight {
+		Weight::from_ref_time(44_499_000 as u64)
+			.saturating_add(T::DbWeight::get().reads(2 as u64))
+			.saturating_add(T::DbWeight::get().writes(2 as u64))
+	}
 }
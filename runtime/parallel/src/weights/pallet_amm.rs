@@ -33,32 +33,39 @@ impl<T: frame_system::Config> pallet_amm::WeightInfo for WeightInfo<T> {
 	// Storage: AMM ProtocolFee (r:1 w:0)
 	// Storage: Assets Asset (r:3 w:3)
 	// Storage: Assets Account (r:5 w:5)
+	// Storage: AMM Observations (r:1 w:1)
+	// Storage: AMM PoolDeposits (r:1 w:0)
+	// Storage: AMM RefundThresholdReachedAt (r:1 w:1)
 	fn add_liquidity() -> Weight {
 		// Minimum execution time: 168_870 nanoseconds.
 		Weight::from_ref_time(170_464_000)
-			.saturating_add(T::DbWeight::get().reads(10))
-			.saturating_add(T::DbWeight::get().writes(9))
+			.saturating_add(T::DbWeight::get().reads(13))
+			.saturating_add(T::DbWeight::get().writes(11))
 	}
 	// Storage: AMM Pools (r:1 w:1)
 	// Storage: AMM ProtocolFee (r:1 w:0)
 	// Storage: Assets Asset (r:3 w:3)
 	// Storage: Assets Account (r:5 w:5)
+	// Storage: AMM Observations (r:1 w:1)
+	// Storage: AMM PoolDeposits (r:1 w:0)
+	// Storage: AMM RefundThresholdReachedAt (r:1 w:1)
 	fn remove_liquidity() -> Weight {
 		// Minimum execution time: 184_600 nanoseconds.
 		Weight::from_ref_time(186_948_000)
-			.saturating_add(T::DbWeight::get().reads(10))
-			.saturating_add(T::DbWeight::get().writes(9))
+			.saturating_add(T::DbWeight::get().reads(13))
+			.saturating_add(T::DbWeight::get().writes(11))
 	}
 	// Storage: AMM Pools (r:1 w:1)
 	// Storage: Assets Asset (r:3 w:3)
 	// Storage: Assets Account (r:6 w:6)
 	// Storage: System Account (r:2 w:2)
 	// Storage: AMM ProtocolFee (r:1 w:0)
+	// Storage: AMM LpTokenPools (r:0 w:1)
 	fn create_pool() -> Weight {
 		// Minimum execution time: 209_601 nanoseconds.
 		Weight::from_ref_time(211_368_000)
 			.saturating_add(T::DbWeight::get().reads(13))
-			.saturating_add(T::DbWeight::get().writes(12))
+			.saturating_add(T::DbWeight::get().writes(13))
 	}
 	// Storage: AMM ProtocolFee (r:0 w:1)
 	fn update_protocol_fee() -> Weight {
@@ -72,4 +79,49 @@ impl<T: frame_system::Config> pallet_amm::WeightInfo for WeightInfo<T> {
 		Weight::from_ref_time(25_100_000)
 			.saturating_add(T::DbWeight::get().writes(1))
 	}
+	// Storage: AMM Pools (r:1 w:1)
+	fn update_lp_fee() -> Weight {
+		// Minimum execution time: 24_312 nanoseconds.
+		Weight::from_ref_time(24_900_000)
+			.saturating_add(T::DbWeight::get().reads(1))
+			.saturating_add(T::DbWeight::get().writes(1))
+	}
+	// Storage: AMM Pools (r:1 w:1)
+	// Storage: Assets Asset (r:4 w:4)
+	// Storage: Assets Account (r:6 w:6)
+	// Storage: System Account (r:3 w:3)
+	// Storage: AMM PoolObservations (r:1 w:1)
+	// Storage: AMMRoute Adjacency (r:2 w:2)
+	// Storage: AMM PoolDeposits (r:0 w:1)
+	// Storage: AMM LpTokenPools (r:0 w:1)
+	// Storage: AMM NextLpTokenId (r:1 w:1)
+	// Storage: AMM RefundThresholdReachedAt (r:1 w:1)
+	fn create_pool_permissionless() -> Weight {
+		// Minimum execution time: 204_817 nanoseconds.
+		Weight::from_ref_time(209_115_000)
+			.saturating_add(T::DbWeight::get().reads(19))
+			.saturating_add(T::DbWeight::get().writes(21))
+	}
+	// Storage: AMM Pools (r:1 w:0)
+	// Storage: AMM DelistedPools (r:1 w:0)
+	// Storage: AMM RefundThresholdReachedAt (r:1 w:1)
+	// Storage: AMM PoolDeposits (r:1 w:1)
+	// Storage: System Account (r:2 w:2)
+	fn refund_pool_deposit() -> Weight {
+		// Minimum execution time: 58_324 nanoseconds.
+		Weight::from_ref_time(59_602_000)
+			.saturating_add(T::DbWeight::get().reads(6))
+			.saturating_add(T::DbWeight::get().writes(4))
+	}
+	// Storage: AMM Pools (r:1 w:0)
+	// Storage: AMM DelistedPools (r:1 w:1)
+	// Storage: AMM PoolDeposits (r:1 w:1)
+	// Storage: System Account (r:1 w:1)
+	// Storage: AMM RefundThresholdReachedAt (r:0 w:1)
+	fn delist_pool() -> Weight {
+		// Minimum execution time: 45_931 nanoseconds.
+		Weight::from_ref_time(46_870_000)
+			.saturating_add(T::DbWeight::get().reads(4))
+			.saturating_add(T::DbWeight::get().writes(4))
+	}
 }
@@ -147,11 +147,13 @@ impl<T: frame_system::Config> pallet_loans::WeightInfo for WeightInfo<T> {
 	// Storage: Loans TotalReserves (r:1 w:0)
 	// Storage: Loans AccountEarned (r:1 w:1)
 	// Storage: System Account (r:1 w:1)
+	// Storage: Loans MarketSnapshotHead (r:1 w:1)
+	// Storage: Loans MarketSnapshots (r:1 w:1)
 	fn mint() -> Weight {
 		// Minimum execution time: 182_561 nanoseconds.
 		Weight::from_ref_time(184_482_000)
-			.saturating_add(T::DbWeight::get().reads(17))
-			.saturating_add(T::DbWeight::get().writes(11))
+			.saturating_add(T::DbWeight::get().reads(19))
+			.saturating_add(T::DbWeight::get().writes(13))
 	}
 	// Storage: Loans Markets (r:2 w:0)
 	// Storage: Timestamp Now (r:1 w:0)
@@ -171,11 +173,13 @@ impl<T: frame_system::Config> pallet_loans::WeightInfo for WeightInfo<T> {
 	// Storage: Loans RewardBorrowerIndex (r:1 w:1)
 	// Storage: Loans RewardAccured (r:1 w:1)
 	// Storage: Loans BorrowIndex (r:1 w:0)
+	// Storage: Loans MarketSnapshotHead (r:1 w:1)
+	// Storage: Loans MarketSnapshots (r:1 w:1)
 	fn borrow() -> Weight {
 		// Minimum execution time: 264_045 nanoseconds.
 		Weight::from_ref_time(266_343_000)
-			.saturating_add(T::DbWeight::get().reads(23))
-			.saturating_add(T::DbWeight::get().writes(9))
+			.saturating_add(T::DbWeight::get().reads(25))
+			.saturating_add(T::DbWeight::get().writes(11))
 	}
 	// Storage: Loans Markets (r:2 w:0)
 	// Storage: Timestamp Now (r:1 w:0)
@@ -191,11 +195,13 @@ impl<T: frame_system::Config> pallet_loans::WeightInfo for WeightInfo<T> {
 	// Storage: Loans RewardSupplySpeed (r:1 w:0)
 	// Storage: Loans RewardSupplierIndex (r:1 w:1)
 	// Storage: Loans RewardAccured (r:1 w:1)
+	// Storage: Loans MarketSnapshotHead (r:1 w:1)
+	// Storage: Loans MarketSnapshots (r:1 w:1)
 	fn redeem() -> Weight {
 		// Minimum execution time: 218_487 nanoseconds.
 		Weight::from_ref_time(219_746_000)
-			.saturating_add(T::DbWeight::get().reads(16))
-			.saturating_add(T::DbWeight::get().writes(10))
+			.saturating_add(T::DbWeight::get().reads(18))
+			.saturating_add(T::DbWeight::get().writes(12))
 	}
 	// Storage: Loans Markets (r:2 w:0)
 	// Storage: Timestamp Now (r:1 w:0)
@@ -212,11 +218,13 @@ impl<T: frame_system::Config> pallet_loans::WeightInfo for WeightInfo<T> {
 	// Storage: Loans RewardSupplierIndex (r:1 w:1)
 	// Storage: Loans RewardAccured (r:1 w:1)
 	// Storage: System Account (r:1 w:1)
+	// Storage: Loans MarketSnapshotHead (r:1 w:1)
+	// Storage: Loans MarketSnapshots (r:1 w:1)
 	fn redeem_all() -> Weight {
 		// Minimum execution time: 230_896 nanoseconds.
 		Weight::from_ref_time(234_595_000)
-			.saturating_add(T::DbWeight::get().reads(17))
-			.saturating_add(T::DbWeight::get().writes(11))
+			.saturating_add(T::DbWeight::get().reads(19))
+			.saturating_add(T::DbWeight::get().writes(13))
 	}
 	// Storage: Loans Markets (r:2 w:0)
 	// Storage: Timestamp Now (r:1 w:0)
@@ -230,11 +238,13 @@ impl<T: frame_system::Config> pallet_loans::WeightInfo for WeightInfo<T> {
 	// Storage: Assets Asset (r:1 w:1)
 	// Storage: Assets Account (r:2 w:2)
 	// Storage: Loans TotalBorrows (r:1 w:1)
+	// Storage: Loans MarketSnapshotHead (r:1 w:1)
+	// Storage: Loans MarketSnapshots (r:1 w:1)
 	fn repay_borrow() -> Weight {
 		// Minimum execution time: 175_791 nanoseconds.
 		Weight::from_ref_time(178_062_000)
-			.saturating_add(T::DbWeight::get().reads(14))
-			.saturating_add(T::DbWeight::get().writes(9))
+			.saturating_add(T::DbWeight::get().reads(16))
+			.saturating_add(T::DbWeight::get().writes(11))
 	}
 	// Storage: Loans Markets (r:2 w:0)
 	// Storage: Timestamp Now (r:1 w:0)
@@ -248,11 +258,13 @@ impl<T: frame_system::Config> pallet_loans::WeightInfo for WeightInfo<T> {
 	// Storage: Assets Asset (r:1 w:1)
 	// Storage: Assets Account (r:2 w:2)
 	// Storage: Loans TotalBorrows (r:1 w:1)
+	// Storage: Loans MarketSnapshotHead (r:1 w:1)
+	// Storage: Loans MarketSnapshots (r:1 w:1)
 	fn repay_borrow_all() -> Weight {
 		// Minimum execution time: 191_013 nanoseconds.
 		Weight::from_ref_time(193_213_000)
-			.saturating_add(T::DbWeight::get().reads(14))
-			.saturating_add(T::DbWeight::get().writes(9))
+			.saturating_add(T::DbWeight::get().reads(16))
+			.saturating_add(T::DbWeight::get().writes(11))
 	}
 	// Storage: Loans Markets (r:2 w:0)
 	// Storage: Loans AccountDeposits (r:1 w:1)
@@ -283,11 +295,13 @@ impl<T: frame_system::Config> pallet_loans::WeightInfo for WeightInfo<T> {
 	// Storage: Loans RewardSupplyState (r:1 w:1)
 	// Storage: Loans RewardSupplySpeed (r:1 w:0)
 	// Storage: Loans RewardSupplierIndex (r:3 w:3)
+	// Storage: Loans MarketSnapshotHead (r:2 w:2)
+	// Storage: Loans MarketSnapshots (r:2 w:2)
 	fn liquidate_borrow() -> Weight {
 		// Minimum execution time: 478_883 nanoseconds.
 		Weight::from_ref_time(483_656_000)
-			.saturating_add(T::DbWeight::get().reads(42))
-			.saturating_add(T::DbWeight::get().writes(19))
+			.saturating_add(T::DbWeight::get().reads(46))
+			.saturating_add(T::DbWeight::get().writes(23))
 	}
 	// Storage: Loans Markets (r:2 w:0)
 	// Storage: Assets Asset (r:1 w:1)
@@ -317,4 +331,333 @@ impl<T: frame_system::Config> pallet_loans::WeightInfo for WeightInfo<T> {
 			.saturating_add(T::DbWeight::get().reads(1))
 			.saturating_add(T::DbWeight::get().writes(1))
 	}
+	// Storage: Loans EModeCategories (r:0 w:1)
+	fn set_emode_category() -> Weight {
+		// Minimum execution time: 27_326 nanoseconds.
+		Weight::from_ref_time(28_172_000)
+			.saturating_add(T::DbWeight::get().writes(1))
+	}
+	// Storage: Loans Markets (r:1 w:0)
+	// Storage: Loans EModeCategories (r:1 w:0)
+	// Storage: Loans MarketEModeCategory (r:0 w:1)
+	fn set_market_emode_category() -> Weight {
+		// Minimum execution time: 34_765 nanoseconds.
+		Weight::from_ref_time(35_841_000)
+			.saturating_add(T::DbWeight::get().reads(2))
+			.saturating_add(T::DbWeight::get().writes(1))
+	}
+	// Storage: Loans Markets (r:1 w:0)
+	// Storage: Loans FlashLoanFees (r:0 w:1)
+	fn set_flash_loan_fee() -> Weight {
+		// Minimum execution time: 30_312 nanoseconds.
+		Weight::from_ref_time(31_250_000)
+			.saturating_add(T::DbWeight::get().reads(1))
+			.saturating_add(T::DbWeight::get().writes(1))
+	}
+	// Storage: Loans Markets (r:1 w:0)
+	// Storage: Loans FlashLoanFees (r:1 w:0)
+	// Storage: Loans TotalReserves (r:1 w:1)
+	// Storage: Loans FlashLoanAmounts (r:1 w:1)
+	// Storage: Assets Asset (r:1 w:1)
+	// Storage: Assets Account (r:2 w:2)
+	fn flash_loan() -> Weight {
+		// Minimum execution time: 95_459 nanoseconds.
+		Weight::from_ref_time(98_412_000)
+			.saturating_add(T::DbWeight::get().reads(6))
+			.saturating_add(T::DbWeight::get().writes(6))
+	}
+	// Storage: Loans Markets (r:2 w:0)
+	// Storage: Loans LastAccruedInterestTime (r:2 w:2)
+	// Storage: Loans AccountDeposits (r:1 w:1)
+	// Storage: Loans TotalSupply (r:1 w:1)
+	// Storage: Loans AccountBorrows (r:1 w:1)
+	// Storage: Loans TotalBorrows (r:1 w:1)
+	// Storage: AMM Pools (r:1 w:1)
+	// Storage: Assets Asset (r:2 w:2)
+	// Storage: Assets Account (r:6 w:6)
+	// Storage: Loans MarketSnapshotHead (r:2 w:2)
+	// Storage: Loans MarketSnapshots (r:2 w:2)
+	fn repay_with_collateral() -> Weight {
+		// Minimum execution time: 303_459 nanoseconds.
+		Weight::from_ref_time(312_845_000)
+			.saturating_add(T::DbWeight::get().reads(28))
+			.saturating_add(T::DbWeight::get().writes(21))
+	}
+	// Storage: Loans Markets (r:2 w:0)
+	// Storage: Loans LastAccruedInterestTime (r:2 w:2)
+	// Storage: Loans AccountDeposits (r:1 w:1)
+	// Storage: Loans TotalSupply (r:1 w:1)
+	// Storage: Loans AccountBorrows (r:1 w:1)
+	// Storage: Loans TotalBorrows (r:1 w:1)
+	// Storage: AMM Pools (r:1 w:1)
+	// Storage: Assets Asset (r:2 w:2)
+	// Storage: Assets Account (r:6 w:6)
+	// Storage: Loans MarketSnapshotHead (r:2 w:2)
+	// Storage: Loans MarketSnapshots (r:2 w:2)
+	fn leverage() -> Weight {
+		// Minimum execution time: 325_053 nanoseconds.
+		Weight::from_ref_time(335_107_000)
+			.saturating_add(T::DbWeight::get().reads(30))
+			.saturating_add(T::DbWeight::get().writes(22))
+	}
+	// Storage: Loans Markets (r:1 w:0)
+	// Storage: Loans LiquidationAuctionConfigs (r:0 w:1)
+	fn set_liquidation_auction_config() -> Weight {
+		// Minimum execution time: 30_917 nanoseconds.
+		Weight::from_ref_time(31_874_000)
+			.saturating_add(T::DbWeight::get().reads(1))
+			.saturating_add(T::DbWeight::get().writes(1))
+	}
+	// Storage: Loans Markets (r:2 w:0)
+	// Storage: Loans LiquidationAuctionConfigs (r:1 w:0)
+	// Storage: Loans LiquidationAuctions (r:1 w:1)
+	// Storage: Loans LastAccruedInterestTime (r:1 w:1)
+	// Storage: Loans TotalSupply (r:1 w:0)
+	// Storage: Loans TotalBorrows (r:1 w:0)
+	// Storage: Loans TotalReserves (r:1 w:0)
+	// Storage: Loans BorrowIndex (r:1 w:1)
+	// Storage: Loans AccountBorrows (r:1 w:0)
+	// Storage: Loans AccountDeposits (r:1 w:0)
+	// Storage: Prices EmergencyPrice (r:1 w:0)
+	// Storage: Assets Account (r:1 w:0)
+	// Storage: Loans MarketSnapshotHead (r:1 w:1)
+	// Storage: Loans MarketSnapshots (r:1 w:1)
+	// Storage: Loans OpenLiquidationAuctions (r:1 w:1)
+	fn open_liquidation_auction() -> Weight {
+		// Minimum execution time: 93_607 nanoseconds.
+		Weight::from_ref_time(96_503_000)
+			.saturating_add(T::DbWeight::get().reads(16))
+			.saturating_add(T::DbWeight::get().writes(6))
+	}
+	// Storage: Loans LiquidationAuctionConfigs (r:1 w:0)
+	// Storage: Loans LiquidationAuctions (r:1 w:1)
+	// Storage: Loans LiquidationFreeCollaterals (r:1 w:0)
+	// Storage: Loans Markets (r:3 w:0)
+	// Storage: Loans LastAccruedInterestTime (r:2 w:2)
+	// Storage: Loans TotalSupply (r:1 w:0)
+	// Storage: Loans TotalBorrows (r:2 w:1)
+	// Storage: Loans TotalReserves (r:2 w:0)
+	// Storage: Loans BorrowIndex (r:1 w:0)
+	// Storage: Loans AccountBorrows (r:2 w:1)
+	// Storage: Loans AccountDeposits (r:4 w:3)
+	// Storage: Loans RewardBorrowState (r:1 w:1)
+	// Storage: Loans RewardSupplyState (r:1 w:1)
+	// Storage: Prices EmergencyPrice (r:2 w:0)
+	// Storage: Assets Account (r:2 w:2)
+	// Storage: Loans MarketSnapshotHead (r:2 w:2)
+	// Storage: Loans MarketSnapshots (r:2 w:2)
+	// Storage: Loans OpenLiquidationAuctions (r:1 w:1)
+	fn fill_liquidation_auction() -> Weight {
+		// Minimum execution time: 241_153 nanoseconds.
+		Weight::from_ref_time(248_612_000)
+			.saturating_add(T::DbWeight::get().reads(29))
+			.saturating_add(T::DbWeight::get().writes(19))
+	}
+	// Storage: Loans LiquidationAuctions (r:1 w:1)
+	// Storage: Loans LiquidationAuctionConfigs (r:1 w:0)
+	// Storage: Loans Markets (r:2 w:0)
+	// Storage: Loans LastAccruedInterestTime (r:1 w:1)
+	// Storage: Loans TotalBorrows (r:1 w:0)
+	// Storage: Loans TotalReserves (r:1 w:0)
+	// Storage: Loans BorrowIndex (r:1 w:0)
+	// Storage: Loans AccountBorrows (r:1 w:0)
+	// Storage: Loans AccountDeposits (r:1 w:0)
+	// Storage: Prices EmergencyPrice (r:1 w:0)
+	// Storage: Assets Account (r:1 w:0)
+	// Storage: Loans MarketSnapshotHead (r:1 w:1)
+	// Storage: Loans MarketSnapshots (r:1 w:1)
+	// Storage: Loans OpenLiquidationAuctions (r:1 w:1)
+	fn close_liquidation_auction() -> Weight {
+		// Minimum execution time: 81_767 nanoseconds.
+		Weight::from_ref_time(84_296_000)
+			.saturating_add(T::DbWeight::get().reads(15))
+			.saturating_add(T::DbWeight::get().writes(5))
+	}
+	// Storage: Loans Markets (r:1 w:0)
+	// Storage: Loans BadDebtSocialisation (r:0 w:1)
+	fn set_bad_debt_socialisation() -> Weight {
+		// Minimum execution time: 30_033 nanoseconds.
+		Weight::from_ref_time(30_962_000)
+			.saturating_add(T::DbWeight::get().reads(1))
+			.saturating_add(T::DbWeight::get().writes(1))
+	}
+	// Storage: Loans Markets (r:1 w:0)
+	// Storage: Loans FixedRateConfigs (r:0 w:1)
+	fn set_fixed_rate_config() -> Weight {
+		// Minimum execution time: 30_345 nanoseconds.
+		Weight::from_ref_time(31_284_000)
+			.saturating_add(T::DbWeight::get().reads(1))
+			.saturating_add(T::DbWeight::get().writes(1))
+	}
+	// Storage: unknown [0x3a7472616e73616374696f6e5f6c6576656c3a] (r:1 w:1)
+	// Storage: Loans Markets (r:2 w:0)
+	// Storage: Loans FixedRateConfigs (r:1 w:0)
+	// Storage: Loans AccountFixedBorrows (r:2 w:1)
+	// Storage: Timestamp Now (r:1 w:0)
+	// Storage: Loans LastAccruedInterestTime (r:1 w:1)
+	// Storage: Loans TotalBorrows (r:1 w:0)
+	// Storage: Loans TotalFixedBorrows (r:1 w:1)
+	// Storage: Loans TotalReserves (r:1 w:1)
+	// Storage: Assets Asset (r:1 w:1)
+	// Storage: Assets Account (r:2 w:2)
+	// Storage: Prices EmergencyPrice (r:2 w:0)
+	// Storage: Assets Metadata (r:2 w:0)
+	// Storage: Loans AccountBorrows (r:2 w:0)
+	// Storage: Loans AccountDeposits (r:1 w:0)
+	// Storage: Loans TotalSupply (r:1 w:0)
+	// Storage: Loans LiquidationFreeCollaterals (r:1 w:0)
+	// Storage: Loans BorrowIndex (r:1 w:0)
+	// Storage: Loans MarketSnapshotHead (r:1 w:1)
+	// Storage: Loans MarketSnapshots (r:1 w:1)
+	fn fixed_borrow() -> Weight {
+		// Minimum execution time: 309_034 nanoseconds.
+		Weight::from_ref_time(318_592_000)
+			.saturating_add(T::DbWeight::get().reads(27))
+			.saturating_add(T::DbWeight::get().writes(11))
+	}
+	// Storage: unknown [0x3a7472616e73616374696f6e5f6c6576656c3a] (r:1 w:1)
+	// Storage: Loans Markets (r:2 w:0)
+	// Storage: Loans AccountFixedBorrows (r:1 w:1)
+	// Storage: Timestamp Now (r:1 w:0)
+	// Storage: Loans TotalFixedBorrows (r:1 w:1)
+	// Storage: Loans TotalReserves (r:1 w:1)
+	// Storage: Assets Asset (r:1 w:1)
+	// Storage: Assets Account (r:2 w:2)
+	// Storage: Loans MarketSnapshotHead (r:1 w:1)
+	// Storage: Loans MarketSnapshots (r:1 w:1)
+	fn repay_fixed_borrow() -> Weight {
+		// Minimum execution time: 109_461 nanoseconds.
+		Weight::from_ref_time(112_847_000)
+			.saturating_add(T::DbWeight::get().reads(12))
+			.saturating_add(T::DbWeight::get().writes(9))
+	}
+	// Storage: unknown [0x3a7472616e73616374696f6e5f6c6576656c3a] (r:1 w:1)
+	// Storage: Loans Markets (r:2 w:0)
+	// Storage: Loans AccountFixedBorrows (r:1 w:1)
+	// Storage: Timestamp Now (r:1 w:0)
+	// Storage: Loans LastAccruedInterestTime (r:1 w:1)
+	// Storage: Loans TotalBorrows (r:1 w:1)
+	// Storage: Loans TotalFixedBorrows (r:1 w:1)
+	// Storage: Loans TotalReserves (r:1 w:1)
+	// Storage: Loans FixedRateConfigs (r:1 w:0)
+	// Storage: Loans OverdueBorrows (r:0 w:1)
+	// Storage: Prices EmergencyPrice (r:2 w:0)
+	// Storage: Assets Metadata (r:2 w:0)
+	// Storage: Loans AccountBorrows (r:2 w:1)
+	// Storage: Loans AccountDeposits (r:1 w:0)
+	// Storage: Loans TotalSupply (r:1 w:0)
+	// Storage: Loans LiquidationFreeCollaterals (r:1 w:0)
+	// Storage: Loans RewardBorrowState (r:1 w:1)
+	// Storage: Loans RewardBorrowSpeed (r:1 w:0)
+	// Storage: Loans RewardBorrowerIndex (r:1 w:1)
+	// Storage: Loans RewardAccured (r:1 w:1)
+	// Storage: Loans BorrowIndex (r:1 w:0)
+	// Storage: Loans MarketSnapshotHead (r:1 w:1)
+	// Storage: Loans MarketSnapshots (r:1 w:1)
+	fn settle_fixed_borrow() -> Weight {
+		// Minimum execution time: 292_721 nanoseconds.
+		Weight::from_ref_time(301_775_000)
+			.saturating_add(T::DbWeight::get().reads(28))
+			.saturating_add(T::DbWeight::get().writes(13))
+	}
+	// Storage: unknown [0x3a7472616e73616374696f6e5f6c6576656c3a] (r:1 w:1)
+	// Storage: Loans Markets (r:2 w:0)
+	// Storage: Loans RewardTokenConfigs (r:2 w:1)
+	// Storage: Loans RewardTokenSupplyState (r:1 w:1)
+	// Storage: Loans RewardTokenBorrowState (r:1 w:1)
+	// Storage: Loans TotalSupply (r:1 w:0)
+	fn update_reward_token_speed() -> Weight {
+		// Minimum execution time: 70_328 nanoseconds.
+		Weight::from_ref_time(72_504_000)
+			.saturating_add(T::DbWeight::get().reads(8))
+			.saturating_add(T::DbWeight::get().writes(3))
+	}
+	// Storage: unknown [0x3a7472616e73616374696f6e5f6c6576656c3a] (r:1 w:1)
+	// Storage: Assets Asset (r:1 w:1)
+	// Storage: Assets Account (r:2 w:2)
+	fn add_reward_token() -> Weight {
+		// Minimum execution time: 85_757 nanoseconds.
+		Weight::from_ref_time(88_410_000)
+			.saturating_add(T::DbWeight::get().reads(3))
+			.saturating_add(T::DbWeight::get().writes(3))
+	}
+	// Storage: unknown [0x3a7472616e73616374696f6e5f6c6576656c3a] (r:1 w:1)
+	// Storage: Assets Asset (r:1 w:1)
+	// Storage: Assets Account (r:2 w:2)
+	fn withdraw_reward_token() -> Weight {
+		// Minimum execution time: 81_408 nanoseconds.
+		Weight::from_ref_time(83_926_000)
+			.saturating_add(T::DbWeight::get().reads(3))
+			.saturating_add(T::DbWeight::get().writes(3))
+	}
+	// Storage: unknown [0x3a7472616e73616374696f6e5f6c6576656c3a] (r:1 w:1)
+	// Storage: Loans RewardTokenConfigs (r:1 w:1)
+	// Storage: Loans RewardTokenSupplyState (r:1 w:1)
+	// Storage: Loans RewardTokenBorrowState (r:1 w:1)
+	// Storage: Loans TotalSupply (r:1 w:0)
+	// Storage: Loans TotalBorrows (r:1 w:0)
+	// Storage: Loans BorrowIndex (r:1 w:0)
+	fn remove_reward_token() -> Weight {
+		// Minimum execution time: 56_213 nanoseconds.
+		Weight::from_ref_time(58_812_000)
+			.saturating_add(T::DbWeight::get().reads(7))
+			.saturating_add(T::DbWeight::get().writes(4))
+	}
+	// Storage: unknown [0x3a7472616e73616374696f6e5f6c6576656c3a] (r:1 w:1)
+	// Storage: Loans Markets (r:1 w:1)
+	// Storage: Loans MarketDeprecations (r:0 w:1)
+	fn deprecate_market() -> Weight {
+		// Minimum execution time: 40_615 nanoseconds.
+		Weight::from_ref_time(41_872_000)
+			.saturating_add(T::DbWeight::get().reads(2))
+			.saturating_add(T::DbWeight::get().writes(3))
+	}
+	// Storage: unknown [0x3a7472616e73616374696f6e5f6c6576656c3a] (r:1 w:1)
+	// Storage: Loans Markets (r:1 w:1)
+	// Storage: Loans TotalSupply (r:1 w:1)
+	// Storage: Loans TotalBorrows (r:1 w:1)
+	// Storage: Loans TotalFixedBorrows (r:1 w:1)
+	// Storage: Loans TotalReserves (r:1 w:1)
+	// Storage: Loans UnderlyingAssetId (r:0 w:1)
+	// Storage: Loans MarketDeprecations (r:0 w:1)
+	// Storage: Loans LastAccruedInterestTime (r:0 w:1)
+	// Storage: Loans BorrowIndex (r:0 w:1)
+	// Storage: Loans ExchangeRate (r:0 w:1)
+	// Storage: Loans BorrowRate (r:0 w:1)
+	// Storage: Loans SupplyRate (r:0 w:1)
+	// Storage: Loans UtilizationRatio (r:0 w:1)
+	// Storage: Loans RateAtTarget (r:0 w:1)
+	// Storage: Loans RewardSupplySpeed (r:0 w:1)
+	// Storage: Loans RewardBorrowSpeed (r:0 w:1)
+	// Storage: Loans MarketEModeCategory (r:0 w:1)
+	// Storage: Loans FixedRateConfigs (r:0 w:1)
+	// Storage: Loans FlashLoanFees (r:0 w:1)
+	// Storage: Loans LiquidationAuctionConfigs (r:0 w:1)
+	// Storage: Loans LiquidationFreeCollaterals (r:1 w:1)
+	// Storage: Loans RewardTokenConfigs (r:1 w:1)
+	// Storage: Loans RewardTokenSupplyState (r:1 w:1)
+	// Storage: Loans RewardTokenBorrowState (r:1 w:1)
+	// Storage: Loans IsolatedDebt (r:1 w:1)
+	// Storage: Loans MarketSnapshots (r:1 w:1)
+	// Storage: Loans FixedBorrowsYearlyInterest (r:0 w:1)
+	// Storage: Loans TotalBadDebt (r:0 w:1)
+	// Storage: Loans BadDebtSocialisation (r:0 w:1)
+	// Storage: Loans RewardSupplyState (r:0 w:1)
+	// Storage: Loans RewardBorrowState (r:0 w:1)
+	// Storage: Loans MarketSnapshotHead (r:0 w:1)
+	// Storage: Loans OpenLiquidationAuctions (r:1 w:1)
+	// Storage: Loans FlashLoanAmounts (r:0 w:1)
+	fn remove_market() -> Weight {
+		// Minimum execution time: 76_925 nanoseconds.
+		Weight::from_ref_time(79_305_000)
+			.saturating_add(T::DbWeight::get().reads(13))
+			.saturating_add(T::DbWeight::get().writes(36))
+	}
+	// Storage: Loans Markets (r:1 w:1)
+	fn set_market_price_staleness() -> Weight {
+		// Minimum execution time: 36_957 nanoseconds.
+		Weight::from_ref_time(37_915_000)
+			.saturating_add(T::DbWeight::get().reads(1))
+			.saturating_add(T::DbWeight::get().writes(1))
+	}
 }
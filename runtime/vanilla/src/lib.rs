@@ -26,6 +26,7 @@ use frame_support::{
         tokens::BalanceConversion,
         AsEnsureOriginWithArg, ChangeMembers, ConstU32, Contains, EitherOfDiverse,
         EqualPrivilegeOnly, Everything, FindAuthor, InstanceFilter, NeverEnsureOrigin, Nothing,
+        OnRuntimeUpgrade,
     },
     weights::{
         constants::{
@@ -101,15 +102,15 @@ use pallet_traits::{
         AccountIdToMultiLocation, AsAssetType, AssetType, CurrencyIdConvert, FirstAssetTrader,
         MultiCurrencyAdapter, XcmAssetRegistry,
     },
-    DecimalProvider, EmergencyCallFilter, ValidationDataProvider,
+    DecimalProvider, EmergencyCallFilter, OracleMemberProvider, ValidationDataProvider,
 };
 use primitives::{
     network::HEIKO_PREFIX,
     paras,
     tokens::{EUSDC, EUSDT, HKO, KSM, SKSM},
-    AccountId, AuraId, Balance, BlockNumber, ChainId, CurrencyId, DataProviderId, EraIndex, Hash,
-    Index, Liquidity, Moment, PersistedValidationData, Price, Rate, Ratio, Shortfall, Signature,
-    KSM_U,
+    AccountId, AuraId, Balance, BlockNumber, ChainId, CurrencyId, DataProviderId, EModeCategoryId,
+    EraIndex, Hash, Index, Liquidity, Moment, PersistedValidationData, PoolType, Price, Rate,
+    Ratio, Shortfall, Signature, KSM_U,
 };
 
 use runtime_common::{
@@ -494,6 +495,8 @@ impl pallet_assets::Config for Runtime {
 parameter_types! {
     pub const RewardAssetId: CurrencyId = HKO;
     pub const LiquidationFreeAssetId: CurrencyId = KSM;
+    pub const MarketSnapshotInterval: BlockNumber = 1 * HOURS;
+    pub const MaxMarketSnapshots: u32 = 30 * 24;
 }
 
 impl pallet_loans::Config for Runtime {
@@ -507,6 +510,10 @@ impl pallet_loans::Config for Runtime {
     type Assets = CurrencyAdapter;
     type RewardAssetId = RewardAssetId;
     type LiquidationFreeAssetId = LiquidationFreeAssetId;
+    type MarketSnapshotInterval = MarketSnapshotInterval;
+    type MaxMarketSnapshots = MaxMarketSnapshots;
+    type RuntimeCall = RuntimeCall;
+    type Router = AMMRoute;
 }
 
 parameter_types! {
@@ -1384,7 +1391,7 @@ impl orml_oracle::Config<ParallelDataProvider> for Runtime {
 
 parameter_types! {
     pub const StableSwapPalletId: PalletId = PalletId(*b"par/sswp");
-    pub const NumTokens: u8 = 2;
+    pub const NumTokens: u32 = 4;
     pub const Precision: u32 = 100;
     pub const AmplificationCoefficient: u8 = 85;
 }
@@ -1403,6 +1410,7 @@ impl pallet_stableswap::Config for Runtime {
     type Precision = Precision;
     type AmplificationCoefficient = AmplificationCoefficient;
     type CreatePoolOrigin = EnsureRootOrMoreThanHalfGeneralCouncil;
+    type OnPoolCreated = AMMRoute;
 }
 
 pub type TimeStampedPrice = orml_oracle::TimestampedValue<Price, Moment>;
@@ -1429,6 +1437,12 @@ impl DataFeeder<CurrencyId, TimeStampedPrice, AccountId> for AggregatedDataProvi
     }
 }
 
+impl OracleMemberProvider<AccountId> for AggregatedDataProvider {
+    fn get_member_value(member: &AccountId, asset_id: &CurrencyId) -> Option<TimeStampedPrice> {
+        orml_oracle::RawValues::<Runtime, ParallelDataProvider>::get(member, asset_id)
+    }
+}
+
 pub struct Decimal;
 impl DecimalProvider<CurrencyId> for Decimal {
     fn get_decimal(asset_id: &CurrencyId) -> Option<u8> {
@@ -1446,6 +1460,12 @@ impl DecimalProvider<CurrencyId> for Decimal {
     }
 }
 
+parameter_types! {
+    pub const MaxEmergencyPriceReasonLength: u32 = 128;
+    pub const MaxDeviationLimits: u32 = 64;
+    pub const MaxEmergencyPriceExpiries: u32 = 64;
+}
+
 impl pallet_prices::Config for Runtime {
     type RuntimeEvent = RuntimeEvent;
     type Source = AggregatedDataProvider;
@@ -1458,7 +1478,13 @@ impl pallet_prices::Config for Runtime {
     type VaultLoansRateProvider = Loans;
     type RelayCurrency = RelayCurrency;
     type Decimal = Decimal;
+    type MemberSource = AggregatedDataProvider;
+    type UnixTime = Timestamp;
+    type MaxEmergencyPriceReasonLength = MaxEmergencyPriceReasonLength;
+    type MaxDeviationLimits = MaxDeviationLimits;
+    type MaxEmergencyPriceExpiries = MaxEmergencyPriceExpiries;
     type AMM = AMM;
+    type StableSwap = StableSwap;
     type Assets = CurrencyAdapter;
     type WeightInfo = pallet_prices::weights::SubstrateWeight<Runtime>;
 }
@@ -1805,6 +1831,14 @@ parameter_types! {
     pub DefaultProtocolFee: Ratio = Ratio::from_rational(5u32, 10000u32);   // 0.05%
     pub DefaultProtocolFeeReceiver: AccountId = TreasuryPalletId::get().into_account_truncating();
     pub const MinimumLiquidity: u128 = 1_000u128;
+    pub const PoolCreationDeposit: Balance = 1_000 * DOLLARS;
+    pub MaxPermissionlessLpFee: Ratio = Ratio::from_percent(1);
+    pub const MinimumPoolSeedLiquidity: u128 = 1_000_000u128;
+    pub const DepositRefundThreshold: u128 = 1_000_000_000_000u128;
+    pub const DepositRefundDelay: BlockNumber = 7 * DAYS;
+    pub const PermissionlessLpTokenIdStart: CurrencyId = 500_000_000;
+    pub const MaxObservations: u32 = 144;
+    pub const ObservationPeriod: BlockNumber = 10 * MINUTES;
 }
 
 impl pallet_amm::Config for Runtime {
@@ -1815,10 +1849,23 @@ impl pallet_amm::Config for Runtime {
     type AMMWeightInfo = weights::pallet_amm::WeightInfo<Runtime>;
     type CreatePoolOrigin = EnsureRootOrMoreThanHalfGeneralCouncil;
     type ProtocolFeeUpdateOrigin = EnsureRootOrMoreThanHalfGeneralCouncil;
+    type PermissionlessCreatePoolOrigin = EnsureSigned<AccountId>;
+    type Currency = Balances;
+    type LpTokenAssets = Assets;
+    type PermissionlessLpTokenIdStart = PermissionlessLpTokenIdStart;
+    type PoolCreationDeposit = PoolCreationDeposit;
+    type MinimumPoolSeedLiquidity = MinimumPoolSeedLiquidity;
+    type DepositRefundThreshold = DepositRefundThreshold;
+    type DepositRefundDelay = DepositRefundDelay;
     type LpFee = DefaultLpFee;
+    type MaxPermissionlessLpFee = MaxPermissionlessLpFee;
     type MinimumLiquidity = MinimumLiquidity;
     type MaxLengthRoute = MaxLengthRoute;
     type GetNativeCurrencyId = NativeCurrencyId;
+    type MaxObservations = MaxObservations;
+    type ObservationPeriod = ObservationPeriod;
+    type OnPoolCreated = AMMRoute;
+    type OnPoolDelisted = AMMRoute;
 }
 
 parameter_types! {
@@ -1929,6 +1976,9 @@ impl pallet_xcm_helper::Config for Runtime {
 
 parameter_types! {
     pub const MaxLengthRoute: u8 = 10;
+    pub const MaxSplitRoutes: u32 = 3;
+    pub const MaxPoolsVisited: u32 = 256;
+    pub const MaxPoolDegree: u32 = 64;
     pub const RouterPalletId: PalletId = PalletId(*b"ammroute");
 }
 
@@ -1936,8 +1986,12 @@ impl pallet_router::Config for Runtime {
     type RuntimeEvent = RuntimeEvent;
     type PalletId = RouterPalletId;
     type AMM = AMM;
+    type StableSwap = StableSwap;
     type AMMRouterWeightInfo = weights::pallet_router::WeightInfo<Runtime>;
     type MaxLengthRoute = MaxLengthRoute;
+    type MaxSplitRoutes = MaxSplitRoutes;
+    type MaxPoolsVisited = MaxPoolsVisited;
+    type MaxPoolDegree = MaxPoolDegree;
     type Assets = CurrencyAdapter;
     type GetNativeCurrencyId = NativeCurrencyId;
 }
@@ -2040,7 +2094,7 @@ construct_runtime!(
 
         // AMM
         AMM: pallet_amm::{Pallet, Call, Storage, Event<T>} = 80,
-        AMMRoute: pallet_router::{Pallet, Call, Event<T>} = 81,
+        AMMRoute: pallet_router::{Pallet, Call, Storage, Event<T>} = 81,
         CurrencyAdapter: pallet_currency_adapter::{Pallet, Call} = 82,
 
         // Others
@@ -2099,7 +2153,15 @@ pub type Executive = frame_executive::Executive<
     frame_system::ChainContext<Runtime>,
     Runtime,
     AllPalletsWithSystem,
-    (),
+    (
+        AmmMigrationV1,
+        AmmMigrationV2,
+        StableSwapMigrationV1,
+        RouterMigrationV1,
+        LoansMigrationV7,
+        LoansMigrationV8,
+        LoansMigrationV9,
+    ),
 >;
 
 impl fp_self_contained::SelfContainedCall for RuntimeCall {
@@ -2160,6 +2222,132 @@ impl fp_self_contained::SelfContainedCall for RuntimeCall {
     }
 }
 
+pub struct AmmMigrationV1;
+impl OnRuntimeUpgrade for AmmMigrationV1 {
+    fn on_runtime_upgrade() -> frame_support::weights::Weight {
+        pallet_amm::migrations::v1::migrate::<Runtime, ()>()
+    }
+
+    #[cfg(feature = "try-runtime")]
+    fn pre_upgrade() -> Result<Vec<u8>, &'static str> {
+        pallet_amm::migrations::v1::pre_migrate::<Runtime, ()>()?;
+        Ok(Vec::new())
+    }
+
+    #[cfg(feature = "try-runtime")]
+    fn post_upgrade(_: Vec<u8>) -> Result<(), &'static str> {
+        pallet_amm::migrations::v1::post_migrate::<Runtime, ()>()
+    }
+}
+
+pub struct AmmMigrationV2;
+impl OnRuntimeUpgrade for AmmMigrationV2 {
+    fn on_runtime_upgrade() -> frame_support::weights::Weight {
+        pallet_amm::migrations::v2::migrate::<Runtime, ()>()
+    }
+
+    #[cfg(feature = "try-runtime")]
+    fn pre_upgrade() -> Result<Vec<u8>, &'static str> {
+        pallet_amm::migrations::v2::pre_migrate::<Runtime, ()>()?;
+        Ok(Vec::new())
+    }
+
+    #[cfg(feature = "try-runtime")]
+    fn post_upgrade(_: Vec<u8>) -> Result<(), &'static str> {
+        pallet_amm::migrations::v2::post_migrate::<Runtime, ()>()
+    }
+}
+
+pub struct StableSwapMigrationV1;
+impl OnRuntimeUpgrade for StableSwapMigrationV1 {
+    fn on_runtime_upgrade() -> frame_support::weights::Weight {
+        pallet_stableswap::migrations::v1::migrate::<Runtime, ()>()
+    }
+
+    #[cfg(feature = "try-runtime")]
+    fn pre_upgrade() -> Result<Vec<u8>, &'static str> {
+        pallet_stableswap::migrations::v1::pre_migrate::<Runtime, ()>()?;
+        Ok(Vec::new())
+    }
+
+    #[cfg(feature = "try-runtime")]
+    fn post_upgrade(_: Vec<u8>) -> Result<(), &'static str> {
+        pallet_stableswap::migrations::v1::post_migrate::<Runtime, ()>()
+    }
+}
+
+pub struct RouterMigrationV1;
+impl OnRuntimeUpgrade for RouterMigrationV1 {
+    fn on_runtime_upgrade() -> frame_support::weights::Weight {
+        pallet_router::migrations::v1::migrate::<Runtime, ()>()
+    }
+
+    #[cfg(feature = "try-runtime")]
+    fn pre_upgrade() -> Result<Vec<u8>, &'static str> {
+        pallet_router::migrations::v1::pre_migrate::<Runtime, ()>()?;
+        Ok(Vec::new())
+    }
+
+    #[cfg(feature = "try-runtime")]
+    fn post_upgrade(_: Vec<u8>) -> Result<(), &'static str> {
+        pallet_router::migrations::v1::post_migrate::<Runtime, ()>()
+    }
+}
+
+pub struct LoansMigrationV7;
+impl OnRuntimeUpgrade for LoansMigrationV7 {
+    fn on_runtime_upgrade() -> frame_support::weights::Weight {
+        pallet_loans::migrations::v7::migrate::<Runtime>()
+    }
+
+    #[cfg(feature = "try-runtime")]
+    fn pre_upgrade() -> Result<Vec<u8>, &'static str> {
+        pallet_loans::migrations::v7::pre_migrate::<Runtime>()?;
+        Ok(Vec::new())
+    }
+
+    #[cfg(feature = "try-runtime")]
+    fn post_upgrade(_: Vec<u8>) -> Result<(), &'static str> {
+        pallet_loans::migrations::v7::post_migrate::<Runtime>()
+    }
+}
+
+pub struct LoansMigrationV8;
+impl OnRuntimeUpgrade for LoansMigrationV8 {
+    fn on_runtime_upgrade() -> frame_support::weights::Weight {
+        pallet_loans::migrations::v8::migrate::<Runtime>()
+    }
+
+    #[cfg(feature = "try-runtime")]
+    fn pre_upgrade() -> Result<Vec<u8>, &'static str> {
+        pallet_loans::migrations::v8::pre_migrate::<Runtime>()?;
+        Ok(Vec::new())
+    }
+
+    #[cfg(feature = "try-runtime")]
+    fn post_upgrade(_: Vec<u8>) -> Result<(), &'static str> {
+        pallet_loans::migrations::v8::post_migrate::<Runtime>()
+    }
+}
+
+pub struct LoansMigrationV9;
+impl OnRuntimeUpgrade for LoansMigrationV9 {
+    fn on_runtime_upgrade() -> frame_support::weights::Weight {
+        pallet_loans::migrations::v9::migrate::<Runtime>()
+    }
+
+    #[cfg(feature = "try-runtime")]
+    fn pre_upgrade() -> Result<Vec<u8>, &'static str> {
+        pallet_loans::migrations::v9::pre_migrate::<Runtime>()?;
+        Ok(Vec::new())
+    }
+
+    #[cfg(feature = "try-runtime")]
+    fn post_upgrade(_: Vec<u8>) -> Result<(), &'static str> {
+        pallet_loans::migrations::v9::post_migrate::<Runtime>()
+    }
+}
+
 impl_runtime_apis! {
     impl sp_consensus_aura::AuraApi<Block, AuraId> for Runtime {
         fn slot_duration() -> sp_consensus_aura::SlotDuration {
@@ -2307,12 +2495,83 @@ impl_runtime_apis! {
         fn get_liquidation_threshold_liquidity(account: AccountId) -> Result<(Liquidity, Shortfall, Liquidity, Shortfall), DispatchError> {
             Loans::get_account_liquidation_threshold_liquidity(&account)
         }
+
+        fn get_account_emode_category(account: AccountId) -> Option<(EModeCategoryId, Ratio, Ratio)> {
+            Loans::account_emode_category(&account)
+                .map(|(category_id, category)| (category_id, category.collateral_factor, category.liquidation_threshold))
+        }
+
+        fn get_pending_rewards(account: AccountId) -> Result<Vec<(CurrencyId, Balance)>, DispatchError> {
+            Loans::get_pending_rewards(&account)
+        }
+
+        fn get_accounts_with_shortfall(start_after: Option<AccountId>, limit: u32) -> Result<(Vec<(AccountId, Shortfall, Shortfall)>, Option<AccountId>), DispatchError> {
+            Loans::get_accounts_with_shortfall(start_after, limit)
+        }
+
+        fn get_account_positions(account: AccountId) -> Result<Vec<(CurrencyId, Balance, Balance, bool, Liquidity, Liquidity)>, DispatchError> {
+            Loans::get_account_positions(&account)
+        }
+
+        fn get_market_snapshots(asset_id: CurrencyId) -> Vec<(primitives::Timestamp, Rate, Rate, Rate, Ratio, Balance, Balance, Balance)> {
+            Loans::get_market_snapshots(asset_id)
+                .into_iter()
+                .map(|snapshot| (
+                    snapshot.timestamp,
+                    snapshot.borrow_rate,
+                    snapshot.supply_rate,
+                    snapshot.exchange_rate,
+                    snapshot.utilization,
+                    snapshot.total_supply,
+                    snapshot.total_borrows,
+                    snapshot.total_reserves,
+                ))
+                .collect()
+        }
+    }
+
+    impl pallet_prices_rpc_runtime_api::PricesApi<Block, BlockNumber, AccountId> for Runtime {
+        fn get_price(asset_id: CurrencyId) -> (Option<primitives::PriceDetail>, Option<primitives::PriceDetail>, Option<Price>, Option<primitives::PriceDetail>) {
+            let breakdown = Prices::get_price_breakdown(&asset_id);
+            (breakdown.price, breakdown.oracle, breakdown.emergency, breakdown.derived)
+        }
+
+        fn get_all_prices() -> Vec<(CurrencyId, Option<primitives::PriceDetail>, Option<primitives::PriceDetail>, Option<Price>, Option<primitives::PriceDetail>)> {
+            Prices::get_all_price_breakdowns()
+                .into_iter()
+                .map(|(asset_id, breakdown)| (
+                    asset_id,
+                    breakdown.price,
+                    breakdown.oracle,
+                    breakdown.emergency,
+                    breakdown.derived,
+                ))
+                .collect()
+        }
+
+        fn get_emergency_prices() -> Vec<(CurrencyId, Price, Option<BlockNumber>, Option<Vec<u8>>)> {
+            Prices::get_emergency_prices()
+                .into_iter()
+                .map(|(asset_id, price, info)| match info {
+                    Some(info) => (asset_id, price, Some(info.expires_at), info.reason.map(Into::into)),
+                    None => (asset_id, price, None, None),
+                })
+                .collect()
+        }
+
+        fn get_price_sources(asset_id: CurrencyId) -> Vec<primitives::PriceSource<AccountId>> {
+            Prices::get_price_breakdown(&asset_id).sources
+        }
     }
 
     impl pallet_router_rpc_runtime_api::RouterApi<Block, Balance> for Runtime {
-        fn get_best_route(amount: Balance, token_in: CurrencyId, token_out: CurrencyId, reversed: bool) -> Result<(Vec<CurrencyId>, Balance), DispatchError> {
-            let (route, amount) = AMMRoute::get_best_route(amount, token_in, token_out, reversed)?;
-            Ok((route, amount))
+        fn get_best_route(amount: Balance, token_in: CurrencyId, token_out: CurrencyId, reversed: bool) -> Result<(Vec<CurrencyId>, Balance, Vec<PoolType>), DispatchError> {
+            let (route, amount, pool_types) = AMMRoute::get_best_route(amount, token_in, token_out, reversed)?;
+            Ok((route, amount, pool_types))
+        }
+
+        fn get_best_split_route(amount: Balance, token_in: CurrencyId, token_out: CurrencyId) -> Result<Vec<(Vec<CurrencyId>, Balance, Balance, Vec<PoolType>)>, DispatchError> {
+            AMMRoute::get_best_split_route(amount, token_in, token_out)
         }
     }
 
@@ -46,4 +46,27 @@ impl<T: frame_system::Config> pallet_router::WeightInfo for WeightInfo<T> {
 			.saturating_add(T::DbWeight::get().reads(8 as u64))
 			.saturating_add(T::DbWeight::get().writes(8 as u64))
 	}
+	// Storage: unknown [0x3a7472616e73616374696f6e5f6c6576656c3a] (r:1 w:1)
+	// Storage: AMM Pools (r:3 w:2)
+	// Storage: Assets Asset (r:3 w:3)
+	// Storage: Assets Account (r:6 w:6)
+	/// The range of component `n` is `[1, 3]`.
+	fn swap_exact_tokens_for_tokens_split(n: u32, ) -> Weight {
+		Weight::from_ref_time(142_377_000 as u64)
+			// Standard Error: 71_000
+			.saturating_add(Weight::from_ref_time(238_915_000 as u64).saturating_mul(n as u64))
+			.saturating_add(T::DbWeight::get().reads(3 as u64))
+			.saturating_add(T::DbWeight::get().reads((8 as u64).saturating_mul(n as u64)))
+			.saturating_add(T::DbWeight::get().writes(1 as u64))
+			.saturating_add(T::DbWeight::get().writes((8 as u64).saturating_mul(n as u64)))
+	}
+	// Storage: AMMRoute Adjacency (r:1 w:0)
+	/// The range of component `p` is `[1, 10]`.
+	fn find_routes(p: u32, ) -> Weight {
+		Weight::from_ref_time(14_285_000 as u64)
+			// Standard Error: 6_000
+			.saturating_add(Weight::from_ref_time(4_731_000 as u64).saturating_mul(p as u64))
+			.saturating_add(T::DbWeight::get().reads(2 as u64))
+			.saturating_add(T::DbWeight::get().reads((1 as u64).saturating_mul(p as u64)))
+	}
 }